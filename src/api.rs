@@ -1,78 +1,617 @@
 //! Defines the members which allow the callers to implement a CLI application through this crate.
 
-use clap::{Parser, Subcommand, arg, command};
+use std::{env, error, io, process::ExitCode};
 
-use crate::{connect::ConnectArgs, list_devices::ListDevicesArgs, scan::ScanArgs};
+use clap::{Parser, Subcommand, ValueEnum};
+
+use crate::{
+    ListDevicesColumn, ScanColumn, battery::BatteryArgs, connect::ConnectArgs,
+    disconnect::DisconnectArgs, discoverable::DiscoverableArgs, export::ExportArgs,
+    import::ImportArgs, info::InfoArgs, list_devices::ListDevicesArgs, listen::ListenArgs,
+    prune::PruneArgs, rssi::RssiArgs, scan::ScanArgs, schema::SchemaArgs, status::StatusArgs,
+    toggle::ToggleArgs, trust::TrustArgs,
+};
 
 /// The main CLI struct that holds all subcommands.
+///
+/// Without a subcommand, a few shorthands are supported: `bt --on`/`bt --off` for `bt toggle --on`/`bt toggle --off`, and `bt <ALIAS>` for `bt connect <ALIAS>`. Otherwise, `bt` shows the status. Please see [`Cli::into_command()`].
+///
+/// [`Cli::into_command()`]: crate::api::Cli::into_command()
 #[derive(Debug, Parser)]
 #[command(version, about, long_about = None)]
 pub struct Cli {
+    /// Abort the command if it does not finish in SECS seconds.
+    #[arg(long, global = true, value_name = "SECS")]
+    pub deadline: Option<u64>,
+
+    /// Use the adapter named NAME (the last segment of its D-Bus path, e.g. hci1), or with the MAC address NAME.
+    ///
+    /// By default, hci0 is used if it exists, otherwise the first adapter by name.
+    #[arg(long, global = true, value_name = "NAME")]
+    pub adapter: Option<String>,
+
+    /// Shorthand for `bt toggle --on`.
+    #[arg(long, conflicts_with_all = ["off", "alias"])]
+    pub on: bool,
+
+    /// Shorthand for `bt toggle --off`.
+    #[arg(long, conflicts_with = "alias")]
+    pub off: bool,
+
+    /// Shorthand for `bt connect ALIAS`.
+    #[arg(value_name = "ALIAS")]
+    pub alias: Option<String>,
+
     #[command(subcommand)]
     pub command: Option<BtCommand>,
 }
 
+impl Cli {
+    /// Provides the subcommand to run, by resolving the shorthands into their subcommands.
+    ///
+    /// It returns [`None`] if neither a subcommand nor a shorthand is provided, in which case the status should be shown.
+    ///
+    /// [`None`]: std::option::Option::None
+    pub fn into_command(self) -> Option<BtCommand> {
+        if self.command.is_some() {
+            return self.command;
+        }
+
+        if self.on || self.off {
+            return Some(BtCommand::Toggle {
+                args: ToggleArgs {
+                    on: self.on,
+                    off: self.off,
+                    ..Default::default()
+                },
+            });
+        }
+
+        self.alias.map(|alias| BtCommand::Connect {
+            args: ConnectArgs {
+                alias: Some(alias),
+                ..Default::default()
+            },
+        })
+    }
+}
+
 /// Defines each individual functionality of this crate as a subcommand of a CLI application.
 ///
 /// For more details, please refer to each module that corresponds to each subcommand:
 ///
 /// - `BtCommand::Status`: [`status`]
 /// - `BtCommand::Toggle`: [`toggle`]
+/// - `BtCommand::IsOn`: [`is_on`]
+/// - `BtCommand::Discoverable`: [`discoverable`]
 /// - `BtCommand::list_devices`: [`list_devices`]
 /// - `BtCommand::scan`: [`scan`]
 /// - `BtCommand::connect`: [`connect`]
 /// - `BtCommand::disconnect`: [`disconnect`]
+/// - `BtCommand::trust`: [`trust`]
+/// - `BtCommand::prune`: [`prune`]
+/// - `BtCommand::rssi`: [`rssi`]
+/// - `BtCommand::battery`: [`battery`]
+/// - `BtCommand::info`: [`info`]
+/// - `BtCommand::listen`: [`listen`]
+/// - `BtCommand::schema`: [`schema`]
+/// - `BtCommand::shell`: [`shell`]
 ///
 /// [`status`]: crate::status
 /// [`toggle`]: crate::toggle
+/// [`is_on`]: crate::is_on
+/// [`discoverable`]: crate::discoverable
 /// [`list_devices`]: crate::list_devices
 /// [`scan`]: crate::scan
 /// [`connect`]: crate::connect
 /// [`disconnect`]: crate::disconnect
+/// [`trust`]: crate::trust
+/// [`prune`]: crate::prune
+/// [`rssi`]: crate::rssi
+/// [`battery`]: crate::battery
+/// [`info`]: crate::info
+/// [`listen`]: crate::listen
+/// [`schema`]: crate::schema
+/// [`shell`]: crate::shell
 #[derive(Debug, Subcommand)]
 pub enum BtCommand {
     /// See Bluetooth status.
     #[clap(visible_alias = "s")]
-    Status,
+    Status {
+        #[command(flatten)]
+        args: StatusArgs,
+    },
 
     /// Toggle Bluetooth status.
     #[clap(visible_alias = "t")]
-    Toggle,
+    Toggle {
+        #[command(flatten)]
+        args: ToggleArgs,
+    },
+
+    /// Exit with 0 if Bluetooth is on, and with 1 if it is off.
+    IsOn,
+
+    /// Make the adapter discoverable by the other devices, e.g. to pair a device that initiates the pairing itself.
+    ///
+    /// Bluez hides the adapter again after --timeout seconds.
+    Discoverable {
+        #[command(flatten)]
+        args: DiscoverableArgs,
+    },
 
     #[clap(visible_alias = "ls")]
+    #[command(after_help = list_devices_examples())]
     /// See known Bluetooth devices on the host.
+    ///
+    /// The devices are shown as a table by default. Use --columns to pick the columns of the table, or --values to get a terse output for scripting.
     ListDevices {
         #[command(flatten)]
         args: ListDevicesArgs,
     },
 
     /// Scan available Bluetooth devices.
+    ///
+    /// The scan blocks for --duration seconds, and then the scanned devices are shown as a table. Use --columns to pick the columns of the table, or --values to get a terse output for scripting.
     #[clap(visible_alias = "sc")]
+    #[command(after_help = scan_examples())]
     Scan {
         #[command(flatten)]
         args: ScanArgs,
     },
 
     /// Connect to an available Bluetooth device.
+    ///
+    /// If an ALIAS is provided, connect tries to connect to that known device directly. (non-interactive mode)
+    ///
+    /// Otherwise, connect scans the available devices first and lets users choose one. (interactive mode)
     #[clap(visible_alias = "c")]
+    #[command(after_help = CONNECT_EXAMPLES)]
     Connect {
         #[command(flatten)]
         args: ConnectArgs,
     },
 
     /// Disconnect from the connected device(s).
+    ///
+    /// If ALIAS(es) are provided, disconnect disconnects from those devices directly. (non-interactive mode)
+    ///
+    /// Otherwise, disconnect shows the connected devices first and lets users choose one or more. (interactive mode)
     #[clap(visible_alias = "d")]
+    #[command(after_help = DISCONNECT_EXAMPLES)]
     Disconnect {
-        /// Remove the device(s) from the known devices list.
-        #[arg(short, long, default_value_t = false)]
-        force: bool,
-
-        /// Disconnect by specifying the full ALIAS of device(s).
-        ///
-        /// If this argument is not provided, then disconnect first shows the list of connected devices to let users choose. (interactive mode)
-        ///
-        /// If this argument is provided, then disconnect does not show the list. (non-interactive mode)
-        #[arg(value_name = "ALIAS", value_delimiter = ',', num_args = 0.., default_value = None)]
-        aliases: Option<Vec<String>>,
+        #[command(flatten)]
+        args: DisconnectArgs,
+    },
+
+    /// Trust the known device(s), so that they can reconnect to the host on their own.
+    ///
+    /// Use --all-connected to trust every connected device instead.
+    #[command(after_help = TRUST_EXAMPLES)]
+    Trust {
+        #[command(flatten)]
+        args: TrustArgs,
+    },
+
+    /// Remove the stale devices from the known devices.
+    ///
+    /// A device is stale if it is not paired, bonded or connected, and it is not advertising at the moment.
+    ///
+    /// The stale devices are shown first, and they are removed only after a confirmation. Use --yes to skip the confirmation.
+    Prune {
+        #[command(flatten)]
+        args: PruneArgs,
     },
+
+    /// See the signal strength (RSSI) of a device.
+    ///
+    /// Use --watch to keep reading the RSSI, e.g. to locate a lost device by watching its RSSI rise while getting closer.
+    Rssi {
+        #[command(flatten)]
+        args: RssiArgs,
+    },
+
+    /// See the battery level of a known device.
+    ///
+    /// Use --watch with --alert-below to exit once it drops below a threshold, e.g. to send a charging reminder.
+    Battery {
+        #[command(flatten)]
+        args: BatteryArgs,
+    },
+
+    /// See the properties of a known device.
+    ///
+    /// Use --raw to also see its manufacturer data as a hex dump, e.g. to debug a BLE beacon.
+    Info {
+        #[command(flatten)]
+        args: InfoArgs,
+    },
+
+    /// Log the signal strength (RSSI) of a device to a file over time.
+    ///
+    /// A `timestamp,rssi` row is appended to --out on every --interval seconds until interrupted, e.g. to diagnose the intermittent drops of a device.
+    Listen {
+        #[command(flatten)]
+        args: ListenArgs,
+    },
+
+    /// Export the known devices along with their notes, e.g. as an annotated backup.
+    ///
+    /// The notes of the devices are kept locally by their address, and they can be restored by import.
+    Export {
+        #[command(flatten)]
+        args: ExportArgs,
+    },
+
+    /// Restore the notes of the devices from a file created by export.
+    Import {
+        #[command(flatten)]
+        args: ImportArgs,
+    },
+
+    /// Print the JSON Schema of a JSON output, e.g. to validate it or generate code from it.
+    Schema {
+        #[command(flatten)]
+        args: SchemaArgs,
+    },
+
+    /// Start an interactive shell, where each line is run as a bt command against the same Bluez connection.
+    ///
+    /// Type exit (or press Ctrl-D) to leave the shell.
+    Shell,
+}
+
+impl BtCommand {
+    /// Provides the exit code of the subcommand when it fails with an error.
+    ///
+    /// It is `2` for [`is_on`] and [`battery`], since their exit code `1` means that their check does not pass (e.g. Bluetooth is off), and it is `1` for the rest.
+    ///
+    /// [`is_on`]: crate::is_on
+    /// [`battery`]: crate::battery
+    pub fn error_exit_code(&self) -> ExitCode {
+        match self {
+            BtCommand::IsOn | BtCommand::Battery { .. } => ExitCode::from(2),
+            _ => ExitCode::FAILURE,
+        }
+    }
+
+    /// Runs the subcommand against `bluez`, by loading the state it needs (e.g. [`History`]) from its default location.
+    ///
+    /// The output of the subcommand is written to `w`, its warnings are written to `warn`, and its input (e.g. an interactive selection) is read from `r`.
+    ///
+    /// It returns the exit code of the subcommand that ran without an error. It is [`ExitCode::FAILURE`] if the check of the subcommand does not pass, e.g. [`is_on`] when Bluetooth is off, or [`battery`] when the battery level is not below its alert threshold. Otherwise, it is [`ExitCode::SUCCESS`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns the error of the subcommand, or the error of loading its state.
+    ///
+    /// [`History`]: crate::History
+    /// [`is_on`]: crate::is_on
+    /// [`battery`]: crate::battery
+    /// [`ExitCode::FAILURE`]: std::process::ExitCode::FAILURE
+    /// [`ExitCode::SUCCESS`]: std::process::ExitCode::SUCCESS
+    pub fn execute(
+        self,
+        bluez: &mut crate::BluezClient,
+        w: &mut impl io::Write,
+        warn: &mut impl io::Write,
+        r: &mut impl io::BufRead,
+    ) -> Result<ExitCode, Box<dyn error::Error + Send + Sync>> {
+        match self {
+            BtCommand::Status { args } => crate::status(bluez, w, warn, &args)?,
+            BtCommand::Toggle { args } => crate::toggle(bluez, w, &args)?,
+            BtCommand::IsOn => return Ok(exit_code(crate::is_on(bluez)?)),
+            BtCommand::Discoverable { args } => crate::discoverable(bluez, w, warn, &args)?,
+            BtCommand::Scan { args } => crate::scan(bluez, w, warn, &args)?,
+            BtCommand::Connect { args } => {
+                let config = config_or_default(warn, crate::Config::load())?;
+                let mut history = crate::History::load()?;
+                let args = pairing_from_env(args, |name| env::var(name).ok());
+                crate::connect(bluez, w, warn, r, &config, &mut history, &args)?
+            }
+            BtCommand::Disconnect { args } => crate::disconnect(bluez, w, warn, r, &args)?,
+            BtCommand::Trust { args } => crate::trust(bluez, w, &args)?,
+            BtCommand::ListDevices { args } => {
+                let history = crate::History::load()?;
+                crate::list_devices(bluez, w, warn, &history, &args)?
+            }
+            BtCommand::Rssi { args } => crate::rssi(bluez, w, &args)?,
+            BtCommand::Battery { args } => {
+                return Ok(exit_code(crate::battery(bluez, w, warn, &args)?));
+            }
+            BtCommand::Info { args } => crate::info(bluez, w, &args)?,
+            BtCommand::Listen { args } => crate::listen(bluez, &args)?,
+            BtCommand::Export { args } => {
+                let notes = crate::Notes::load()?;
+                crate::export(bluez, w, &notes, &args)?
+            }
+            BtCommand::Import { args } => {
+                let mut notes = crate::Notes::load()?;
+                crate::import(w, &mut notes, &args)?
+            }
+            BtCommand::Schema { args } => crate::schema(w, &args)?,
+            BtCommand::Prune { args } => crate::prune(bluez, w, r, &args)?,
+            BtCommand::Shell => crate::shell(bluez, w, warn, r)?,
+        }
+
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+/// Maps whether the check of a subcommand passed to its exit code.
+fn exit_code(passed: bool) -> ExitCode {
+    if passed {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Provides the loaded `config`, or the default [`Config`] if it could not be loaded, e.g. because the file is malformed.
+///
+/// The config only provides the preferences of the devices, so a config that cannot be loaded is ignored with a warning written to `warn`, instead of failing the connection.
+///
+/// [`Config`]: crate::Config
+fn config_or_default(
+    warn: &mut impl io::Write,
+    config: Result<crate::Config, crate::ConfigError>,
+) -> io::Result<crate::Config> {
+    match config {
+        Ok(config) => Ok(config),
+        Err(e) => {
+            writeln!(warn, "connect: warning: the config is ignored: {}", e)?;
+            Ok(crate::Config::default())
+        }
+    }
+}
+
+/// The environment variable that supplies the PIN code (or passkey) of a pairing, e.g. in a headless setup.
+const PIN_ENV: &str = "BT_PAIR_PIN";
+
+/// The environment variable that makes `connect` confirm the pairing of the device on its own, if it is set to `1`, `true` or `yes`.
+const AUTO_CONFIRM_ENV: &str = "BT_PAIR_AUTOCONFIRM";
+
+/// Completes the pairing arguments of `args` that are not provided by the values of [`PIN_ENV`] and [`AUTO_CONFIRM_ENV`], which are read by `var`.
+fn pairing_from_env(mut args: ConnectArgs, var: impl Fn(&str) -> Option<String>) -> ConnectArgs {
+    if args.input_pin.is_none() {
+        args.input_pin = var(PIN_ENV).filter(|pin_code| !pin_code.is_empty());
+    }
+    args.auto_confirm |= var(AUTO_CONFIRM_ENV)
+        .is_some_and(|value| matches!(value.trim().to_lowercase().as_str(), "1" | "true" | "yes"));
+
+    args
+}
+
+const CONNECT_EXAMPLES: &str = "Examples:
+  bt connect                        Scan for 5 seconds, then choose a device to connect.
+  bt connect --duration 10          Scan for 10 seconds, then choose a device to connect.
+  bt connect --contains-name dummy  Only show the scanned devices which contain \"dummy\".
+  bt connect <ALIAS>                Connect to a known device without scanning.
+  echo dev1 | bt connect -          Connect to the known device whose ALIAS is read from stdin.";
+
+const DISCONNECT_EXAMPLES: &str = "Examples:
+  bt disconnect                     Choose the connected device(s) to disconnect from.
+  bt disconnect dev1,dev2           Disconnect from dev1 and dev2 without choosing.
+  bt disconnect --force dev1        Remove dev1 from the known devices.
+  echo dev1 | bt disconnect -       Disconnect from the device whose ALIAS is read from stdin.";
+
+const TRUST_EXAMPLES: &str = "Examples:
+  bt trust dev1,dev2                Trust dev1 and dev2.
+  bt trust --all-connected          Trust every connected device.";
+
+fn column_names<C: ValueEnum>() -> String {
+    C::value_variants()
+        .iter()
+        .filter_map(|c| c.to_possible_value())
+        .map(|c| c.get_name().to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn list_devices_examples() -> String {
+    format!(
+        "Columns: {}
+
+Examples:
+  bt ls                             Show all known devices as a table.
+  bt ls --columns alias,connected   Only show the ALIAS and CONNECTED columns.
+  bt ls --columns a,c               The same, with the short column names.
+  bt ls --values alias,address      Show ALIAS/ADDRESS pairs, one per line.
+  bt ls --status connected          Only show the connected devices.",
+        column_names::<ListDevicesColumn>()
+    )
+}
+
+fn scan_examples() -> String {
+    format!(
+        "Columns: {}
+
+Examples:
+  bt scan                           Scan for 5 seconds and show the devices as a table.
+  bt scan --duration 10             Scan for 10 seconds.
+  bt scan --columns alias,rssi      Only show the ALIAS and RSSI columns.
+  bt scan --columns a,r             The same, with the short column names.
+  bt scan --values alias            Show the ALIAS of each device, one per line.",
+        column_names::<ScanColumn>()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    fn render_help(subcommand: &str) -> String {
+        let mut command = Cli::command();
+        let subcommand = command.find_subcommand_mut(subcommand).unwrap();
+
+        subcommand.render_long_help().to_string()
+    }
+
+    #[test]
+    fn it_should_list_every_list_devices_column_in_help() {
+        let help = render_help("list-devices");
+
+        for column in ListDevicesColumn::value_variants() {
+            let name = column.to_possible_value().unwrap();
+            assert!(help.contains(name.get_name()));
+        }
+        assert!(help.contains("Examples:"));
+    }
+
+    #[test]
+    fn it_should_list_every_scan_column_in_help() {
+        let help = render_help("scan");
+
+        for column in ScanColumn::value_variants() {
+            let name = column.to_possible_value().unwrap();
+            assert!(help.contains(name.get_name()));
+        }
+        assert!(help.contains("Examples:"));
+    }
+
+    #[test]
+    fn it_should_accept_the_short_column_names() {
+        let command = Cli::try_parse_from(["bt", "ls", "--columns", "a,c"])
+            .unwrap()
+            .into_command();
+        assert!(matches!(
+            command,
+            Some(BtCommand::ListDevices { args }) if matches!(
+                args.columns.as_deref(),
+                Some([ListDevicesColumn::Alias, ListDevicesColumn::Connected])
+            )
+        ));
+
+        let command = Cli::try_parse_from(["bt", "scan", "--columns", "a,r"])
+            .unwrap()
+            .into_command();
+        assert!(matches!(
+            command,
+            Some(BtCommand::Scan { args })
+                if args.columns == Some(vec![ScanColumn::Alias, ScanColumn::Rssi])
+        ));
+    }
+
+    #[test]
+    fn it_should_route_the_shorthands_to_their_subcommands() {
+        let command = Cli::try_parse_from(["bt", "mydevice"])
+            .unwrap()
+            .into_command();
+        assert!(matches!(
+            command,
+            Some(BtCommand::Connect { args }) if args.alias.as_deref() == Some("mydevice")
+        ));
+
+        let command = Cli::try_parse_from(["bt", "--on"]).unwrap().into_command();
+        assert!(matches!(
+            command,
+            Some(BtCommand::Toggle { args }) if args.on && !args.off && !args.all
+        ));
+
+        let command = Cli::try_parse_from(["bt", "--off"]).unwrap().into_command();
+        assert!(matches!(
+            command,
+            Some(BtCommand::Toggle { args }) if args.off && !args.on
+        ));
+
+        let command = Cli::try_parse_from(["bt"]).unwrap().into_command();
+        assert!(command.is_none());
+    }
+
+    #[test]
+    fn it_should_keep_the_explicit_subcommands() {
+        for argv in [["bt", "status"], ["bt", "s"], ["bt", "c"]] {
+            let command = Cli::try_parse_from(argv).unwrap().into_command();
+
+            match argv[1] {
+                "c" => assert!(matches!(
+                    command,
+                    Some(BtCommand::Connect { args }) if args.alias.is_none()
+                )),
+                _ => assert!(matches!(command, Some(BtCommand::Status { .. }))),
+            }
+        }
+
+        let cli = Cli::try_parse_from(["bt", "--deadline", "5", "status"]).unwrap();
+        assert_eq!(cli.deadline, Some(5));
+        assert!(matches!(cli.into_command(), Some(BtCommand::Status { .. })));
+
+        assert!(Cli::try_parse_from(["bt", "--on", "--off"]).is_err());
+        assert!(Cli::try_parse_from(["bt", "--on", "mydevice"]).is_err());
+    }
+
+    #[test]
+    fn it_should_not_accept_both_columns_and_values() {
+        let result = Cli::try_parse_from(["bt", "ls", "--columns", "alias", "--values", "alias"]);
+
+        let error = result.unwrap_err();
+        assert_eq!(error.kind(), clap::error::ErrorKind::ArgumentConflict);
+        assert!(error.to_string().contains("cannot be used with"));
+    }
+
+    #[test]
+    fn it_should_ignore_a_malformed_config_with_a_warning() {
+        let mut warn_buf = io::Cursor::new(vec![]);
+
+        let config = config_or_default(&mut warn_buf, "[devices".parse::<crate::Config>());
+        assert!(!config.unwrap().has_devices());
+
+        let warnings = String::from_utf8(warn_buf.into_inner()).unwrap();
+        assert!(
+            warnings.starts_with("connect: warning: the config is ignored: config: parse error: ")
+        );
+    }
+
+    #[test]
+    fn it_should_retry_the_empty_scan_once_by_default() {
+        let retries = |argv: &[&str]| match Cli::try_parse_from(argv).unwrap().into_command() {
+            Some(BtCommand::Scan { args }) => args.retry_if_empty,
+            _ => panic!("not a scan"),
+        };
+
+        assert_eq!(retries(&["bt", "scan"]), None);
+        assert_eq!(retries(&["bt", "scan", "--retry-if-empty"]), Some(1));
+        assert_eq!(retries(&["bt", "scan", "--retry-if-empty=3"]), Some(3));
+    }
+
+    #[test]
+    fn it_should_complete_the_pairing_arguments_from_the_environment() {
+        let vars = |name: &str| match name {
+            PIN_ENV => Some(String::from("123456")),
+            AUTO_CONFIRM_ENV => Some(String::from("true")),
+            _ => None,
+        };
+
+        let args = pairing_from_env(ConnectArgs::default(), vars);
+        assert_eq!(args.input_pin.as_deref(), Some("123456"));
+        assert!(args.auto_confirm);
+
+        let args = ConnectArgs {
+            input_pin: Some(String::from("0000")),
+            ..Default::default()
+        };
+        let args = pairing_from_env(args, vars);
+        assert_eq!(args.input_pin.as_deref(), Some("0000"));
+
+        let args = pairing_from_env(ConnectArgs::default(), |name| {
+            (name == AUTO_CONFIRM_ENV).then(|| String::from("0"))
+        });
+        assert_eq!(args.input_pin, None);
+        assert!(!args.auto_confirm);
+    }
+
+    #[test]
+    fn it_should_tell_the_errors_of_the_checks_apart_from_their_failures() {
+        assert_eq!(BtCommand::IsOn.error_exit_code(), ExitCode::from(2));
+        assert_eq!(
+            BtCommand::Battery {
+                args: BatteryArgs::default()
+            }
+            .error_exit_code(),
+            ExitCode::from(2)
+        );
+        assert_eq!(BtCommand::Shell.error_exit_code(), ExitCode::FAILURE);
+    }
 }
@@ -0,0 +1,334 @@
+use std::{error, fmt, io, thread, time::Duration};
+
+use clap::Args;
+
+use crate::BluezError;
+
+/// Defines error variants that may be returned from a [`battery`] call.
+///
+/// [`battery`]: crate::battery
+#[derive(Debug)]
+pub enum Error {
+    /// Happens when the [`BluezClient`] fails to provide the known devices during a [`battery`] call.
+    /// It holds the underlying [`BluezError`].
+    ///
+    /// [`BluezError`]: crate::BluezError
+    /// [`BluezClient`]: crate::BluezClient
+    /// [`battery`]: crate::battery
+    Bluez(BluezError),
+
+    /// Happens when no known device has the ALIAS (or ADDRESS).
+    ///
+    /// It holds the ALIAS.
+    NotFound(String),
+
+    /// Happens when [`battery`] cannot write to the provided [`io::Write`].
+    ///
+    /// It holds the underlying [`io::Error`].
+    ///
+    /// [`battery`]: crate::battery
+    /// [`io::Error`]: std::io::Error
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Bluez(error) => write!(f, "battery: bluez error: {}", error),
+            Error::NotFound(alias) => write!(f, "battery: no known device matches '{}'", alias),
+            Error::Io(error) => write!(f, "battery: io error: {}", error),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<BluezError> for Error {
+    fn from(value: BluezError) -> Self {
+        Self::Bluez(value)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/// Defines the arguments that [`battery`] can take.
+///
+/// [`battery`]: crate::battery
+#[derive(Debug, Args)]
+pub struct BatteryArgs {
+    /// The full ALIAS (or ADDRESS) of the known device.
+    #[arg(value_name = "ALIAS")]
+    pub alias: String,
+
+    /// Keep reading the battery level on every interval until interrupted, or until it drops below --alert-below.
+    #[arg(short, long, default_value_t = false)]
+    pub watch: bool,
+
+    /// Set the interval between the reads in seconds.
+    #[arg(short, long, default_value_t = 60u64)]
+    pub interval: u64,
+
+    /// Exit with 0 once the battery level drops below PERCENT, e.g. to send a charging reminder.
+    ///
+    /// Without --watch, it exits with 1 if the battery level is not below PERCENT. An error exits with 2.
+    #[arg(long, value_name = "PERCENT")]
+    pub alert_below: Option<u8>,
+}
+
+impl Default for BatteryArgs {
+    fn default() -> Self {
+        Self {
+            alias: String::new(),
+            watch: false,
+            interval: 60,
+            alert_below: None,
+        }
+    }
+}
+
+/// Provides the battery level of a single known device by using a [`BluezClient`].
+///
+/// The device is matched by its full ALIAS, or by its ADDRESS (case-insensitive). The battery level is written to the provided [`io::Write`] on its own line. If it is unknown (e.g. the device is disconnected, or it does not expose its battery), `-` is written instead.
+///
+/// If `args.watch` is `true`, then [`battery`] keeps reading the battery level on every `args.interval` seconds. Once the device is found, a failed read (e.g. while Bluez restarts) or a read where the device is briefly not known does not end the watch. Instead, a warning is written to `warn`, and the next read is tried on the next interval.
+///
+/// If `args.alert_below` is [`Some`], then [`battery`] writes an alert and returns `true` once the battery level drops below it. In watch mode, it keeps reading until then. Otherwise, it returns `false` if the battery level is not below `args.alert_below` (or it is unknown). The CLI exits with 0 on an alert, with 1 without an alert, and with 2 on an error. This makes it easy to chain a charging reminder, e.g. `bt battery mouse --watch --alert-below 15 && notify-send "charge your mouse"`.
+///
+/// [`battery`] is a blocking call in watch mode.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function can return all variants of [`BatteryError`] based on given conditions. For more details, please see the error documentation.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+/// use bt::{battery, BatteryArgs, BluezClient};
+///
+/// let bluez_client = BluezClient::new().unwrap();
+///
+/// let args = BatteryArgs {
+///     alias: "mouse".to_string(),
+///     watch: true,
+///     alert_below: Some(15),
+///     ..Default::default()
+/// };
+///
+/// // Writes the battery level of "mouse" every minute, until it drops below 15%.
+/// match battery(&bluez_client, &mut io::stdout(), &mut io::stderr(), &args) {
+///     Ok(true) => println!("charge your mouse"),
+///     Ok(false) => {}
+///     Err(e) => eprintln!("{}", e),
+/// }
+/// ```
+///
+/// [`BluezClient`]: crate::BluezClient
+/// [`io::Write`]: std::io::Write
+/// [`Some`]: std::option::Option::Some
+/// [`BatteryError`]: crate::BatteryError
+/// [`battery`]: crate::battery
+pub fn battery(
+    bluez: &crate::BluezClient,
+    w: &mut impl io::Write,
+    warn: &mut impl io::Write,
+    args: &BatteryArgs,
+) -> Result<bool, Error> {
+    // NOTE: Only the watch mode reads more than once, and it waits for the
+    // interval before each read after the device is found.
+    let mut is_found = false;
+    loop {
+        if is_found {
+            thread::sleep(Duration::from_secs(args.interval));
+        }
+
+        let devices = match bluez.devices() {
+            Ok(devices) => devices,
+            Err(e) if is_found => {
+                writeln!(
+                    warn,
+                    "battery: warning: could not read the known devices: {}",
+                    e
+                )?;
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let Some(device) = devices
+            .into_iter()
+            .find(|d| d.alias() == args.alias || d.address().eq_ignore_ascii_case(&args.alias))
+        else {
+            if is_found {
+                writeln!(
+                    warn,
+                    "battery: warning: device {} is not known at the moment",
+                    args.alias
+                )?;
+                continue;
+            }
+
+            return Err(Error::NotFound(args.alias.clone()));
+        };
+        is_found = true;
+
+        match device.battery() {
+            Some(battery) => writeln!(w, "{}", battery)?,
+            None => writeln!(w, "-")?,
+        }
+
+        if let (Some(threshold), Some(battery)) = (args.alert_below, *device.battery())
+            && battery < threshold
+        {
+            writeln!(
+                w,
+                "battery of device {} dropped below {}%",
+                device.alias(),
+                threshold
+            )?;
+            return Ok(true);
+        }
+        w.flush()?;
+
+        if !args.watch {
+            return Ok(args.alert_below.is_none());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::bluez;
+
+    fn test_bluez() -> crate::BluezClient {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("mouse")
+                .address("AA:AA:AA:AA:AA:AA")
+                .connected(true)
+                .battery(Some(22))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("speaker")
+                .address("BB:BB:BB:BB:BB:BB")
+                .build(),
+        ]);
+
+        bluez
+    }
+
+    #[test]
+    fn it_should_write_the_battery_of_a_device() {
+        let bluez = test_bluez();
+
+        for (alias, alert_below, expected, succeeded) in [
+            ("mouse", None, "22\n", true),
+            ("aa:aa:aa:aa:aa:aa", Some(20), "22\n", false),
+            ("speaker", Some(20), "-\n", false),
+        ] {
+            let mut out_buf = Cursor::new(vec![]);
+
+            let args = BatteryArgs {
+                alias: alias.to_string(),
+                alert_below,
+                ..Default::default()
+            };
+            let result = battery(&bluez, &mut out_buf, &mut io::sink(), &args);
+            assert_eq!(result.unwrap(), succeeded);
+
+            let result = String::from_utf8(out_buf.into_inner()).unwrap();
+            assert_eq!(result, expected);
+        }
+
+        let result = battery(
+            &bluez,
+            &mut io::sink(),
+            &mut io::sink(),
+            &BatteryArgs {
+                alias: "unknown".to_string(),
+                ..Default::default()
+            },
+        );
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+
+    #[test]
+    fn it_should_alert_once_the_battery_drops_below_the_threshold() {
+        let mut bluez = test_bluez();
+        bluez.set_battery_drain(5);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = BatteryArgs {
+            alias: "mouse".to_string(),
+            watch: true,
+            interval: 0,
+            alert_below: Some(20),
+        };
+        let result = battery(&bluez, &mut out_buf, &mut io::sink(), &args);
+        assert!(result.unwrap());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(
+            result,
+            "22\n17\nbattery of device mouse dropped below 20%\n"
+        );
+    }
+
+    #[test]
+    fn it_should_keep_watching_through_a_failed_read() {
+        let mut bluez = test_bluez();
+        bluez.set_battery_drain(5);
+        bluez.set_failing_calls("devices", [1], zbus::Error::InvalidReply);
+        bluez.set_vanishing_reads(&[2]);
+
+        let mut out_buf = Cursor::new(vec![]);
+        let mut warn_buf = Cursor::new(vec![]);
+
+        let args = BatteryArgs {
+            alias: "mouse".to_string(),
+            watch: true,
+            interval: 0,
+            alert_below: Some(15),
+        };
+        let result = battery(&bluez, &mut out_buf, &mut warn_buf, &args);
+        assert!(result.unwrap());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "22\n7\nbattery of device mouse dropped below 15%\n");
+
+        let warnings = String::from_utf8(warn_buf.into_inner()).unwrap();
+        let warnings = warnings.lines().collect::<Vec<&str>>();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].starts_with("battery: warning: could not read the known devices: "));
+        assert_eq!(
+            warnings[1],
+            "battery: warning: device mouse is not known at the moment"
+        );
+    }
+
+    #[test]
+    fn it_should_fail_if_the_first_read_fails_in_watch_mode() {
+        let mut bluez = test_bluez();
+        bluez.set_failing_calls("devices", [0], zbus::Error::InvalidReply);
+
+        let args = BatteryArgs {
+            alias: "mouse".to_string(),
+            watch: true,
+            interval: 0,
+            alert_below: Some(15),
+        };
+        let result = battery(&bluez, &mut io::sink(), &mut io::sink(), &args);
+        assert!(matches!(result, Err(Error::Bluez(_))));
+    }
+}
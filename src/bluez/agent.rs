@@ -0,0 +1,196 @@
+use zbus::{DBusError, interface, zvariant::OwnedObjectPath};
+
+/// The object path where [`BluezAgent`] is served on the system bus.
+pub const AGENT_PATH: &str = "/org/bluez/bt/agent";
+
+/// The IO capability [`BluezAgent`] registers with.
+///
+/// It makes Bluez request PIN codes and passkeys from the agent instead of displaying them.
+pub const AGENT_CAPABILITY: &str = "KeyboardOnly";
+
+/// The IO capability [`BluezAgent`] registers with when it confirms the pairings, so that Bluez sends the passkey confirmations to it as well.
+pub const AUTO_CONFIRM_CAPABILITY: &str = "KeyboardDisplay";
+
+/// Defines the errors that [`BluezAgent`] replies with to Bluez.
+#[derive(Debug, DBusError)]
+#[zbus(prefix = "org.bluez.Error")]
+pub enum AgentError {
+    #[zbus(error)]
+    ZBus(zbus::Error),
+
+    /// Rejects a pairing request.
+    Rejected(String),
+}
+
+/// Defines the pairing agent that answers the authentication requests of Bluez non-interactively.
+///
+/// It holds the callback state that is provided by the caller, such as the PIN code of a legacy device.
+/// The requests that cannot be answered from the callback state are rejected.
+#[derive(Debug, Default, Clone)]
+pub struct BluezAgent {
+    pin_code: Option<String>,
+    auto_confirm: Option<String>,
+}
+
+impl BluezAgent {
+    pub fn new(pin_code: Option<String>) -> Self {
+        Self {
+            pin_code,
+            auto_confirm: None,
+        }
+    }
+
+    /// Makes the agent accept the passkey confirmation and the authorization requests of the device with the object path `device`, so that its pairing can be answered without a TTY (e.g. in CI).
+    ///
+    /// The requests of the other devices are still rejected.
+    pub fn auto_confirm(mut self, device: impl Into<String>) -> Self {
+        self.auto_confirm = Some(device.into());
+        self
+    }
+
+    /// Indicates whether the agent can answer any request, i.e. it has a PIN code or it confirms the pairings. Otherwise, there is no need to register it.
+    pub fn can_answer(&self) -> bool {
+        self.pin_code.is_some() || self.auto_confirm.is_some()
+    }
+
+    /// Provides the IO capability that the agent should be registered with.
+    pub fn capability(&self) -> &'static str {
+        if self.auto_confirm.is_some() {
+            AUTO_CONFIRM_CAPABILITY
+        } else {
+            AGENT_CAPABILITY
+        }
+    }
+
+    fn confirms(&self, device: &OwnedObjectPath) -> bool {
+        self.auto_confirm.as_deref() == Some(device.as_str())
+    }
+
+    fn rejected(&self, request: &str) -> AgentError {
+        AgentError::Rejected(format!("bt agent cannot answer {}", request))
+    }
+}
+
+#[interface(name = "org.bluez.Agent1")]
+impl BluezAgent {
+    fn release(&self) {}
+
+    pub(crate) fn request_pin_code(&self, _device: OwnedObjectPath) -> Result<String, AgentError> {
+        match &self.pin_code {
+            Some(pin_code) => Ok(pin_code.clone()),
+            None => Err(self.rejected("RequestPinCode")),
+        }
+    }
+
+    fn display_pin_code(&self, _device: OwnedObjectPath, _pin_code: String) {}
+
+    fn request_passkey(&self, _device: OwnedObjectPath) -> Result<u32, AgentError> {
+        self.pin_code
+            .as_ref()
+            .and_then(|pin_code| pin_code.parse::<u32>().ok())
+            .ok_or(self.rejected("RequestPasskey"))
+    }
+
+    fn display_passkey(&self, _device: OwnedObjectPath, _passkey: u32, _entered: u16) {}
+
+    pub(crate) fn request_confirmation(
+        &self,
+        device: OwnedObjectPath,
+        _passkey: u32,
+    ) -> Result<(), AgentError> {
+        if self.confirms(&device) {
+            return Ok(());
+        }
+
+        Err(self.rejected("RequestConfirmation"))
+    }
+
+    fn request_authorization(&self, device: OwnedObjectPath) -> Result<(), AgentError> {
+        if self.confirms(&device) {
+            return Ok(());
+        }
+
+        Err(self.rejected("RequestAuthorization"))
+    }
+
+    fn authorize_service(&self, _device: OwnedObjectPath, _uuid: String) -> Result<(), AgentError> {
+        Err(self.rejected("AuthorizeService"))
+    }
+
+    fn cancel(&self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use zbus::zvariant::ObjectPath;
+
+    use super::*;
+
+    fn device_path() -> OwnedObjectPath {
+        ObjectPath::try_from("/org/bluez/hci0/dev_XX_XX_XX_XX_XX_XX")
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn it_should_answer_pin_code_requests_with_the_supplied_pin() {
+        let agent = BluezAgent::new(Some("0000".to_string()));
+
+        let result = agent.request_pin_code(device_path());
+
+        assert_eq!(result.unwrap(), "0000");
+    }
+
+    #[test]
+    fn it_should_answer_passkey_requests_with_a_numeric_pin() {
+        let agent = BluezAgent::new(Some("123456".to_string()));
+
+        let result = agent.request_passkey(device_path());
+
+        assert_eq!(result.unwrap(), 123456);
+    }
+
+    #[test]
+    fn it_should_reject_pin_code_requests_without_a_pin() {
+        let agent = BluezAgent::new(None);
+
+        let result = agent.request_pin_code(device_path());
+
+        assert!(matches!(result, Err(AgentError::Rejected(_))));
+    }
+
+    #[test]
+    fn it_should_confirm_the_requests_of_the_target_device_only() {
+        let agent = BluezAgent::new(None).auto_confirm(device_path().as_str());
+
+        assert!(agent.can_answer());
+        assert_eq!(agent.capability(), AUTO_CONFIRM_CAPABILITY);
+        assert!(agent.request_confirmation(device_path(), 123456).is_ok());
+        assert!(agent.request_authorization(device_path()).is_ok());
+        assert!(matches!(
+            agent.request_pin_code(device_path()),
+            Err(AgentError::Rejected(_))
+        ));
+
+        let other_device: OwnedObjectPath =
+            ObjectPath::try_from("/org/bluez/hci0/dev_AA_AA_AA_AA_AA_AA")
+                .unwrap()
+                .into();
+        assert!(matches!(
+            agent.request_confirmation(other_device.clone(), 123456),
+            Err(AgentError::Rejected(_))
+        ));
+        assert!(matches!(
+            agent.request_authorization(other_device),
+            Err(AgentError::Rejected(_))
+        ));
+
+        let agent = BluezAgent::new(None);
+        assert!(!agent.can_answer());
+        assert_eq!(agent.capability(), AGENT_CAPABILITY);
+        assert!(matches!(
+            agent.request_confirmation(device_path(), 123456),
+            Err(AgentError::Rejected(_))
+        ));
+    }
+}
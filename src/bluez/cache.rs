@@ -0,0 +1,114 @@
+use std::{
+    cell::Cell,
+    time::{Duration, Instant},
+};
+
+/// The default time-to-live of [`TtlCache`].
+pub const DEFAULT_TTL: Duration = Duration::from_millis(200);
+
+/// Defines a single-value cache whose value expires after a time-to-live (TTL).
+///
+/// It uses interior mutability, so that the value can be cached behind a shared reference.
+/// A cache without a TTL is disabled, and it never keeps a value.
+#[derive(Debug)]
+pub struct TtlCache<T: Copy> {
+    ttl: Option<Duration>,
+    entry: Cell<Option<(Instant, T)>>,
+}
+
+impl<T: Copy> TtlCache<T> {
+    pub fn new(ttl: Option<Duration>) -> Self {
+        Self {
+            ttl,
+            entry: Cell::new(None),
+        }
+    }
+
+    /// Provides the cached value if it has not expired yet, otherwise caches and provides the value returned by `read`.
+    ///
+    /// A failed `read` is not cached.
+    pub fn get_or_read<E>(&self, read: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+        let Some(ttl) = self.ttl else {
+            return read();
+        };
+
+        if let Some((read_at, value)) = self.entry.get()
+            && read_at.elapsed() < ttl
+        {
+            return Ok(value);
+        }
+
+        let value = read()?;
+        self.entry.set(Some((Instant::now(), value)));
+
+        Ok(value)
+    }
+
+    /// Replaces the cached value, e.g. after the value is changed through the client.
+    pub fn set(&self, value: T) {
+        if self.ttl.is_some() {
+            self.entry.set(Some((Instant::now(), value)));
+        }
+    }
+
+    /// Drops the cached value, e.g. after the source of the value is changed.
+    pub fn clear(&self) {
+        self.entry.set(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, convert::Infallible, thread};
+
+    use super::*;
+
+    fn counted_read(reads: &Cell<usize>) -> impl FnOnce() -> Result<bool, Infallible> {
+        || {
+            reads.set(reads.get() + 1);
+            Ok(true)
+        }
+    }
+
+    #[test]
+    fn it_should_reuse_the_value_within_the_ttl() {
+        let reads = Cell::new(0);
+        let cache = TtlCache::new(Some(Duration::from_secs(60)));
+
+        assert!(cache.get_or_read(counted_read(&reads)).unwrap());
+        assert!(cache.get_or_read(counted_read(&reads)).unwrap());
+
+        assert_eq!(reads.get(), 1);
+    }
+
+    #[test]
+    fn it_should_read_again_after_the_ttl() {
+        let reads = Cell::new(0);
+        let cache = TtlCache::new(Some(Duration::from_millis(1)));
+
+        cache.get_or_read(counted_read(&reads)).unwrap();
+        thread::sleep(Duration::from_millis(5));
+        cache.get_or_read(counted_read(&reads)).unwrap();
+
+        assert_eq!(reads.get(), 2);
+    }
+
+    #[test]
+    fn it_should_always_read_if_disabled() {
+        let reads = Cell::new(0);
+        let cache = TtlCache::new(None);
+
+        cache.get_or_read(counted_read(&reads)).unwrap();
+        cache.get_or_read(counted_read(&reads)).unwrap();
+
+        assert_eq!(reads.get(), 2);
+    }
+
+    #[test]
+    fn it_should_not_cache_a_failed_read() {
+        let cache = TtlCache::new(Some(Duration::from_secs(60)));
+
+        assert!(cache.get_or_read(|| Err::<bool, ()>(())).is_err());
+        assert!(cache.get_or_read(|| Ok::<bool, ()>(true)).unwrap());
+    }
+}
@@ -1,23 +1,37 @@
 #![allow(dead_code, reason = "cfg test/not(test) for BluezDBusClient")]
 
-use std::{error, fmt};
+use std::{cell::RefCell, collections::BTreeMap, error, fmt, io, thread, time::Duration};
 
+use futures_lite::StreamExt;
 use zbus::{
     blocking::{Connection, fdo::ObjectManagerProxy},
-    zvariant::OwnedObjectPath,
+    fdo::ManagedObjects,
+    proxy::CacheProperties,
+    zvariant::{ObjectPath, OwnedObjectPath},
 };
 
-use super::proxies::{BluezAdapterProxy, BluezDeviceBatteryProxy, BluezDeviceProxy};
+use super::{
+    agent::{AGENT_PATH, BluezAgent},
+    cache::{DEFAULT_TTL, TtlCache},
+    proxies::{
+        BluezAdapterProxy, BluezAgentManagerProxy, BluezDeviceBatteryProxy, BluezDeviceProxy,
+    },
+    rfkill::RfkillProbe,
+};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BluezPowerState {
     On,
     Off,
+    /// The adapter is off because Bluetooth is blocked by an rfkill switch, and it cannot be powered on until it is unblocked.
+    Blocked,
 }
 impl fmt::Display for BluezPowerState {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self {
             BluezPowerState::On => write!(f, "enabled"),
             BluezPowerState::Off => write!(f, "disabled"),
+            BluezPowerState::Blocked => write!(f, "blocked (rfkill)"),
         }
     }
 }
@@ -36,7 +50,7 @@ impl std::ops::Not for BluezPowerState {
     fn not(self) -> Self::Output {
         match self {
             BluezPowerState::On => Self::Off,
-            BluezPowerState::Off => Self::On,
+            BluezPowerState::Off | BluezPowerState::Blocked => Self::On,
         }
     }
 }
@@ -44,7 +58,28 @@ impl From<&BluezPowerState> for bool {
     fn from(value: &BluezPowerState) -> Self {
         match value {
             BluezPowerState::On => true,
-            BluezPowerState::Off => false,
+            BluezPowerState::Off | BluezPowerState::Blocked => false,
+        }
+    }
+}
+
+/// Defines the connection state of a [`BluezDevice`].
+///
+/// Bluez does not expose a connecting state, so it is inferred: a device is connecting while it is connected but its services are not resolved yet.
+///
+/// [`BluezDevice`]: crate::BluezDevice
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BluezConnectionState {
+    Disconnected,
+    Connecting,
+    Connected,
+}
+impl fmt::Display for BluezConnectionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            BluezConnectionState::Disconnected => write!(f, "disconnected"),
+            BluezConnectionState::Connecting => write!(f, "connecting"),
+            BluezConnectionState::Connected => write!(f, "connected"),
         }
     }
 }
@@ -52,17 +87,26 @@ impl From<&BluezPowerState> for bool {
 /// Defines a Bluetooth device.
 /// It is constructed from [`BluezClient`] methods.
 ///
+/// Two [`BluezDevice`]'s are equal if all of their properties are equal, which makes it possible to compare the snapshots of a device over time.
+///
 /// [`BluezClient`]: crate::BluezClient
-#[derive(Debug)]
+/// [`BluezDevice`]: crate::BluezDevice
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BluezDevice {
     alias: String,
     address: String,
+    adapter: String,
     connected: bool,
     paired: bool,
     trusted: bool,
     bonded: bool,
+    blocked: bool,
     battery: Option<u8>,
     rssi: Option<i16>,
+    tx_power: Option<i16>,
+    uuids: Vec<String>,
+    services_resolved: bool,
+    manufacturer_data: Option<BTreeMap<u16, Vec<u8>>>,
 }
 impl BluezDevice {
     /// Indicates whether a [`BluezDevice`] is connected or not.
@@ -72,6 +116,50 @@ impl BluezDevice {
         self.connected
     }
 
+    /// Creates a [`BluezDevice`] from its ALIAS, ADDRESS and statuses, e.g. to restore a device of a saved snapshot. Its adapter, battery, RSSI and UUIDs are unknown.
+    ///
+    /// [`BluezDevice`]: crate::BluezDevice
+    pub(crate) fn with_statuses(
+        alias: String,
+        address: String,
+        connected: bool,
+        trusted: bool,
+        bonded: bool,
+        paired: bool,
+    ) -> Self {
+        Self {
+            alias,
+            address,
+            adapter: String::new(),
+            connected,
+            paired,
+            trusted,
+            bonded,
+            blocked: false,
+            battery: None,
+            rssi: None,
+            tx_power: None,
+            uuids: vec![],
+            services_resolved: true,
+            manufacturer_data: None,
+        }
+    }
+
+    /// Provides the [`BluezConnectionState`] of a [`BluezDevice`].
+    ///
+    /// Unlike [`BluezDevice.connected()`], it distinguishes the devices that are still connecting.
+    ///
+    /// [`BluezDevice`]: crate::BluezDevice
+    /// [`BluezConnectionState`]: crate::BluezConnectionState
+    /// [`BluezDevice.connected()`]: crate::BluezDevice::connected()
+    pub fn connection_state(&self) -> BluezConnectionState {
+        match (self.connected, self.services_resolved) {
+            (false, _) => BluezConnectionState::Disconnected,
+            (true, false) => BluezConnectionState::Connecting,
+            (true, true) => BluezConnectionState::Connected,
+        }
+    }
+
     /// Indicates whether a [`BluezDevice`] is paired or not.
     ///
     /// [`BluezDevice`]: crate::BluezDevice
@@ -93,6 +181,13 @@ impl BluezDevice {
         self.bonded
     }
 
+    /// Indicates whether a [`BluezDevice`] is blocked, in which case Bluez rejects its connections.
+    ///
+    /// [`BluezDevice`]: crate::BluezDevice
+    pub fn blocked(&self) -> bool {
+        self.blocked
+    }
+
     /// Provides a [`BluezDevice`]'s alias.
     ///
     /// [`BluezDevice`]: crate::BluezDevice
@@ -107,6 +202,26 @@ impl BluezDevice {
         &self.address
     }
 
+    /// Provides the name of the adapter that a [`BluezDevice`] is registered to, such as `hci0`.
+    ///
+    /// The same device is registered to each adapter that discovers it, so there may be more than one [`BluezDevice`] with the same address on a host with multiple adapters.
+    ///
+    /// [`BluezDevice`]: crate::BluezDevice
+    pub fn adapter(&self) -> &str {
+        &self.adapter
+    }
+
+    /// Provides the D-Bus object path of a [`BluezDevice`], e.g. `/org/bluez/hci0/dev_AA_BB_CC_DD_EE_FF`.
+    ///
+    /// [`BluezDevice`]: crate::BluezDevice
+    pub fn path(&self) -> String {
+        format!(
+            "/org/bluez/{}/dev_{}",
+            self.adapter,
+            self.address.to_uppercase().replace(':', "_")
+        )
+    }
+
     /// Provides a [`BluezDevice`]'s battery.
     ///
     /// If a [`BluezDevice`] is connected, then the returned value is [`Some`].
@@ -130,8 +245,99 @@ impl BluezDevice {
     pub fn rssi(&self) -> &Option<i16> {
         &self.rssi
     }
+
+    /// Provides the transmission power (in dBm) advertised by a [`BluezDevice`], which can be combined with its RSSI to estimate the distance.
+    ///
+    /// This value is [`Some`] only for the devices that advertise it, which are mostly BLE devices.
+    /// Otherwise, it is [`None`].
+    ///
+    /// [`BluezDevice`]: crate::BluezDevice
+    /// [`Some`]: std::option::Option::Some
+    /// [`None`]: std::option::Option::None
+    pub fn tx_power(&self) -> &Option<i16> {
+        &self.tx_power
+    }
+
+    /// Provides the service UUIDs advertised by a [`BluezDevice`].
+    ///
+    /// The list is empty if the device does not advertise any services.
+    ///
+    /// [`BluezDevice`]: crate::BluezDevice
+    pub fn uuids(&self) -> &[String] {
+        &self.uuids
+    }
+
+    /// Provides the manufacturer-specific advertisement data of a [`BluezDevice`] (e.g. a BLE beacon), keyed by the company identifier.
+    ///
+    /// This value is [`None`] if the device does not advertise such data, which is the case for most classic devices.
+    ///
+    /// [`BluezDevice`]: crate::BluezDevice
+    /// [`None`]: std::option::Option::None
+    pub fn manufacturer_data(&self) -> Option<&BTreeMap<u16, Vec<u8>>> {
+        self.manufacturer_data.as_ref()
+    }
+
+    /// Provides the capability categories of a [`BluezDevice`], such as `audio` or `input`.
+    ///
+    /// The categories are derived from the well-known service UUIDs in [`BluezDevice.uuids()`]. Unknown UUIDs are ignored, and each category is listed once.
+    ///
+    /// [`BluezDevice`]: crate::BluezDevice
+    /// [`BluezDevice.uuids()`]: crate::BluezDevice::uuids()
+    pub fn capabilities(&self) -> Vec<&'static str> {
+        let short_uuids = self
+            .uuids
+            .iter()
+            .filter_map(|uuid| {
+                let uuid = uuid.to_lowercase();
+                match uuid.strip_suffix(BLUETOOTH_BASE_UUID_SUFFIX) {
+                    Some(prefix) if prefix.len() == 8 && prefix.starts_with("0000") => {
+                        Some(prefix[4..].to_string())
+                    }
+                    _ => None,
+                }
+            })
+            .collect::<Vec<String>>();
+
+        let mut capabilities: Vec<&'static str> = Vec::new();
+        for (short_uuid, capability) in UUID_CAPABILITIES {
+            if !capabilities.contains(&capability) && short_uuids.iter().any(|u| u == short_uuid) {
+                capabilities.push(capability);
+            }
+        }
+
+        capabilities
+    }
 }
 
+const BLUETOOTH_BASE_UUID_SUFFIX: &str = "-0000-1000-8000-00805f9b34fb";
+
+const UUID_CAPABILITIES: [(&str, &str); 24] = [
+    ("1108", "audio"),
+    ("110a", "audio"),
+    ("110b", "audio"),
+    ("110c", "audio"),
+    ("110d", "audio"),
+    ("110e", "audio"),
+    ("110f", "audio"),
+    ("111e", "audio"),
+    ("1131", "audio"),
+    ("184e", "audio"),
+    ("1850", "audio"),
+    ("1124", "input"),
+    ("1812", "input"),
+    ("1112", "phone"),
+    ("111f", "phone"),
+    ("112e", "phone"),
+    ("112f", "phone"),
+    ("1132", "phone"),
+    ("1133", "phone"),
+    ("1134", "phone"),
+    ("1103", "network"),
+    ("1115", "network"),
+    ("1116", "network"),
+    ("1117", "network"),
+];
+
 /// Defines error variants that may be returned from [`BluezClient`].
 ///
 /// [`BluezClient`]: crate::BluezClient
@@ -144,6 +350,23 @@ pub enum Error {
     /// [`BluezClient`]: crate::BluezClient
     Init(zbus::Error),
 
+    /// Happens when [`BluezClient`] cannot be initialized because the D-Bus system bus socket is not found, or nothing listens on it. This error mainly indicates a host without a D-Bus system bus, such as a container without `/run/dbus`, or a non-Linux host.
+    ///
+    /// It holds the underlying DBus error.
+    ///
+    /// [`BluezClient`]: crate::BluezClient
+    NoSystemBus(zbus::Error),
+
+    /// Happens when [`BluezClient`] cannot be initialized because Bluez does not report any Bluetooth adapter. This error mainly indicates a host without Bluetooth hardware, or an adapter whose driver is not loaded.
+    ///
+    /// [`BluezClient`]: crate::BluezClient
+    NoAdapter,
+
+    /// Happens when the adapter is off because Bluetooth is blocked by an rfkill switch, and it cannot be powered on until it is unblocked.
+    ///
+    /// Its message carries the hint to unblock it, which the commands reuse for their own blocked errors.
+    Blocked,
+
     /// Happens when a [`BluezClient`] process fails.
     ///
     /// It holds the process' ID, and the underlying DBus error.
@@ -158,6 +381,16 @@ impl fmt::Display for Error {
             Error::Init(error) => {
                 write!(f, "unable to establish a Bluez D-Bus connection: {}", error)
             }
+            Error::NoSystemBus(error) => write!(
+                f,
+                "the D-Bus system bus socket is not found, bt needs a Linux host with a running D-Bus system bus and Bluez (a container likely lacks /run/dbus): {}",
+                error
+            ),
+            Error::NoAdapter => write!(f, "no Bluetooth adapter found"),
+            Error::Blocked => write!(
+                f,
+                "bluetooth is blocked by rfkill, unblock it first (e.g. `rfkill unblock bluetooth`)"
+            ),
             Error::Process(pid, error) => {
                 write!(f, "the Bluez process '{}' failed: {}", pid, error)
             }
@@ -166,31 +399,235 @@ impl fmt::Display for Error {
 }
 impl error::Error for Error {}
 
+impl Error {
+    /// Indicates whether the failed call may succeed when it is retried right away, e.g. a connection that timed out, or an adapter that is still busy with another call.
+    ///
+    /// The errors which retrying cannot fix, such as an unknown device or a rejected authentication, are not transient.
+    pub fn is_transient(&self) -> bool {
+        let Error::Process(_, error) = self else {
+            return false;
+        };
+
+        match error {
+            zbus::Error::MethodError(name, message, _) => {
+                NOT_READY_ERROR_NAMES.contains(&name.as_str())
+                    || (name.as_str() == "org.bluez.Error.Failed"
+                        && message.as_deref() == Some(PAGE_TIMEOUT_MESSAGE))
+            }
+            _ => false,
+        }
+    }
+
+    /// Indicates whether the failed call did not reach Bluez, because bluetoothd or the connection to the bus is gone, e.g. when bluetoothd is restarted.
+    ///
+    /// Such a call may succeed once it is retried after [`BluezClient.refresh()`]. Only the errors that are returned before the call reaches bluetoothd count as stale, so a call that may have had an effect (e.g. a `Connect` that failed with an I/O error on the device side) is never sent twice.
+    ///
+    /// [`BluezClient.refresh()`]: crate::BluezClient::refresh()
+    pub fn is_stale(&self) -> bool {
+        let Error::Process(_, error) = self else {
+            return false;
+        };
+
+        match error {
+            zbus::Error::MethodError(name, _, _) => STALE_ERROR_NAMES.contains(&name.as_str()),
+            zbus::Error::FDO(error) => matches!(
+                **error,
+                zbus::fdo::Error::ServiceUnknown(_) | zbus::fdo::Error::NameHasNoOwner(_)
+            ),
+            zbus::Error::InputOutput(error) => matches!(
+                error.kind(),
+                io::ErrorKind::BrokenPipe
+                    | io::ErrorKind::ConnectionReset
+                    | io::ErrorKind::ConnectionAborted
+                    | io::ErrorKind::NotConnected
+                    | io::ErrorKind::UnexpectedEof
+            ),
+            _ => false,
+        }
+    }
+}
+
+/// The message of the `org.bluez.Error.Failed` that Bluez returns when the device does not answer the page of a connection in time.
+const PAGE_TIMEOUT_MESSAGE: &str = "br-connection-page-timeout";
+
+const ADAPTER_INTERFACE: &str = "org.bluez.Adapter1";
+
+/// The path of the adapter that is used by default, if it exists.
+const DEFAULT_ADAPTER_PATH: &str = "/org/bluez/hci0";
+
+/// Defines a Bluetooth adapter, which is any Bluez D-Bus object that implements `org.bluez.Adapter1`, regardless of its path.
+///
+/// An adapter is named after the last segment of its path, e.g. `hci0` for `/org/bluez/hci0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BluezAdapter {
+    path: String,
+    address: String,
+}
+impl BluezAdapter {
+    /// Provides the name of the adapter, which is the last segment of its path.
+    pub fn name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or_default()
+    }
+
+    /// Provides the D-Bus object path of the adapter.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Provides the MAC address of the adapter. It is empty if Bluez does not report it.
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Indicates whether `adapter` selects this adapter, either by its name or by its MAC address (case-insensitive).
+    pub fn matches(&self, adapter: &str) -> bool {
+        self.name() == adapter
+            || (!self.address.is_empty() && self.address.eq_ignore_ascii_case(adapter))
+    }
+}
+
+/// Provides the adapters among the managed Bluez D-Bus objects, sorted by their names.
+fn adapters_from_objects(objects: ManagedObjects) -> Vec<BluezAdapter> {
+    let mut adapters = objects
+        .into_iter()
+        .filter_map(|(path, interfaces)| {
+            let (_, properties) = interfaces
+                .iter()
+                .find(|(interface, _)| interface.as_str() == ADAPTER_INTERFACE)?;
+            let address = properties
+                .get("Address")
+                .and_then(|address| address.downcast_ref::<&str>().ok())
+                .unwrap_or_default();
+
+            Some(BluezAdapter {
+                path: path.to_string(),
+                address: address.to_string(),
+            })
+        })
+        .collect::<Vec<BluezAdapter>>();
+    adapters.sort_by(|a, b| a.name().cmp(b.name()).then_with(|| a.path.cmp(&b.path)));
+
+    adapters
+}
+
+/// Provides the adapter that is used unless another one is selected: `hci0` if it exists, otherwise the first adapter.
+fn default_adapter(adapters: &[BluezAdapter]) -> Option<&BluezAdapter> {
+    adapters
+        .iter()
+        .find(|adapter| adapter.path == DEFAULT_ADAPTER_PATH)
+        .or(adapters.first())
+}
+
+/// Provides the path of the adapter that a new client starts with, from the adapters read from Bluez.
+///
+/// If the adapters cannot be read yet (e.g. bluetoothd is not running), then the default path is kept, so that the error surfaces from the first call that needs the adapter. If Bluez reports no adapters at all, then it fails with [`Error::NoAdapter`].
+fn initial_adapter_path(adapters: zbus::Result<Vec<BluezAdapter>>) -> Result<String, Error> {
+    match adapters {
+        Ok(adapters) => default_adapter(&adapters)
+            .map(|adapter| adapter.path.clone())
+            .ok_or(Error::NoAdapter),
+        Err(_) => Ok(String::from(DEFAULT_ADAPTER_PATH)),
+    }
+}
+
+fn read_adapters(connection: &Connection) -> zbus::Result<Vec<BluezAdapter>> {
+    let object_manager_proxy = ObjectManagerProxy::new(connection, "org.bluez", "/")?;
+    let objects = object_manager_proxy.get_managed_objects()?;
+
+    Ok(adapters_from_objects(objects))
+}
+
+/// Provides the name of the adapter that the device at `dev_path` is registered to, which is the parent segment of its path, e.g. `hci0` for `/org/bluez/hci0/dev_XX_XX_XX_XX_XX_XX`.
+fn adapter_name(dev_path: &str) -> String {
+    dev_path.rsplit('/').nth(1).unwrap_or_default().to_string()
+}
+
 /// Defines the client that interacts with Bluez D-Bus.
 pub struct BluezDBusClient {
-    connection: Connection,
-    adapter_proxy: BluezAdapterProxy<'static>,
+    connection: RefCell<Connection>,
+    adapter_proxy: RefCell<BluezAdapterProxy<'static>>,
+    power_state_cache: TtlCache<BluezPowerState>,
+    rfkill_probe: RfkillProbe,
+    agent: RefCell<Option<BluezAgent>>,
 }
 
 impl BluezDBusClient {
     /// Init method. The initialized [`BluezClient`] can be re-used for multiple processes.
     ///
-    /// The error returning from this method is of [`BluezError::Init`] variant.
+    /// The error returning from this method is of [`BluezError::NoSystemBus`] variant if the D-Bus system bus is not reachable, of [`BluezError::NoAdapter`] variant if Bluez reports no adapters, and of [`BluezError::Init`] variant otherwise.
     ///
     /// [`BluezClient`]: crate::BluezClient
     /// [`BluezError::Init`]: crate::BluezError::Init
+    /// [`BluezError::NoSystemBus`]: crate::BluezError::NoSystemBus
+    /// [`BluezError::NoAdapter`]: crate::BluezError::NoAdapter
     pub fn new() -> Result<Self, Error> {
-        let connection = Connection::system().map_err(Error::Init)?;
-        let adapter_proxy = BluezAdapterProxy::new(&connection).map_err(Error::Init)?;
+        let connection = Connection::system().map_err(init_error)?;
+
+        let adapter_path = initial_adapter_path(read_adapters(&connection))?;
+        let adapter_proxy = BluezAdapterProxy::builder(&connection)
+            .path(adapter_path)
+            .and_then(|builder| builder.build())
+            .map_err(Error::Init)?;
 
         Ok(Self {
-            connection,
-            adapter_proxy,
+            connection: RefCell::new(connection),
+            adapter_proxy: RefCell::new(adapter_proxy),
+            power_state_cache: TtlCache::new(Some(DEFAULT_TTL)),
+            rfkill_probe: RfkillProbe::default(),
+            agent: RefCell::new(None),
         })
     }
 
+    /// Rebuilds the connection to Bluez D-Bus and the proxy of the selected adapter in place, e.g. after bluetoothd is restarted.
+    ///
+    /// The other methods call it once on their own when they fail because Bluez D-Bus is not reachable anymore, and then retry the failed call. So, it rarely needs to be called directly.
+    ///
+    /// The agent registered by [`BluezClient.register_agent()`] is served and registered again on the new connection, so it can still be unregistered afterwards.
+    ///
+    /// The error returning from this method is of [`BluezError::NoSystemBus`] or [`BluezError::Init`] variant, similar to [`BluezClient::new()`].
+    ///
+    /// [`BluezError::Init`]: crate::BluezError::Init
+    /// [`BluezError::NoSystemBus`]: crate::BluezError::NoSystemBus
+    /// [`BluezClient::new()`]: crate::BluezClient::new()
+    /// [`BluezClient.register_agent()`]: crate::BluezClient::register_agent()
+    pub fn refresh(&self) -> Result<(), Error> {
+        let connection = Connection::system().map_err(init_error)?;
+        let adapter_path = self.adapter_proxy.borrow().inner().path().clone();
+        let adapter_proxy = BluezAdapterProxy::builder(&connection)
+            .path(adapter_path)
+            .and_then(|builder| builder.build())
+            .map_err(Error::Init)?;
+
+        // NOTE: The stale connection is closed once it is replaced.
+        *self.connection.borrow_mut() = connection;
+        *self.adapter_proxy.borrow_mut() = adapter_proxy;
+        self.power_state_cache.clear();
+
+        let agent = self.agent.borrow().clone();
+        if let Some(agent) = agent {
+            self.serve_agent(agent).map_err(Error::Init)?;
+        }
+
+        Ok(())
+    }
+
+    fn with_refresh<T>(&self, call: impl FnMut() -> Result<T, Error>) -> Result<T, Error> {
+        retry_when_stale(call, || self.refresh())
+    }
+
+    /// Sets how long a power state read from Bluez D-Bus is re-used by [`BluezClient.power_state()`].
+    ///
+    /// Repeated reads within `ttl` do not hit Bluez D-Bus. The power state is cached for 200ms by default. Setting `ttl` to [`None`] disables the cache.
+    ///
+    /// [`BluezClient.power_state()`]: crate::BluezClient::power_state()
+    /// [`None`]: std::option::Option::None
+    pub fn set_power_state_ttl(&mut self, ttl: Option<Duration>) {
+        self.power_state_cache = TtlCache::new(ttl);
+    }
+
     fn dev_object_iter(&self) -> zbus::Result<impl Iterator<Item = OwnedObjectPath>> {
-        let object_manager_proxy = ObjectManagerProxy::new(&self.connection, "org.bluez", "/")?;
+        let object_manager_proxy =
+            ObjectManagerProxy::new(&self.connection.borrow(), "org.bluez", "/")?;
         let objects = object_manager_proxy.get_managed_objects()?;
 
         let dev_paths = objects.into_keys().filter(|k| {
@@ -206,19 +643,48 @@ impl BluezDBusClient {
 
     /// Provides the power state of the Bluetooth adapter.
     ///
+    /// If the adapter is off and Bluetooth is blocked by an rfkill switch (read from `/sys/class/rfkill`), then the power state is `Blocked` instead of `Off`.
+    ///
+    /// The power state is cached for a short while, see [`BluezClient.set_power_state_ttl()`].
+    ///
     /// It fails when the power state cannot be read from Bluez D-Bus.
     ///
     /// The error returning from this method is of [`BluezError::Process`] variant.
     ///
+    /// [`BluezClient.set_power_state_ttl()`]: crate::BluezClient::set_power_state_ttl()
     /// [`BluezError::Process`]: crate::BluezError::Process
     pub fn power_state(&self) -> Result<BluezPowerState, Error> {
-        let result = self
-            .adapter_proxy
-            .power_state()
-            .map(BluezPowerState::from)
-            .map_err(|e| Error::Process(String::from("power_state"), e))?;
+        self.with_refresh(move || {
+            let result = self.power_state_cache.get_or_read(|| {
+                self.adapter_proxy
+                    .borrow()
+                    .power_state()
+                    .map(BluezPowerState::from)
+                    .map(|power_state| match power_state {
+                        BluezPowerState::Off if self.rfkill_probe.is_blocked() => {
+                            BluezPowerState::Blocked
+                        }
+                        power_state => power_state,
+                    })
+                    .map_err(|e| Error::Process(String::from("power_state"), e))
+            })?;
+
+            Ok(result)
+        })
+    }
+
+    /// Indicates whether the Bluetooth adapter is powered on or not.
+    ///
+    /// It is a convenience over [`BluezClient.power_state()`], and it fails under the same conditions.
+    ///
+    /// The error returning from this method is of [`BluezError::Process`] variant.
+    ///
+    /// [`BluezClient.power_state()`]: crate::BluezClient::power_state()
+    /// [`BluezError::Process`]: crate::BluezError::Process
+    pub fn is_powered(&self) -> Result<bool, Error> {
+        let power_state = self.power_state()?;
 
-        Ok(result)
+        Ok(bool::from(&power_state))
     }
 
     /// Toggles the power state of the Bluetooth adapter.
@@ -232,238 +698,1424 @@ impl BluezDBusClient {
         let prev_state = self.power_state()?;
 
         let new_state = !prev_state;
-        self.adapter_proxy
-            .set_powered(bool::from(&new_state))
-            .map_err(|e| Error::Process(String::from("toggle_power_state"), e))?;
+        self.with_refresh(move || {
+            self.adapter_proxy
+                .borrow()
+                .set_powered(bool::from(&new_state))
+                .map_err(|e| Error::Process(String::from("toggle_power_state"), e))
+        })?;
+        self.power_state_cache.set(new_state);
 
         Ok(new_state)
     }
 
-    /// Provides the list of [`BluezDevice`]'s registered on the host.
-    ///
-    /// For the connected devices, each [`BluezDevice.battery()`] returns [`Some`].
-    /// For the non-connected devices, each [`BluezDevice.battery()`] returns [`None`].
-    /// For the devices that emit Bluetooth signals, each [`BluezDevice.rssi()`] returns [`Some`].
+    /// Sets the power state of the Bluetooth adapter.
     ///
-    /// It fails when the device object paths are cannot be read from Bluez D-Bus.
+    /// It fails when Bluez D-Bus fails to set the power state.
     ///
     /// The error returning from this method is of [`BluezError::Process`] variant.
     ///
-    /// [`BluezDevice`]: crate::BluezDevice
     /// [`BluezError::Process`]: crate::BluezError::Process
-    /// [`Some`]: std::option::Option::Some
-    /// [`None`]: std::option::Option::None
-    /// [`BluezDevice.battery()`]: crate::BluezDevice::battery()
-    /// [`BluezDevice.rssi()`]: crate::BluezDevice::rssi()
-    pub fn devices(&self) -> Result<Vec<BluezDevice>, Error> {
-        let dev_object_iter = self
-            .dev_object_iter()
-            .map_err(|e| Error::Process(String::from("devices"), e))?;
-
-        Ok(dev_object_iter
-            .filter_map(|dev_path| {
-                let dev_proxy = BluezDeviceProxy::new(&self.connection, &dev_path).ok()?;
-
-                let mut dev = BluezDevice {
-                    alias: dev_proxy.alias().ok()?,
-                    address: dev_proxy.address().ok()?,
-                    connected: dev_proxy.connected().ok()?,
-                    paired: dev_proxy.paired().ok()?,
-                    trusted: dev_proxy.trusted().ok()?,
-                    bonded: dev_proxy.bonded().ok()?,
-                    battery: None,
-                    rssi: None,
-                };
-
-                if let Ok(rssi) = dev_proxy.rssi() {
-                    dev.rssi = Some(rssi);
-                }
-
-                if !dev.connected {
-                    return Some(dev);
-                }
-
-                let battery_proxy =
-                    BluezDeviceBatteryProxy::new(&self.connection, &dev_path).ok()?;
-                dev.battery = Some(battery_proxy.percentage().ok()?);
+    pub fn set_powered(&self, power_state: BluezPowerState) -> Result<(), Error> {
+        self.with_refresh(move || {
+            self.adapter_proxy
+                .borrow()
+                .set_powered(bool::from(&power_state))
+                .map_err(|e| Error::Process(String::from("set_powered"), e))?;
+            self.power_state_cache.set(power_state);
 
-                Some(dev)
-            })
-            .collect::<Vec<BluezDevice>>())
+            Ok(())
+        })
     }
 
-    /// Connects to a Bluetooth device by it's alias.
+    /// Indicates whether the Bluetooth adapter is discoverable by the other devices or not.
     ///
-    /// It fails if a device cannot be found for the provided alias, or the Bluez D-Bus fails during the connection process.
+    /// It fails when the discoverable state cannot be read from Bluez D-Bus.
     ///
     /// The error returning from this method is of [`BluezError::Process`] variant.
     ///
     /// [`BluezError::Process`]: crate::BluezError::Process
-    pub fn connect(&self, alias: &str) -> Result<(), Error> {
-        let to_connect_err = |e: zbus::Error| Error::Process(String::from("connect"), e);
-
-        let dev_paths = self.dev_object_iter().map_err(to_connect_err)?;
-
-        for dev_path in dev_paths {
-            let dev_proxy =
-                BluezDeviceProxy::new(&self.connection, &dev_path).map_err(to_connect_err)?;
-
-            let dev_alias = dev_proxy.alias().map_err(to_connect_err)?;
-            if dev_alias == alias {
-                return dev_proxy.connect().map_err(to_connect_err);
-            }
-        }
-
-        Err(to_connect_err(zbus::Error::InterfaceNotFound))
+    pub fn discoverable(&self) -> Result<bool, Error> {
+        self.with_refresh(move || {
+            self.adapter_proxy
+                .borrow()
+                .discoverable()
+                .map_err(|e| Error::Process(String::from("discoverable"), e))
+        })
     }
 
-    /// Provides a list of connected [`BluezDevice`]'s.
+    /// Makes the Bluetooth adapter discoverable by the other devices, or hides it.
     ///
-    /// Each [`BluezDevice`] has their [`BluezDevice.battery()`] set to [`Some`].
+    /// Once the adapter is discoverable, it is hidden again by Bluez after the discoverable timeout, see [`BluezClient.set_discoverable_timeout()`].
     ///
-    /// The error returning from this method is of [`BluezError::Process`] variant. The error cases are the same with [`BluezClient::devices()`].
+    /// It fails when Bluez D-Bus fails to set the discoverable state, e.g. when the adapter is off.
     ///
-    /// [`BluezDevice`]: crate::BluezDevice
-    /// [`BluezClient::devices()`]: crate::BluezClient::devices()
+    /// The error returning from this method is of [`BluezError::Process`] variant.
+    ///
+    /// [`BluezClient.set_discoverable_timeout()`]: crate::BluezClient::set_discoverable_timeout()
     /// [`BluezError::Process`]: crate::BluezError::Process
-    /// [`Some`]: std::option::Option::Some
-    /// [`BluezDevice.battery()`]: crate::BluezDevice::battery()
-    pub fn connected_devices(&self) -> Result<Vec<BluezDevice>, Error> {
-        let devs = self.devices()?;
-
-        Ok(devs.into_iter().filter(|d| d.connected).collect())
+    pub fn set_discoverable(&self, discoverable: bool) -> Result<(), Error> {
+        self.with_refresh(move || {
+            self.adapter_proxy
+                .borrow()
+                .set_discoverable(discoverable)
+                .map_err(|e| Error::Process(String::from("set_discoverable"), e))
+        })
     }
 
-    /// Starts the device discovery to register available Bluetooth devices to the host.
-    ///
-    /// Only one discovery session can be alive at a time for each [`BluezClient`] instance.
-    ///
-    /// In order to stop the device discovery, use [`BluezClient.stop_discovery()`]. It is the caller's responsiblity to stop the device discovery.
+    /// Provides how long the Bluetooth adapter stays discoverable in seconds, where `0` means forever.
     ///
-    /// In order to get a list of scanned devices, use [`BluezClient.scanned_devices()`].    
+    /// It fails when the timeout cannot be read from Bluez D-Bus.
     ///
     /// The error returning from this method is of [`BluezError::Process`] variant.
     ///
-    /// [`BluezClient`]: crate::BluezClient
-    /// [`BluezClient.stop_discovery()`]: crate::BluezClient::stop_discovery()
-    /// [`BluezClient.scanned_devices()`]: crate::BluezClient::scanned_devices()
     /// [`BluezError::Process`]: crate::BluezError::Process
-    pub fn start_discovery(&self) -> Result<(), Error> {
-        self.adapter_proxy
-            .start_discovery()
-            .map_err(|e| Error::Process(String::from("start_disc"), e))
+    pub fn discoverable_timeout(&self) -> Result<u32, Error> {
+        self.with_refresh(move || {
+            self.adapter_proxy
+                .borrow()
+                .discoverable_timeout()
+                .map_err(|e| Error::Process(String::from("discoverable_timeout"), e))
+        })
     }
 
-    /// Stops the device discovery to remove registered available Bluetooth devices from the host and end the scanning process.
+    /// Sets how long the Bluetooth adapter stays discoverable in seconds, where `0` means forever.
     ///
-    /// In order to get a list of scanned devices, use [`BluezClient.scanned_devices()`]. It is recommended to get the list of scanned devices before calling this method.    
+    /// The timeout applies from the next time the adapter is made discoverable.
     ///
-    /// It fails when Bluez D-Bus fails to start the discovery.
+    /// It fails when Bluez D-Bus fails to set the timeout.
     ///
     /// The error returning from this method is of [`BluezError::Process`] variant.
     ///
-    /// [`BluezClient`]: crate::BluezClient
-    /// [`BluezClient.scanned_devices()`]: crate::BluezClient::scanned_devices()
     /// [`BluezError::Process`]: crate::BluezError::Process
-    pub fn stop_discovery(&self) -> Result<(), Error> {
-        self.adapter_proxy
-            .stop_discovery()
-            .map_err(|e| Error::Process(String::from("stop_disc"), e))
+    pub fn set_discoverable_timeout(&self, secs: u32) -> Result<(), Error> {
+        self.with_refresh(move || {
+            self.adapter_proxy
+                .borrow()
+                .set_discoverable_timeout(secs)
+                .map_err(|e| Error::Process(String::from("set_discoverable_timeout"), e))
+        })
     }
 
-    /// Returns a list of scanned [`BluezDevice`]'s. These devices are registered to the host during a device discovery session.
+    /// Provides the names of the Bluetooth adapters on the host, such as `hci0`.
     ///
-    /// Each [`BluezDevice`] has their [`BluezDevice.rssi()`] set to [`Some`].
+    /// Every object that implements `org.bluez.Adapter1` is an adapter, regardless of its path. The name of an adapter is the last segment of its path.
     ///
-    /// The error returning from this method is of [`BluezError::Process`] variant. The error cases are the same with [`BluezClient::devices()`].
+    /// It fails when the adapter objects cannot be read from Bluez D-Bus.
+    ///
+    /// The error returning from this method is of [`BluezError::Process`] variant.
     ///
-    /// [`BluezDevice`]: crate::BluezDevice
-    /// [`BluezDevice.rssi()`]: crate::BluezDevice::rssi()
-    /// [`BluezClient::devices()`]: crate::BluezClient::devices()
     /// [`BluezError::Process`]: crate::BluezError::Process
-    pub fn scanned_devices(&self) -> Result<Vec<BluezDevice>, Error> {
-        let devs = self.devices()?;
-        Ok(devs.into_iter().filter(|d| d.rssi.is_some()).collect())
+    pub fn adapters(&self) -> Result<Vec<String>, Error> {
+        self.with_refresh(move || {
+            let adapters = read_adapters(&self.connection.borrow())
+                .map_err(|e| Error::Process(String::from("adapters"), e))?;
+
+            Ok(adapters
+                .iter()
+                .map(|adapter| adapter.name().to_string())
+                .collect())
+        })
+    }
+
+    fn adapter_proxy(&self, adapter: &str) -> zbus::Result<BluezAdapterProxy<'static>> {
+        let path = read_adapters(&self.connection.borrow())?
+            .into_iter()
+            .find(|a| a.matches(adapter))
+            .map(|a| a.path)
+            .ok_or_else(|| zbus::Error::Failure(format!("no adapter matches '{}'", adapter)))?;
+
+        BluezAdapterProxy::builder(&self.connection.borrow())
+            .path(path)?
+            .build()
     }
 
-    /// Removes a Bluetooth device from the known device list on the host by it's alias.
+    /// Selects the Bluetooth adapter that is used by the rest of the [`BluezClient`] methods.
     ///
-    /// It fails if a device cannot be found for the provided alias, or if Bluez D-Bus fails to remove.
+    /// `adapter` is either the name of the adapter (e.g. `hci1`) or its MAC address. By default, `hci0` is used if it exists, otherwise the first adapter by name.
+    ///
+    /// It fails when no adapter matches `adapter`, or the adapters cannot be read from Bluez D-Bus.
     ///
     /// The error returning from this method is of [`BluezError::Process`] variant.
     ///
+    /// [`BluezClient`]: crate::BluezClient
     /// [`BluezError::Process`]: crate::BluezError::Process
-    pub fn remove(&self, alias: &str) -> Result<(), Error> {
-        let to_remove_err = |e: zbus::Error| Error::Process(String::from("remove"), e);
-
-        let mut dev_object_iter = self.dev_object_iter().map_err(to_remove_err)?;
-
-        let dev_object = dev_object_iter.find_map(|obj| {
-            let dev_object = obj.into_inner();
-            let dev_proxy = BluezDeviceProxy::new(&self.connection, &dev_object).ok()?;
+    pub fn select_adapter(&mut self, adapter: &str) -> Result<(), Error> {
+        let adapter_proxy = self
+            .adapter_proxy(adapter)
+            .map_err(|e| Error::Process(String::from("select_adapter"), e))?;
+        self.adapter_proxy.replace(adapter_proxy);
+        self.power_state_cache.clear();
+
+        Ok(())
+    }
 
-            if alias == dev_proxy.alias().ok()? {
-                Some(dev_object)
-            } else {
-                None
-            }
-        });
+    /// Provides the name of the Bluetooth adapter that is selected by [`BluezClient.select_adapter()`], e.g. `hci0`.
+    ///
+    /// [`BluezClient.select_adapter()`]: crate::BluezClient::select_adapter()
+    pub fn selected_adapter(&self) -> String {
+        let adapter_proxy = self.adapter_proxy.borrow();
+        let path = adapter_proxy.inner().path();
 
-        if let Some(dev_object) = dev_object {
-            self.adapter_proxy
-                .remove_device(dev_object)
-                .map_err(to_remove_err)
-        } else {
-            Err(to_remove_err(zbus::Error::InterfaceNotFound))
-        }
+        path.rsplit('/').next().unwrap_or_default().to_string()
     }
 
-    /// Disconnects a Bluetooth device from the host by it's alias.
+    /// Provides the power state of the Bluetooth adapter named `adapter`.
+    ///
+    /// Unlike [`BluezClient.power_state()`], the power state is not cached.
     ///
-    /// It fails if a device cannot be found for the provided alias, or if Bluez D-Bus fails to disconnect.
+    /// It fails when the power state cannot be read from Bluez D-Bus.
     ///
     /// The error returning from this method is of [`BluezError::Process`] variant.
     ///
+    /// [`BluezClient.power_state()`]: crate::BluezClient::power_state()
     /// [`BluezError::Process`]: crate::BluezError::Process
-    pub fn disconnect(&self, alias: &str) -> Result<(), Error> {
-        let to_disconnect_err = |e: zbus::Error| Error::Process(String::from("disconnect"), e);
-
-        let mut dev_object_iter = self.dev_object_iter().map_err(to_disconnect_err)?;
-
-        let dev_proxy = dev_object_iter.find_map(|obj| {
-            let dev_object = obj.into_inner();
-            let dev_proxy = BluezDeviceProxy::new(&self.connection, &dev_object).ok()?;
+    pub fn adapter_power_state(&self, adapter: &str) -> Result<BluezPowerState, Error> {
+        self.with_refresh(move || {
+            self.adapter_proxy(adapter)
+                .and_then(|adapter_proxy| adapter_proxy.power_state())
+                .map(BluezPowerState::from)
+                .map_err(|e| Error::Process(String::from("adapter_power_state"), e))
+        })
+    }
 
-            if alias == dev_proxy.alias().ok()? {
-                Some(dev_proxy)
-            } else {
-                None
+    /// Sets the power state of the Bluetooth adapter named `adapter`.
+    ///
+    /// It fails when Bluez D-Bus fails to set the power state.
+    ///
+    /// The error returning from this method is of [`BluezError::Process`] variant.
+    ///
+    /// [`BluezError::Process`]: crate::BluezError::Process
+    pub fn set_adapter_power_state(
+        &self,
+        adapter: &str,
+        power_state: BluezPowerState,
+    ) -> Result<(), Error> {
+        self.with_refresh(move || {
+            let adapter_proxy = self
+                .adapter_proxy(adapter)
+                .map_err(|e| Error::Process(String::from("set_adapter_power_state"), e))?;
+
+            adapter_proxy
+                .set_powered(bool::from(&power_state))
+                .map_err(|e| Error::Process(String::from("set_adapter_power_state"), e))?;
+
+            if adapter_proxy.inner().path() == self.adapter_proxy.borrow().inner().path() {
+                self.power_state_cache.set(power_state);
             }
-        });
 
-        if let Some(dev_proxy) = dev_proxy {
-            dev_proxy.disconnect().map_err(to_disconnect_err)
-        } else {
-            Err(to_disconnect_err(zbus::Error::InterfaceNotFound))
+            Ok(())
+        })
+    }
+
+    /// Provides the list of [`BluezDevice`]'s registered on the host.
+    ///
+    /// For the connected devices, each [`BluezDevice.battery()`] returns [`Some`] if the battery can be read.
+    /// For the non-connected devices, each [`BluezDevice.battery()`] returns [`None`].
+    /// For the devices that emit Bluetooth signals, each [`BluezDevice.rssi()`] returns [`Some`].
+    ///
+    /// It fails when the device object paths are cannot be read from Bluez D-Bus.
+    ///
+    /// The error returning from this method is of [`BluezError::Process`] variant.
+    ///
+    /// [`BluezDevice`]: crate::BluezDevice
+    /// [`BluezError::Process`]: crate::BluezError::Process
+    /// [`Some`]: std::option::Option::Some
+    /// [`None`]: std::option::Option::None
+    /// [`BluezDevice.battery()`]: crate::BluezDevice::battery()
+    /// [`BluezDevice.rssi()`]: crate::BluezDevice::rssi()
+    pub fn devices(&self) -> Result<Vec<BluezDevice>, Error> {
+        self.devices_with_unread().map(|(devices, _)| devices)
+    }
+
+    /// Provides the same list as [`BluezClient.devices()`], along with the number of devices that could not be fully read.
+    ///
+    /// [`BluezClient.devices()`] skips such devices silently. This method allows the callers to surface them.
+    ///
+    /// It fails under the same conditions as [`BluezClient.devices()`].
+    ///
+    /// [`BluezClient.devices()`]: crate::BluezClient::devices()
+    pub fn devices_with_unread(&self) -> Result<(Vec<BluezDevice>, usize), Error> {
+        let mut unread = 0;
+        let devices = self
+            .devices_iter()?
+            .filter_map(|dev| {
+                if dev.is_none() {
+                    unread += 1;
+                }
+
+                dev
+            })
+            .collect::<Vec<BluezDevice>>();
+
+        Ok((devices, unread))
+    }
+
+    /// Provides the devices of [`BluezClient.devices()`] lazily, one at a time.
+    ///
+    /// The device object paths are read from Bluez D-Bus up front, but the properties of each device are only read when the iterator reaches it. This allows the callers to stop early, e.g. after a window of devices, without reading the rest.
+    ///
+    /// Each item is [`None`] if that device could not be fully read, which is the device [`BluezClient.devices()`] skips.
+    ///
+    /// It fails under the same conditions as [`BluezClient.devices()`].
+    ///
+    /// [`BluezClient.devices()`]: crate::BluezClient::devices()
+    /// [`None`]: std::option::Option::None
+    pub fn devices_iter(&self) -> Result<impl Iterator<Item = Option<BluezDevice>> + '_, Error> {
+        self.with_refresh(move || {
+            let dev_object_iter = self
+                .dev_object_iter()
+                .map_err(|e| Error::Process(String::from("devices"), e))?;
+
+            Ok(dev_object_iter.map(|dev_path| self.read_device(&dev_path)))
+        })
+    }
+
+    fn read_device(&self, dev_path: &OwnedObjectPath) -> Option<BluezDevice> {
+        let dev_proxy = BluezDeviceProxy::new(&self.connection.borrow(), dev_path).ok()?;
+
+        let mut dev = BluezDevice {
+            alias: dev_proxy.alias().ok()?,
+            address: dev_proxy.address().ok()?,
+            adapter: adapter_name(dev_path.as_str()),
+            connected: dev_proxy.connected().ok()?,
+            paired: dev_proxy.paired().ok()?,
+            trusted: dev_proxy.trusted().ok()?,
+            bonded: dev_proxy.bonded().ok()?,
+            blocked: dev_proxy.blocked().unwrap_or_default(),
+            battery: None,
+            rssi: None,
+            tx_power: dev_proxy.tx_power().ok(),
+            uuids: dev_proxy.uuids().unwrap_or_default(),
+            // NOTE: If ServicesResolved cannot be read, the device is not reported as connecting.
+            services_resolved: dev_proxy.services_resolved().unwrap_or(true),
+            manufacturer_data: dev_proxy.manufacturer_data().ok().map(|data| {
+                data.into_iter()
+                    .filter_map(|(company_id, bytes)| Some((company_id, bytes.try_into().ok()?)))
+                    .collect()
+            }),
+        };
+
+        if let Ok(rssi) = dev_proxy.rssi() {
+            dev.rssi = Some(rssi);
+        }
+
+        if !dev.connected {
+            return Some(dev);
+        }
+
+        // NOTE: Not every connected device exposes its battery, and the ones
+        // that do may fail to report it. Then, the battery is unknown, which
+        // is not a reason to hide the device.
+        dev.battery = BluezDeviceBatteryProxy::new(&self.connection.borrow(), dev_path)
+            .and_then(|battery_proxy| battery_proxy.percentage())
+            .ok();
+
+        Some(dev)
+    }
+
+    /// Provides the RSSI of a single Bluetooth device by it's alias or address.
+    ///
+    /// It is cheaper than [`BluezClient.scanned_devices()`] since only the RSSI of the matching device is read.
+    /// The returned value is [`None`] if the device does not emit Bluetooth signals at the moment. RSSI values are only updated during a device discovery.
+    ///
+    /// It fails if a device cannot be found for the provided alias or address, or the device object paths cannot be read from Bluez D-Bus.
+    ///
+    /// The error returning from this method is of [`BluezError::Process`] variant.
+    ///
+    /// [`BluezClient.scanned_devices()`]: crate::BluezClient::scanned_devices()
+    /// [`BluezError::Process`]: crate::BluezError::Process
+    /// [`None`]: std::option::Option::None
+    pub fn rssi(&self, alias: &str) -> Result<Option<i16>, Error> {
+        self.with_refresh(move || {
+            self.device_proxy(alias)
+                .map(|dev_proxy| dev_proxy.rssi().ok())
+                .map_err(|e| Error::Process(String::from("rssi"), e))
+        })
+    }
+
+    /// Connects to a Bluetooth device by it's alias or address.
+    ///
+    /// The address is matched case-insensitively. Connecting by address is unambiguous, even if more than one device shares the same alias.
+    ///
+    /// It fails if a device cannot be found for the provided alias or address, or the Bluez D-Bus fails during the connection process.
+    ///
+    /// Unlike the other calls, a connection is not retried once Bluez D-Bus is stale, so that the callers can decide how many times a connection is attempted. Please see [`BluezError::is_stale()`].
+    ///
+    /// The error returning from this method is of [`BluezError::Process`] variant.
+    ///
+    /// [`BluezError::Process`]: crate::BluezError::Process
+    /// [`BluezError::is_stale()`]: crate::BluezError::is_stale()
+    pub fn connect(&self, alias: &str) -> Result<(), Error> {
+        self.device_proxy(alias)
+            .and_then(|dev_proxy| dev_proxy.connect())
+            .map_err(|e| Error::Process(String::from("connect"), e))
+    }
+
+    /// Connects the profile with `uuid` of a Bluetooth device by it's alias or address, e.g. to prefer A2DP for a headset.
+    ///
+    /// The device is looked up the same way as [`BluezClient.connect()`], and it is not retried once Bluez D-Bus is stale either.
+    ///
+    /// It fails if a device cannot be found for the provided alias or address, or the Bluez D-Bus fails during the connection process, e.g. when the device does not support the profile.
+    ///
+    /// The error returning from this method is of [`BluezError::Process`] variant.
+    ///
+    /// [`BluezClient.connect()`]: crate::BluezClient::connect()
+    /// [`BluezError::Process`]: crate::BluezError::Process
+    pub fn connect_profile(&self, alias: &str, uuid: &str) -> Result<(), Error> {
+        self.device_proxy(alias)
+            .and_then(|dev_proxy| dev_proxy.connect_profile(uuid))
+            .map_err(|e| Error::Process(String::from("connect_profile"), e))
+    }
+
+    /// Connects to the Bluetooth device with `address` through `adapter`, regardless of the selected adapter.
+    ///
+    /// A device discovered by more than one adapter is registered to each of them, see [`BluezDevice.adapter()`]. This method connects the one that is registered to `adapter`.
+    ///
+    /// It fails if no adapter matches `adapter`, the device is not registered to it, or the Bluez D-Bus fails during the connection process. Similar to [`BluezClient.connect()`], it is not retried once Bluez D-Bus is stale.
+    ///
+    /// The error returning from this method is of [`BluezError::Process`] variant.
+    ///
+    /// [`BluezDevice.adapter()`]: crate::BluezDevice::adapter()
+    /// [`BluezClient.connect()`]: crate::BluezClient::connect()
+    /// [`BluezError::Process`]: crate::BluezError::Process
+    pub fn connect_on(&self, adapter: &str, address: &str) -> Result<(), Error> {
+        self.adapter_proxy(adapter)
+            .and_then(|adapter_proxy| {
+                let dev_path = format!(
+                    "{}/dev_{}",
+                    adapter_proxy.inner().path(),
+                    address.to_uppercase().replace(':', "_")
+                );
+
+                BluezDeviceProxy::new(&self.connection.borrow(), dev_path)
+            })
+            .and_then(|dev_proxy| dev_proxy.connect())
+            .map_err(|e| Error::Process(String::from("connect_on"), e))
+    }
+
+    /// Finds the device proxy whose alias or address matches `alias`.
+    fn device_proxy(&self, alias: &str) -> zbus::Result<BluezDeviceProxy<'_>> {
+        for dev_path in self.dev_object_iter()? {
+            let dev_proxy = BluezDeviceProxy::new(&self.connection.borrow(), dev_path)?;
+
+            if dev_proxy.alias()? == alias || dev_proxy.address()?.eq_ignore_ascii_case(alias) {
+                return Ok(dev_proxy);
+            }
         }
+
+        Err(zbus::Error::InterfaceNotFound)
+    }
+
+    /// Waits until the Bluetooth device with `address` appears in the object tree of Bluez, which happens when a device discovery finds it.
+    ///
+    /// The wait is driven by the `InterfacesAdded` signals of Bluez, which are filtered by the object path of the device. It returns immediately if the device is already known.
+    /// It does not start a device discovery, see [`BluezClient.start_discovery()`].
+    ///
+    /// It fails if the device does not appear in `timeout`, or the signals cannot be received from Bluez D-Bus.
+    ///
+    /// The error returning from this method is of [`BluezError::Process`] variant.
+    ///
+    /// [`BluezClient.start_discovery()`]: crate::BluezClient::start_discovery()
+    /// [`BluezError::Process`]: crate::BluezError::Process
+    pub fn wait_for_device(&self, address: &str, timeout: Duration) -> Result<(), Error> {
+        self.with_refresh(move || {
+            let to_wait_err = |e: zbus::Error| Error::Process(String::from("wait_for_device"), e);
+
+            let dev_path = format!(
+                "{}/dev_{}",
+                self.adapter_proxy.borrow().inner().path(),
+                address.to_uppercase().replace(':', "_")
+            );
+
+            let connection = self.connection.borrow().inner().clone();
+
+            // NOTE: The signals are received with a timeout on the same
+            // runtime that subscribes to them, so that nothing keeps waiting
+            // for them once the timeout has passed.
+            let is_added = zbus::block_on(async {
+                let object_manager_proxy =
+                    zbus::fdo::ObjectManagerProxy::new(&connection, "org.bluez", "/").await?;
+
+                // NOTE: The signals are subscribed before the object tree is read,
+                // so that a device added in between is not missed.
+                let mut interfaces_added = object_manager_proxy.receive_interfaces_added().await?;
+
+                if object_manager_proxy
+                    .get_managed_objects()
+                    .await?
+                    .keys()
+                    .any(|p| p.as_str() == dev_path)
+                {
+                    return Ok(true);
+                }
+
+                let is_added = interfaces_added.any(|signal| {
+                    signal
+                        .args()
+                        .is_ok_and(|args| args.object_path().as_str() == dev_path)
+                });
+
+                Ok(tokio::time::timeout(timeout, is_added)
+                    .await
+                    .unwrap_or(false))
+            })
+            .map_err(to_wait_err)?;
+
+            match is_added {
+                true => Ok(()),
+                false => Err(to_wait_err(zbus::Error::InterfaceNotFound)),
+            }
+        })
+    }
+
+    /// Serves the given `BluezAgent` on the system bus and registers it to Bluez.
+    ///
+    /// While the agent is registered, Bluez sends the authentication requests (such as PIN code requests of legacy devices) to it instead of prompting.
+    /// It is the caller's responsibility to unregister the agent via [`BluezClient.unregister_agent()`].
+    ///
+    /// The error returning from this method is of [`BluezError::Process`] variant.
+    ///
+    /// [`BluezClient.unregister_agent()`]: crate::BluezClient::unregister_agent()
+    /// [`BluezError::Process`]: crate::BluezError::Process
+    pub fn register_agent(&self, agent: BluezAgent) -> Result<(), Error> {
+        self.serve_agent(agent.clone())
+            .map_err(|e| Error::Process(String::from("register_agent"), e))?;
+        *self.agent.borrow_mut() = Some(agent);
+
+        Ok(())
+    }
+
+    fn serve_agent(&self, agent: BluezAgent) -> zbus::Result<()> {
+        let capability = agent.capability();
+
+        self.connection
+            .borrow()
+            .object_server()
+            .at(AGENT_PATH, agent)?;
+
+        let agent_manager_proxy = BluezAgentManagerProxy::new(&self.connection.borrow())?;
+        agent_manager_proxy.register_agent(
+            &ObjectPath::from_static_str_unchecked(AGENT_PATH),
+            capability,
+        )
+    }
+
+    /// Unregisters the `BluezAgent` registered by [`BluezClient.register_agent()`] and stops serving it.
+    ///
+    /// The error returning from this method is of [`BluezError::Process`] variant.
+    ///
+    /// [`BluezClient.register_agent()`]: crate::BluezClient::register_agent()
+    /// [`BluezError::Process`]: crate::BluezError::Process
+    pub fn unregister_agent(&self) -> Result<(), Error> {
+        let to_unregister_err =
+            |e: zbus::Error| Error::Process(String::from("unregister_agent"), e);
+
+        let agent_manager_proxy =
+            BluezAgentManagerProxy::new(&self.connection.borrow()).map_err(to_unregister_err)?;
+        agent_manager_proxy
+            .unregister_agent(&ObjectPath::from_static_str_unchecked(AGENT_PATH))
+            .map_err(to_unregister_err)?;
+
+        self.connection
+            .borrow()
+            .object_server()
+            .remove::<BluezAgent, _>(AGENT_PATH)
+            .map_err(to_unregister_err)?;
+        *self.agent.borrow_mut() = None;
+
+        Ok(())
+    }
+
+    /// Provides a list of connected [`BluezDevice`]'s.
+    ///
+    /// Each [`BluezDevice`] has their [`BluezDevice.battery()`] set to [`Some`].
+    ///
+    /// Only the `Connected` property of each device is read up front. The other properties, including the battery, are read only for the connected devices, so the disconnected devices are never touched. This keeps the call cheap on hosts with many known devices.
+    ///
+    /// The error returning from this method is of [`BluezError::Process`] variant. The error cases are the same with [`BluezClient::devices()`].
+    ///
+    /// [`BluezDevice`]: crate::BluezDevice
+    /// [`BluezClient::devices()`]: crate::BluezClient::devices()
+    /// [`BluezError::Process`]: crate::BluezError::Process
+    /// [`Some`]: std::option::Option::Some
+    /// [`BluezDevice.battery()`]: crate::BluezDevice::battery()
+    pub fn connected_devices(&self) -> Result<Vec<BluezDevice>, Error> {
+        self.with_refresh(move || {
+            let dev_object_iter = self
+                .dev_object_iter()
+                .map_err(|e| Error::Process(String::from("connected_devices"), e))?;
+
+            Ok(dev_object_iter
+                .filter(|dev_path| self.is_connected_at(dev_path))
+                .filter_map(|dev_path| self.read_device(&dev_path))
+                .collect())
+        })
+    }
+
+    /// Indicates whether the device at `dev_path` is connected.
+    ///
+    /// The properties of the device are not cached, so that only the `Connected` property is read from Bluez D-Bus. A device whose `Connected` property cannot be read is not considered connected.
+    fn is_connected_at(&self, dev_path: &OwnedObjectPath) -> bool {
+        BluezDeviceProxy::builder(&self.connection.borrow())
+            .path(dev_path)
+            .and_then(|builder| builder.cache_properties(CacheProperties::No).build())
+            .and_then(|dev_proxy| dev_proxy.connected())
+            .unwrap_or(false)
+    }
+
+    /// Indicates whether the device with `alias` (or address) is connected, by reading its `Connected` property from Bluez D-Bus without caching.
+    ///
+    /// A device that cannot be found, e.g. because it is removed, is not connected.
+    ///
+    /// It fails when the device object paths cannot be read from Bluez D-Bus.
+    ///
+    /// The error returning from this method is of [`BluezError::Process`] variant.
+    ///
+    /// [`BluezError::Process`]: crate::BluezError::Process
+    pub fn is_connected(&self, alias: &str) -> Result<bool, Error> {
+        self.with_refresh(move || {
+            let mut dev_object_iter = self
+                .dev_object_iter()
+                .map_err(|e| Error::Process(String::from("is_connected"), e))?;
+
+            let dev_path = dev_object_iter.find(|dev_path| {
+                BluezDeviceProxy::new(&self.connection.borrow(), dev_path)
+                    .and_then(|dev_proxy| {
+                        Ok(dev_proxy.alias()? == alias
+                            || dev_proxy.address()?.eq_ignore_ascii_case(alias))
+                    })
+                    .unwrap_or(false)
+            });
+
+            Ok(dev_path.is_some_and(|dev_path| self.is_connected_at(&dev_path)))
+        })
+    }
+
+    /// Blocks until the device with `alias` (or address) disconnects, which is detected by the `PropertiesChanged` signals of its `Connected` property.
+    ///
+    /// It returns `true` immediately if the device is already disconnected. It returns `false` if the signals end before a disconnection, e.g. when the connection to Bluez D-Bus is closed.
+    ///
+    /// It fails if a device cannot be found for the provided alias or address, or its `Connected` property cannot be read.
+    ///
+    /// The error returning from this method is of [`BluezError::Process`] variant.
+    ///
+    /// [`BluezError::Process`]: crate::BluezError::Process
+    pub fn wait_for_disconnect(&self, alias: &str) -> Result<bool, Error> {
+        self.with_refresh(move || {
+            let to_wait_err =
+                |e: zbus::Error| Error::Process(String::from("wait_for_disconnect"), e);
+
+            let dev_proxy = self.device_proxy(alias).map_err(to_wait_err)?;
+
+            // NOTE: The signals are subscribed before the property is read,
+            // so that a disconnection in between is not missed.
+            let connected_changes = dev_proxy.receive_connected_changed();
+            if !dev_proxy.connected().map_err(to_wait_err)? {
+                return Ok(true);
+            }
+
+            for change in connected_changes {
+                if !change.get().map_err(to_wait_err)? {
+                    return Ok(true);
+                }
+            }
+
+            Ok(false)
+        })
+    }
+
+    /// Starts the device discovery to register available Bluetooth devices to the host.
+    ///
+    /// Only one discovery session can be alive at a time for each [`BluezClient`] instance.
+    ///
+    /// In order to stop the device discovery, use [`BluezClient.stop_discovery()`]. It is the caller's responsiblity to stop the device discovery.
+    ///
+    /// In order to get a list of scanned devices, use [`BluezClient.scanned_devices()`].    
+    ///
+    /// If the adapter is not ready yet (e.g. right after it is powered on), then starting the discovery is retried a few times with a short delay before giving up. Other errors are returned immediately.
+    ///
+    /// The error returning from this method is of [`BluezError::Process`] variant.
+    ///
+    /// [`BluezClient`]: crate::BluezClient
+    /// [`BluezClient.stop_discovery()`]: crate::BluezClient::stop_discovery()
+    /// [`BluezClient.scanned_devices()`]: crate::BluezClient::scanned_devices()
+    /// [`BluezError::Process`]: crate::BluezError::Process
+    pub fn start_discovery(&self) -> Result<(), Error> {
+        self.with_refresh(move || {
+            retry_when_not_ready(DISCOVERY_RETRY_DELAY, || {
+                self.adapter_proxy.borrow().start_discovery()
+            })
+            .map_err(|e| Error::Process(String::from("start_disc"), e))
+        })
+    }
+
+    /// Indicates whether the Bluetooth adapter is discovering the devices or not, e.g. because another program (such as a GUI) has already started the discovery.
+    ///
+    /// It fails when the discovering state cannot be read from Bluez D-Bus.
+    ///
+    /// The error returning from this method is of [`BluezError::Process`] variant.
+    ///
+    /// [`BluezError::Process`]: crate::BluezError::Process
+    pub fn is_discovering(&self) -> Result<bool, Error> {
+        self.with_refresh(move || {
+            self.adapter_proxy
+                .borrow()
+                .discovering()
+                .map_err(|e| Error::Process(String::from("discovering"), e))
+        })
+    }
+
+    /// Stops the device discovery to remove registered available Bluetooth devices from the host and end the scanning process.
+    ///
+    /// In order to get a list of scanned devices, use [`BluezClient.scanned_devices()`]. It is recommended to get the list of scanned devices before calling this method.    
+    ///
+    /// It fails when Bluez D-Bus fails to start the discovery.
+    ///
+    /// The error returning from this method is of [`BluezError::Process`] variant.
+    ///
+    /// [`BluezClient`]: crate::BluezClient
+    /// [`BluezClient.scanned_devices()`]: crate::BluezClient::scanned_devices()
+    /// [`BluezError::Process`]: crate::BluezError::Process
+    pub fn stop_discovery(&self) -> Result<(), Error> {
+        self.with_refresh(move || {
+            self.adapter_proxy
+                .borrow()
+                .stop_discovery()
+                .map_err(|e| Error::Process(String::from("stop_disc"), e))
+        })
+    }
+
+    /// Starts the device discovery on `adapter` instead of the selected adapter, e.g. to discover the devices on every adapter of the host.
+    ///
+    /// `adapter` is either the name of the adapter (e.g. `hci1`) or its MAC address. Similar to [`BluezClient.start_discovery()`], it is retried a few times if the adapter is not ready yet, and it is the caller's responsibility to stop the device discovery by [`BluezClient.stop_discovery_on()`].
+    ///
+    /// It fails when no adapter matches `adapter`, or Bluez D-Bus fails to start the discovery.
+    ///
+    /// The error returning from this method is of [`BluezError::Process`] variant.
+    ///
+    /// [`BluezClient.start_discovery()`]: crate::BluezClient::start_discovery()
+    /// [`BluezClient.stop_discovery_on()`]: crate::BluezClient::stop_discovery_on()
+    /// [`BluezError::Process`]: crate::BluezError::Process
+    pub fn start_discovery_on(&self, adapter: &str) -> Result<(), Error> {
+        self.with_refresh(move || {
+            let adapter_proxy = self
+                .adapter_proxy(adapter)
+                .map_err(|e| Error::Process(String::from("start_disc"), e))?;
+
+            retry_when_not_ready(DISCOVERY_RETRY_DELAY, || adapter_proxy.start_discovery())
+                .map_err(|e| Error::Process(String::from("start_disc"), e))
+        })
+    }
+
+    /// Stops the device discovery on `adapter`, which is started by [`BluezClient.start_discovery_on()`].
+    ///
+    /// It fails when no adapter matches `adapter`, or Bluez D-Bus fails to stop the discovery.
+    ///
+    /// The error returning from this method is of [`BluezError::Process`] variant.
+    ///
+    /// [`BluezClient.start_discovery_on()`]: crate::BluezClient::start_discovery_on()
+    /// [`BluezError::Process`]: crate::BluezError::Process
+    pub fn stop_discovery_on(&self, adapter: &str) -> Result<(), Error> {
+        self.with_refresh(move || {
+            self.adapter_proxy(adapter)
+                .and_then(|adapter_proxy| adapter_proxy.stop_discovery())
+                .map_err(|e| Error::Process(String::from("stop_disc"), e))
+        })
+    }
+
+    /// Returns a list of scanned [`BluezDevice`]'s. These devices are registered to the host during a device discovery session.
+    ///
+    /// Each [`BluezDevice`] has their [`BluezDevice.rssi()`] set to [`Some`].
+    ///
+    /// The error returning from this method is of [`BluezError::Process`] variant. The error cases are the same with [`BluezClient::devices()`].
+    ///
+    /// [`BluezDevice`]: crate::BluezDevice
+    /// [`BluezDevice.rssi()`]: crate::BluezDevice::rssi()
+    /// [`BluezClient::devices()`]: crate::BluezClient::devices()
+    /// [`BluezError::Process`]: crate::BluezError::Process
+    pub fn scanned_devices(&self) -> Result<Vec<BluezDevice>, Error> {
+        let devs = self.devices()?;
+        Ok(devs.into_iter().filter(|d| d.rssi.is_some()).collect())
+    }
+
+    /// Provides the same list as [`BluezClient.scanned_devices()`], along with the number of devices that could not be fully read.
+    ///
+    /// It fails under the same conditions as [`BluezClient.scanned_devices()`].
+    ///
+    /// [`BluezClient.scanned_devices()`]: crate::BluezClient::scanned_devices()
+    pub fn scanned_devices_with_unread(&self) -> Result<(Vec<BluezDevice>, usize), Error> {
+        let (devs, unread) = self.devices_with_unread()?;
+        Ok((
+            devs.into_iter().filter(|d| d.rssi.is_some()).collect(),
+            unread,
+        ))
+    }
+
+    /// Removes a Bluetooth device from the known device list on the host by it's alias or address.
+    ///
+    /// It fails if a device cannot be found for the provided alias or address, or if Bluez D-Bus fails to remove.
+    ///
+    /// The error returning from this method is of [`BluezError::Process`] variant.
+    ///
+    /// [`BluezError::Process`]: crate::BluezError::Process
+    pub fn remove(&self, alias: &str) -> Result<(), Error> {
+        self.with_refresh(move || {
+            let to_remove_err = |e: zbus::Error| Error::Process(String::from("remove"), e);
+
+            let mut dev_object_iter = self.dev_object_iter().map_err(to_remove_err)?;
+
+            let dev_object = dev_object_iter.find_map(|obj| {
+                let dev_object = obj.into_inner();
+                let dev_proxy =
+                    BluezDeviceProxy::new(&self.connection.borrow(), &dev_object).ok()?;
+
+                if alias == dev_proxy.alias().ok()?
+                    || dev_proxy.address().ok()?.eq_ignore_ascii_case(alias)
+                {
+                    Some(dev_object)
+                } else {
+                    None
+                }
+            });
+
+            if let Some(dev_object) = dev_object {
+                self.adapter_proxy
+                    .borrow()
+                    .remove_device(dev_object)
+                    .map_err(to_remove_err)
+            } else {
+                Err(to_remove_err(zbus::Error::InterfaceNotFound))
+            }
+        })
+    }
+
+    /// Disconnects a Bluetooth device from the host by it's alias or address.
+    ///
+    /// It fails if a device cannot be found for the provided alias or address, or if Bluez D-Bus fails to disconnect.
+    ///
+    /// The error returning from this method is of [`BluezError::Process`] variant.
+    ///
+    /// [`BluezError::Process`]: crate::BluezError::Process
+    pub fn disconnect(&self, alias: &str) -> Result<(), Error> {
+        self.with_refresh(move || {
+            let to_disconnect_err = |e: zbus::Error| Error::Process(String::from("disconnect"), e);
+
+            let mut dev_object_iter = self.dev_object_iter().map_err(to_disconnect_err)?;
+
+            let dev_proxy = dev_object_iter.find_map(|obj| {
+                let dev_object = obj.into_inner();
+                let dev_proxy =
+                    BluezDeviceProxy::new(&self.connection.borrow(), &dev_object).ok()?;
+
+                if alias == dev_proxy.alias().ok()?
+                    || dev_proxy.address().ok()?.eq_ignore_ascii_case(alias)
+                {
+                    Some(dev_proxy)
+                } else {
+                    None
+                }
+            });
+
+            if let Some(dev_proxy) = dev_proxy {
+                dev_proxy.disconnect().map_err(to_disconnect_err)
+            } else {
+                Err(to_disconnect_err(zbus::Error::InterfaceNotFound))
+            }
+        })
+    }
+
+    /// Trusts or untrusts a Bluetooth device by it's alias or address. Bluez reconnects to a trusted device on its own, e.g. when the device is turned on.
+    ///
+    /// It fails if a device cannot be found for the provided alias or address, or if Bluez D-Bus fails to set the trust.
+    ///
+    /// The error returning from this method is of [`BluezError::Process`] variant.
+    ///
+    /// [`BluezError::Process`]: crate::BluezError::Process
+    pub fn set_trusted(&self, alias: &str, trusted: bool) -> Result<(), Error> {
+        self.with_refresh(move || {
+            self.device_proxy(alias)
+                .and_then(|dev_proxy| dev_proxy.set_trusted(trusted))
+                .map_err(|e| Error::Process(String::from("set_trusted"), e))
+        })
+    }
+}
+
+/// The number of attempts of a call that fails because the adapter is not ready.
+const NOT_READY_ATTEMPTS: usize = 3;
+
+/// The delay between the attempts of [`retry_when_not_ready`] on the actual adapter.
+const DISCOVERY_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// The names of the Bluez D-Bus errors that are returned while the adapter is still busy or initializing. They may go away when the call is retried.
+const NOT_READY_ERROR_NAMES: [&str; 2] = ["org.bluez.Error.InProgress", "org.bluez.Error.NotReady"];
+
+/// Indicates whether a Bluez D-Bus call failed because the adapter is still busy or initializing.
+fn is_not_ready(error: &zbus::Error) -> bool {
+    matches!(
+        error,
+        zbus::Error::MethodError(name, _, _) if NOT_READY_ERROR_NAMES.contains(&name.as_str())
+    )
+}
+
+/// Calls `op` until it succeeds, or fails with an error other than a not-ready error, up to [`NOT_READY_ATTEMPTS`] times.
+fn retry_when_not_ready<T>(
+    delay: Duration,
+    mut op: impl FnMut() -> zbus::Result<T>,
+) -> zbus::Result<T> {
+    let mut attempts = 1;
+    loop {
+        match op() {
+            Err(e) if is_not_ready(&e) && attempts < NOT_READY_ATTEMPTS => {
+                attempts += 1;
+                thread::sleep(delay);
+            }
+            result => return result,
+        }
+    }
+}
+
+/// The names of the D-Bus errors that are returned while bluetoothd is not on the bus, e.g. when it is restarted.
+const STALE_ERROR_NAMES: [&str; 2] = [
+    "org.freedesktop.DBus.Error.ServiceUnknown",
+    "org.freedesktop.DBus.Error.NameHasNoOwner",
+];
+
+/// Maps the failure of connecting to the D-Bus system bus to an [`Error`].
+///
+/// A missing socket (or a socket that nothing listens on) is an I/O error, which is told apart from the other failures, e.g. an authentication or a protocol error.
+fn init_error(error: zbus::Error) -> Error {
+    match &error {
+        zbus::Error::InputOutput(io_error)
+            if matches!(
+                io_error.kind(),
+                io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused
+            ) =>
+        {
+            Error::NoSystemBus(error)
+        }
+        _ => Error::Init(error),
+    }
+}
+
+/// Calls `call`, and calls it once more after `refresh` if it fails because Bluez D-Bus is stale.
+///
+/// If `refresh` fails as well, then the error of `call` is returned.
+fn retry_when_stale<T>(
+    mut call: impl FnMut() -> Result<T, Error>,
+    refresh: impl FnOnce() -> Result<(), Error>,
+) -> Result<T, Error> {
+    match call() {
+        Err(e) if e.is_stale() => match refresh() {
+            Ok(()) => call(),
+            Err(_) => Err(e),
+        },
+        result => result,
+    }
+}
+
+#[cfg(test)]
+pub struct BluezTestDeviceBuilder {
+    device: BluezDevice,
+}
+
+#[cfg(test)]
+impl BluezTestDeviceBuilder {
+    pub fn new(alias: &str) -> Self {
+        Self {
+            device: BluezDevice {
+                alias: alias.to_string(),
+                address: String::from("XX:XX:XX:XX:XX:XX"),
+                adapter: String::from("hci0"),
+                connected: false,
+                paired: false,
+                trusted: false,
+                bonded: false,
+                blocked: false,
+                battery: None,
+                rssi: None,
+                tx_power: None,
+                uuids: vec![],
+                services_resolved: false,
+                manufacturer_data: None,
+            },
+        }
+    }
+
+    pub fn address(mut self, address: &str) -> Self {
+        self.device.address = address.to_string();
+        self
+    }
+
+    pub fn manufacturer_data(mut self, data: &[(u16, &[u8])]) -> Self {
+        let data = data
+            .iter()
+            .map(|(company_id, bytes)| (*company_id, bytes.to_vec()))
+            .collect();
+        self.device.manufacturer_data = Some(data);
+        self
+    }
+
+    pub fn adapter(mut self, adapter: &str) -> Self {
+        self.device.adapter = adapter.to_string();
+        self
+    }
+
+    /// Sets whether the device is connected. A connected device has its services resolved, unless [`services_resolved()`] is set after.
+    ///
+    /// [`services_resolved()`]: BluezTestDeviceBuilder::services_resolved()
+    pub fn connected(mut self, connected: bool) -> Self {
+        self.device.connected = connected;
+        self.device.services_resolved = connected;
+        self
+    }
+
+    pub fn services_resolved(mut self, services_resolved: bool) -> Self {
+        self.device.services_resolved = services_resolved;
+        self
+    }
+
+    pub fn paired(mut self, paired: bool) -> Self {
+        self.device.paired = paired;
+        self
+    }
+
+    pub fn trusted(mut self, trusted: bool) -> Self {
+        self.device.trusted = trusted;
+        self
+    }
+
+    pub fn bonded(mut self, bonded: bool) -> Self {
+        self.device.bonded = bonded;
+        self
+    }
+
+    pub fn blocked(mut self, blocked: bool) -> Self {
+        self.device.blocked = blocked;
+        self
+    }
+
+    pub fn battery(mut self, battery: Option<u8>) -> Self {
+        self.device.battery = battery;
+        self
+    }
+
+    pub fn rssi(mut self, rssi: Option<i16>) -> Self {
+        self.device.rssi = rssi;
+        self
+    }
+
+    pub fn tx_power(mut self, tx_power: Option<i16>) -> Self {
+        self.device.tx_power = tx_power;
+        self
+    }
+
+    pub fn uuids(mut self, uuids: &[&str]) -> Self {
+        self.device.uuids = uuids.iter().map(|u| u.to_string()).collect();
+        self
+    }
+
+    pub fn build(self) -> BluezDevice {
+        self.device
+    }
+}
+
+#[cfg(test)]
+pub struct BluezTestClient {
+    erred_method_name: Option<String>,
+    erred_alias: Option<String>,
+    err: Error,
+    devices: Option<Vec<BluezDevice>>,
+    power_state: std::cell::Cell<BluezPowerState>,
+    adapters: std::cell::RefCell<Vec<(String, BluezPowerState)>>,
+    agent: std::cell::RefCell<Option<BluezAgent>>,
+    agent_answers: std::cell::RefCell<Vec<Option<String>>>,
+    agent_confirmations: std::cell::RefCell<Vec<bool>>,
+    removed_aliases: std::cell::RefCell<Vec<String>>,
+    discovery_calls: std::cell::RefCell<Vec<String>>,
+    connected_aliases: std::cell::RefCell<Vec<String>>,
+    disconnected_aliases: std::cell::RefCell<Vec<String>>,
+    connected_profiles: std::cell::RefCell<Vec<(String, String)>>,
+    unread_devices: usize,
+    dropped_alias: Option<String>,
+    selected_adapter: String,
+    added_addresses: Vec<String>,
+    failing_calls: std::collections::HashMap<String, Vec<(usize, zbus::Error)>>,
+    calls: std::cell::RefCell<std::collections::HashMap<String, usize>>,
+    connected_devices_reads: std::cell::Cell<usize>,
+    read_devices: std::cell::Cell<usize>,
+    discoverable_devices: Vec<BluezDevice>,
+    has_discovered: std::cell::Cell<bool>,
+    discoverable: std::cell::Cell<bool>,
+    discoverable_timeout: std::cell::Cell<u32>,
+    lingering_polls: std::cell::Cell<usize>,
+    discovering_adapters: std::cell::RefCell<Vec<String>>,
+    adapter_connections: std::cell::RefCell<Vec<(String, String)>>,
+    disconnect_signals: std::cell::Cell<usize>,
+    refreshes: std::cell::Cell<usize>,
+    trust_changes: std::cell::RefCell<Vec<(String, bool)>>,
+    discovering: std::cell::Cell<bool>,
+    empty_scans: std::cell::Cell<usize>,
+    battery_drain: u8,
+    battery_reads: std::cell::Cell<u8>,
+    vanishing_reads: Vec<u8>,
+}
+
+#[cfg(test)]
+impl BluezTestClient {
+    pub fn new() -> Result<Self, Error> {
+        Ok(Self {
+            erred_method_name: None,
+            erred_alias: None,
+            err: Error::Process(String::from("test_proc"), zbus::Error::InvalidReply),
+            devices: None,
+            power_state: std::cell::Cell::new(BluezPowerState::On),
+            adapters: std::cell::RefCell::new(vec![(String::from("hci0"), BluezPowerState::On)]),
+            agent: std::cell::RefCell::new(None),
+            agent_answers: std::cell::RefCell::new(vec![]),
+            agent_confirmations: std::cell::RefCell::new(vec![]),
+            removed_aliases: std::cell::RefCell::new(vec![]),
+            discovery_calls: std::cell::RefCell::new(vec![]),
+            connected_aliases: std::cell::RefCell::new(vec![]),
+            disconnected_aliases: std::cell::RefCell::new(vec![]),
+            connected_profiles: std::cell::RefCell::new(vec![]),
+            unread_devices: 0,
+            dropped_alias: None,
+            selected_adapter: String::from("hci0"),
+            added_addresses: vec![],
+            failing_calls: std::collections::HashMap::new(),
+            calls: std::cell::RefCell::new(std::collections::HashMap::new()),
+            connected_devices_reads: std::cell::Cell::new(0),
+            read_devices: std::cell::Cell::new(0),
+            discoverable_devices: vec![],
+            has_discovered: std::cell::Cell::new(false),
+            discoverable: std::cell::Cell::new(false),
+            discoverable_timeout: std::cell::Cell::new(180),
+            lingering_polls: std::cell::Cell::new(0),
+            discovering_adapters: std::cell::RefCell::new(vec![]),
+            adapter_connections: std::cell::RefCell::new(vec![]),
+            disconnect_signals: std::cell::Cell::new(0),
+            refreshes: std::cell::Cell::new(0),
+            trust_changes: std::cell::RefCell::new(vec![]),
+            discovering: std::cell::Cell::new(false),
+            empty_scans: std::cell::Cell::new(0),
+            battery_drain: 0,
+            battery_reads: std::cell::Cell::new(0),
+            vanishing_reads: vec![],
+        })
+    }
+
+    pub fn set_erred_method_name(&mut self, name: String) {
+        self.erred_method_name = Some(name);
+    }
+
+    /// Makes the device methods such as [`disconnect()`] and [`remove()`] fail only for the device with `alias`.
+    ///
+    /// [`disconnect()`]: BluezTestClient::disconnect()
+    /// [`remove()`]: BluezTestClient::remove()
+    pub fn set_erred_alias(&mut self, alias: String) {
+        self.erred_alias = Some(alias);
+    }
+
+    /// Overrides the devices returned by [`devices()`], [`connected_devices()`] and [`scanned_devices()`].
+    ///
+    /// [`devices()`]: BluezTestClient::devices()
+    /// [`connected_devices()`]: BluezTestClient::connected_devices()
+    /// [`scanned_devices()`]: BluezTestClient::scanned_devices()
+    pub fn set_devices(&mut self, devices: Vec<BluezDevice>) {
+        self.devices = Some(devices);
+    }
+
+    /// Sets the number of devices that [`devices_with_unread()`] and [`scanned_devices_with_unread()`] report as unread.
+    ///
+    /// [`devices_with_unread()`]: BluezTestClient::devices_with_unread()
+    /// [`scanned_devices_with_unread()`]: BluezTestClient::scanned_devices_with_unread()
+    pub fn set_unread_devices(&mut self, unread: usize) {
+        self.unread_devices = unread;
+    }
+
+    /// Keeps the disconnected devices connected for the first `polls` calls of [`is_connected()`] after their [`disconnect()`], e.g. to mimic a link that drops a while after a disconnection.
+    ///
+    /// [`disconnect()`]: BluezTestClient::disconnect()
+    /// [`is_connected()`]: BluezTestClient::is_connected()
+    pub fn set_lingering_polls(&mut self, polls: usize) {
+        self.lingering_polls.set(polls);
+    }
+
+    /// Makes the first `drops` calls of [`wait_for_disconnect()`] report a disconnection. The later calls report that the signals have ended.
+    ///
+    /// [`wait_for_disconnect()`]: BluezTestClient::wait_for_disconnect()
+    pub fn set_disconnect_signals(&mut self, drops: usize) {
+        self.disconnect_signals.set(drops);
+    }
+
+    /// Makes the device with `alias` disconnect on its own after the first [`connected_devices()`] call, e.g. by going out of range.
+    ///
+    /// [`connected_devices()`]: BluezTestClient::connected_devices()
+    pub fn set_dropped_alias(&mut self, alias: String) {
+        self.dropped_alias = Some(alias);
+    }
+
+    /// Simulates the `InterfacesAdded` signals of the devices with `addresses`, which [`wait_for_device()`] waits for.
+    ///
+    /// [`wait_for_device()`]: BluezTestClient::wait_for_device()
+    pub fn set_added_addresses(&mut self, addresses: Vec<String>) {
+        self.added_addresses = addresses;
+    }
+
+    /// Sets the devices that are only known after a successful [`start_discovery()`] call, e.g. a device that is pruned from the cache of Bluez.
+    ///
+    /// [`start_discovery()`]: BluezTestClient::start_discovery()
+    pub fn set_discoverable_devices(&mut self, devices: Vec<BluezDevice>) {
+        self.discoverable_devices = devices;
+    }
+
+    /// Makes the given `calls` of `method` fail with `error`. The calls are counted from the next call of `method`, e.g. `0..2` fails the next two calls. The other calls of `method` behave as usual.
+    ///
+    /// Unlike [`set_erred_method_name()`], more than one method can fail, and a method can fail with more than one error.
+    ///
+    /// [`set_erred_method_name()`]: BluezTestClient::set_erred_method_name()
+    pub fn set_failing_calls(
+        &mut self,
+        method: &str,
+        calls: impl IntoIterator<Item = usize>,
+        error: zbus::Error,
+    ) {
+        let next_call = self.calls.borrow().get(method).copied().unwrap_or_default();
+
+        self.failing_calls
+            .entry(method.to_string())
+            .or_default()
+            .extend(
+                calls
+                    .into_iter()
+                    .map(|call| (next_call + call, error.clone())),
+            );
+    }
+
+    /// Makes the next `count` calls of [`scanned_devices()`] find no devices, e.g. like an adapter whose first discovery after powering on comes back empty.
+    ///
+    /// [`scanned_devices()`]: BluezTestClient::scanned_devices()
+    pub fn set_empty_scans(&mut self, count: usize) {
+        self.empty_scans.set(count);
+    }
+
+    /// Counts a call of `method`, and fails with the error set by [`set_failing_calls()`] for that call, if any.
+    ///
+    /// [`set_failing_calls()`]: BluezTestClient::set_failing_calls()
+    fn count_call(&self, method: &str) -> zbus::Result<()> {
+        let mut calls = self.calls.borrow_mut();
+        let call = calls.entry(method.to_string()).or_default();
+        *call += 1;
+
+        match self
+            .failing_calls
+            .get(method)
+            .and_then(|failures| failures.iter().find(|(c, _)| *c == *call - 1))
+        {
+            Some((_, error)) => Err(error.clone()),
+            None => Ok(()),
+        }
+    }
+
+    /// Builds the Bluez D-Bus error `name` with `message`, as a failed method call returns it.
+    pub fn method_error(name: &str, message: &str) -> zbus::Error {
+        let msg = zbus::Message::method_call("/org/bluez/hci0", "Test")
+            .and_then(|m| m.build(&()))
+            .unwrap();
+
+        zbus::Error::MethodError(
+            zbus::names::OwnedErrorName::try_from(name).unwrap(),
+            Some(message.to_string()),
+            msg,
+        )
+    }
+
+    /// Provides the number of successful [`refresh()`] calls.
+    ///
+    /// [`refresh()`]: BluezTestClient::refresh()
+    pub fn refreshes(&self) -> usize {
+        self.refreshes.get()
+    }
+
+    /// Makes the known battery levels drop by `percent` after each [`devices()`] call, e.g. like a device that is discharging between the polls.
+    ///
+    /// [`devices()`]: BluezTestClient::devices()
+    pub fn set_battery_drain(&mut self, percent: u8) {
+        self.battery_drain = percent;
+    }
+
+    /// Makes the [`devices()`] calls at the given `reads` (starting from 0) provide no device, e.g. like a poll while the devices briefly vanish.
+    ///
+    /// [`devices()`]: BluezTestClient::devices()
+    pub fn set_vanishing_reads(&mut self, reads: &[u8]) {
+        self.vanishing_reads = reads.to_vec();
+    }
+
+    /// Provides the devices set by [`set_devices()`], along with the ones set by [`set_discoverable_devices()`] once a discovery is started.
+    ///
+    /// If the erred method name is `battery`, then reading the battery of each connected device fails.
+    ///
+    /// [`set_devices()`]: BluezTestClient::set_devices()
+    /// [`set_discoverable_devices()`]: BluezTestClient::set_discoverable_devices()
+    fn test_devices(&self) -> Option<Vec<BluezDevice>> {
+        let mut devices = self.devices.clone()?;
+        if self.has_discovered.get() {
+            devices.extend(self.discoverable_devices.iter().cloned());
+        }
+
+        if self.erred_method_name.as_deref() == Some("battery") {
+            for dev in devices.iter_mut().filter(|d| d.connected) {
+                let percentage: zbus::Result<u8> = Err(zbus::Error::InvalidReply);
+                dev.battery = percentage.ok();
+            }
+        }
+
+        Some(devices)
+    }
+
+    /// Provides the device that [`devices()`], [`connected_devices()`] and [`scanned_devices()`] fall back to, unless the devices are set by [`set_devices()`].
+    ///
+    /// [`devices()`]: BluezTestClient::devices()
+    /// [`connected_devices()`]: BluezTestClient::connected_devices()
+    /// [`scanned_devices()`]: BluezTestClient::scanned_devices()
+    /// [`set_devices()`]: BluezTestClient::set_devices()
+    fn fallback_device() -> BluezDevice {
+        BluezDevice {
+            alias: String::from("test_dev"),
+            address: String::from("XX:XX:XX:XX:XX:XX"),
+            adapter: String::from("hci0"),
+            connected: true,
+            paired: true,
+            trusted: true,
+            bonded: false,
+            blocked: false,
+            battery: Some(50),
+            rssi: None,
+            tx_power: None,
+            uuids: vec![],
+            services_resolved: true,
+            manufacturer_data: None,
+        }
+    }
+
+    pub fn set_power_state(&mut self, power_state: BluezPowerState) {
+        self.power_state.set(power_state);
+    }
+
+    /// Overrides the adapters returned by [`adapters()`], with their power states.
+    ///
+    /// [`adapters()`]: BluezTestClient::adapters()
+    pub fn set_adapters(&mut self, adapters: &[(&str, BluezPowerState)]) {
+        self.adapters.replace(
+            adapters
+                .iter()
+                .map(|(name, power_state)| (name.to_string(), *power_state))
+                .collect(),
+        );
+    }
+
+    /// Provides the adapters with their current power states, including the ones set by [`set_adapter_power_state()`].
+    ///
+    /// [`set_adapter_power_state()`]: BluezTestClient::set_adapter_power_state()
+    pub fn test_adapters(&self) -> Vec<(String, BluezPowerState)> {
+        self.adapters.borrow().clone()
+    }
+
+    /// Provides the PIN codes the registered agent answered with during [`connect()`] calls.
+    /// A rejected request is recorded as [`None`].
+    ///
+    /// [`connect()`]: BluezTestClient::connect()
+    pub fn agent_answers(&self) -> Vec<Option<String>> {
+        self.agent_answers.borrow().clone()
+    }
+
+    /// Provides whether the registered agent accepted the passkey confirmation requested by each connected device, in call order.
+    pub fn agent_confirmations(&self) -> Vec<bool> {
+        self.agent_confirmations.borrow().clone()
+    }
+
+    /// Provides the number of devices that are read through [`devices_iter()`] so far.
+    ///
+    /// [`devices_iter()`]: BluezTestClient::devices_iter()
+    pub fn read_devices(&self) -> usize {
+        self.read_devices.get()
+    }
+
+    pub fn is_agent_registered(&self) -> bool {
+        self.agent.borrow().is_some()
+    }
+
+    /// Provides the aliases passed to successful [`remove()`] calls, in call order.
+    ///
+    /// [`remove()`]: BluezTestClient::remove()
+    pub fn removed_aliases(&self) -> Vec<String> {
+        self.removed_aliases.borrow().clone()
+    }
+
+    /// Provides the discovery calls (`start_discovery` and `stop_discovery`) in the order they are made.
+    pub fn discovery_calls(&self) -> Vec<String> {
+        self.discovery_calls.borrow().clone()
+    }
+
+    /// Provides the aliases (or addresses) passed to successful [`disconnect()`] calls, in call order.
+    ///
+    /// [`disconnect()`]: BluezTestClient::disconnect()
+    pub fn disconnected_aliases(&self) -> Vec<String> {
+        self.disconnected_aliases.borrow().clone()
     }
-}
 
-pub struct BluezTestClient {
-    erred_method_name: Option<String>,
-    err: Error,
-}
+    /// Provides the aliases (or addresses) passed to successful [`connect()`] calls, in call order.
+    ///
+    /// [`connect()`]: BluezTestClient::connect()
+    pub fn connected_aliases(&self) -> Vec<String> {
+        self.connected_aliases.borrow().clone()
+    }
 
-impl BluezTestClient {
-    pub fn new() -> Result<Self, Error> {
-        Ok(Self {
-            erred_method_name: None,
-            err: Error::Process(String::from("test_proc"), zbus::Error::InvalidReply),
-        })
+    /// Provides the adapters passed to [`start_discovery_on()`] whose discovery is not stopped yet.
+    ///
+    /// [`start_discovery_on()`]: BluezTestClient::start_discovery_on()
+    pub fn discovering_adapters(&self) -> Vec<String> {
+        self.discovering_adapters.borrow().clone()
     }
 
-    pub fn set_erred_method_name(&mut self, name: String) {
-        self.erred_method_name = Some(name);
+    /// Makes the adapter look like another program has already started the discovery, so that the discoverable devices are scanned as well.
+    pub fn set_discovering(&mut self, discovering: bool) {
+        self.discovering.set(discovering);
+        self.has_discovered.set(discovering);
+    }
+
+    /// Provides the `(alias, trusted)` pairs of the successful [`set_trusted()`] calls, in order.
+    ///
+    /// [`set_trusted()`]: BluezTestClient::set_trusted()
+    pub fn trust_changes(&self) -> Vec<(String, bool)> {
+        self.trust_changes.borrow().clone()
+    }
+
+    /// Provides the `(adapter, address)` pairs passed to successful [`connect_on()`] calls, in call order.
+    ///
+    /// [`connect_on()`]: BluezTestClient::connect_on()
+    pub fn adapter_connections(&self) -> Vec<(String, String)> {
+        self.adapter_connections.borrow().clone()
+    }
+
+    /// Provides the aliases (or addresses) and the profile UUIDs passed to successful [`connect_profile()`] calls, in call order.
+    ///
+    /// [`connect_profile()`]: BluezTestClient::connect_profile()
+    pub fn connected_profiles(&self) -> Vec<(String, String)> {
+        self.connected_profiles.borrow().clone()
+    }
+
+    pub fn refresh(&self) -> Result<(), Error> {
+        let err_key = String::from("refresh");
+
+        match &self.erred_method_name {
+            Some(v) if v == &err_key => Err(self.err.clone()),
+            _ => {
+                self.refreshes.set(self.refreshes.get() + 1);
+                Ok(())
+            }
+        }
     }
 
     pub fn power_state(&self) -> Result<BluezPowerState, Error> {
@@ -471,10 +2123,148 @@ impl BluezTestClient {
 
         match &self.erred_method_name {
             Some(v) if v == &err_key => Err(self.err.clone()),
-            _ => Ok(BluezPowerState::On),
+            _ => Ok(self.power_state.get()),
+        }
+    }
+
+    pub fn set_powered(&self, power_state: BluezPowerState) -> Result<(), Error> {
+        let err_key = String::from("set_powered");
+
+        match &self.erred_method_name {
+            Some(v) if v == &err_key => Err(self.err.clone()),
+            _ => {
+                self.count_call(&err_key)
+                    .map_err(|e| Error::Process(err_key.clone(), e))?;
+                self.power_state.set(power_state);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn discoverable(&self) -> Result<bool, Error> {
+        let err_key = String::from("discoverable");
+
+        match &self.erred_method_name {
+            Some(v) if v == &err_key => Err(self.err.clone()),
+            _ => Ok(self.discoverable.get()),
+        }
+    }
+
+    pub fn set_discoverable(&self, discoverable: bool) -> Result<(), Error> {
+        let err_key = String::from("set_discoverable");
+
+        match &self.erred_method_name {
+            Some(v) if v == &err_key => Err(self.err.clone()),
+            _ => {
+                self.discoverable.set(discoverable);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn discoverable_timeout(&self) -> Result<u32, Error> {
+        let err_key = String::from("discoverable_timeout");
+
+        match &self.erred_method_name {
+            Some(v) if v == &err_key => Err(self.err.clone()),
+            _ => Ok(self.discoverable_timeout.get()),
+        }
+    }
+
+    pub fn set_discoverable_timeout(&self, secs: u32) -> Result<(), Error> {
+        let err_key = String::from("set_discoverable_timeout");
+
+        match &self.erred_method_name {
+            Some(v) if v == &err_key => Err(self.err.clone()),
+            _ => {
+                self.discoverable_timeout.set(secs);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn adapters(&self) -> Result<Vec<String>, Error> {
+        let err_key = String::from("adapters");
+
+        match &self.erred_method_name {
+            Some(v) if v == &err_key => Err(self.err.clone()),
+            _ => Ok(self
+                .adapters
+                .borrow()
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect()),
+        }
+    }
+
+    pub fn select_adapter(&mut self, adapter: &str) -> Result<(), Error> {
+        let err_key = String::from("select_adapter");
+
+        match &self.erred_method_name {
+            Some(v) if v == &err_key => Err(self.err.clone()),
+            _ => {
+                let power_state = self
+                    .adapters
+                    .borrow()
+                    .iter()
+                    .find(|(name, _)| name == adapter)
+                    .map(|(_, power_state)| *power_state)
+                    .ok_or(self.err.clone())?;
+                self.power_state.set(power_state);
+                self.selected_adapter = adapter.to_string();
+
+                Ok(())
+            }
+        }
+    }
+
+    pub fn selected_adapter(&self) -> String {
+        self.selected_adapter.clone()
+    }
+
+    pub fn adapter_power_state(&self, adapter: &str) -> Result<BluezPowerState, Error> {
+        let err_key = String::from("adapter_power_state");
+
+        match &self.erred_method_name {
+            Some(v) if v == &err_key => Err(self.err.clone()),
+            _ => self
+                .adapters
+                .borrow()
+                .iter()
+                .find(|(name, _)| name == adapter)
+                .map(|(_, power_state)| *power_state)
+                .ok_or(self.err.clone()),
+        }
+    }
+
+    pub fn set_adapter_power_state(
+        &self,
+        adapter: &str,
+        power_state: BluezPowerState,
+    ) -> Result<(), Error> {
+        let err_key = String::from("set_adapter_power_state");
+
+        match &self.erred_method_name {
+            Some(v) if v == &err_key => Err(self.err.clone()),
+            _ => {
+                let mut adapters = self.adapters.borrow_mut();
+                let (_, state) = adapters
+                    .iter_mut()
+                    .find(|(name, _)| name == adapter)
+                    .ok_or(self.err.clone())?;
+                *state = power_state;
+
+                Ok(())
+            }
         }
     }
 
+    pub fn is_powered(&self) -> Result<bool, Error> {
+        let power_state = self.power_state()?;
+
+        Ok(bool::from(&power_state))
+    }
+
     pub fn toggle_power_state(&self) -> Result<BluezPowerState, Error> {
         let err_key = String::from("toggle_power_state");
 
@@ -487,31 +2277,112 @@ impl BluezTestClient {
     pub fn devices(&self) -> Result<Vec<BluezDevice>, Error> {
         let err_key = String::from("devices");
 
+        match &self.erred_method_name {
+            Some(v) if v == &err_key => Err(self.err.clone()),
+            _ => match self.test_devices() {
+                Some(mut devices) => {
+                    let reads = self.battery_reads.get();
+                    self.battery_reads.set(reads.saturating_add(1));
+                    self.count_call(&err_key)
+                        .map_err(|e| Error::Process(err_key.clone(), e))?;
+                    if self.vanishing_reads.contains(&reads) {
+                        return Ok(vec![]);
+                    }
+
+                    let drained = self.battery_drain.saturating_mul(reads);
+                    for dev in devices.iter_mut() {
+                        dev.battery = dev.battery.map(|b| b.saturating_sub(drained));
+                    }
+
+                    Ok(devices)
+                }
+                None => Ok(vec![Self::fallback_device()]),
+            },
+        }
+    }
+
+    pub fn connect(&self, alias: &str) -> Result<(), Error> {
+        let err_key = String::from("connect");
+
         match &self.erred_method_name {
             Some(v) if v == &err_key => Err(self.err.clone()),
             _ => {
-                let device = BluezDevice {
-                    alias: String::from("test_dev"),
-                    address: String::from("XX:XX:XX:XX:XX:XX"),
-                    connected: true,
-                    paired: true,
-                    trusted: true,
-                    bonded: false,
-                    battery: Some(50),
-                    rssi: None,
-                };
+                self.count_call(&err_key)
+                    .map_err(|e| Error::Process(err_key.clone(), e))?;
+                self.connected_aliases.borrow_mut().push(alias.to_string());
+
+                // NOTE: Simulates a legacy device requesting a PIN code, and a
+                // device requesting a passkey confirmation during the connection.
+                if let Some(agent) = self.agent.borrow().as_ref() {
+                    let device = self
+                        .test_devices()
+                        .unwrap_or_default()
+                        .into_iter()
+                        .find(|d| d.alias == alias || d.address.eq_ignore_ascii_case(alias))
+                        .map(|d| d.path())
+                        .unwrap_or_else(|| String::from("/org/bluez/hci0/dev_XX_XX_XX_XX_XX_XX"));
+                    let device = OwnedObjectPath::try_from(device).unwrap();
+
+                    let answer = agent.request_pin_code(device.clone()).ok();
+                    self.agent_answers.borrow_mut().push(answer);
+                    let confirmed = agent.request_confirmation(device, 123456).is_ok();
+                    self.agent_confirmations.borrow_mut().push(confirmed);
+                }
+                Ok(())
+            }
+        }
+    }
 
-                Ok(vec![device])
+    pub fn connect_profile(&self, alias: &str, uuid: &str) -> Result<(), Error> {
+        let err_key = String::from("connect_profile");
+
+        match &self.erred_method_name {
+            Some(v) if v == &err_key => Err(self.err.clone()),
+            _ => {
+                self.connected_profiles
+                    .borrow_mut()
+                    .push((alias.to_string(), uuid.to_string()));
+                Ok(())
             }
         }
     }
 
-    pub fn connect(&self, _: &str) -> Result<(), Error> {
-        let err_key = String::from("connect");
+    pub fn connect_on(&self, adapter: &str, address: &str) -> Result<(), Error> {
+        let err_key = String::from("connect_on");
+
+        match &self.erred_method_name {
+            Some(v) if v == &err_key => Err(self.err.clone()),
+            _ => {
+                self.test_adapter(adapter)?;
+                self.adapter_connections
+                    .borrow_mut()
+                    .push((adapter.to_string(), address.to_string()));
+                Ok(())
+            }
+        }
+    }
+
+    pub fn register_agent(&self, agent: BluezAgent) -> Result<(), Error> {
+        let err_key = String::from("register_agent");
+
+        match &self.erred_method_name {
+            Some(v) if v == &err_key => Err(self.err.clone()),
+            _ => {
+                *self.agent.borrow_mut() = Some(agent);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn unregister_agent(&self) -> Result<(), Error> {
+        let err_key = String::from("unregister_agent");
 
         match &self.erred_method_name {
             Some(v) if v == &err_key => Err(self.err.clone()),
-            _ => Ok(()),
+            _ => {
+                *self.agent.borrow_mut() = None;
+                Ok(())
+            }
         }
     }
 
@@ -521,28 +2392,51 @@ impl BluezTestClient {
         match &self.erred_method_name {
             Some(v) if v == &err_key => Err(self.err.clone()),
             _ => {
-                let device = BluezDevice {
-                    alias: String::from("test_dev"),
-                    address: String::from("XX:XX:XX:XX:XX:XX"),
-                    connected: true,
-                    paired: true,
-                    trusted: true,
-                    bonded: false,
-                    battery: Some(50),
-                    rssi: None,
+                let reads = self.connected_devices_reads.get();
+                self.connected_devices_reads.set(reads + 1);
+
+                let devices: Vec<BluezDevice> = match self.test_devices() {
+                    Some(devices) => devices.into_iter().filter(|d| d.connected).collect(),
+                    None => vec![Self::fallback_device()],
                 };
 
-                Ok(vec![device])
+                Ok(devices
+                    .into_iter()
+                    .filter(|d| reads == 0 || self.dropped_alias.as_deref() != Some(d.alias()))
+                    .collect())
             }
         }
     }
 
+    pub fn wait_for_device(&self, address: &str, _: Duration) -> Result<(), Error> {
+        let err_key = String::from("wait_for_device");
+
+        let is_known = self
+            .test_devices()
+            .is_some_and(|devices| devices.iter().any(|d| d.address == address));
+        let is_added = self.added_addresses.iter().any(|a| a == address);
+
+        match &self.erred_method_name {
+            Some(v) if v == &err_key => Err(self.err.clone()),
+            _ if is_known || is_added => Ok(()),
+            _ => Err(self.err.clone()),
+        }
+    }
+
     pub fn start_discovery(&self) -> Result<(), Error> {
         let err_key = String::from("start_discovery");
 
         match &self.erred_method_name {
             Some(v) if v == &err_key => Err(self.err.clone()),
-            _ => Ok(()),
+            _ => retry_when_not_ready(Duration::ZERO, || self.count_call(&err_key))
+                .map_err(|e| Error::Process(String::from("start_disc"), e))
+                .inspect(|_| {
+                    self.has_discovered.set(true);
+                    self.discovering.set(true);
+                    self.discovery_calls
+                        .borrow_mut()
+                        .push(String::from("start_discovery"));
+                }),
         }
     }
 
@@ -551,47 +2445,553 @@ impl BluezTestClient {
 
         match &self.erred_method_name {
             Some(v) if v == &err_key => Err(self.err.clone()),
-            _ => Ok(()),
+            _ => {
+                self.discovering.set(false);
+                self.discovery_calls
+                    .borrow_mut()
+                    .push(String::from("stop_discovery"));
+                Ok(())
+            }
+        }
+    }
+
+    pub fn is_discovering(&self) -> Result<bool, Error> {
+        let err_key = String::from("is_discovering");
+
+        match &self.erred_method_name {
+            Some(v) if v == &err_key => Err(self.err.clone()),
+            _ => Ok(self.discovering.get()),
+        }
+    }
+
+    pub fn start_discovery_on(&self, adapter: &str) -> Result<(), Error> {
+        let err_key = String::from("start_discovery_on");
+
+        match &self.erred_method_name {
+            Some(v) if v == &err_key => Err(self.err.clone()),
+            _ => {
+                self.count_call(&err_key)
+                    .map_err(|e| Error::Process(err_key.clone(), e))?;
+                self.test_adapter(adapter)?;
+                self.discovering_adapters
+                    .borrow_mut()
+                    .push(adapter.to_string());
+                self.has_discovered.set(true);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn stop_discovery_on(&self, adapter: &str) -> Result<(), Error> {
+        let err_key = String::from("stop_discovery_on");
+
+        match &self.erred_method_name {
+            Some(v) if v == &err_key => Err(self.err.clone()),
+            _ => {
+                self.test_adapter(adapter)?;
+                self.discovering_adapters
+                    .borrow_mut()
+                    .retain(|a| a != adapter);
+                Ok(())
+            }
+        }
+    }
+
+    /// Fails unless `adapter` is one of the adapters set by [`set_adapters()`].
+    ///
+    /// [`set_adapters()`]: BluezTestClient::set_adapters()
+    fn test_adapter(&self, adapter: &str) -> Result<(), Error> {
+        match self
+            .adapters
+            .borrow()
+            .iter()
+            .any(|(name, _)| name == adapter)
+        {
+            true => Ok(()),
+            false => Err(self.err.clone()),
         }
     }
 
+    pub fn devices_with_unread(&self) -> Result<(Vec<BluezDevice>, usize), Error> {
+        self.devices().map(|devices| (devices, self.unread_devices))
+    }
+
+    pub fn devices_iter(&self) -> Result<impl Iterator<Item = Option<BluezDevice>> + '_, Error> {
+        // NOTE: The test devices stand in for the object paths, which are read
+        // up front. Each device is only read (and counted) once the iterator
+        // reaches it, and the unread devices are read as `None`.
+        let devices = self.devices()?;
+        let count = devices.len() + self.unread_devices;
+
+        Ok((0..count).map(move |i| {
+            self.read_devices.set(self.read_devices.get() + 1);
+            devices.get(i).cloned()
+        }))
+    }
+
+    pub fn scanned_devices_with_unread(&self) -> Result<(Vec<BluezDevice>, usize), Error> {
+        self.scanned_devices()
+            .map(|devices| (devices, self.unread_devices))
+    }
+
     pub fn scanned_devices(&self) -> Result<Vec<BluezDevice>, Error> {
         let err_key = String::from("scanned_devices");
 
         match &self.erred_method_name {
             Some(v) if v == &err_key => Err(self.err.clone()),
-            _ => {
-                let device = BluezDevice {
-                    alias: String::from("test_dev"),
-                    address: String::from("XX:XX:XX:XX:XX:XX"),
-                    connected: true,
-                    paired: true,
-                    trusted: true,
-                    bonded: false,
+            _ if self.empty_scans.get() > 0 => {
+                self.empty_scans.set(self.empty_scans.get() - 1);
+                Ok(vec![])
+            }
+            _ => match self.test_devices() {
+                Some(devices) => Ok(devices.into_iter().filter(|d| d.rssi.is_some()).collect()),
+                None => Ok(vec![BluezDevice {
                     battery: None,
                     rssi: Some(50),
-                };
+                    ..Self::fallback_device()
+                }]),
+            },
+        }
+    }
 
-                Ok(vec![device])
-            }
+    pub fn rssi(&self, alias: &str) -> Result<Option<i16>, Error> {
+        let err_key = String::from("rssi");
+
+        match &self.erred_method_name {
+            Some(v) if v == &err_key => Err(self.err.clone()),
+            _ => match self.test_devices() {
+                Some(devices) => devices
+                    .into_iter()
+                    .find(|d| d.alias == alias || d.address.eq_ignore_ascii_case(alias))
+                    .map(|d| d.rssi)
+                    .ok_or(self.err.clone()),
+                None => Ok(Some(50)),
+            },
         }
     }
 
-    pub fn remove(&self, _: &str) -> Result<(), Error> {
+    pub fn remove(&self, alias: &str) -> Result<(), Error> {
         let err_key = String::from("remove");
 
         match &self.erred_method_name {
             Some(v) if v == &err_key => Err(self.err.clone()),
-            _ => Ok(()),
+            _ if self.erred_alias.as_deref() == Some(alias) => Err(self.err.clone()),
+            _ => {
+                self.removed_aliases.borrow_mut().push(alias.to_string());
+                Ok(())
+            }
         }
     }
 
-    pub fn disconnect(&self, _: &str) -> Result<(), Error> {
+    pub fn disconnect(&self, alias: &str) -> Result<(), Error> {
         let err_key = String::from("disconnect");
 
         match &self.erred_method_name {
             Some(v) if v == &err_key => Err(self.err.clone()),
-            _ => Ok(()),
+            _ if self.erred_alias.as_deref() == Some(alias) => Err(self.err.clone()),
+            _ => {
+                self.disconnected_aliases
+                    .borrow_mut()
+                    .push(alias.to_string());
+                Ok(())
+            }
+        }
+    }
+
+    pub fn set_trusted(&self, alias: &str, trusted: bool) -> Result<(), Error> {
+        let err_key = String::from("set_trusted");
+
+        match &self.erred_method_name {
+            Some(v) if v == &err_key => Err(self.err.clone()),
+            _ if self.erred_alias.as_deref() == Some(alias) => Err(self.err.clone()),
+            _ => {
+                self.trust_changes
+                    .borrow_mut()
+                    .push((alias.to_string(), trusted));
+                Ok(())
+            }
+        }
+    }
+
+    pub fn is_connected(&self, alias: &str) -> Result<bool, Error> {
+        let err_key = String::from("is_connected");
+
+        match &self.erred_method_name {
+            Some(v) if v == &err_key => Err(self.err.clone()),
+            _ => {
+                let connected = self.test_devices().is_some_and(|devices| {
+                    devices.iter().any(|d| {
+                        d.connected && (d.alias == alias || d.address.eq_ignore_ascii_case(alias))
+                    })
+                });
+
+                if !connected {
+                    return Ok(false);
+                }
+
+                let is_disconnected = self
+                    .disconnected_aliases
+                    .borrow()
+                    .iter()
+                    .any(|a| a == alias);
+                let polls = self.lingering_polls.get();
+                if is_disconnected && polls > 0 {
+                    self.lingering_polls.set(polls.saturating_sub(1));
+                    return Ok(true);
+                }
+
+                Ok(!is_disconnected)
+            }
+        }
+    }
+
+    pub fn wait_for_disconnect(&self, alias: &str) -> Result<bool, Error> {
+        let err_key = String::from("wait_for_disconnect");
+
+        match &self.erred_method_name {
+            Some(v) if v == &err_key => Err(self.err.clone()),
+            _ if self.erred_alias.as_deref() == Some(alias) => Err(self.err.clone()),
+            _ => {
+                let drops = self.disconnect_signals.get();
+                self.disconnect_signals.set(drops.saturating_sub(1));
+
+                Ok(drops > 0)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use zbus::{
+        names::OwnedInterfaceName,
+        zvariant::{OwnedValue, Value},
+    };
+
+    use super::*;
+
+    fn object(
+        interface: &str,
+        address: &str,
+    ) -> HashMap<OwnedInterfaceName, HashMap<String, OwnedValue>> {
+        let properties = HashMap::from([(
+            String::from("Address"),
+            OwnedValue::try_from(Value::from(address)).unwrap(),
+        )]);
+
+        HashMap::from([(OwnedInterfaceName::try_from(interface).unwrap(), properties)])
+    }
+
+    #[test]
+    fn it_should_find_and_select_the_adapters_at_any_path() {
+        let objects = ManagedObjects::from([
+            (
+                OwnedObjectPath::try_from("/org/bluez/hci0").unwrap(),
+                object(ADAPTER_INTERFACE, "00:00:00:00:00:01"),
+            ),
+            (
+                OwnedObjectPath::try_from("/org/bluez/virtual/usb_remote").unwrap(),
+                object(ADAPTER_INTERFACE, "00:00:00:00:00:02"),
+            ),
+            (
+                OwnedObjectPath::try_from("/org/bluez/hci0/dev_AA_AA_AA_AA_AA_AA").unwrap(),
+                object("org.bluez.Device1", "AA:AA:AA:AA:AA:AA"),
+            ),
+        ]);
+
+        let adapters = adapters_from_objects(objects);
+        let names = adapters
+            .iter()
+            .map(BluezAdapter::name)
+            .collect::<Vec<&str>>();
+        assert_eq!(names, vec!["hci0", "usb_remote"]);
+
+        let default = default_adapter(&adapters).unwrap();
+        assert_eq!(default.path(), "/org/bluez/hci0");
+
+        let by_name = adapters.iter().find(|a| a.matches("usb_remote")).unwrap();
+        assert_eq!(by_name.path(), "/org/bluez/virtual/usb_remote");
+
+        let by_address = adapters
+            .iter()
+            .find(|a| a.matches("00:00:00:00:00:02"))
+            .unwrap();
+        assert_eq!(by_address, by_name);
+
+        assert!(!adapters.iter().any(|a| a.matches("hci1")));
+
+        assert_eq!(
+            adapter_name("/org/bluez/hci0/dev_AA_AA_AA_AA_AA_AA"),
+            "hci0"
+        );
+    }
+
+    #[test]
+    fn it_should_default_to_the_first_adapter_without_hci0() {
+        let objects = ManagedObjects::from([(
+            OwnedObjectPath::try_from("/org/bluez/virtual/usb_remote").unwrap(),
+            object(ADAPTER_INTERFACE, "00:00:00:00:00:02"),
+        )]);
+
+        let adapters = adapters_from_objects(objects);
+
+        assert_eq!(
+            default_adapter(&adapters).map(BluezAdapter::name),
+            Some("usb_remote")
+        );
+    }
+
+    #[test]
+    fn it_should_retry_only_while_the_adapter_is_not_ready() {
+        let method_error = |name: &str| {
+            let msg = zbus::Message::method_call("/org/bluez/hci0", "StartDiscovery")
+                .and_then(|m| m.build(&()))
+                .unwrap();
+
+            zbus::Error::MethodError(
+                zbus::names::OwnedErrorName::try_from(name).unwrap(),
+                None,
+                msg,
+            )
+        };
+
+        for (failures, error_name, expected_calls, is_ok) in [
+            (0, "org.bluez.Error.NotReady", 1, true),
+            (2, "org.bluez.Error.NotReady", 3, true),
+            (2, "org.bluez.Error.InProgress", 3, true),
+            (3, "org.bluez.Error.NotReady", NOT_READY_ATTEMPTS, false),
+            (1, "org.bluez.Error.Failed", 1, false),
+        ] {
+            let mut calls = 0;
+            let result = retry_when_not_ready(Duration::ZERO, || {
+                calls += 1;
+                match calls <= failures {
+                    true => Err(method_error(error_name)),
+                    false => Ok(()),
+                }
+            });
+
+            assert_eq!(result.is_ok(), is_ok);
+            assert_eq!(calls, expected_calls);
+        }
+    }
+
+    #[test]
+    fn it_should_fail_to_start_without_any_adapter() {
+        let adapters = adapters_from_objects(ManagedObjects::from([(
+            OwnedObjectPath::try_from("/org/bluez").unwrap(),
+            object("org.bluez.AgentManager1", "00:00:00:00:00:00"),
+        )]));
+        assert!(adapters.is_empty());
+
+        let error = initial_adapter_path(Ok(adapters)).unwrap_err();
+        assert!(matches!(error, Error::NoAdapter));
+        assert_eq!(error.to_string(), "no Bluetooth adapter found");
+
+        assert_eq!(
+            initial_adapter_path(Err(zbus::Error::Unsupported)).unwrap(),
+            DEFAULT_ADAPTER_PATH
+        );
+    }
+
+    #[test]
+    fn it_should_compare_the_devices_by_all_properties() {
+        let device = BluezTestDeviceBuilder::new("dev")
+            .address("AA:AA:AA:AA:AA:AA")
+            .connected(true)
+            .battery(Some(50))
+            .build();
+
+        assert_eq!(device, device.clone());
+        assert_eq!(
+            device,
+            BluezTestDeviceBuilder::new("dev")
+                .address("AA:AA:AA:AA:AA:AA")
+                .connected(true)
+                .battery(Some(50))
+                .build()
+        );
+
+        assert_ne!(
+            device,
+            BluezTestDeviceBuilder::new("dev")
+                .address("AA:AA:AA:AA:AA:AA")
+                .connected(true)
+                .battery(Some(49))
+                .build()
+        );
+        assert_ne!(
+            device,
+            BluezTestDeviceBuilder::new("dev")
+                .address("AA:AA:AA:AA:AA:AA")
+                .connected(true)
+                .battery(Some(50))
+                .rssi(Some(-60))
+                .build()
+        );
+    }
+
+    #[test]
+    fn it_should_retry_a_stale_call_once_after_refreshing() {
+        let stale_err = || {
+            Error::Process(
+                String::from("power_state"),
+                zbus::Error::FDO(Box::new(zbus::fdo::Error::ServiceUnknown(String::from(
+                    "org.bluez",
+                )))),
+            )
+        };
+
+        let calls = std::cell::Cell::new(0);
+        let refreshes = std::cell::Cell::new(0);
+        let stale_then_refreshed = || {
+            calls.set(calls.get() + 1);
+            match refreshes.get() {
+                0 => Err(stale_err()),
+                _ => Ok(BluezPowerState::On),
+            }
+        };
+        let refresh = || {
+            refreshes.set(refreshes.get() + 1);
+            Ok(())
+        };
+
+        let result = retry_when_stale(stale_then_refreshed, refresh);
+        assert!(matches!(result, Ok(BluezPowerState::On)));
+        assert_eq!((calls.get(), refreshes.get()), (2, 1));
+
+        // A call that is still stale after the refresh is not retried again.
+        let calls = std::cell::Cell::new(0);
+        let result = retry_when_stale(
+            || {
+                calls.set(calls.get() + 1);
+                Err::<(), _>(stale_err())
+            },
+            || Ok(()),
+        );
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 2);
+
+        // A failed refresh returns the error of the call.
+        let result = retry_when_stale(
+            || Err::<(), _>(stale_err()),
+            || Err(Error::Init(zbus::Error::InvalidReply)),
+        );
+        assert!(matches!(result, Err(Error::Process(_, _))));
+
+        let calls = std::cell::Cell::new(0);
+        let result = retry_when_stale(
+            || {
+                calls.set(calls.get() + 1);
+                Err::<(), _>(Error::Process(
+                    String::from("connect"),
+                    zbus::Error::InvalidReply,
+                ))
+            },
+            || unreachable!(),
+        );
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 1);
+
+        // An I/O failure is stale only if the socket to the bus is closed.
+        for (kind, stale) in [
+            (io::ErrorKind::BrokenPipe, true),
+            (io::ErrorKind::UnexpectedEof, true),
+            (io::ErrorKind::TimedOut, false),
+            (io::ErrorKind::Other, false),
+        ] {
+            let error = Error::Process(
+                String::from("connect"),
+                zbus::Error::InputOutput(std::sync::Arc::new(io::Error::from(kind))),
+            );
+            assert_eq!(error.is_stale(), stale);
+        }
+    }
+
+    #[test]
+    fn it_should_tell_a_missing_system_bus_apart_from_other_init_errors() {
+        let io_error = |kind| zbus::Error::InputOutput(std::sync::Arc::new(io::Error::from(kind)));
+
+        let error = init_error(io_error(io::ErrorKind::NotFound));
+        assert!(matches!(error, Error::NoSystemBus(_)));
+        assert!(
+            error
+                .to_string()
+                .starts_with("the D-Bus system bus socket is not found")
+        );
+        assert!(matches!(
+            init_error(io_error(io::ErrorKind::ConnectionRefused)),
+            Error::NoSystemBus(_)
+        ));
+
+        assert!(matches!(
+            init_error(io_error(io::ErrorKind::PermissionDenied)),
+            Error::Init(_)
+        ));
+        assert!(matches!(
+            init_error(zbus::Error::Handshake(String::from("auth failed"))),
+            Error::Init(_)
+        ));
+    }
+
+    #[test]
+    fn it_should_only_retry_a_busy_adapter_or_a_page_timeout() {
+        let method_error = |name: &str, message: &str| {
+            let msg =
+                zbus::Message::method_call("/org/bluez/hci0/dev_XX_XX_XX_XX_XX_XX", "Connect")
+                    .and_then(|m| m.build(&()))
+                    .unwrap();
+
+            Error::Process(
+                String::from("connect"),
+                zbus::Error::MethodError(
+                    zbus::names::OwnedErrorName::try_from(name).unwrap(),
+                    Some(message.to_string()),
+                    msg,
+                ),
+            )
+        };
+
+        for (error, transient) in [
+            (
+                method_error("org.bluez.Error.InProgress", "In Progress"),
+                true,
+            ),
+            (
+                method_error("org.bluez.Error.NotReady", "Resource Not Ready"),
+                true,
+            ),
+            (
+                method_error("org.bluez.Error.Failed", "br-connection-page-timeout"),
+                true,
+            ),
+            (
+                method_error(
+                    "org.bluez.Error.Failed",
+                    "br-connection-profile-unavailable",
+                ),
+                false,
+            ),
+            (
+                method_error(
+                    "org.bluez.Error.AuthenticationFailed",
+                    "Authentication Failed",
+                ),
+                false,
+            ),
+            (
+                Error::Process(
+                    String::from("connect"),
+                    zbus::Error::InputOutput(std::sync::Arc::new(io::Error::from(
+                        io::ErrorKind::TimedOut,
+                    ))),
+                ),
+                false,
+            ),
+        ] {
+            assert_eq!(error.is_transient(), transient);
         }
     }
 }
@@ -1,7 +1,14 @@
+mod agent;
+mod cache;
 mod client;
 mod proxies;
+mod rfkill;
 
-pub use client::{BluezDevice, Error};
+pub use agent::BluezAgent;
+pub use client::{BluezConnectionState, BluezDevice, BluezPowerState, Error};
+
+#[cfg(test)]
+pub use client::BluezTestDeviceBuilder;
 
 #[cfg(not(test))]
 pub use client::BluezDBusClient as Client;
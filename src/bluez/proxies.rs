@@ -1,4 +1,9 @@
-use zbus::{proxy, zvariant::ObjectPath};
+use std::collections::HashMap;
+
+use zbus::{
+    proxy,
+    zvariant::{ObjectPath, OwnedValue},
+};
 
 #[proxy(
     default_service = "org.bluez",
@@ -15,6 +20,21 @@ pub trait BluezAdapter {
     #[zbus(property)]
     fn set_powered(&self, power_state: bool) -> zbus::Result<()>;
 
+    #[zbus(property)]
+    fn discoverable(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn set_discoverable(&self, discoverable: bool) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn discoverable_timeout(&self) -> zbus::Result<u32>;
+
+    #[zbus(property)]
+    fn set_discoverable_timeout(&self, timeout: u32) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn discovering(&self) -> zbus::Result<bool>;
+
     fn start_discovery(&self) -> zbus::Result<()>;
 
     fn stop_discovery(&self) -> zbus::Result<()>;
@@ -42,6 +62,12 @@ pub trait BluezDevice {
     #[zbus(property)]
     fn trusted(&self) -> zbus::Result<bool>;
 
+    #[zbus(property)]
+    fn blocked(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn set_trusted(&self, trusted: bool) -> zbus::Result<()>;
+
     #[zbus(property)]
     fn alias(&self) -> zbus::Result<String>;
 
@@ -51,8 +77,22 @@ pub trait BluezDevice {
     #[zbus(property, name = "RSSI")]
     fn rssi(&self) -> zbus::Result<i16>;
 
+    #[zbus(property)]
+    fn tx_power(&self) -> zbus::Result<i16>;
+
+    #[zbus(property, name = "UUIDs")]
+    fn uuids(&self) -> zbus::Result<Vec<String>>;
+
+    #[zbus(property)]
+    fn services_resolved(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn manufacturer_data(&self) -> zbus::Result<HashMap<u16, OwnedValue>>;
+
     fn connect(&self) -> zbus::Result<()>;
 
+    fn connect_profile(&self, uuid: &str) -> zbus::Result<()>;
+
     fn disconnect(&self) -> zbus::Result<()>;
 }
 
@@ -67,3 +107,17 @@ pub trait BluezDeviceBattery {
     #[zbus(property)]
     fn percentage(&self) -> zbus::Result<u8>;
 }
+
+#[proxy(
+    default_service = "org.bluez",
+    default_path = "/org/bluez",
+    interface = "org.bluez.AgentManager1",
+    gen_blocking = true,
+    blocking_name = "BluezAgentManagerProxy",
+    async_name = "BluezAsyncAgentManagerProxy"
+)]
+pub trait BluezAgentManager {
+    fn register_agent(&self, agent: &ObjectPath<'_>, capability: &str) -> zbus::Result<()>;
+
+    fn unregister_agent(&self, agent: &ObjectPath<'_>) -> zbus::Result<()>;
+}
@@ -0,0 +1,99 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The directory that the kernel exposes the rfkill switches under.
+pub const RFKILL_ROOT: &str = "/sys/class/rfkill";
+
+/// Defines a probe that reads the rfkill switches of Bluetooth.
+///
+/// Bluez does not expose rfkill, so the switches are read from sysfs instead. The root directory can be changed, so that the probe can be tested against a fake sysfs tree.
+#[derive(Debug)]
+pub struct RfkillProbe {
+    root: PathBuf,
+}
+
+impl Default for RfkillProbe {
+    fn default() -> Self {
+        Self::new(RFKILL_ROOT)
+    }
+}
+
+impl RfkillProbe {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Indicates whether any Bluetooth rfkill switch is blocked, either by software or by hardware.
+    ///
+    /// If the switches cannot be read (e.g. rfkill is not supported), then Bluetooth is not considered blocked.
+    pub fn is_blocked(&self) -> bool {
+        let Ok(entries) = fs::read_dir(&self.root) else {
+            return false;
+        };
+
+        entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|switch| read_value(&switch.join("type")).as_deref() == Some("bluetooth"))
+            .any(|switch| {
+                ["soft", "hard"]
+                    .iter()
+                    .any(|state| read_value(&switch.join(state)).as_deref() == Some("1"))
+            })
+    }
+}
+
+fn read_value(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|v| v.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fake_sysfs(name: &str, switches: &[(&str, &str, &str)]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("bt-rfkill-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        for (idx, (switch_type, soft, hard)) in switches.iter().enumerate() {
+            let switch = root.join(format!("rfkill{}", idx));
+            fs::create_dir_all(&switch).unwrap();
+            fs::write(switch.join("type"), format!("{}\n", switch_type)).unwrap();
+            fs::write(switch.join("soft"), format!("{}\n", soft)).unwrap();
+            fs::write(switch.join("hard"), format!("{}\n", hard)).unwrap();
+        }
+
+        root
+    }
+
+    #[test]
+    fn it_should_detect_a_blocked_bluetooth_switch() {
+        for (name, soft, hard) in [("soft", "1", "0"), ("hard", "0", "1")] {
+            let root = fake_sysfs(name, &[("wlan", "0", "0"), ("bluetooth", soft, hard)]);
+
+            let is_blocked = RfkillProbe::new(&root).is_blocked();
+            fs::remove_dir_all(&root).unwrap();
+
+            assert!(is_blocked);
+        }
+    }
+
+    #[test]
+    fn it_should_ignore_the_switches_of_other_devices() {
+        let root = fake_sysfs("other", &[("wlan", "1", "1"), ("bluetooth", "0", "0")]);
+
+        let is_blocked = RfkillProbe::new(&root).is_blocked();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(!is_blocked);
+    }
+
+    #[test]
+    fn it_should_not_be_blocked_without_rfkill() {
+        let probe = RfkillProbe::new("/nonexistent/rfkill");
+
+        assert!(!probe.is_blocked());
+    }
+}
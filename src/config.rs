@@ -0,0 +1,209 @@
+use std::{collections::HashMap, env, error, fmt, fs, io, path::PathBuf, str::FromStr};
+
+/// Defines error variants that may be returned while loading a [`Config`].
+///
+/// [`Config`]: crate::Config
+#[derive(Debug)]
+pub enum Error {
+    /// Happens when the config file exists, but it cannot be read.
+    /// It holds the underlying [`io::Error`].
+    ///
+    /// [`io::Error`]: std::io::Error
+    Io(io::Error),
+
+    /// Happens when the config file is not a valid TOML document.
+    /// It holds the underlying [`toml::de::Error`].
+    Parse(toml::de::Error),
+
+    /// Happens when a key of the config file has an unexpected type, e.g. a `profile` which is not a string.
+    ///
+    /// It holds the dotted path of the key.
+    InvalidValue(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "config: io error: {}", error),
+            Error::Parse(error) => write!(f, "config: parse error: {}", error),
+            Error::InvalidValue(key) => write!(f, "config: '{}' has an invalid value", key),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(value: toml::de::Error) -> Self {
+        Self::Parse(value)
+    }
+}
+
+/// Defines the preferences of a single device in the config file.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DeviceConfig {
+    /// The UUID of the profile that is connected instead of the default ones, e.g. A2DP for a headset.
+    pub profile: Option<String>,
+}
+
+/// Defines the config file of `bt`.
+///
+/// The config file is a TOML document, which is read from `$XDG_CONFIG_HOME/bt/config.toml` (or `~/.config/bt/config.toml`).
+///
+/// The preferences of each device live under a `[devices."ADDRESS"]` table:
+///
+/// ```toml
+/// [devices."AA:BB:CC:DD:EE:FF"]
+/// # A2DP sink
+/// profile = "0000110b-0000-1000-8000-00805f9b34fb"
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Config {
+    devices: HashMap<String, DeviceConfig>,
+}
+
+impl FromStr for Config {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let table = s.parse::<toml::Table>()?;
+
+        let mut devices = HashMap::new();
+        if let Some(value) = table.get("devices") {
+            let device_tables = value
+                .as_table()
+                .ok_or_else(|| Error::InvalidValue(String::from("devices")))?;
+
+            for (address, value) in device_tables {
+                let key = format!("devices.\"{}\"", address);
+                let device_table = value
+                    .as_table()
+                    .ok_or_else(|| Error::InvalidValue(key.clone()))?;
+
+                let profile = match device_table.get("profile") {
+                    Some(profile) => Some(
+                        profile
+                            .as_str()
+                            .ok_or_else(|| Error::InvalidValue(format!("{}.profile", key)))?
+                            .to_string(),
+                    ),
+                    None => None,
+                };
+
+                devices.insert(address.to_uppercase(), DeviceConfig { profile });
+            }
+        }
+
+        Ok(Self { devices })
+    }
+}
+
+impl Config {
+    /// Loads the config file from its default location.
+    ///
+    /// If the config file does not exist, then the default [`Config`] is returned, which has no preferences.
+    ///
+    /// # Errors
+    ///
+    /// This function can return all variants of [`ConfigError`] based on given conditions. For more details, please see the error documentation.
+    ///
+    /// [`Config`]: crate::Config
+    /// [`ConfigError`]: crate::ConfigError
+    pub fn load() -> Result<Self, Error> {
+        match Self::path() {
+            Some(path) => Self::load_from(path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Loads the config file from `path`.
+    ///
+    /// Similar to [`Config::load()`], the default [`Config`] is returned if the file does not exist.
+    ///
+    /// [`Config`]: crate::Config
+    /// [`Config::load()`]: crate::Config::load()
+    pub fn load_from(path: impl Into<PathBuf>) -> Result<Self, Error> {
+        match fs::read_to_string(path.into()) {
+            Ok(content) => content.parse(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Provides the default location of the config file, which is `$XDG_CONFIG_HOME/bt/config.toml`, or `$HOME/.config/bt/config.toml` if `$XDG_CONFIG_HOME` is not set.
+    pub fn path() -> Option<PathBuf> {
+        env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .map(|dir| dir.join("bt").join("config.toml"))
+    }
+
+    /// Indicates whether the config has the preferences of any device.
+    pub fn has_devices(&self) -> bool {
+        !self.devices.is_empty()
+    }
+
+    /// Provides the preferences of the device with `address`, which is matched case-insensitively.
+    pub fn device(&self, address: &str) -> Option<&DeviceConfig> {
+        self.devices.get(&address.to_uppercase())
+    }
+
+    /// Provides the configured profile UUID of the device with `address`, if any.
+    pub fn device_profile(&self, address: &str) -> Option<&str> {
+        self.device(address)
+            .and_then(|device| device.profile.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_parse_the_device_profiles() {
+        let config = "
+[devices.\"aa:bb:cc:dd:ee:ff\"]
+profile = \"0000110b-0000-1000-8000-00805f9b34fb\"
+
+[devices.\"11:22:33:44:55:66\"]
+"
+        .parse::<Config>()
+        .unwrap();
+
+        assert_eq!(
+            config.device_profile("AA:BB:CC:DD:EE:FF"),
+            Some("0000110b-0000-1000-8000-00805f9b34fb")
+        );
+        assert_eq!(
+            config.device("11:22:33:44:55:66"),
+            Some(&DeviceConfig::default())
+        );
+        assert_eq!(config.device_profile("11:22:33:44:55:66"), None);
+        assert_eq!(config.device_profile("00:00:00:00:00:00"), None);
+    }
+
+    #[test]
+    fn it_should_fail_if_a_value_is_invalid() {
+        let result = "[devices.\"AA:BB:CC:DD:EE:FF\"]\nprofile = 1".parse::<Config>();
+        assert!(
+            matches!(result, Err(Error::InvalidValue(key)) if key == "devices.\"AA:BB:CC:DD:EE:FF\".profile")
+        );
+
+        let result = "[devices".parse::<Config>();
+        assert!(matches!(result, Err(Error::Parse(_))));
+    }
+
+    #[test]
+    fn it_should_use_the_default_config_if_the_file_does_not_exist() {
+        let config = Config::load_from("/nonexistent/bt/config.toml").unwrap();
+
+        assert!(!config.has_devices());
+    }
+}
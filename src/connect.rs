@@ -1,11 +1,24 @@
-use std::{collections::BTreeMap, error, fmt, io, num::ParseIntError, thread, time::Duration};
+use std::{
+    collections::BTreeMap,
+    error, fmt, fs, io, mem,
+    num::ParseIntError,
+    path::PathBuf,
+    thread,
+    time::{Duration, Instant},
+};
 
 use clap::Args;
+use tabled::settings::Alignment;
 
 use crate::{
-    BluezError,
-    bluez::{self},
+    BluezError, Config, History, HistoryError, ScanSnapshot,
+    bluez::{self, BluezAgent, BluezPowerState},
     format::{PrettyFormatter, TableFormattable},
+    power::{self, PowerCheck},
+    select::{
+        self, DeviceSelectionError, NameMatch, STDIN_ALIAS, SelectBy, Selector, SelectorError,
+        TargetError,
+    },
 };
 
 /// Defines error variants that may be returned from a [`connect`] call.
@@ -20,13 +33,73 @@ pub enum Error {
     /// [`BluezClient`]: crate::BluezClient
     Bluez(BluezError),
 
-    /// Happens when the user selects an invalid alias. This variant may only occur during the interactive mode.
+    /// Happens when the ALIAS is `-` and the line read from the [`io::BufRead`] is empty.
+    InvalidAlias,
+
+    /// Happens when the user keeps entering selections which do not match any device on the list, until the attempts run out. This variant may only occur during the interactive mode.
     ///
-    /// The selection is invalid when:
+    /// It holds the underlying [`DeviceSelectionError`], which lists the valid IDX range.
     ///
-    /// - User enters an index which does not exist on the list.
-    /// - User enters something other than the provided indexes.
-    InvalidAlias,
+    /// [`DeviceSelectionError`]: crate::DeviceSelectionError
+    InvalidSelection(DeviceSelectionError),
+
+    /// Happens when the input ends before a selection is read, e.g. because the pipe is closed. This variant may only occur during the interactive mode.
+    Cancelled,
+
+    /// Happens when the user selects by name, and the entered name matches more than one device on the list. This variant may only occur during the interactive mode.
+    ///
+    /// It holds the entered name.
+    AmbiguousAlias(String),
+
+    /// Happens when a selector cannot be parsed, e.g. because of an `idx:` selector which is not a number.
+    ///
+    /// It holds the underlying [`SelectorError`].
+    ///
+    /// [`SelectorError`]: crate::SelectorError
+    InvalidSelector(SelectorError),
+
+    /// Happens when a selector cannot be resolved to a device in the non-interactive mode, e.g. because of an `idx:` selector, or a `name:` selector that matches no known device.
+    ///
+    /// It holds the underlying [`TargetError`].
+    ///
+    /// [`TargetError`]: crate::TargetError
+    InvalidTarget(TargetError),
+
+    /// Happens when `args.discover` is `true`, but the ALIAS is not an ADDRESS. A device that is not known yet can only be discovered by its ADDRESS.
+    ///
+    /// It holds the ALIAS.
+    NotAnAddress(String),
+
+    /// Happens when `args.discover_if_missing` is `true`, and the device with the ALIAS cannot be found even after a discovery.
+    ///
+    /// It holds the ALIAS.
+    NotFound(String),
+
+    /// Happens when the adapter is off, and `args.auto_power` is `false`.
+    PoweredOff,
+
+    /// Happens when the connection is established, but it cannot be recorded to the history file.
+    /// It holds the underlying [`HistoryError`].
+    ///
+    /// [`HistoryError`]: crate::HistoryError
+    History(HistoryError),
+
+    /// Happens when the adapter is off because Bluetooth is blocked by an rfkill switch.
+    Blocked,
+
+    /// Happens when `args.verify_profile` is [`Some`], and the profile is not active on the device after connecting.
+    ///
+    /// It holds the UUID of the profile.
+    ///
+    /// [`Some`]: std::option::Option::Some
+    ProfileNotActive(String),
+
+    /// Happens when `args.index` is [`Some`], but the cached scan is missing, stale, or it has no device at that index.
+    ///
+    /// It holds the reason.
+    ///
+    /// [`Some`]: std::option::Option::Some
+    Cache(String),
 
     /// Happens when [`connect`] cannot write to the provided [`io::Write`] or cannot read from the provided [`io::BufRead`].
     ///
@@ -44,7 +117,37 @@ impl fmt::Display for Error {
             Error::InvalidAlias => {
                 write!(f, "connect: the selected alias is not valid")
             }
+            Error::AmbiguousAlias(name) => {
+                write!(
+                    f,
+                    "connect: the name '{}' matches more than one device",
+                    name
+                )
+            }
+            Error::NotAnAddress(alias) => write!(
+                f,
+                "connect: --discover needs the address of the device, but '{}' is not an address",
+                alias
+            ),
             Error::Io(error) => write!(f, "connect: io error: {}", error),
+            Error::InvalidSelection(error) => write!(f, "connect: {}", error),
+            Error::Cancelled => write!(f, "connect: the selection is cancelled"),
+            Error::InvalidSelector(error) => write!(f, "connect: {}", error),
+            Error::InvalidTarget(error) => write!(f, "connect: {}", error),
+            Error::NotFound(alias) => write!(
+                f,
+                "connect: no device matches '{}', even after a discovery",
+                alias
+            ),
+            Error::PoweredOff => write!(f, "connect: bluetooth is off; run 'bt toggle --on'"),
+            Error::History(error) => write!(f, "connect: {}", error),
+            Error::Blocked => write!(f, "connect: {}", BluezError::Blocked),
+            Error::ProfileNotActive(uuid) => write!(
+                f,
+                "connect: the device is connected, but the profile '{}' is not active",
+                uuid
+            ),
+            Error::Cache(reason) => write!(f, "connect: cache error: {}", reason),
         }
     }
 }
@@ -63,6 +166,33 @@ impl From<io::Error> for Error {
     }
 }
 
+impl From<HistoryError> for Error {
+    fn from(value: HistoryError) -> Self {
+        Self::History(value)
+    }
+}
+
+impl From<DeviceSelectionError> for Error {
+    fn from(value: DeviceSelectionError) -> Self {
+        Self::InvalidSelection(value)
+    }
+}
+
+impl From<SelectorError> for Error {
+    fn from(value: SelectorError) -> Self {
+        Self::InvalidSelector(value)
+    }
+}
+
+impl From<TargetError> for Error {
+    fn from(value: TargetError) -> Self {
+        match value {
+            TargetError::Bluez(error) => Self::Bluez(error),
+            error => Self::InvalidTarget(error),
+        }
+    }
+}
+
 impl From<ParseIntError> for Error {
     fn from(_: ParseIntError) -> Self {
         Self::InvalidAlias
@@ -72,11 +202,11 @@ impl From<ParseIntError> for Error {
 /// Defines the arguments that [`connect`] can take.
 ///
 /// [`connect`]: crate::connect
-#[derive(Debug, Args)]
+#[derive(Debug, Default, Args)]
 pub struct ConnectArgs {
-    /// Set the duration of the interactive scan.
+    /// Set the duration of the interactive scan, or the maximum wait of --discover in seconds.
     ///
-    /// This option has no effect if the device ALIAS is provided.
+    /// This option has no effect if the device ALIAS is provided without --discover.
     #[arg(short, long)]
     pub duration: Option<u8>,
 
@@ -86,6 +216,18 @@ pub struct ConnectArgs {
     #[arg(short, long)]
     pub contains_name: Option<String>,
 
+    /// Hide the devices that contain the name <EXCLUDE_NAME> during the interactive scan.
+    ///
+    /// It is applied after --contains-name. This option has no effect if the device ALIAS is provided.
+    #[arg(long)]
+    pub exclude_name: Option<String>,
+
+    /// Only show the devices that are already paired or bonded during the interactive scan, to avoid connecting to a random nearby device.
+    ///
+    /// This option has no effect if the device ALIAS is provided.
+    #[arg(long)]
+    pub paired_only: bool,
+
     /// Connect to a known device via its full device ALIAS.
     ///
     /// The ALIAS provided must be the full device ALIAS, unlike --contains-name.
@@ -93,15 +235,122 @@ pub struct ConnectArgs {
     /// If this argument is not provided, then connect first initiates a scan to let users choose a device ALIAS. (interactive mode)
     ///
     /// If this argument is provided, then connect does not initiate a scan and attempts to connect to a known device via ALIAS. (non-interactive mode)
+    ///
+    /// If ALIAS is `-`, then it is read from stdin instead (one line).
+    ///
+    /// ALIAS can be prefixed with `mac:` or `name:` to match the device by its ADDRESS or by its ALIAS only.
     pub alias: Option<String>,
+
+    /// Supply the PIN code of a legacy device that requests one during the connection.
+    ///
+    /// This option has no effect if the device does not request a PIN code. Without it, the PIN code is read from the BT_PAIR_PIN environment variable, if set.
+    #[arg(long, value_name = "PIN")]
+    pub input_pin: Option<String>,
+
+    /// Accept the passkey confirmation and the authorization requests of the device on its own, e.g. to pair it without a TTY.
+    ///
+    /// The requests of the other devices are rejected. Without this option, the requests are accepted if the BT_PAIR_AUTOCONFIRM environment variable is set to 1, true or yes.
+    #[arg(long)]
+    pub auto_confirm: bool,
+
+    /// Set how a device is selected during the interactive scan.
+    ///
+    /// This option has no effect if the device ALIAS is provided.
+    #[arg(long, value_enum, default_value_t = SelectBy::Index)]
+    pub select_by: SelectBy,
+
+    /// Set how many times a selection is read during the interactive scan before giving up (default 2).
+    ///
+    /// This option has no effect if the device ALIAS is provided.
+    #[arg(long, value_name = "N")]
+    pub attempts: Option<u8>,
+
+    /// Discover the device before connecting, if it is not known yet.
+    ///
+    /// The ALIAS must be the ADDRESS of the device. Connect waits until the device is found, or until --duration passes.
+    #[arg(long, requires = "alias")]
+    pub discover: bool,
+
+    /// Discover the device by its ALIAS if it is not known, e.g. because it is removed from the cache of Bluez, and retry the lookup before giving up.
+    ///
+    /// The discovery lasts up to --duration seconds, and it stops as soon as the device is found.
+    #[arg(long, requires = "alias", conflicts_with = "discover")]
+    pub discover_if_missing: bool,
+
+    /// Power on the adapter before connecting if it is off, instead of failing.
+    #[arg(long)]
+    pub auto_power: bool,
+
+    /// Connect the profile with UUID instead of the default profiles of the device, e.g. A2DP for a headset.
+    ///
+    /// It overrides the profile configured for the device in the config file.
+    #[arg(long, value_name = "UUID")]
+    pub profile: Option<String>,
+
+    /// Warn that the connection may be unstable if the signal strength of the device is below DBM after connecting (default -80).
+    #[arg(long, value_name = "DBM", allow_negative_numbers = true)]
+    pub weak_signal: Option<i16>,
+
+    /// Discover the devices on every adapter during the interactive scan, and connect the selected device through the adapter that found it.
+    ///
+    /// The scanned devices are listed with an additional ADAPTER column. This option cannot be used with ALIAS.
+    #[arg(long, conflicts_with = "alias")]
+    pub all_adapters: bool,
+
+    /// Fail if the profile with UUID is not active on the device after connecting, e.g. when A2DP does not attach to a headset.
+    #[arg(long, value_name = "UUID")]
+    pub verify_profile: Option<String>,
+
+    /// Stay running after connecting, and reconnect whenever the device disconnects, until interrupted (e.g. by Ctrl-C).
+    ///
+    /// Each reconnect attempt is printed. A failed attempt is retried with a doubling delay, up to 30 seconds.
+    #[arg(long, requires = "alias")]
+    pub keep_alive: bool,
+
+    /// Connect to the device at IDX of the scan cached by `bt scan --cache`, without scanning again.
+    ///
+    /// IDX is the position of the device in the output of the scan, starting from 0. The cached scan must be at most 10 minutes old.
+    #[arg(long, value_name = "IDX", conflicts_with_all = ["alias", "all_adapters"])]
+    pub index: Option<usize>,
+
+    /// Read the cached scan of --index from the file at PATH instead.
+    #[arg(long, value_name = "PATH", requires = "index")]
+    pub cache_file: Option<PathBuf>,
+
+    /// Retry the connection right away up to N times when it fails with a transient error, e.g. a page timeout (default 0).
+    ///
+    /// Unlike --keep-alive, the retries are not delayed, and they only cover this connection.
+    #[arg(long, value_name = "N")]
+    pub retries: Option<u8>,
+
+    /// Report how long the connection took in milliseconds, e.g. to benchmark the adapters.
+    ///
+    /// It covers the retries of --retries as well.
+    #[arg(long)]
+    pub measure: bool,
 }
 
+/// The signal strength (RSSI) below which [`connect`] warns about a weak signal.
+///
+/// [`connect`]: crate::connect
+const DEFAULT_WEAK_SIGNAL: i16 = -80;
+
+/// The maximum age of the cached scan that `--index` connects from.
+const CACHED_SCAN_MAX_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// The delay before retrying the first failed reconnect of `--keep-alive`. It is doubled after each failed attempt.
+const KEEP_ALIVE_MIN_BACKOFF: Duration = Duration::from_secs(1);
+
+/// The maximum delay between the failed reconnects of `--keep-alive`.
+const KEEP_ALIVE_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
 #[derive(Clone, Copy)]
 enum ConnectColumn {
     Idx,
     Alias,
     Address,
     Rssi,
+    Adapter,
 }
 
 impl From<&ConnectColumn> for String {
@@ -111,6 +360,7 @@ impl From<&ConnectColumn> for String {
             ConnectColumn::Alias => "ALIAS",
             ConnectColumn::Address => "ADDRESS",
             ConnectColumn::Rssi => "RSSI",
+            ConnectColumn::Adapter => "ADAPTER",
         };
 
         str.to_string()
@@ -127,6 +377,14 @@ impl TableFormattable<ConnectColumn> for (&usize, &bluez::BluezDevice) {
                 Some(rssi) => rssi.to_string(),
                 None => "-".to_string(),
             },
+            ConnectColumn::Adapter => self.1.adapter().to_string(),
+        }
+    }
+
+    fn get_alignment_by_column(column: &ConnectColumn) -> Alignment {
+        match column {
+            ConnectColumn::Rssi => Alignment::right(),
+            _ => Alignment::left(),
         }
     }
 }
@@ -138,6 +396,14 @@ const DEFAULT_LISTING_COLUMNS: [ConnectColumn; 4] = [
     ConnectColumn::Rssi,
 ];
 
+const ALL_ADAPTERS_LISTING_COLUMNS: [ConnectColumn; 5] = [
+    ConnectColumn::Idx,
+    ConnectColumn::Alias,
+    ConnectColumn::Address,
+    ConnectColumn::Rssi,
+    ConnectColumn::Adapter,
+];
+
 /// Provides the ability of establishing a connection to an available device by using a [`BluezClient`].
 ///
 /// [`connect`] has **interactive** and **non-interactive** modes and they are based on the provided [`ConnectArgs`].
@@ -150,12 +416,22 @@ const DEFAULT_LISTING_COLUMNS: [ConnectColumn; 4] = [
 ///
 /// The scanned devices can be filtered by their ALIAS by providing `args.contains_name`. This argument is expected to be a simple substring of the target ALIAS. It is NOT a regex pattern. Please see the examples for its usage.
 ///
+/// Similarly, the scanned devices whose ALIAS contains `args.exclude_name` are hidden. It is applied after `args.contains_name`, so both can be combined, e.g. to see the "LE-" devices except the "LE-Bose" ones.
+///
+/// If `args.paired_only` is `true`, then only the scanned devices that are already paired or bonded are listed. This is a safer default for keybindings, since a random nearby device cannot be selected by accident.
+///
 /// The interactive scan is blocking, similar to [`scan`]. It blocks the current thread by 5 seconds and this duration can be adjusted by setting `args.duration`. Setting `args.duration` to 0 is not recommended since a certain amount of time needs to be passed to discover available devices.
 ///
 /// When the scan is completed, the scanned devices are written to the provided [`io::Write`]. The written list is in pretty format (is a table) and has the same columns as what [`scan`] provides with the addition of IDX column. Unlike [`scan`], the columns or the formatting are not customizable.
 ///
 /// The selected IDX of a scanned device is read from the provided [`io::BufRead`].
 ///
+/// If `args.select_by` is [`SelectBy::Name`], then a part of the device ALIAS is read instead of an IDX. The name is matched case-insensitively, and it must match exactly one device on the list.
+///
+/// Regardless of `args.select_by`, the selection can be prefixed with `idx:`, `mac:` or `name:` to pick a device by its IDX, ADDRESS or ALIAS respectively. Please see [`Selector`] for more details.
+///
+/// If a selection does not match any device, then the valid IDX range is shown and the selection is read again, up to `args.attempts` times in total (2 by default).
+///
 /// Here is how the table of scanned devices looks like:
 ///
 /// ```txt
@@ -166,6 +442,11 @@ const DEFAULT_LISTING_COLUMNS: [ConnectColumn; 4] = [
 /// ```
 ///
 /// Once an IDX is selected, [`connect`] tries to establish a connection by using a [`BluezClient`].
+/// The scanned devices are ordered by their last connection in `history`, most recent first, so that the frequently used devices are at the top. The devices that are never connected are listed last, in the order of the scan.
+///
+/// The selected device is connected by its ADDRESS rather than its ALIAS, so that the right device is targeted even if more than one scanned device shares the same ALIAS.
+///
+/// If `args.all_adapters` is `true`, then the discovery is started on every adapter of the host instead of the selected one, and the scanned devices of all adapters are listed together with an additional ADAPTER column. The selected device is connected through the adapter that found it, unless a profile is connected, in which case it is looked up by its ADDRESS as usual.
 /// Upon establishing a connection, [`connect`] writes a message to the provided [`io::Write`].
 ///
 /// # Non-Interactive Mode
@@ -180,6 +461,61 @@ const DEFAULT_LISTING_COLUMNS: [ConnectColumn; 4] = [
 ///
 /// In order to see whether the device is known or not, [`list_devices`] can be used.
 ///
+/// If `args.alias` is `-`, then the ALIAS is read from the provided [`io::BufRead`] instead (a single trimmed line). This allows piping an ALIAS from other tools, and it does not trigger the interactive mode.
+///
+/// If `args.discover_if_missing` is `true` and no known device matches `args.alias`, e.g. because the device is removed from the cache of Bluez, then [`connect`] starts a device discovery and looks the device up again every second (up to `args.duration` seconds, 5 by default). If the device is still not found, then [`connect`] fails with [`ConnectError::NotFound`]. Unlike `args.discover`, the device can be looked up by its ALIAS.
+///
+/// If the device is not known by the host yet, `args.discover` can be set to `true` with the ADDRESS of the device as `args.alias`. Then, [`connect`] starts a device discovery and waits until the device is found (up to `args.duration` seconds, 5 by default) before connecting. If `args.alias` is not an ADDRESS, then [`connect`] fails with [`ConnectError::NotAnAddress`]. The discovery is stopped after the connection is established, or before an error is returned.
+///
+/// # Cached Scan
+///
+/// If `args.index` is [`Some`], then [`connect`] does not scan. Instead, it connects to the device at that index of the scan cached by [`scan`] (see `args.cache` of [`ScanArgs`]), by the ADDRESS of the device. The cached scan is read from [`ScanSnapshot::cache_path()`], unless `args.cache_file` is set. If the cached scan is missing, older than 10 minutes, or it has no device at that index, then [`connect`] fails with [`ConnectError::Cache`].
+///
+/// Bluez forgets an unpaired device shortly (about 30 seconds) after the discovery that found it. So, if the device of the cached scan is not known anymore, then [`connect`] starts a device discovery and waits until the device is found again by its ADDRESS (up to `args.duration` seconds, 5 by default), the same way as `args.discover`.
+///
+/// # Powering On
+///
+/// In both modes, [`connect`] checks whether the adapter is powered on first. If it is off, then [`connect`] fails with [`ConnectError::PoweredOff`], unless `args.auto_power` is `true`, in which case the adapter is powered on before connecting. A blocked adapter is never powered on, and [`connect`] fails with [`ConnectError::Blocked`] instead.
+///
+/// # Profiles
+///
+/// By default, [`connect`] connects the default profiles of the device. If `args.profile` is [`Some`], then only the profile with that UUID is connected instead.
+///
+/// If `args.profile` is [`None`], then the profile configured for the device in `config` is used, if any. The configured profiles are keyed by the ADDRESS of the device, so the ADDRESS of a device that is connected by its ALIAS is read from the known devices. Please see [`Config`] for the format of the config file.
+///
+/// # Verifying Profiles
+///
+/// A profile may silently fail to attach even though the connection is established. If `args.verify_profile` is [`Some`], then the device is read again after connecting, and [`connect`] fails with [`ConnectError::ProfileNotActive`] unless the device has resolved its services and provides the profile with that UUID. The connection itself is kept.
+///
+/// # Keeping Alive
+///
+/// If `args.keep_alive` is `true`, then [`connect`] does not return after connecting. Instead, it waits for the device to disconnect, which is detected by the `PropertiesChanged` signals of its `Connected` property, and reconnects it. Each reconnect attempt is written to the provided [`io::Write`]. A failed attempt is retried with a doubling delay, starting from 1 second up to 30 seconds. [`connect`] only returns once the signals end, so it is usually stopped by interrupting the process (e.g. by Ctrl-C).
+///
+/// # History
+///
+/// In both modes, each successful connection is recorded to `history` by the ADDRESS of the device, and `history` is saved. Please see [`History`] for the location of the history file.
+///
+/// Since the connection is already established by then, a failure to record it (e.g. an unwritable history file) does not fail [`connect`]. A warning is written to `warn` instead.
+///
+/// # Signal Strength
+///
+/// In both modes, the signal strength (RSSI) of the device is read after the connection is established. If it is below `args.weak_signal` (-80 dBm by default), then a warning is appended to the message, e.g. `warning: weak signal (-88 dBm), connection may be unstable`. If the RSSI is not available, then the warning is skipped.
+///
+/// # Legacy Devices
+///
+/// Some legacy devices request a fixed PIN code during the connection. In order to supply it non-interactively, `args.input_pin` can be provided in both modes.
+///
+/// If `args.input_pin` is [`Some`], then [`connect`] registers a pairing agent to Bluez which answers the PIN code request with `args.input_pin`, and unregisters it once the connection attempt is done. A failure to unregister the agent is written to `warn` as a warning, and the result of the connection is returned regardless.
+/// If the device does not request a PIN code, then `args.input_pin` has no effect.
+///
+/// For headless setups, the agent is also registered if `args.auto_confirm` is `true`. Then, it accepts the passkey confirmation and the authorization requests of the device on its own, and rejects the requests of any other device. The CLI sets `args.input_pin` and `args.auto_confirm` from the `BT_PAIR_PIN` and `BT_PAIR_AUTOCONFIRM` environment variables, if they are not provided.
+///
+/// If `args.measure` is `true`, then the time the connection took (including its retries) is appended to the success message in milliseconds, e.g. `connected to device: dev1 (connected in 412ms)`.
+///
+/// If `args.retries` is [`Some`], then a connection that fails with a transient error (see [`BluezError::is_transient()`]) is retried right away, up to `args.retries` times. The last error is returned once the retries run out.
+///
+/// A connection that fails because Bluez D-Bus is stale (see [`BluezError::is_stale()`]), e.g. after bluetoothd is restarted, is retried once after [`BluezClient.refresh()`], even without `args.retries`. This retry does not stack on the retries of `args.retries`, so the device is connected at most `args.retries + 2` times.
+///
 /// # Panics
 ///
 /// This function does not panic.
@@ -194,7 +530,7 @@ const DEFAULT_LISTING_COLUMNS: [ConnectColumn; 4] = [
 ///
 /// ```no_run
 /// use std::io;
-/// use bt::{connect, BluezClient, ConnectArgs};
+/// use bt::{connect, BluezClient, Config, ConnectArgs, History};
 ///
 /// let bluez_client = BluezClient::new().unwrap();
 /// let mut input = io::stdin();
@@ -204,11 +540,12 @@ const DEFAULT_LISTING_COLUMNS: [ConnectColumn; 4] = [
 ///     duration: None,
 ///     contains_name: None,
 ///     alias: None,
+///     ..Default::default()
 /// };
 ///
 /// // Before returning `connect_result`, [`connect`] writes the list of scanned devices to `output`.
 /// // The selection will be read from `input`.
-/// let connect_result = connect(&bluez_client, &mut output, &mut input.lock(), &args);
+/// let connect_result = connect(&bluez_client, &mut output, &mut io::stderr(), &mut input.lock(), &Config::default(), &mut History::default(), &args);
 /// match connect_result {
 ///     Ok(_) => {
 ///          // `output` contains the success message.
@@ -222,7 +559,7 @@ const DEFAULT_LISTING_COLUMNS: [ConnectColumn; 4] = [
 ///
 ///```no_run
 /// use std::io;
-/// use bt::{connect, BluezClient, ConnectArgs};
+/// use bt::{connect, BluezClient, Config, ConnectArgs, History};
 ///
 /// let bluez_client = BluezClient::new().unwrap();
 /// let mut input = io::stdin();
@@ -232,11 +569,12 @@ const DEFAULT_LISTING_COLUMNS: [ConnectColumn; 4] = [
 ///     duration: None,
 ///     contains_name: Some("dev".to_string()),
 ///     alias: None,
+///     ..Default::default()
 /// };
 ///
 /// // Before returning `connect_result`, [`connect`] writes the list of scanned devices to `output`.
 /// // The selection will be read from `input`.
-/// let connect_result = connect(&bluez_client, &mut output, &mut input.lock(), &args);
+/// let connect_result = connect(&bluez_client, &mut output, &mut io::stderr(), &mut input.lock(), &Config::default(), &mut History::default(), &args);
 /// match connect_result {
 ///     Ok(_) => {
 ///          // `output` contains the success message.
@@ -250,7 +588,7 @@ const DEFAULT_LISTING_COLUMNS: [ConnectColumn; 4] = [
 ///
 ///```no_run
 /// use std::io;
-/// use bt::{connect, BluezClient, ConnectArgs};
+/// use bt::{connect, BluezClient, Config, ConnectArgs, History};
 ///
 /// let bluez_client = BluezClient::new().unwrap();
 /// let mut input = io::stdin();
@@ -260,12 +598,13 @@ const DEFAULT_LISTING_COLUMNS: [ConnectColumn; 4] = [
 ///     duration: None,
 ///     contains_name: None,
 ///     alias: Some("known_dev".to_string()),
+///     ..Default::default()
 /// };
 ///
 /// // `connect` tries to connect to a device that has the alias "known_dev".
 /// // There is no scanning here.
 /// // `output` is only used to provide the success message.
-/// let connect_result = connect(&bluez_client, &mut output, &mut input.lock(), &args);
+/// let connect_result = connect(&bluez_client, &mut output, &mut io::stderr(), &mut input.lock(), &Config::default(), &mut History::default(), &args);
 /// match connect_result {
 ///     Ok(_) => {
 ///          // `output` contains the success message.
@@ -279,7 +618,7 @@ const DEFAULT_LISTING_COLUMNS: [ConnectColumn; 4] = [
 ///
 /// ```no_run
 /// use std::io::Cursor;
-/// use bt::{connect, BluezClient, ConnectArgs, ConnectError};
+/// use bt::{connect, BluezClient, Config, ConnectArgs, ConnectError, History};
 ///
 /// let bluez_client = BluezClient::new().unwrap();
 /// let mut input = Cursor::new([]);
@@ -289,9 +628,10 @@ const DEFAULT_LISTING_COLUMNS: [ConnectColumn; 4] = [
 ///     duration: None,
 ///     contains_name: None,
 ///     alias: Some("known_dev".to_string()),
+///     ..Default::default()
 /// };
 ///
-/// let connect_result = connect(&bluez_client, &mut output, &mut input, &args);
+/// let connect_result = connect(&bluez_client, &mut output, &mut std::io::stderr(), &mut input, &Config::default(), &mut History::default(), &args);
 /// match connect_result {
 ///     Err(ConnectError::Io(err)) => eprintln!("{}", err),
 ///     _ => unreachable!(),
@@ -302,166 +642,708 @@ const DEFAULT_LISTING_COLUMNS: [ConnectColumn; 4] = [
 /// [`io::BufRead`]: std::io::BufRead
 /// [`Some`]: std::option::Option::Some
 /// [`None`]: std::option::Option::None
+/// [`Config`]: crate::Config
+/// [`History`]: crate::History
 /// [`ConnectError`]: crate::ConnectError
+/// [`ConnectError::NotAnAddress`]: crate::ConnectError::NotAnAddress
+/// [`ConnectError::NotFound`]: crate::ConnectError::NotFound
+/// [`ConnectError::PoweredOff`]: crate::ConnectError::PoweredOff
+/// [`ConnectError::Blocked`]: crate::ConnectError::Blocked
+/// [`ConnectError::ProfileNotActive`]: crate::ConnectError::ProfileNotActive
 /// [`ConnectArgs`]: crate::ConnectArgs
+/// [`SelectBy::Name`]: crate::SelectBy::Name
+/// [`Selector`]: crate::Selector
 /// [`connect`]: crate::connect
 /// [`scan`]: crate::scan
 /// [`list_devices`]: crate::list_devices
+/// [`ScanArgs`]: crate::ScanArgs
+/// [`ScanSnapshot::cache_path()`]: crate::ScanSnapshot::cache_path()
+/// [`ConnectError::Cache`]: crate::ConnectError::Cache
+/// [`BluezError::is_transient()`]: crate::BluezError::is_transient()
+/// [`BluezError::is_stale()`]: crate::BluezError::is_stale()
+/// [`BluezClient.refresh()`]: crate::BluezClient::refresh()
 pub fn connect(
     bluez: &crate::BluezClient,
     w: &mut impl io::Write,
+    warn: &mut impl io::Write,
     r: &mut impl io::BufRead,
+    config: &Config,
+    history: &mut History,
     args: &ConnectArgs,
 ) -> Result<(), Error> {
-    let (alias, did_scan) = match &args.alias {
-        Some(a) => (a, false),
-        None => (
-            &{
-                let devices = scan_devices(bluez, &args.duration, &args.contains_name)?;
+    match power::ensure_powered(bluez, args.auto_power)? {
+        PowerCheck::On | PowerCheck::PoweredOn => {}
+        PowerCheck::Unpowered(BluezPowerState::Blocked) => return Err(Error::Blocked),
+        PowerCheck::Unpowered(_) => return Err(Error::PoweredOff),
+    }
 
-                read_device_alias(w, r, devices)?
-            },
-            true,
-        ),
+    let scan_adapters = match args.all_adapters {
+        true => Some(bluez.adapters()?),
+        false => None,
     };
 
-    bluez.connect(alias)?;
+    // NOTE: The device selected in the interactive mode is connected by its address,
+    // since more than one scanned device may share the same alias.
+    let mut discovery = Discovery::new(bluez);
+    let mut selector = None;
+    let (alias, address, adapter) = match (&args.alias, args.index) {
+        (None, Some(index)) => {
+            let device = cached_device(args, index)?;
 
-    let out_buf = format!("connected to device: {}", alias);
-    w.write_all(out_buf.as_bytes())?;
-
-    if did_scan {
-        bluez.stop_discovery()?;
-    }
+            (
+                device.alias().to_string(),
+                Some(device.address().to_string()),
+                None,
+            )
+        }
+        (Some(a), _) if a == STDIN_ALIAS => (
+            select::read_stdin_alias(r)?.ok_or(Error::InvalidAlias)?,
+            None,
+            None,
+        ),
+        (Some(a), _) => {
+            let parsed = a.parse::<Selector>()?;
+            let value = parsed.value().ok_or(TargetError::Index)?.to_string();
+            selector = Some(parsed);
 
-    Ok(())
-}
+            (value, None, None)
+        }
+        (None, None) => {
+            let devices =
+                scan_devices(bluez, warn, args, scan_adapters.as_deref(), &mut discovery)?;
+            let devices = history.sort_by_recency(devices, |d| d.address());
+            let columns: &[ConnectColumn] = match scan_adapters {
+                Some(_) => &ALL_ADAPTERS_LISTING_COLUMNS,
+                None => &DEFAULT_LISTING_COLUMNS,
+            };
+            let device = read_device(w, r, devices, columns, &args.select_by, args.attempts)?;
 
-fn scan_devices(
-    bluez: &crate::BluezClient,
-    duration: &Option<u8>,
-    contains_name: &Option<String>,
-) -> Result<Vec<bluez::BluezDevice>, Error> {
-    bluez.start_discovery()?;
+            (
+                device.alias().to_string(),
+                Some(device.address().to_string()),
+                scan_adapters.as_ref().map(|_| device.adapter().to_string()),
+            )
+        }
+    };
 
-    let scan_duration = u64::from(duration.unwrap_or(5));
-    thread::sleep(Duration::from_secs(scan_duration));
+    // NOTE: Bluez forgets an unpaired device about 30 seconds after the
+    // discovery that found it, so a device of the cached scan is discovered
+    // again by its address once it is not known anymore.
+    let discovered_address = match (&address, args.index) {
+        (None, _) if args.discover && !select::is_address(&alias) => {
+            return Err(Error::NotAnAddress(alias));
+        }
+        (None, _) if args.discover => Some(alias.as_str()),
+        (Some(address), Some(_)) if !is_known(bluez, address).unwrap_or(false) => {
+            Some(address.as_str())
+        }
+        _ => None,
+    };
+    if let Some(discovered_address) = discovered_address {
+        discovery.start()?;
 
-    let scan_result = bluez.scanned_devices()?;
-    Ok(match contains_name {
-        Some(name) => scan_result
-            .into_iter()
-            .filter(|d| d.alias().contains(name))
-            .collect(),
-        None => scan_result,
-    })
-}
+        let timeout = Duration::from_secs(u64::from(args.duration.unwrap_or(5)));
+        bluez.wait_for_device(discovered_address, timeout)?;
+    }
 
-fn read_device_alias(
-    w: &mut impl io::Write,
-    r: &mut impl io::BufRead,
-    devices: Vec<bluez::BluezDevice>,
-) -> Result<String, Error> {
-    let mut device_map: BTreeMap<usize, bluez::BluezDevice> =
-        BTreeMap::from_iter(devices.into_iter().enumerate());
+    // NOTE: If the known devices cannot be read, then the device is
+    // discovered anyway, since the discovery is the way to find it.
+    if args.discover_if_missing && !is_known(bluez, &alias).unwrap_or(false) {
+        discovery.start()?;
 
-    let devices = device_map
-        .iter()
-        .to_pretty(&DEFAULT_LISTING_COLUMNS)
-        .to_string();
+        let timeout = Duration::from_secs(u64::from(args.duration.unwrap_or(5)));
+        if !wait_until_known(bluez, &alias, timeout)? {
+            return Err(Error::NotFound(alias));
+        }
+    }
 
-    let prompt = [&devices, "\n", "Select the device you wish to connect: "].concat();
-    w.write_all(prompt.as_bytes())?;
-    w.flush()?;
+    // NOTE: A prefixed selector is resolved once the discovery is done,
+    // so that it can match a device found by the discovery.
+    let address = match &selector {
+        Some(selector @ (Selector::Address(_) | Selector::Name(_))) => {
+            Some(selector.target(bluez)?)
+        }
+        _ => address,
+    };
 
-    let mut read_buf = String::with_capacity(1);
-    r.read_line(&mut read_buf)?;
+    let target = address.as_deref().unwrap_or(&alias);
+    let profile = match &args.profile {
+        Some(profile) => Some(profile.as_str()),
+        None => configured_profile(bluez, config, target)?,
+    };
 
-    let selected_idx = read_buf.trim().parse::<u8>()?;
-    let selected_device = device_map
-        .remove(&(selected_idx as usize))
-        .ok_or(Error::InvalidAlias)?;
+    let mut agent = BluezAgent::new(args.input_pin.clone());
+    if args.auto_confirm
+        && let Some(device) = known_device_path(bluez, target, adapter.as_deref())?
+    {
+        agent = agent.auto_confirm(device);
+    }
+    let has_agent = agent.can_answer();
+    if has_agent {
+        bluez.register_agent(agent)?;
+    }
 
-    Ok(selected_device.alias().to_string())
-}
+    let connect_device = || match (profile, &adapter) {
+        (Some(uuid), _) => bluez.connect_profile(target, uuid),
+        (None, Some(adapter)) => bluez.connect_on(adapter, target),
+        (None, None) => bluez.connect(target),
+    };
+    let connect_started = Instant::now();
+    let mut connect_result = connect_device();
+    let mut retries = args.retries.unwrap_or_default();
+    let mut did_refresh = false;
+    loop {
+        match &connect_result {
+            Err(e) if e.is_stale() && !did_refresh => {
+                did_refresh = true;
+                if bluez.refresh().is_err() {
+                    break;
+                }
+            }
+            Err(e) if e.is_transient() && retries > 0 => retries -= 1,
+            _ => break,
+        }
+        connect_result = connect_device();
+    }
+    let connect_elapsed = connect_started.elapsed();
 
-#[cfg(test)]
-mod tests {
+    // NOTE: A failure to unregister the agent must not hide the result of the connection.
+    if has_agent && let Err(e) = bluez.unregister_agent() {
+        writeln!(
+            warn,
+            "connect: warning: could not unregister the pairing agent: {}",
+            e
+        )?;
+    }
 
-    use super::*;
-    use io::Cursor;
+    connect_result?;
 
-    #[test]
-    fn it_should_connect_without_scanning_if_alias_is_provided() {
-        let mut bluez = crate::BluezClient::new().unwrap();
-        // NOTE: The Bluez scan is set to err to see that scan is not
-        // executed by checking res.is_ok().
-        bluez.set_erred_method_name("start_discovery".to_string());
+    if let Some(uuid) = &args.verify_profile
+        && !is_profile_active(bluez, target, adapter.as_deref(), uuid)?
+    {
+        return Err(Error::ProfileNotActive(uuid.clone()));
+    }
 
-        let mut in_buf = Cursor::new([]);
-        let mut out_buf = Cursor::new(vec![]);
+    // NOTE: The connection is already established, so failing to record it
+    // only writes a warning.
+    if let Err(e) = record_history(bluez, history, address.as_deref(), target) {
+        writeln!(
+            warn,
+            "connect: warning: could not record the connection to the history: {}",
+            e
+        )?;
+    }
 
-        let connect_args = ConnectArgs {
-            duration: Some(0),
-            contains_name: None,
-            alias: Some("known_dev".to_string()),
-        };
+    let mut out_buf = format!("connected to device: {}", alias);
+    if args.measure {
+        out_buf.push_str(&format!(
+            " (connected in {}ms)",
+            connect_elapsed.as_millis()
+        ));
+    }
+    // NOTE: The connection is already established, so a failed RSSI read
+    // only skips the warning.
+    let weak_signal = args.weak_signal.unwrap_or(DEFAULT_WEAK_SIGNAL);
+    if let Ok(Some(rssi)) = bluez.rssi(target)
+        && rssi < weak_signal
+    {
+        out_buf.push_str(&format!(
+            "\nwarning: weak signal ({} dBm), connection may be unstable",
+            rssi
+        ));
+    }
+    w.write_all(out_buf.as_bytes())?;
 
-        let result = connect(&bluez, &mut out_buf, &mut in_buf, &connect_args);
+    discovery.stop(warn)?;
 
-        assert!(result.is_ok());
-        assert!(!out_buf.into_inner().is_empty());
+    if args.keep_alive {
+        keep_alive(bluez, w, &alias, target, connect_device)?;
     }
 
-    #[test]
-    fn it_should_run_a_scan_before_connecting_if_alias_is_not_provided() {
-        let bluez = crate::BluezClient::new().unwrap();
+    Ok(())
+}
 
-        let mut out_buf = Cursor::new(vec![]);
+/// Reconnects the device with `target` by `connect_device` whenever it disconnects, until the disconnection signals end.
+///
+/// A failed reconnect is retried after [`KEEP_ALIVE_MIN_BACKOFF`], which is doubled after each failed attempt up to [`KEEP_ALIVE_MAX_BACKOFF`].
+fn keep_alive(
+    bluez: &crate::BluezClient,
+    w: &mut impl io::Write,
+    alias: &str,
+    target: &str,
+    connect_device: impl Fn() -> Result<(), BluezError>,
+) -> Result<(), Error> {
+    // NOTE: The connection message is not terminated by a newline.
+    writeln!(w)?;
+    w.flush()?;
 
-        let user_scan_selection = String::from("0\n");
-        let mut in_buf = Cursor::new(user_scan_selection.as_bytes().to_vec());
+    while bluez.wait_for_disconnect(target)? {
+        writeln!(w, "device {} disconnected, reconnecting", alias)?;
+        w.flush()?;
 
-        let connect_args = ConnectArgs {
-            duration: Some(0),
-            contains_name: None,
-            alias: None,
-        };
+        let mut backoff = KEEP_ALIVE_MIN_BACKOFF;
+        for attempt in 1.. {
+            match connect_device() {
+                Ok(_) => {
+                    writeln!(
+                        w,
+                        "reconnect attempt {}: connected to device: {}",
+                        attempt, alias
+                    )?;
+                    w.flush()?;
+                    break;
+                }
+                Err(e) => {
+                    // NOTE: The refresh is best-effort, since a failed one
+                    // leaves the next attempt stale and it is retried anyway.
+                    if e.is_stale() {
+                        let _ = bluez.refresh();
+                    }
 
-        let result = connect(&bluez, &mut out_buf, &mut in_buf, &connect_args);
+                    writeln!(
+                        w,
+                        "reconnect attempt {}: failed, retrying in {}s: {}",
+                        attempt,
+                        backoff.as_secs(),
+                        e
+                    )?;
+                    w.flush()?;
 
-        assert!(result.is_ok());
-        assert!(!out_buf.into_inner().is_empty());
+                    thread::sleep(backoff);
+                    backoff = (backoff * 2).min(KEEP_ALIVE_MAX_BACKOFF);
+                }
+            }
+        }
     }
 
-    #[test]
-    fn it_should_fail_if_interactive_scan_fails() {
-        let mut bluez = crate::BluezClient::new().unwrap();
+    Ok(())
+}
 
-        let user_scan_selection = String::from("0\n");
-        let mut in_buf = Cursor::new(user_scan_selection.as_bytes().to_vec());
+/// Looks up the profile configured for the device with `target`, which is either an ALIAS or an ADDRESS.
+///
+/// The known devices are only read if any device is configured.
+fn configured_profile<'a>(
+    bluez: &crate::BluezClient,
+    config: &'a Config,
+    target: &str,
+) -> Result<Option<&'a str>, Error> {
+    if !config.has_devices() {
+        return Ok(None);
+    }
 
-        let connect_args = ConnectArgs {
-            duration: Some(0),
-            contains_name: None,
-            alias: None,
-        };
+    Ok(config.device_profile(&known_address(bluez, target)?))
+}
 
-        for scan_err in ["start_discovery", "scanned_devices", "stop_discovery"] {
-            bluez.set_erred_method_name(scan_err.to_string());
-            let mut out_buf = Cursor::new(vec![]);
+/// Records the connection to the device with `target` to `history` by its ADDRESS, and saves `history`.
+///
+/// If `address` is [`None`], then the ADDRESS is read from the known devices.
+fn record_history(
+    bluez: &crate::BluezClient,
+    history: &mut History,
+    address: Option<&str>,
+    target: &str,
+) -> Result<(), Box<dyn error::Error>> {
+    let address = match address {
+        Some(address) => address.to_string(),
+        None => known_address(bluez, target)?,
+    };
+    history.record(&address);
+    history.save()?;
 
-            let result = connect(&bluez, &mut out_buf, &mut in_buf, &connect_args);
+    Ok(())
+}
 
-            assert!(result.is_err());
+/// Provides the D-Bus object path of the known device with `target`, which is either an ALIAS or an ADDRESS. If `adapter` is [`Some`], then only the device registered to that adapter is looked up.
+fn known_device_path(
+    bluez: &crate::BluezClient,
+    target: &str,
+    adapter: Option<&str>,
+) -> Result<Option<String>, Error> {
+    Ok(bluez
+        .devices()?
+        .into_iter()
+        .filter(|d| adapter.is_none_or(|adapter| d.adapter() == adapter))
+        .find(|d| d.alias() == target || d.address().eq_ignore_ascii_case(target))
+        .map(|d| d.path()))
+}
 
-            if scan_err != "stop_discovery" {
-                assert!(out_buf.into_inner().is_empty());
-            } else {
-                assert!(!out_buf.into_inner().is_empty());
-            }
-        }
-    }
+/// Provides the ADDRESS of the known device with `target`, which is either an ALIAS or an ADDRESS.
+///
+/// If the device is not known, then `target` itself is provided.
+fn known_address(bluez: &crate::BluezClient, target: &str) -> Result<String, BluezError> {
+    Ok(bluez
+        .devices()?
+        .into_iter()
+        .find(|d| d.alias() == target || d.address().eq_ignore_ascii_case(target))
+        .map(|d| d.address().to_string())
+        .unwrap_or_else(|| target.to_string()))
+}
+
+/// Indicates whether the profile with `uuid` is active on the device with `target`, which is either an ALIAS or an ADDRESS.
+///
+/// A profile is active if the device is connected with its services resolved, and the device provides the profile among its UUIDs (case-insensitive). If `adapter` is [`Some`], then only the device registered to that adapter is checked.
+fn is_profile_active(
+    bluez: &crate::BluezClient,
+    target: &str,
+    adapter: Option<&str>,
+    uuid: &str,
+) -> Result<bool, Error> {
+    Ok(bluez
+        .devices()?
+        .iter()
+        .filter(|d| adapter.is_none_or(|adapter| d.adapter() == adapter))
+        .find(|d| d.alias() == target || d.address().eq_ignore_ascii_case(target))
+        .is_some_and(|d| {
+            d.connection_state() == bluez::BluezConnectionState::Connected
+                && d.uuids().iter().any(|u| u.eq_ignore_ascii_case(uuid))
+        }))
+}
+
+/// Provides the device at `index` of the cached scan, which is read from `args.cache_file` if it is set.
+fn cached_device(args: &ConnectArgs, index: usize) -> Result<bluez::BluezDevice, Error> {
+    let path = args
+        .cache_file
+        .clone()
+        .or_else(ScanSnapshot::cache_path)
+        .ok_or_else(|| Error::Cache(String::from("neither $XDG_STATE_HOME nor $HOME is set")))?;
+    let no_cache = || {
+        Error::Cache(format!(
+            "no scan is cached in {}, cache one with 'bt scan --cache'",
+            path.display()
+        ))
+    };
+
+    let age = fs::metadata(&path)
+        .and_then(|metadata| metadata.modified())
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => no_cache(),
+            _ => Error::Io(e),
+        })?
+        .elapsed()
+        .unwrap_or_default();
+    if age > CACHED_SCAN_MAX_AGE {
+        return Err(Error::Cache(String::from(
+            "the cached scan is stale, cache a new one with 'bt scan --cache'",
+        )));
+    }
+
+    let snapshot = ScanSnapshot::load_from(&path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => no_cache(),
+        _ => Error::Io(e),
+    })?;
+    let device_count = snapshot.devices().len();
+
+    snapshot.devices().get(index).cloned().ok_or_else(|| {
+        Error::Cache(format!(
+            "the cached scan has no device at index {} ({} device(s) cached)",
+            index, device_count
+        ))
+    })
+}
+
+/// Indicates whether the device with `target`, which is either an ALIAS or an ADDRESS, is known by the host.
+fn is_known(bluez: &crate::BluezClient, target: &str) -> Result<bool, Error> {
+    Ok(bluez
+        .devices()?
+        .iter()
+        .any(|d| d.alias() == target || d.address().eq_ignore_ascii_case(target)))
+}
+
+/// Polls the known devices every second until the device with `target` is found, or until `timeout` passes.
+///
+/// The devices are checked at least once, even if `timeout` is zero.
+fn wait_until_known(
+    bluez: &crate::BluezClient,
+    target: &str,
+    timeout: Duration,
+) -> Result<bool, Error> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if is_known(bluez, target)? {
+            return Ok(true);
+        }
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Ok(false);
+        }
+
+        thread::sleep(remaining.min(Duration::from_secs(1)));
+    }
+}
+
+/// Scans the available devices on the selected adapter, or on each of `adapters` if it is [`Some`].
+///
+/// An adapter whose discovery cannot be started is skipped with a warning, unless none of `adapters` can be started. The started discovery is kept in `discovery`, so that only the started adapters are stopped afterwards.
+fn scan_devices(
+    bluez: &crate::BluezClient,
+    warn: &mut impl io::Write,
+    args: &ConnectArgs,
+    adapters: Option<&[String]>,
+    discovery: &mut Discovery,
+) -> Result<Vec<bluez::BluezDevice>, Error> {
+    match adapters {
+        Some(adapters) => {
+            let mut failures = vec![];
+            for adapter in adapters {
+                if let Err(e) = discovery.start_on(adapter) {
+                    failures.push((adapter, e));
+                }
+            }
+
+            if discovery.adapters.is_empty() && !failures.is_empty() {
+                return Err(failures.swap_remove(0).1.into());
+            }
+            for (adapter, e) in failures {
+                writeln!(
+                    warn,
+                    "connect: warning: could not start the discovery on adapter {}: {}",
+                    adapter, e
+                )?;
+            }
+        }
+        None => discovery.start()?,
+    }
+
+    let scan_duration = u64::from(args.duration.unwrap_or(5));
+    thread::sleep(Duration::from_secs(scan_duration));
+
+    Ok(bluez
+        .scanned_devices()?
+        .into_iter()
+        .filter(|d| !args.paired_only || d.paired() || d.bonded())
+        .filter(|d| {
+            select::filter_by_name(
+                d.alias(),
+                args.contains_name.as_deref(),
+                args.exclude_name.as_deref(),
+            )
+        })
+        .collect())
+}
+
+/// Keeps track of the discovery started by [`connect`], so that it does not outlive [`connect`].
+///
+/// Once the device is connected, the discovery is stopped by [`Discovery::stop()`]. If [`connect`] returns early instead, e.g. because the connection fails, then the discovery is stopped on a best-effort basis when [`Discovery`] is dropped.
+///
+/// [`connect`]: crate::connect
+struct Discovery<'a> {
+    bluez: &'a crate::BluezClient,
+    is_started: bool,
+    adapters: Vec<String>,
+}
+
+impl<'a> Discovery<'a> {
+    fn new(bluez: &'a crate::BluezClient) -> Self {
+        Self {
+            bluez,
+            is_started: false,
+            adapters: vec![],
+        }
+    }
+
+    /// Starts the discovery on the selected adapter.
+    fn start(&mut self) -> Result<(), BluezError> {
+        self.bluez.start_discovery()?;
+        self.is_started = true;
+
+        Ok(())
+    }
+
+    /// Starts the discovery on `adapter`.
+    fn start_on(&mut self, adapter: &str) -> Result<(), BluezError> {
+        self.bluez.start_discovery_on(adapter)?;
+        self.adapters.push(adapter.to_string());
+
+        Ok(())
+    }
+
+    /// Stops each started discovery. A failure to stop one of the adapters is only a warning, so that the other adapters are still stopped.
+    fn stop(&mut self, warn: &mut impl io::Write) -> Result<(), Error> {
+        stop_discovery_on_each(self.bluez, warn, &mem::take(&mut self.adapters))?;
+        if mem::take(&mut self.is_started) {
+            self.bluez.stop_discovery()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Discovery<'_> {
+    fn drop(&mut self) {
+        // NOTE: A failure to stop the discovery must not hide why connect
+        // returns early.
+        for adapter in &self.adapters {
+            let _ = self.bluez.stop_discovery_on(adapter);
+        }
+        if self.is_started {
+            let _ = self.bluez.stop_discovery();
+        }
+    }
+}
+
+/// Stops the discovery on each of `adapters`. A failure to stop an adapter is only a warning, so that the other adapters are still stopped.
+fn stop_discovery_on_each(
+    bluez: &crate::BluezClient,
+    warn: &mut impl io::Write,
+    adapters: &[String],
+) -> Result<(), Error> {
+    for adapter in adapters {
+        if let Err(e) = bluez.stop_discovery_on(adapter) {
+            writeln!(
+                warn,
+                "connect: warning: could not stop the discovery on adapter {}: {}",
+                adapter, e
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn read_device(
+    w: &mut impl io::Write,
+    r: &mut impl io::BufRead,
+    devices: Vec<bluez::BluezDevice>,
+    columns: &[ConnectColumn],
+    select_by: &SelectBy,
+    attempts: Option<u8>,
+) -> Result<bluez::BluezDevice, Error> {
+    let mut device_map: BTreeMap<usize, bluez::BluezDevice> =
+        BTreeMap::from_iter(devices.into_iter().enumerate());
+
+    let devices = device_map.iter().to_pretty(columns).to_string();
+
+    let selected_idx = select::read_selection(
+        w,
+        r,
+        &devices,
+        "Select the device you wish to connect: ",
+        attempts.unwrap_or(select::DEFAULT_SELECT_ATTEMPTS),
+        |selection| match selection
+            .parse::<Selector>()?
+            .resolve(&device_map, select_by)
+        {
+            NameMatch::Unique(idx) => Ok(idx),
+            NameMatch::Ambiguous => Err(Error::AmbiguousAlias(selection.to_string())),
+            NameMatch::NotFound => Err(Error::from(DeviceSelectionError {
+                selection: selection.to_string(),
+                device_count: device_map.len(),
+            })),
+        },
+    )?
+    .ok_or(Error::Cancelled)?;
+    let selected_device = device_map
+        .remove(&selected_idx)
+        .ok_or(Error::InvalidAlias)?;
+
+    Ok(selected_device)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use io::Cursor;
+
+    #[test]
+    fn it_should_connect_without_scanning_if_alias_is_provided() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        // NOTE: The Bluez scan is set to err to see that scan is not
+        // executed by checking res.is_ok().
+        bluez.set_erred_method_name("start_discovery".to_string());
+
+        let mut in_buf = Cursor::new([]);
+        let mut out_buf = Cursor::new(vec![]);
+
+        let connect_args = ConnectArgs {
+            duration: Some(0),
+            contains_name: None,
+            alias: Some("known_dev".to_string()),
+            ..Default::default()
+        };
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+
+        assert!(result.is_ok());
+        assert!(!out_buf.into_inner().is_empty());
+    }
+
+    #[test]
+    fn it_should_run_a_scan_before_connecting_if_alias_is_not_provided() {
+        let bluez = crate::BluezClient::new().unwrap();
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let user_scan_selection = String::from("0\n");
+        let mut in_buf = Cursor::new(user_scan_selection.as_bytes().to_vec());
+
+        let connect_args = ConnectArgs {
+            duration: Some(0),
+            contains_name: None,
+            alias: None,
+            ..Default::default()
+        };
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+
+        assert!(result.is_ok());
+        assert!(!out_buf.into_inner().is_empty());
+    }
+
+    #[test]
+    fn it_should_fail_if_interactive_scan_fails() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+
+        let user_scan_selection = String::from("0\n");
+        let mut in_buf = Cursor::new(user_scan_selection.as_bytes().to_vec());
+
+        let connect_args = ConnectArgs {
+            duration: Some(0),
+            contains_name: None,
+            alias: None,
+            ..Default::default()
+        };
+
+        for scan_err in ["start_discovery", "scanned_devices", "stop_discovery"] {
+            bluez.set_erred_method_name(scan_err.to_string());
+            let mut out_buf = Cursor::new(vec![]);
+
+            let result = connect(
+                &bluez,
+                &mut out_buf,
+                &mut io::sink(),
+                &mut in_buf,
+                &Config::default(),
+                &mut History::default(),
+                &connect_args,
+            );
+
+            assert!(result.is_err());
+
+            if scan_err != "stop_discovery" {
+                assert!(out_buf.into_inner().is_empty());
+            } else {
+                assert!(!out_buf.into_inner().is_empty());
+            }
+        }
+    }
 
     #[test]
     fn it_should_fail_if_connect_fails() {
@@ -475,9 +1357,18 @@ mod tests {
             duration: Some(0),
             contains_name: None,
             alias: Some("known_dev".to_string()),
+            ..Default::default()
         };
 
-        let result = connect(&bluez, &mut out_buf, &mut in_buf, &connect_args);
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
 
         assert!(result.is_err());
         assert!(out_buf.into_inner().is_empty());
@@ -495,11 +1386,1669 @@ mod tests {
             duration: Some(0),
             contains_name: None,
             alias: Some("known_dev".to_string()),
+            ..Default::default()
         };
 
-        let result = connect(&bluez, &mut out_buf, &mut in_buf, &connect_args);
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
 
         assert!(result.is_err());
         assert!(out_buf.into_inner().is_empty())
     }
+
+    #[test]
+    fn it_should_answer_pin_code_requests_with_the_input_pin() {
+        let bluez = crate::BluezClient::new().unwrap();
+
+        let mut in_buf = Cursor::new([]);
+        let mut out_buf = Cursor::new(vec![]);
+
+        let connect_args = ConnectArgs {
+            alias: Some("legacy_dev".to_string()),
+            input_pin: Some("0000".to_string()),
+            ..Default::default()
+        };
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+
+        assert!(result.is_ok());
+        assert_eq!(bluez.agent_answers(), vec![Some("0000".to_string())]);
+        assert!(!bluez.is_agent_registered());
+    }
+
+    #[test]
+    fn it_should_not_register_an_agent_without_an_input_pin() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_erred_method_name("register_agent".to_string());
+
+        let mut in_buf = Cursor::new([]);
+        let mut out_buf = Cursor::new(vec![]);
+
+        let connect_args = ConnectArgs {
+            alias: Some("known_dev".to_string()),
+            ..Default::default()
+        };
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+
+        assert!(result.is_ok());
+        assert!(bluez.agent_answers().is_empty());
+    }
+
+    #[test]
+    fn it_should_select_the_device_by_index_or_by_name() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("Office Keyboard")
+                .rssi(Some(-40))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("Office Mouse")
+                .rssi(Some(-50))
+                .build(),
+        ]);
+
+        for (select_by, selection) in [(SelectBy::Index, "1\n"), (SelectBy::Name, "mOuSe\n")] {
+            let mut in_buf = Cursor::new(selection.as_bytes().to_vec());
+            let mut out_buf = Cursor::new(vec![]);
+
+            let connect_args = ConnectArgs {
+                duration: Some(0),
+                select_by,
+                ..Default::default()
+            };
+
+            let result = connect(
+                &bluez,
+                &mut out_buf,
+                &mut io::sink(),
+                &mut in_buf,
+                &Config::default(),
+                &mut History::default(),
+                &connect_args,
+            );
+            assert!(result.is_ok());
+
+            let result = String::from_utf8(out_buf.into_inner()).unwrap();
+            assert!(result.ends_with("connected to device: Office Mouse"));
+        }
+    }
+
+    #[test]
+    fn it_should_prompt_again_if_the_selection_is_invalid() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("Office Keyboard")
+                .rssi(Some(-40))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("Office Mouse")
+                .rssi(Some(-50))
+                .build(),
+        ]);
+
+        let mut in_buf = Cursor::new("5\n1\n".as_bytes().to_vec());
+        let mut out_buf = Cursor::new(vec![]);
+
+        let connect_args = ConnectArgs {
+            duration: Some(0),
+            ..Default::default()
+        };
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert!(result.contains(
+            "connect: '5' does not match any device, select an IDX between 0 and 1\nSelect the device you wish to connect: "
+        ));
+        assert!(result.ends_with("connected to device: Office Mouse"));
+    }
+
+    #[test]
+    fn it_should_fail_if_the_attempts_run_out() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("Office Keyboard")
+                .rssi(Some(-40))
+                .build(),
+        ]);
+
+        let mut in_buf = Cursor::new("5\nfoo\n0\n".as_bytes().to_vec());
+        let mut out_buf = Cursor::new(vec![]);
+
+        let connect_args = ConnectArgs {
+            duration: Some(0),
+            attempts: Some(2),
+            ..Default::default()
+        };
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+
+        assert!(matches!(
+            result,
+            Err(Error::InvalidSelection(DeviceSelectionError { selection, device_count: 1 })) if selection == "foo"
+        ));
+        assert!(bluez.connected_aliases().is_empty());
+    }
+
+    #[test]
+    fn it_should_fail_if_the_selected_name_is_ambiguous() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("Office Keyboard")
+                .rssi(Some(-40))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("Office Mouse")
+                .rssi(Some(-50))
+                .build(),
+        ]);
+
+        let mut in_buf = Cursor::new("office\n".as_bytes().to_vec());
+        let mut out_buf = Cursor::new(vec![]);
+
+        let connect_args = ConnectArgs {
+            duration: Some(0),
+            select_by: SelectBy::Name,
+            ..Default::default()
+        };
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+
+        assert!(matches!(result, Err(Error::AmbiguousAlias(name)) if name == "office"));
+    }
+
+    #[test]
+    fn it_should_read_the_alias_from_the_reader_if_alias_is_a_dash() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_erred_method_name("start_discovery".to_string());
+
+        let connect_args = ConnectArgs {
+            alias: Some("-".to_string()),
+            ..Default::default()
+        };
+
+        let mut in_buf = Cursor::new("  piped_dev \n".as_bytes().to_vec());
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "connected to device: piped_dev");
+
+        let mut in_buf = Cursor::new("\n".as_bytes().to_vec());
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(matches!(result, Err(Error::InvalidAlias)));
+    }
+
+    #[test]
+    fn it_should_connect_to_the_selected_device_by_address() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("LE-Device")
+                .address("AA:AA:AA:AA:AA:AA")
+                .rssi(Some(-40))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("LE-Device")
+                .address("BB:BB:BB:BB:BB:BB")
+                .rssi(Some(-50))
+                .build(),
+        ]);
+
+        let mut in_buf = Cursor::new("1\n".as_bytes().to_vec());
+        let mut out_buf = Cursor::new(vec![]);
+
+        let connect_args = ConnectArgs {
+            duration: Some(0),
+            ..Default::default()
+        };
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(result.is_ok());
+
+        assert_eq!(bluez.connected_aliases(), vec!["BB:BB:BB:BB:BB:BB"]);
+    }
+
+    #[test]
+    fn it_should_only_show_the_paired_devices_with_paired_only() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("Headset")
+                .address("AA:AA:AA:AA:AA:AA")
+                .paired(true)
+                .rssi(Some(-40))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("Stranger")
+                .address("BB:BB:BB:BB:BB:BB")
+                .rssi(Some(-30))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("Keyboard")
+                .address("CC:CC:CC:CC:CC:CC")
+                .bonded(true)
+                .rssi(Some(-50))
+                .build(),
+        ]);
+
+        let mut in_buf = Cursor::new("1\n".as_bytes().to_vec());
+        let mut out_buf = Cursor::new(vec![]);
+
+        let connect_args = ConnectArgs {
+            duration: Some(0),
+            paired_only: true,
+            ..Default::default()
+        };
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert!(result.contains("Headset"));
+        assert!(result.contains("Keyboard"));
+        assert!(!result.contains("Stranger"));
+
+        assert_eq!(bluez.connected_aliases(), vec!["CC:CC:CC:CC:CC:CC"]);
+    }
+
+    #[test]
+    fn it_should_connect_through_the_adapter_of_the_selected_device() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_adapters(&[("hci0", BluezPowerState::On), ("hci1", BluezPowerState::On)]);
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("Speaker")
+                .address("AA:AA:AA:AA:AA:AA")
+                .adapter("hci0")
+                .rssi(Some(-40))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("Keyboard")
+                .address("BB:BB:BB:BB:BB:BB")
+                .adapter("hci1")
+                .rssi(Some(-50))
+                .build(),
+        ]);
+
+        let mut in_buf = Cursor::new("1\n".as_bytes().to_vec());
+        let mut out_buf = Cursor::new(vec![]);
+
+        let connect_args = ConnectArgs {
+            duration: Some(0),
+            all_adapters: true,
+            ..Default::default()
+        };
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        let listing = result
+            .lines()
+            .map(|line| line.split_whitespace().collect::<Vec<&str>>())
+            .take(3)
+            .collect::<Vec<Vec<&str>>>();
+        assert_eq!(
+            listing,
+            vec![
+                vec!["IDX", "ALIAS", "ADDRESS", "RSSI", "ADAPTER"],
+                vec!["(0)", "Speaker", "AA:AA:AA:AA:AA:AA", "-40", "hci0"],
+                vec!["(1)", "Keyboard", "BB:BB:BB:BB:BB:BB", "-50", "hci1"],
+            ]
+        );
+
+        assert_eq!(
+            bluez.adapter_connections(),
+            vec![(String::from("hci1"), String::from("BB:BB:BB:BB:BB:BB"))]
+        );
+        assert!(bluez.connected_aliases().is_empty());
+        assert!(bluez.discovering_adapters().is_empty());
+    }
+
+    #[test]
+    fn it_should_discover_an_unknown_device_before_connecting() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![]);
+
+        let mut connect_args = ConnectArgs {
+            alias: Some("AA:AA:AA:AA:AA:AA".to_string()),
+            discover: true,
+            ..Default::default()
+        };
+
+        let mut in_buf = Cursor::new(vec![]);
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(matches!(result, Err(Error::Bluez(_))));
+        assert!(bluez.connected_aliases().is_empty());
+
+        bluez.set_added_addresses(vec!["AA:AA:AA:AA:AA:AA".to_string()]);
+        connect_args.duration = Some(0);
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(result.is_ok());
+        assert_eq!(bluez.connected_aliases(), vec!["AA:AA:AA:AA:AA:AA"]);
+    }
+
+    #[test]
+    fn it_should_stop_the_discovery_when_the_connection_fails() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![]);
+        bluez.set_added_addresses(vec!["AA:AA:AA:AA:AA:AA".to_string()]);
+        bluez.set_erred_method_name("connect".to_string());
+
+        let connect_args = ConnectArgs {
+            alias: Some("AA:AA:AA:AA:AA:AA".to_string()),
+            duration: Some(0),
+            discover: true,
+            ..Default::default()
+        };
+
+        let result = connect(
+            &bluez,
+            &mut Cursor::new(vec![]),
+            &mut io::sink(),
+            &mut Cursor::new(vec![]),
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(matches!(result, Err(Error::Bluez(_))));
+        assert_eq!(
+            bluez.discovery_calls(),
+            vec!["start_discovery", "stop_discovery"]
+        );
+    }
+
+    #[test]
+    fn it_should_not_discover_a_device_by_its_alias() {
+        let bluez = crate::BluezClient::new().unwrap();
+
+        let connect_args = ConnectArgs {
+            alias: Some("name:speaker".to_string()),
+            duration: Some(0),
+            discover: true,
+            ..Default::default()
+        };
+
+        let result = connect(
+            &bluez,
+            &mut Cursor::new(vec![]),
+            &mut io::sink(),
+            &mut Cursor::new(vec![]),
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(matches!(result, Err(Error::NotAnAddress(alias)) if alias == "speaker"));
+        assert!(bluez.discovery_calls().is_empty());
+    }
+
+    #[test]
+    fn it_should_reconnect_whenever_the_device_disconnects_with_keep_alive() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_disconnect_signals(2);
+
+        let connect_args = ConnectArgs {
+            alias: Some("known_dev".to_string()),
+            keep_alive: true,
+            ..Default::default()
+        };
+
+        let mut in_buf = Cursor::new(vec![]);
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(result.is_ok());
+
+        assert_eq!(bluez.connected_aliases(), vec!["known_dev"; 3]);
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(
+            result,
+            "connected to device: known_dev\n\
+             device known_dev disconnected, reconnecting\n\
+             reconnect attempt 1: connected to device: known_dev\n\
+             device known_dev disconnected, reconnecting\n\
+             reconnect attempt 1: connected to device: known_dev\n"
+        );
+    }
+
+    #[test]
+    fn it_should_warn_about_a_weak_signal() {
+        let connect_args = ConnectArgs {
+            alias: Some("dev1".to_string()),
+            ..Default::default()
+        };
+
+        for (rssi, expected) in [
+            (
+                Some(-88),
+                "connected to device: dev1\nwarning: weak signal (-88 dBm), connection may be unstable",
+            ),
+            (Some(-60), "connected to device: dev1"),
+            (None, "connected to device: dev1"),
+        ] {
+            let mut bluez = crate::BluezClient::new().unwrap();
+            bluez.set_devices(vec![
+                bluez::BluezTestDeviceBuilder::new("dev1")
+                    .address("AA:AA:AA:AA:AA:AA")
+                    .rssi(rssi)
+                    .build(),
+            ]);
+
+            let mut out_buf = Cursor::new(vec![]);
+
+            let result = connect(
+                &bluez,
+                &mut out_buf,
+                &mut io::sink(),
+                &mut Cursor::new(vec![]),
+                &Config::default(),
+                &mut History::default(),
+                &connect_args,
+            );
+            assert!(result.is_ok());
+
+            let result = String::from_utf8(out_buf.into_inner()).unwrap();
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn it_should_read_the_signal_of_the_connected_device_by_its_address() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .rssi(Some(-60))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("BB:BB:BB:BB:BB:BB")
+                .rssi(Some(-88))
+                .build(),
+        ]);
+
+        let connect_args = ConnectArgs {
+            alias: Some("mac:BB:BB:BB:BB:BB:BB".to_string()),
+            ..Default::default()
+        };
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut Cursor::new(vec![]),
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(
+            result,
+            "connected to device: BB:BB:BB:BB:BB:BB\nwarning: weak signal (-88 dBm), connection may be unstable"
+        );
+    }
+
+    #[test]
+    fn it_should_discover_a_missing_device_before_connecting() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![]);
+
+        let connect_args = ConnectArgs {
+            alias: Some("OldHeadset".to_string()),
+            duration: Some(0),
+            discover_if_missing: true,
+            ..Default::default()
+        };
+
+        let mut in_buf = Cursor::new(vec![]);
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(matches!(result, Err(Error::NotFound(alias)) if alias == "OldHeadset"));
+        assert!(bluez.connected_aliases().is_empty());
+
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![]);
+        bluez.set_discoverable_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("OldHeadset")
+                .address("AA:AA:AA:AA:AA:AA")
+                .build(),
+        ]);
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(result.is_ok());
+        assert_eq!(bluez.connected_aliases(), vec!["OldHeadset"]);
+    }
+
+    #[test]
+    fn it_should_discover_a_missing_device_on_a_best_effort_basis() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![]);
+        bluez.set_discoverable_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("OldHeadset")
+                .address("AA:AA:AA:AA:AA:AA")
+                .build(),
+        ]);
+        bluez.set_failing_calls("devices", [0], zbus::Error::InvalidReply);
+
+        let connect_args = ConnectArgs {
+            alias: Some("OldHeadset".to_string()),
+            duration: Some(0),
+            discover_if_missing: true,
+            ..Default::default()
+        };
+
+        let result = connect(
+            &bluez,
+            &mut Cursor::new(vec![]),
+            &mut io::sink(),
+            &mut Cursor::new(vec![]),
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(result.is_ok());
+        assert_eq!(bluez.connected_aliases(), vec!["OldHeadset"]);
+
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![]);
+        bluez.set_erred_method_name("stop_discovery".to_string());
+
+        let result = connect(
+            &bluez,
+            &mut Cursor::new(vec![]),
+            &mut io::sink(),
+            &mut Cursor::new(vec![]),
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(matches!(result, Err(Error::NotFound(alias)) if alias == "OldHeadset"));
+    }
+
+    #[test]
+    fn it_should_connect_to_a_device_selected_by_a_prefix() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("Office Keyboard")
+                .address("AA:AA:AA:AA:AA:AA")
+                .rssi(Some(-40))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("Office Mouse")
+                .address("BB:BB:BB:BB:BB:BB")
+                .rssi(Some(-50))
+                .build(),
+        ]);
+
+        for selection in ["idx:1\n", "mac:bb:bb:bb:bb:bb:bb\n", "name:mouse\n"] {
+            let mut in_buf = Cursor::new(selection.as_bytes().to_vec());
+            let mut out_buf = Cursor::new(vec![]);
+
+            let connect_args = ConnectArgs {
+                duration: Some(0),
+                ..Default::default()
+            };
+
+            let result = connect(
+                &bluez,
+                &mut out_buf,
+                &mut io::sink(),
+                &mut in_buf,
+                &Config::default(),
+                &mut History::default(),
+                &connect_args,
+            );
+            assert!(result.is_ok());
+        }
+
+        assert_eq!(bluez.connected_aliases(), vec!["BB:BB:BB:BB:BB:BB"; 3]);
+    }
+
+    #[test]
+    fn it_should_fail_if_the_selector_is_invalid() {
+        let bluez = crate::BluezClient::new().unwrap();
+
+        for alias in ["mac:dev", "idx:0"] {
+            let connect_args = ConnectArgs {
+                alias: Some(alias.to_string()),
+                ..Default::default()
+            };
+
+            let mut in_buf = Cursor::new(vec![]);
+            let mut out_buf = Cursor::new(vec![]);
+
+            let result = connect(
+                &bluez,
+                &mut out_buf,
+                &mut io::sink(),
+                &mut in_buf,
+                &Config::default(),
+                &mut History::default(),
+                &connect_args,
+            );
+            assert!(matches!(
+                result,
+                Err(Error::InvalidSelector(_) | Error::InvalidTarget(TargetError::Index))
+            ));
+        }
+        assert!(bluez.connected_aliases().is_empty());
+    }
+
+    #[test]
+    fn it_should_connect_to_the_device_that_a_prefixed_selector_matches() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("BB:BB:BB:BB:BB:BB")
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("BB:BB:BB:BB:BB:BB")
+                .address("CC:CC:CC:CC:CC:CC")
+                .build(),
+        ]);
+
+        for (alias, expected) in [
+            ("name:BB:BB:BB:BB:BB:BB", "CC:CC:CC:CC:CC:CC"),
+            ("mac:bb:bb:bb:bb:bb:bb", "BB:BB:BB:BB:BB:BB"),
+            ("audio:dev1", "audio:dev1"),
+        ] {
+            let connect_args = ConnectArgs {
+                alias: Some(alias.to_string()),
+                ..Default::default()
+            };
+
+            let result = connect(
+                &bluez,
+                &mut io::sink(),
+                &mut io::sink(),
+                &mut Cursor::new([]),
+                &Config::default(),
+                &mut History::default(),
+                &connect_args,
+            );
+            assert!(result.is_ok());
+            assert_eq!(bluez.connected_aliases().last().unwrap(), expected);
+        }
+
+        let connect_args = ConnectArgs {
+            alias: Some("name:bb:bb:bb:bb:bb:bb".to_string()),
+            ..Default::default()
+        };
+        let result = connect(
+            &bluez,
+            &mut io::sink(),
+            &mut io::sink(),
+            &mut Cursor::new([]),
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(matches!(
+            result,
+            Err(Error::InvalidTarget(TargetError::NotFound(_)))
+        ));
+    }
+
+    #[test]
+    fn it_should_fail_if_bluetooth_is_off() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_power_state(BluezPowerState::Off);
+
+        let connect_args = ConnectArgs {
+            alias: Some("known_dev".to_string()),
+            ..Default::default()
+        };
+
+        let mut in_buf = Cursor::new(vec![]);
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+
+        assert!(matches!(result, Err(Error::PoweredOff)));
+        assert!(bluez.connected_aliases().is_empty());
+    }
+
+    #[test]
+    fn it_should_power_on_before_connecting_with_auto_power() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_power_state(BluezPowerState::Off);
+
+        let connect_args = ConnectArgs {
+            alias: Some("known_dev".to_string()),
+            auto_power: true,
+            ..Default::default()
+        };
+
+        let mut in_buf = Cursor::new(vec![]);
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+
+        assert!(result.is_ok());
+        assert_eq!(bluez.power_state().unwrap(), BluezPowerState::On);
+        assert_eq!(bluez.connected_aliases(), vec!["known_dev"]);
+    }
+
+    #[test]
+    fn it_should_connect_the_configured_profile_of_a_device() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("headset")
+                .address("AA:AA:AA:AA:AA:AA")
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("keyboard")
+                .address("BB:BB:BB:BB:BB:BB")
+                .build(),
+        ]);
+
+        let config = "[devices.\"aa:aa:aa:aa:aa:aa\"]\nprofile = \"a2dp\""
+            .parse::<Config>()
+            .unwrap();
+
+        for alias in ["headset", "keyboard"] {
+            let connect_args = ConnectArgs {
+                alias: Some(alias.to_string()),
+                ..Default::default()
+            };
+
+            let mut in_buf = Cursor::new(vec![]);
+            let mut out_buf = Cursor::new(vec![]);
+
+            let result = connect(
+                &bluez,
+                &mut out_buf,
+                &mut io::sink(),
+                &mut in_buf,
+                &config,
+                &mut History::default(),
+                &connect_args,
+            );
+            assert!(result.is_ok());
+        }
+
+        assert_eq!(
+            bluez.connected_profiles(),
+            vec![("headset".to_string(), "a2dp".to_string())]
+        );
+        assert_eq!(bluez.connected_aliases(), vec!["keyboard"]);
+    }
+
+    #[test]
+    fn it_should_prefer_the_profile_argument_over_the_config() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("headset")
+                .address("AA:AA:AA:AA:AA:AA")
+                .build(),
+        ]);
+
+        let config = "[devices.\"AA:AA:AA:AA:AA:AA\"]\nprofile = \"a2dp\""
+            .parse::<Config>()
+            .unwrap();
+        let connect_args = ConnectArgs {
+            alias: Some("AA:AA:AA:AA:AA:AA".to_string()),
+            profile: Some("hfp".to_string()),
+            ..Default::default()
+        };
+
+        let mut in_buf = Cursor::new(vec![]);
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &config,
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(result.is_ok());
+
+        assert_eq!(
+            bluez.connected_profiles(),
+            vec![("AA:AA:AA:AA:AA:AA".to_string(), "hfp".to_string())]
+        );
+    }
+
+    #[test]
+    fn it_should_fail_if_the_verified_profile_is_not_active() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("headset")
+                .address("AA:AA:AA:AA:AA:AA")
+                .connected(true)
+                .uuids(&["0000111e-0000-1000-8000-00805f9b34fb"])
+                .build(),
+        ]);
+
+        let connect_args = ConnectArgs {
+            alias: Some("headset".to_string()),
+            verify_profile: Some("0000110b-0000-1000-8000-00805f9b34fb".to_string()),
+            ..Default::default()
+        };
+
+        let mut in_buf = Cursor::new(vec![]);
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+
+        assert!(matches!(
+            result,
+            Err(Error::ProfileNotActive(uuid)) if uuid == "0000110b-0000-1000-8000-00805f9b34fb"
+        ));
+        assert!(out_buf.into_inner().is_empty());
+    }
+
+    #[test]
+    fn it_should_connect_if_the_verified_profile_is_active() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("headset")
+                .address("AA:AA:AA:AA:AA:AA")
+                .connected(true)
+                .uuids(&[
+                    "0000111e-0000-1000-8000-00805f9b34fb",
+                    "0000110B-0000-1000-8000-00805F9B34FB",
+                ])
+                .build(),
+        ]);
+
+        let connect_args = ConnectArgs {
+            alias: Some("headset".to_string()),
+            verify_profile: Some("0000110b-0000-1000-8000-00805f9b34fb".to_string()),
+            ..Default::default()
+        };
+
+        let mut in_buf = Cursor::new(vec![]);
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "connected to device: headset");
+    }
+
+    #[test]
+    fn it_should_move_the_connected_device_to_the_top() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .rssi(Some(-40))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .address("BB:BB:BB:BB:BB:BB")
+                .rssi(Some(-50))
+                .build(),
+        ]);
+
+        let mut history = History::default();
+        history.record_at("BB:BB:BB:BB:BB:BB", 10);
+
+        let connect_args = ConnectArgs {
+            duration: Some(0),
+            ..Default::default()
+        };
+
+        // NOTE: dev2 is listed first since it is the only device in the history,
+        // and dev1 moves to the top once it is connected.
+        for selection in ["0\n", "1\n", "0\n"] {
+            let mut in_buf = Cursor::new(selection.as_bytes().to_vec());
+            let mut out_buf = Cursor::new(vec![]);
+
+            let result = connect(
+                &bluez,
+                &mut out_buf,
+                &mut io::sink(),
+                &mut in_buf,
+                &Config::default(),
+                &mut history,
+                &connect_args,
+            );
+            assert!(result.is_ok());
+        }
+
+        assert_eq!(
+            bluez.connected_aliases(),
+            vec![
+                "BB:BB:BB:BB:BB:BB",
+                "AA:AA:AA:AA:AA:AA",
+                "AA:AA:AA:AA:AA:AA"
+            ]
+        );
+        assert!(history.last_connected("AA:AA:AA:AA:AA:AA").is_some());
+    }
+
+    #[test]
+    fn it_should_warn_instead_of_failing_when_the_history_cannot_be_saved() {
+        let file = std::env::temp_dir().join(format!("bt-connect-history-{}", std::process::id()));
+        let mut history = History::load_from(&file).unwrap();
+        // NOTE: The history file is replaced by a directory, so it cannot be written.
+        std::fs::create_dir_all(&file).unwrap();
+
+        let bluez = crate::BluezClient::new().unwrap();
+
+        let connect_args = ConnectArgs {
+            alias: Some("known_dev".to_string()),
+            ..Default::default()
+        };
+
+        let mut out_buf = Cursor::new(vec![]);
+        let mut warn_buf = Cursor::new(vec![]);
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut warn_buf,
+            &mut Cursor::new(vec![]),
+            &Config::default(),
+            &mut history,
+            &connect_args,
+        );
+        std::fs::remove_dir_all(&file).unwrap();
+        assert!(result.is_ok());
+        assert_eq!(bluez.connected_aliases(), vec!["known_dev"]);
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "connected to device: known_dev");
+
+        let warning = String::from_utf8(warn_buf.into_inner()).unwrap();
+        assert!(
+            warning
+                .starts_with("connect: warning: could not record the connection to the history: ")
+        );
+    }
+
+    #[test]
+    fn it_should_return_the_connection_result_when_the_agent_cannot_be_unregistered() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_erred_method_name("unregister_agent".to_string());
+
+        let connect_args = ConnectArgs {
+            alias: Some("legacy_dev".to_string()),
+            input_pin: Some("0000".to_string()),
+            ..Default::default()
+        };
+
+        for (failing_connects, connected) in [(0, true), (1, false)] {
+            bluez.set_failing_calls(
+                "connect",
+                0..failing_connects,
+                crate::BluezClient::method_error(
+                    "org.bluez.Error.Failed",
+                    "br-connection-page-timeout",
+                ),
+            );
+
+            let mut warn_buf = Cursor::new(vec![]);
+
+            let result = connect(
+                &bluez,
+                &mut io::sink(),
+                &mut warn_buf,
+                &mut Cursor::new([]),
+                &Config::default(),
+                &mut History::default(),
+                &connect_args,
+            );
+            match connected {
+                true => assert!(result.is_ok()),
+                false => assert!(matches!(result, Err(Error::Bluez(_)))),
+            }
+
+            let warning = String::from_utf8(warn_buf.into_inner()).unwrap();
+            assert!(
+                warning.starts_with("connect: warning: could not unregister the pairing agent: ")
+            );
+        }
+    }
+
+    #[test]
+    fn it_should_stop_the_discovery_when_a_missing_device_cannot_be_connected() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![]);
+
+        let connect_args = ConnectArgs {
+            alias: Some("OldHeadset".to_string()),
+            duration: Some(0),
+            discover_if_missing: true,
+            ..Default::default()
+        };
+
+        let result = connect(
+            &bluez,
+            &mut Cursor::new(vec![]),
+            &mut io::sink(),
+            &mut Cursor::new(vec![]),
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(matches!(result, Err(Error::NotFound(_))));
+        assert_eq!(
+            bluez.discovery_calls(),
+            vec!["start_discovery", "stop_discovery"]
+        );
+
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![]);
+        bluez.set_discoverable_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("OldHeadset")
+                .address("AA:AA:AA:AA:AA:AA")
+                .build(),
+        ]);
+        bluez.set_erred_method_name("connect".to_string());
+
+        let result = connect(
+            &bluez,
+            &mut Cursor::new(vec![]),
+            &mut io::sink(),
+            &mut Cursor::new(vec![]),
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(matches!(result, Err(Error::Bluez(_))));
+        assert_eq!(
+            bluez.discovery_calls(),
+            vec!["start_discovery", "stop_discovery"]
+        );
+    }
+
+    #[test]
+    fn it_should_scan_on_the_other_adapters_when_one_adapter_fails() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_adapters(&[("hci0", BluezPowerState::On), ("hci1", BluezPowerState::On)]);
+        // NOTE: hci0 is the first adapter whose discovery is started.
+        bluez.set_failing_calls("start_discovery_on", [0], zbus::Error::InvalidReply);
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("Keyboard")
+                .address("BB:BB:BB:BB:BB:BB")
+                .adapter("hci1")
+                .rssi(Some(-50))
+                .build(),
+        ]);
+
+        let connect_args = ConnectArgs {
+            duration: Some(0),
+            all_adapters: true,
+            ..Default::default()
+        };
+
+        let mut warn_buf = Cursor::new(vec![]);
+
+        let result = connect(
+            &bluez,
+            &mut io::sink(),
+            &mut warn_buf,
+            &mut Cursor::new("0\n".as_bytes().to_vec()),
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            bluez.adapter_connections(),
+            vec![(String::from("hci1"), String::from("BB:BB:BB:BB:BB:BB"))]
+        );
+        assert!(bluez.discovering_adapters().is_empty());
+
+        let warning = String::from_utf8(warn_buf.into_inner()).unwrap();
+        assert!(
+            warning
+                .starts_with("connect: warning: could not start the discovery on adapter hci0: ")
+        );
+    }
+
+    #[test]
+    fn it_should_stop_the_started_adapters_when_the_scan_fails() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_adapters(&[("hci0", BluezPowerState::On), ("hci1", BluezPowerState::On)]);
+        bluez.set_erred_method_name("scanned_devices".to_string());
+
+        let connect_args = ConnectArgs {
+            duration: Some(0),
+            all_adapters: true,
+            ..Default::default()
+        };
+
+        let result = connect(
+            &bluez,
+            &mut io::sink(),
+            &mut io::sink(),
+            &mut Cursor::new(vec![]),
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(matches!(result, Err(Error::Bluez(_))));
+        assert!(bluez.discovering_adapters().is_empty());
+    }
+
+    #[test]
+    fn it_should_cancel_the_selection_if_the_input_is_closed() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("Office Keyboard")
+                .rssi(Some(-40))
+                .build(),
+        ]);
+
+        let mut in_buf = io::empty();
+        let mut out_buf = Cursor::new(vec![]);
+
+        let connect_args = ConnectArgs {
+            duration: Some(0),
+            ..Default::default()
+        };
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+        assert!(matches!(result, Err(Error::Cancelled)));
+        assert!(bluez.connected_aliases().is_empty());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert!(result.ends_with("Select the device you wish to connect: \n"));
+    }
+
+    #[test]
+    fn it_should_connect_to_a_device_of_the_cached_scan_by_index() {
+        let file = std::env::temp_dir()
+            .join(format!("bt-connect-cache-{}", std::process::id()))
+            .join("last-scan.json");
+
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("LE-Device")
+                .address("AA:AA:AA:AA:AA:AA")
+                .rssi(Some(-40))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("LE-Device")
+                .address("BB:BB:BB:BB:BB:BB")
+                .rssi(Some(-50))
+                .build(),
+        ]);
+
+        let scan_args = crate::ScanArgs {
+            duration: 0,
+            cache: true,
+            cache_file: Some(file.clone()),
+            ..Default::default()
+        };
+        let result = crate::scan(&bluez, &mut io::sink(), &mut io::sink(), &scan_args);
+        assert!(result.is_ok());
+
+        let run = |index| {
+            let connect_args = ConnectArgs {
+                index: Some(index),
+                cache_file: Some(file.clone()),
+                ..Default::default()
+            };
+
+            connect(
+                &bluez,
+                &mut io::sink(),
+                &mut io::sink(),
+                &mut io::empty(),
+                &Config::default(),
+                &mut History::default(),
+                &connect_args,
+            )
+        };
+
+        assert!(run(1).is_ok());
+        assert_eq!(bluez.connected_aliases(), vec!["BB:BB:BB:BB:BB:BB"]);
+
+        let result = run(2);
+        assert!(matches!(result, Err(Error::Cache(_))));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "connect: cache error: the cached scan has no device at index 2 (2 device(s) cached)"
+        );
+
+        let stale = std::time::SystemTime::now() - CACHED_SCAN_MAX_AGE * 2;
+        fs::File::options()
+            .write(true)
+            .open(&file)
+            .and_then(|f| f.set_modified(stale))
+            .unwrap();
+        assert!(matches!(run(0), Err(Error::Cache(_))));
+
+        fs::remove_dir_all(file.parent().unwrap()).unwrap();
+        assert!(matches!(run(0), Err(Error::Cache(_))));
+        assert_eq!(bluez.connected_aliases(), vec!["BB:BB:BB:BB:BB:BB"]);
+    }
+
+    #[test]
+    fn it_should_discover_a_device_of_the_cached_scan_again_once_bluez_forgets_it() {
+        let file = std::env::temp_dir()
+            .join(format!("bt-connect-forgotten-{}", std::process::id()))
+            .join("last-scan.json");
+
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("LE-Device")
+                .address("AA:AA:AA:AA:AA:AA")
+                .rssi(Some(-40))
+                .build(),
+        ]);
+
+        let scan_args = crate::ScanArgs {
+            duration: 0,
+            cache: true,
+            cache_file: Some(file.clone()),
+            ..Default::default()
+        };
+        let result = crate::scan(&bluez, &mut io::sink(), &mut io::sink(), &scan_args);
+        assert!(result.is_ok());
+
+        // NOTE: Bluez purges the unpaired device after the scan.
+        bluez.set_devices(vec![]);
+
+        let connect_args = ConnectArgs {
+            index: Some(0),
+            cache_file: Some(file.clone()),
+            duration: Some(0),
+            ..Default::default()
+        };
+        let run = |bluez: &crate::BluezClient| {
+            connect(
+                bluez,
+                &mut io::sink(),
+                &mut io::sink(),
+                &mut io::empty(),
+                &Config::default(),
+                &mut History::default(),
+                &connect_args,
+            )
+        };
+
+        assert!(matches!(run(&bluez), Err(Error::Bluez(_))));
+        assert!(bluez.connected_aliases().is_empty());
+
+        bluez.set_added_addresses(vec!["AA:AA:AA:AA:AA:AA".to_string()]);
+        let result = run(&bluez);
+        fs::remove_dir_all(file.parent().unwrap()).unwrap();
+
+        assert!(result.is_ok());
+        assert_eq!(bluez.connected_aliases(), vec!["AA:AA:AA:AA:AA:AA"]);
+        assert_eq!(
+            bluez.discovery_calls().split_off(2),
+            vec![
+                String::from("start_discovery"),
+                String::from("stop_discovery"),
+                String::from("start_discovery"),
+                String::from("stop_discovery")
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_retry_a_connection_that_fails_with_a_transient_error() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_failing_calls(
+            "connect",
+            0..2,
+            crate::BluezClient::method_error(
+                "org.bluez.Error.Failed",
+                "br-connection-page-timeout",
+            ),
+        );
+
+        let mut in_buf = Cursor::new([]);
+        let mut out_buf = Cursor::new(vec![]);
+
+        let connect_args = ConnectArgs {
+            alias: Some("known_dev".to_string()),
+            retries: Some(2),
+            ..Default::default()
+        };
+
+        let result = connect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+
+        assert!(result.is_ok());
+        assert_eq!(bluez.connected_aliases(), vec![String::from("known_dev")]);
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "connected to device: known_dev");
+
+        bluez.set_failing_calls(
+            "connect",
+            0..2,
+            crate::BluezClient::method_error(
+                "org.bluez.Error.Failed",
+                "br-connection-page-timeout",
+            ),
+        );
+        let connect_args = ConnectArgs {
+            alias: Some("known_dev".to_string()),
+            retries: Some(1),
+            ..Default::default()
+        };
+
+        let result = connect(
+            &bluez,
+            &mut Cursor::new(vec![]),
+            &mut io::sink(),
+            &mut Cursor::new([]),
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn it_should_connect_through_a_refresh_without_stacking_the_retries() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+
+        let run = |bluez: &crate::BluezClient, retries| {
+            let connect_args = ConnectArgs {
+                alias: Some("known_dev".to_string()),
+                retries,
+                ..Default::default()
+            };
+
+            connect(
+                bluez,
+                &mut io::sink(),
+                &mut io::sink(),
+                &mut Cursor::new([]),
+                &Config::default(),
+                &mut History::default(),
+                &connect_args,
+            )
+        };
+
+        bluez.set_failing_calls(
+            "connect",
+            [0],
+            zbus::Error::FDO(Box::new(zbus::fdo::Error::ServiceUnknown(String::from(
+                "org.bluez",
+            )))),
+        );
+        assert!(run(&bluez, None).is_ok());
+        assert_eq!(bluez.refreshes(), 1);
+        assert_eq!(bluez.connected_aliases(), vec!["known_dev"]);
+
+        // A connection that is still stale after the refresh is not refreshed again.
+        bluez.set_failing_calls(
+            "connect",
+            0..2,
+            zbus::Error::FDO(Box::new(zbus::fdo::Error::ServiceUnknown(String::from(
+                "org.bluez",
+            )))),
+        );
+        assert!(run(&bluez, None).is_err());
+        assert_eq!(bluez.refreshes(), 2);
+
+        // The retries cover the transient failures after the refresh, but not
+        // another stale connection.
+        bluez.set_failing_calls(
+            "connect",
+            [0],
+            zbus::Error::FDO(Box::new(zbus::fdo::Error::ServiceUnknown(String::from(
+                "org.bluez",
+            )))),
+        );
+        bluez.set_failing_calls(
+            "connect",
+            [1],
+            crate::BluezClient::method_error(
+                "org.bluez.Error.Failed",
+                "br-connection-page-timeout",
+            ),
+        );
+        assert!(run(&bluez, Some(1)).is_ok());
+        assert_eq!(bluez.refreshes(), 3);
+        assert_eq!(bluez.connected_aliases(), vec!["known_dev", "known_dev"]);
+    }
+
+    #[test]
+    fn it_should_report_the_connection_latency_with_measure() {
+        let bluez = crate::BluezClient::new().unwrap();
+
+        for (measure, has_latency) in [(false, false), (true, true)] {
+            let mut out_buf = Cursor::new(vec![]);
+
+            let connect_args = ConnectArgs {
+                alias: Some("known_dev".to_string()),
+                measure,
+                ..Default::default()
+            };
+
+            let result = connect(
+                &bluez,
+                &mut out_buf,
+                &mut io::sink(),
+                &mut Cursor::new([]),
+                &Config::default(),
+                &mut History::default(),
+                &connect_args,
+            );
+            assert!(result.is_ok());
+
+            let result = String::from_utf8(out_buf.into_inner()).unwrap();
+            let latency = result
+                .strip_prefix("connected to device: known_dev (connected in ")
+                .and_then(|rest| rest.strip_suffix("ms)"));
+            assert_eq!(
+                latency.is_some_and(|ms| ms.parse::<u128>().is_ok()),
+                has_latency
+            );
+        }
+    }
+
+    #[test]
+    fn it_should_confirm_the_pairing_of_the_target_device_only_with_auto_confirm() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .address("BB:BB:BB:BB:BB:BB")
+                .build(),
+        ]);
+
+        let connect_args = ConnectArgs {
+            alias: Some("dev1".to_string()),
+            auto_confirm: true,
+            ..Default::default()
+        };
+
+        let result = connect(
+            &bluez,
+            &mut io::sink(),
+            &mut io::sink(),
+            &mut Cursor::new([]),
+            &Config::default(),
+            &mut History::default(),
+            &connect_args,
+        );
+
+        assert!(result.is_ok());
+        assert_eq!(bluez.agent_confirmations(), vec![true]);
+        assert_eq!(bluez.agent_answers(), vec![None]);
+        assert!(!bluez.is_agent_registered());
+    }
 }
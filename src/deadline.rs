@@ -0,0 +1,102 @@
+use std::{error, fmt, sync::mpsc, thread, time::Duration};
+
+/// Defines error variants that may be returned from a [`with_deadline`] call.
+///
+/// [`with_deadline`]: crate::with_deadline
+#[derive(Debug)]
+pub enum Error {
+    /// Happens when the operation does not finish before the deadline.
+    /// It holds the deadline.
+    Timeout(Duration),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Timeout(deadline) => write!(
+                f,
+                "deadline: the command did not finish in {} seconds",
+                deadline.as_secs()
+            ),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// Runs `operation` on a worker thread, and waits for its result until `deadline`.
+///
+/// This bounds every blocking call of the operation, such as the Bluez D-Bus calls or the scan duration, without changing them.
+///
+/// If the deadline passes first, the worker thread is detached and left running. Callers are expected to exit shortly after, which ends the operation as well.
+///
+/// # Panics
+///
+/// This function panics if `operation` panics.
+///
+/// # Errors
+///
+/// This function can return all variants of [`DeadlineError`] based on given conditions. For more details, please see the error documentation.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use bt::{with_deadline, BluezClient};
+///
+/// let result = with_deadline(Duration::from_secs(10), || {
+///     let bluez_client = BluezClient::new().unwrap();
+///     bluez_client.is_powered().is_ok()
+/// });
+///
+/// match result {
+///     Ok(is_ok) => println!("finished in time: {}", is_ok),
+///     Err(e) => eprintln!("{}", e),
+/// }
+/// ```
+///
+/// [`DeadlineError`]: crate::DeadlineError
+pub fn with_deadline<T>(
+    deadline: Duration,
+    operation: impl FnOnce() -> T + Send + 'static,
+) -> Result<T, Error>
+where
+    T: Send + 'static,
+{
+    let (tx, rx) = mpsc::channel();
+
+    let worker = thread::spawn(move || {
+        // NOTE: The receiver is gone if the deadline has already passed.
+        let _ = tx.send(operation());
+    });
+
+    match rx.recv_timeout(deadline) {
+        Ok(result) => Ok(result),
+        Err(mpsc::RecvTimeoutError::Timeout) => Err(Error::Timeout(deadline)),
+        Err(mpsc::RecvTimeoutError::Disconnected) => match worker.join() {
+            Err(panic) => std::panic::resume_unwind(panic),
+            Ok(_) => unreachable!("the worker sends its result before it finishes"),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_return_the_result_before_the_deadline() {
+        let result = with_deadline(Duration::from_secs(5), || 42);
+
+        assert_eq!(result.unwrap(), 42);
+    }
+
+    #[test]
+    fn it_should_time_out_if_the_operation_exceeds_the_deadline() {
+        let result = with_deadline(Duration::from_millis(10), || {
+            thread::sleep(Duration::from_secs(1));
+        });
+
+        assert!(matches!(result, Err(Error::Timeout(_))));
+    }
+}
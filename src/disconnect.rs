@@ -1,8 +1,20 @@
-use std::{collections::BTreeMap, error, fmt, io, num::ParseIntError};
+use std::{
+    collections::BTreeMap,
+    error, fmt, io,
+    num::ParseIntError,
+    thread,
+    time::{Duration, Instant},
+};
+
+use clap::Args;
 
 use crate::{
     BluezError, bluez,
-    format::{PrettyFormatter, TableFormattable},
+    format::{self, PrettyFormatter, TableFormattable},
+    select::{
+        self, DeviceSelectionError, NameMatch, STDIN_ALIAS, SelectBy, Selector, SelectorError,
+        TargetError,
+    },
 };
 
 /// Defines error variants that may be returned from a [`disconnect`] call.
@@ -17,17 +29,66 @@ pub enum Error {
     /// [`BluezClient`]: crate::BluezClient
     Bluez(BluezError),
 
-    /// Happens when the user selects an invalid alias. This variant may only occur during the interactive mode.
+    /// Happens when the user selects an invalid alias. This variant may occur during the interactive mode, or when an ALIAS is read from the [`io::BufRead`].
     ///
     /// The selection is invalid when:
     ///
-    /// - User enters an index which does not exist on the list.
-    /// - User enters something other than the provided indexes.
+    /// - User selects the same device more than once.
+    /// - An ALIAS is `-` and the line read from the [`io::BufRead`] is empty.
     InvalidAlias,
 
+    /// Happens when the user keeps entering selections which do not match any device on the list, until the attempts run out. This variant may only occur during the interactive mode.
+    ///
+    /// It holds the underlying [`DeviceSelectionError`], which lists the valid IDX range.
+    ///
+    /// [`DeviceSelectionError`]: crate::DeviceSelectionError
+    InvalidSelection(DeviceSelectionError),
+
+    /// Happens when the input ends before a selection is read, e.g. because the pipe is closed. This variant may only occur during the interactive mode.
+    Cancelled,
+
+    /// Happens when the user selects by name, and an entered name matches more than one device on the list. This variant may only occur during the interactive mode.
+    ///
+    /// It holds the entered name.
+    AmbiguousAlias(String),
+
     /// Happens when there are no connected devices on the host to disconnect from. This variant may only occur during the interactive mode.
     NoConnectedDevices,
 
+    /// Happens when `args.match_regex` is not a valid regex.
+    ///
+    /// It holds the underlying [`regex::Error`].
+    InvalidRegex(regex::Error),
+
+    /// Happens when no connected device matches `args.match_regex`.
+    ///
+    /// It holds the regex.
+    NoMatch(String),
+
+    /// Happens when `args.summary` is `true`, and some of the devices could not be disconnected (or removed). The summary is written before this error is returned.
+    ///
+    /// It holds the number of failed devices.
+    BatchFailed(usize),
+
+    /// Happens when `args.wait` is `true`, and a device is still connected once `args.timeout` passes. The device is disconnected, but the link has not dropped yet.
+    ///
+    /// It holds the alias of the device.
+    NotDisconnected(String),
+
+    /// Happens when a selector cannot be parsed, e.g. because of an `idx:` selector which is not a number.
+    ///
+    /// It holds the underlying [`SelectorError`].
+    ///
+    /// [`SelectorError`]: crate::SelectorError
+    InvalidSelector(SelectorError),
+
+    /// Happens when a selector cannot be resolved to a device in the non-interactive mode, e.g. because of an `idx:` selector, or a `name:` selector that matches no known device.
+    ///
+    /// It holds the underlying [`TargetError`].
+    ///
+    /// [`TargetError`]: crate::TargetError
+    InvalidTarget(TargetError),
+
     /// Happens when [`disconnect`] cannot write to the provided [`io::Write`] or cannot read from the provided [`io::BufRead`].
     ///
     /// It holds the underlying [`io::Error`].
@@ -41,12 +102,37 @@ impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Error::InvalidAlias => write!(f, "disconnect: the provided alias is invalid"),
+            Error::AmbiguousAlias(name) => write!(
+                f,
+                "disconnect: the name '{}' matches more than one device",
+                name
+            ),
             Error::Io(error) => write!(f, "disconnect: io error: {}", error),
+            Error::InvalidSelection(error) => write!(f, "disconnect: {}", error),
+            Error::Cancelled => write!(f, "disconnect: the selection is cancelled"),
+            Error::InvalidSelector(error) => write!(f, "disconnect: {}", error),
+            Error::InvalidTarget(error) => write!(f, "disconnect: {}", error),
+            Error::InvalidRegex(error) => write!(f, "disconnect: invalid regex: {}", error),
+            Error::NoMatch(pattern) => {
+                write!(f, "disconnect: no connected device matches '{}'", pattern)
+            }
+            Error::NotDisconnected(alias) => write!(
+                f,
+                "disconnect: device {} is still connected after the timeout",
+                alias
+            ),
             Error::NoConnectedDevices => write!(
                 f,
                 "disconnect: there are no connected devices to disconnect"
             ),
             Error::Bluez(error) => write!(f, "disconnect: bluez error: {}", error),
+            Error::BatchFailed(failed) => {
+                write!(
+                    f,
+                    "disconnect: {} device(s) could not be disconnected",
+                    failed
+                )
+            }
         }
     }
 }
@@ -65,12 +151,102 @@ impl From<io::Error> for Error {
     }
 }
 
+impl From<DeviceSelectionError> for Error {
+    fn from(value: DeviceSelectionError) -> Self {
+        Self::InvalidSelection(value)
+    }
+}
+
+impl From<regex::Error> for Error {
+    fn from(value: regex::Error) -> Self {
+        Self::InvalidRegex(value)
+    }
+}
+
+impl From<SelectorError> for Error {
+    fn from(value: SelectorError) -> Self {
+        Self::InvalidSelector(value)
+    }
+}
+
+impl From<TargetError> for Error {
+    fn from(value: TargetError) -> Self {
+        match value {
+            TargetError::Bluez(error) => Self::Bluez(error),
+            error => Self::InvalidTarget(error),
+        }
+    }
+}
+
 impl From<ParseIntError> for Error {
     fn from(_: ParseIntError) -> Self {
         Self::InvalidAlias
     }
 }
 
+/// Defines the arguments that [`disconnect`] can take.
+///
+/// [`disconnect`]: crate::disconnect
+#[derive(Debug, Default, Args)]
+pub struct DisconnectArgs {
+    /// Remove the device(s) from the known devices list.
+    #[arg(short, long, default_value_t = false)]
+    pub force: bool,
+
+    /// Set how the device(s) are selected from the list of connected devices.
+    ///
+    /// This option has no effect if ALIAS(es) are provided.
+    #[arg(long, value_enum, default_value_t = SelectBy::Index)]
+    pub select_by: SelectBy,
+
+    /// Set how many times the selection is read from the list of connected devices before giving up (default 2).
+    ///
+    /// This option has no effect if ALIAS(es) are provided.
+    #[arg(long, value_name = "N")]
+    pub attempts: Option<u8>,
+
+    /// Keep going when a device fails, and print the outcome counts at the end, e.g. `disconnected=2 failed=1`.
+    #[arg(long, default_value_t = false)]
+    pub summary: bool,
+
+    /// Print the summary as JSON, e.g. `{"ok":2,"failed":1}`.
+    #[arg(long, default_value_t = false, requires = "summary")]
+    pub json: bool,
+
+    /// Disconnect by specifying the full ALIAS of device(s).
+    ///
+    /// If this argument is not provided, then disconnect first shows the list of connected devices to let users choose. (interactive mode)
+    ///
+    /// If this argument is provided, then disconnect does not show the list. (non-interactive mode)
+    ///
+    /// If an ALIAS is `-`, then it is read from stdin instead (one line).
+    ///
+    /// An ALIAS can be prefixed with `mac:` or `name:` to match the device by its ADDRESS or by its ALIAS only.
+    #[arg(value_name = "ALIAS", value_delimiter = ',', num_args = 0.., default_value = None)]
+    pub aliases: Option<Vec<String>>,
+
+    /// Wait until the link of each device actually drops before moving on.
+    #[arg(long, default_value_t = false)]
+    pub wait: bool,
+
+    /// Set how long --wait waits for each device in seconds (default 5).
+    #[arg(long, value_name = "SECS", requires = "wait")]
+    pub timeout: Option<u8>,
+
+    /// Disconnect from every connected device whose ALIAS matches REGEX as a whole, instead of specifying each ALIAS. (non-interactive mode)
+    #[arg(long = "match", value_name = "REGEX", conflicts_with = "aliases")]
+    pub match_regex: Option<String>,
+
+    /// Untrust each device after disconnecting from it, so that it does not reconnect on its own.
+    ///
+    /// A device that cannot be untrusted is reported as a warning, and it is still counted as disconnected.
+    #[arg(long, default_value_t = false, conflicts_with = "force")]
+    pub untrust: bool,
+}
+
+/// The interval between the reads of the `Connected` property of a device while waiting for its link to drop.
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
 const DEFAULT_LISTING_COLUMNS: [DisconnectColumn; 3] = [
     DisconnectColumn::Idx,
     DisconnectColumn::Alias,
@@ -108,11 +284,11 @@ impl TableFormattable<DisconnectColumn> for (&usize, &bluez::BluezDevice) {
 
 /// Provides the ability of disconnecting from a connected device by using a [`BluezClient`].
 ///
-/// [`disconnect`] has **interactive** and **non-interactive** modes and they are based on the provided `aliases`.
+/// [`disconnect`] has **interactive** and **non-interactive** modes and they are based on the provided `args.aliases`.
 ///
 /// # Interactive Mode
 ///
-/// [`disconnect`] runs interactively if `aliases` is [`None`].
+/// [`disconnect`] runs interactively if `args.aliases` is [`None`].
 ///
 /// In this mode, [`disconnect`] fetches the connected devices first to find out the device to disconnect from.
 ///
@@ -120,6 +296,12 @@ impl TableFormattable<DisconnectColumn> for (&usize, &bluez::BluezDevice) {
 ///
 /// The selected IDX of a connected device is read from the provided [`io::BufRead`].
 ///
+/// If `args.select_by` is [`SelectBy::Name`], then a part of the device ALIAS is read instead of an IDX. Each name is matched case-insensitively, and it must match exactly one device on the list.
+///
+/// Regardless of `args.select_by`, each selection can be prefixed with `idx:`, `mac:` or `name:` to pick a device by its IDX, ADDRESS or ALIAS respectively. Please see [`Selector`] for more details.
+///
+/// If a selection does not match any device, then the valid IDX range is shown and the selections are read again, up to `args.attempts` times in total (2 by default).
+///
 /// Here is how the table of connected devices looks like:
 ///
 /// ```txt
@@ -129,30 +311,58 @@ impl TableFormattable<DisconnectColumn> for (&usize, &bluez::BluezDevice) {
 /// (2)    Dev3    XX:XX:XX:XX:XX:XX
 /// ```
 ///
-/// Once an IDX is selected, [`disconnect`] tries to disconnect from that device by using a [`BluezClient`].
+/// Once an IDX is selected, [`disconnect`] tries to disconnect from that device by using a [`BluezClient`]. The selected device is disconnected by its ADDRESS, since more than one connected device may share the same ALIAS.
+/// If the selected device has disconnected on its own in the meantime (e.g. went out of range), then `device <ALIAS> is already disconnected` is written instead, and it is not treated as an error.
 /// Upon disconnecting, [`disconnect`] writes a message to the provided [`io::Write`].
 ///
 /// # Non-Interactive Mode
 ///
-/// [`disconnect`] runs non-interactively if `aliases` is [`Some`].
+/// [`disconnect`] runs non-interactively if `args.aliases` is [`Some`].
 ///
-/// In this mode, [`disconnect`] does NOT fetch the connected devices and tries to disconnect from each device through their aliases defined in `aliases`.
+/// In this mode, [`disconnect`] does NOT fetch the connected devices and tries to disconnect from each device through their aliases defined in `args.aliases`.
 ///
 /// Upon disconnecting, [`disconnect`] writes a messages to the provided [`io::Write`].
 ///
-/// Both modes can be used depending on how convenient defining the `aliases` is.
+/// If an ALIAS in `args.aliases` is `-`, then it is read from the provided [`io::BufRead`] instead (a single trimmed line). This allows piping an ALIAS from other tools, and it does not trigger the interactive mode.
+///
+/// A repeated ALIAS in `args.aliases` (e.g. `a,a,b`) is only disconnected once, in the order it is first seen.
+///
+/// If `args.match_regex` is [`Some`], then [`disconnect`] also runs non-interactively, and disconnects from every connected device whose ALIAS matches the regex as a whole (e.g. `Office.*`). If no connected device matches, then [`DisconnectError::NoMatch`] is returned.
+///
+/// Both modes can be used depending on how convenient defining the `args.aliases` is.
 ///
 /// In order to see the connected devices, [`list_devices`] or [`status`] can be used.
 ///
 /// # Removing a device
 ///
-/// [`disconnect`] also provides the ability to remove a device completely based on whether `force` is true or not.
+/// [`disconnect`] also provides the ability to remove a device completely based on whether `args.force` is true or not.
+///
+/// If `args.force` is `true`, then both interactive and non-interactive mode results in removing the device from the known devices list on the host.
+///
+/// If `args.force` is `false`, the both interactive and non-interactive mode results in disconnecting from the device. The device will be kept in the known device list.
+///
+/// `args.force` does not change the behavior of interactive and non-interactive mode explained above.
+///
+/// # Untrusting a device
+///
+/// Disconnecting never changes whether a device is trusted, and Bluez reconnects to a trusted device on its own (e.g. when it is turned on).
+///
+/// If `args.untrust` is `true`, then each device is untrusted right after it is disconnected, and `untrusted device <ALIAS>` is written as well. If a device cannot be untrusted, then a warning is written to `warn` instead, and the device still counts as disconnected.
 ///
-/// If `force` is `true`, then both interactive and non-interactive mode results in removing the device from the known devices list on the host.
+/// # Waiting
 ///
-/// If `force` is `false`, the both interactive and non-interactive mode results in disconnecting from the device. The device will be kept in the known device list.
+/// By default, [`disconnect`] moves on as soon as Bluez accepts the disconnection, although the link may drop a while later.
 ///
-/// `force` does not change the behavior of interactive and non-interactive mode explained above.
+/// If `args.wait` is `true`, then [`disconnect`] reads whether each device is still connected until its link drops, or until `args.timeout` seconds pass (5 by default). If a device is still connected after the timeout, then [`DisconnectError::NotDisconnected`] is returned. With `args.summary`, both a device that is still connected and a device whose connection cannot be read count as failed devices instead.
+///
+/// # Summary
+///
+/// By default, [`disconnect`] stops at the first device that cannot be disconnected (or removed).
+///
+/// If `args.summary` is `true`, then [`disconnect`] writes a message for the failed device instead and keeps going. Once every device is processed, a summary line is written, such as `disconnected=2 failed=1` (or `removed=2 failed=1` if `args.force` is `true`).
+/// If `args.json` is `true`, then the summary is written as JSON instead, such as `{"ok":2,"failed":1}`.
+///
+/// If any device fails, [`DisconnectError::BatchFailed`] is returned after the summary is written.
 ///
 /// # Panics
 ///
@@ -164,22 +374,25 @@ impl TableFormattable<DisconnectColumn> for (&usize, &bluez::BluezDevice) {
 ///
 /// # Examples
 ///
-/// Here is an example for an interactive [`disconnect`]. `force` is `false`, so [`disconnect`] does not remove the device.
+/// Here is an example for an interactive [`disconnect`]. `args.force` is `false`, so [`disconnect`] does not remove the device.
 ///
 /// ```no_run
 /// use std::io;
-/// use bt::{disconnect, BluezClient};
+/// use bt::{disconnect, BluezClient, DisconnectArgs};
 ///
 /// let bluez_client = BluezClient::new().unwrap();
 /// let mut input = io::stdin();
 /// let mut output = io::stdout();
 ///
-/// let force = false;
-/// let aliases = None;
+/// let args = DisconnectArgs {
+///     force: false,
+///     aliases: None,
+///     ..Default::default()
+/// };
 ///
 /// // Before returning `disconnect_result`, [`disconnect`] writes the list of connected devices to `output`.
 /// // The selection will be read from `input`.
-/// let disconnect_result = disconnect(&bluez_client, &mut output, &mut input.lock(), &force, &aliases);
+/// let disconnect_result = disconnect(&bluez_client, &mut output, &mut io::stderr(), &mut input.lock(), &args);
 /// match disconnect_result {
 ///     Ok(_) => {
 ///          // `output` contains the success message.
@@ -189,22 +402,25 @@ impl TableFormattable<DisconnectColumn> for (&usize, &bluez::BluezDevice) {
 /// }
 ///```
 ///
-/// In order to remove a connected device, use `force`.
+/// In order to remove a connected device, use `args.force`.
 ///
 ///```no_run
 /// use std::io;
-/// use bt::{disconnect, BluezClient};
+/// use bt::{disconnect, BluezClient, DisconnectArgs};
 ///
 /// let bluez_client = BluezClient::new().unwrap();
 /// let mut input = io::stdin();
 /// let mut output = io::stdout();
 ///
-/// let force = true;
-/// let aliases = None;
+/// let args = DisconnectArgs {
+///     force: true,
+///     aliases: None,
+///     ..Default::default()
+/// };
 ///
 /// // Before returning `disconnect_result`, [`disconnect`] writes the list of connected devices to `output`.
 /// // The selection will be read from `input`.
-/// let disconnect_result = disconnect(&bluez_client, &mut output, &mut input.lock(), &force, &aliases);
+/// let disconnect_result = disconnect(&bluez_client, &mut output, &mut io::stderr(), &mut input.lock(), &args);
 /// match disconnect_result {
 ///     Ok(_) => {
 ///          // `output` contains the success message.
@@ -214,23 +430,26 @@ impl TableFormattable<DisconnectColumn> for (&usize, &bluez::BluezDevice) {
 /// }
 /// ```
 ///
-/// Here is an example for a non-interactive [`disconnect`]. In this example, `aliases` is set to a vector which holds the ALIAS of the connected device.
+/// Here is an example for a non-interactive [`disconnect`]. In this example, `args.aliases` is set to a vector which holds the ALIAS of the connected device.
 ///
 ///```no_run
 /// use std::io;
-/// use bt::{disconnect, BluezClient};
+/// use bt::{disconnect, BluezClient, DisconnectArgs};
 ///
 /// let bluez_client = BluezClient::new().unwrap();
 /// let mut input = io::stdin();
 /// let mut output = io::stdout();
 ///
-/// let force = false;
-/// let aliases = Some(vec!["connected_dev".to_string()]);
+/// let args = DisconnectArgs {
+///     force: false,
+///     aliases: Some(vec!["connected_dev".to_string()]),
+///     ..Default::default()
+/// };
 ///
 /// // `disconnect` tries to disconnect from the device that has the alias "connected_dev".
 /// // It will not show the connected devices.
 /// // `output` is only used to provide the success message.
-/// let disconnect_result = disconnect(&bluez_client, &mut output, &mut input.lock(), &force, &aliases);
+/// let disconnect_result = disconnect(&bluez_client, &mut output, &mut io::stderr(), &mut input.lock(), &args);
 /// match disconnect_result {
 ///     Ok(_) => {
 ///          // `output` contains the success message.
@@ -240,23 +459,26 @@ impl TableFormattable<DisconnectColumn> for (&usize, &bluez::BluezDevice) {
 /// }
 /// ```
 ///
-/// In order to remove a device in the non-interactive mode, use `force` just as we did in the interactive mode.
+/// In order to remove a device in the non-interactive mode, use `args.force` just as we did in the interactive mode.
 ///
 ///```no_run
 /// use std::io;
-/// use bt::{disconnect, BluezClient};
+/// use bt::{disconnect, BluezClient, DisconnectArgs};
 ///
 /// let bluez_client = BluezClient::new().unwrap();
 /// let mut input = io::stdin();
 /// let mut output = io::stdout();
 ///
-/// let force = true;
-/// let aliases = Some(vec!["connected_dev".to_string()]);
+/// let args = DisconnectArgs {
+///     force: true,
+///     aliases: Some(vec!["connected_dev".to_string()]),
+///     ..Default::default()
+/// };
 ///
 /// // `disconnect` tries to remove the device that has the alias "connected_dev".
 /// // It will not show the connected devices.
 /// // `output` is only used to provide the success message.
-/// let disconnect_result = disconnect(&bluez_client, &mut output, &mut input.lock(), &force, &aliases);
+/// let disconnect_result = disconnect(&bluez_client, &mut output, &mut io::stderr(), &mut input.lock(), &args);
 /// match disconnect_result {
 ///     Ok(_) => {
 ///          // `output` contains the success message.
@@ -269,17 +491,20 @@ impl TableFormattable<DisconnectColumn> for (&usize, &bluez::BluezDevice) {
 /// Here is an error case. The example triggers an [`io::Error`] by passing an array as a buffer, instead of a growable buffer.
 ///
 /// ```no_run
-/// use std::io::Cursor;
-/// use bt::{disconnect, BluezClient, DisconnectError};
+/// use std::io::{self, Cursor};
+/// use bt::{disconnect, BluezClient, DisconnectArgs, DisconnectError};
 ///
 /// let bluez_client = BluezClient::new().unwrap();
 /// let mut input = Cursor::new([]);
 /// let mut output = Cursor::new([]);
 ///
-/// let force = false;
-/// let aliases = None;
+/// let args = DisconnectArgs {
+///     force: false,
+///     aliases: None,
+///     ..Default::default()
+/// };
 ///
-/// let disconnect_result = disconnect(&bluez_client, &mut output, &mut input, &force, &aliases);
+/// let disconnect_result = disconnect(&bluez_client, &mut output, &mut io::stderr(), &mut input, &args);
 /// match disconnect_result {
 ///     Err(DisconnectError::Io(err)) => eprintln!("{}", err),
 ///     _ => unreachable!(),
@@ -291,6 +516,11 @@ impl TableFormattable<DisconnectColumn> for (&usize, &bluez::BluezDevice) {
 /// [`Some`]: std::option::Option::Some
 /// [`None`]: std::option::Option::None
 /// [`DisconnectError`]: crate::DisconnectError
+/// [`DisconnectError::BatchFailed`]: crate::DisconnectError::BatchFailed
+/// [`DisconnectError::NoMatch`]: crate::DisconnectError::NoMatch
+/// [`DisconnectError::NotDisconnected`]: crate::DisconnectError::NotDisconnected
+/// [`SelectBy::Name`]: crate::SelectBy::Name
+/// [`Selector`]: crate::Selector
 /// [`disconnect`]: crate::disconnect
 /// [`connect`]: crate::connect
 /// [`list_devices`]: crate::list_devices
@@ -298,41 +528,244 @@ impl TableFormattable<DisconnectColumn> for (&usize, &bluez::BluezDevice) {
 pub fn disconnect(
     bluez: &crate::BluezClient,
     w: &mut impl io::Write,
+    warn: &mut impl io::Write,
     r: &mut impl io::BufRead,
-    force: &bool,
-    aliases: &Option<Vec<String>>,
+    args: &DisconnectArgs,
 ) -> Result<(), Error> {
-    let aliases = match aliases.as_ref() {
-        Some(aliases) => aliases,
-        None => &{
+    let matched_aliases;
+    let selections = match (args.aliases.as_ref(), args.match_regex.as_ref()) {
+        (Some(aliases), _) => select::dedup_aliases(aliases)
+            .into_iter()
+            .map(Selection::Alias)
+            .collect(),
+        (None, Some(pattern)) => {
+            matched_aliases = matching_aliases(bluez, pattern)?;
+            select::dedup_aliases(&matched_aliases)
+                .into_iter()
+                .map(Selection::Alias)
+                .collect()
+        }
+        (None, None) => {
             let devices = bluez.connected_devices()?;
+            let devices = get_devices_from_user(w, r, devices, &args.select_by, args.attempts)?;
+            let devices = match args.force {
+                true => devices,
+                false => retain_connected_devices(bluez, w, devices)?,
+            };
 
-            get_aliases_from_user(w, r, devices)?
-        },
+            devices
+                .into_iter()
+                .map(Selection::Device)
+                .collect::<Vec<_>>()
+        }
     };
 
-    for alias in aliases {
-        let alias = alias.trim();
+    let (mut ok, mut failed) = (0, 0);
+    for selection in selections {
+        // NOTE: A device selected in the interactive mode is disconnected by
+        // its address, since more than one connected device may share the same alias.
+        let (alias, target) = match selection {
+            Selection::Alias(alias) => resolve_alias(bluez, r, alias)?,
+            Selection::Device(device) => (device.alias().to_string(), device.address().to_string()),
+        };
+        let alias = alias.as_str();
 
-        let disconnect_result = if *force {
-            bluez.remove(alias)?;
-            format!("removed device {} (forced)\n", alias)
+        let disconnect_result = if args.force {
+            bluez
+                .remove(&target)
+                .map(|_| format!("removed device {} (forced)\n", alias))
         } else {
-            bluez.disconnect(alias)?;
-            format!("disconnected from device {}\n", alias)
+            bluez
+                .disconnect(&target)
+                .map(|_| format!("disconnected from device {}\n", alias))
+        };
+
+        // NOTE: A failure to confirm the disconnection is a failure of the
+        // device too, so that --summary keeps going with the next device.
+        let timeout = Duration::from_secs(u64::from(args.timeout.unwrap_or(5)));
+        let disconnect_result = disconnect_result.and_then(|result| match args.wait {
+            true => {
+                wait_until_disconnected(bluez, &target, timeout).map(|dropped| (result, dropped))
+            }
+            false => Ok((result, true)),
+        });
+        let disconnect_result = match disconnect_result {
+            Ok((_, false)) => {
+                if !args.summary {
+                    return Err(Error::NotDisconnected(alias.to_string()));
+                }
+
+                failed += 1;
+                format!("device {} is still connected after the timeout\n", alias)
+            }
+            Ok((mut result, true)) => {
+                ok += 1;
+                if args.untrust {
+                    result.push_str(&untrust(bluez, warn, alias, &target)?);
+                }
+                result
+            }
+            Err(e) if args.summary => {
+                failed += 1;
+                let action = if args.force {
+                    "remove"
+                } else {
+                    "disconnect from"
+                };
+                format!("failed to {} device {}: {}\n", action, alias, e)
+            }
+            Err(e) => return Err(e.into()),
         };
 
         w.write_all(disconnect_result.as_bytes())?;
     }
 
+    if args.summary {
+        let action = if args.force {
+            "removed"
+        } else {
+            "disconnected"
+        };
+        let summary = format::format_summary(action, ok, failed, args.json);
+        w.write_all(summary.as_bytes())?;
+
+        if failed > 0 {
+            return Err(Error::BatchFailed(failed));
+        }
+    }
+
     Ok(())
 }
 
-fn get_aliases_from_user(
+/// Defines how a device of a [`disconnect`] call is selected.
+///
+/// [`disconnect`]: crate::disconnect
+enum Selection<'a> {
+    /// The device is selected by an ALIAS of `args.aliases`, which may be a selector or [`STDIN_ALIAS`], or by a match of `args.match_regex`.
+    Alias(&'a str),
+
+    /// The device is selected from the list of connected devices in the interactive mode.
+    Device(bluez::BluezDevice),
+}
+
+/// Resolves `alias` to the ALIAS of the device for the messages, and the target to disconnect.
+///
+/// If `alias` is [`STDIN_ALIAS`], then the ALIAS is read from `r` first.
+fn resolve_alias(
+    bluez: &crate::BluezClient,
+    r: &mut impl io::BufRead,
+    alias: &str,
+) -> Result<(String, String), Error> {
+    let piped_alias;
+    let alias = match alias {
+        STDIN_ALIAS => {
+            piped_alias = select::read_stdin_alias(r)?.ok_or(Error::InvalidAlias)?;
+            piped_alias.as_str()
+        }
+        alias => alias,
+    };
+    let selector = alias.parse::<Selector>()?;
+    let alias = selector.value().ok_or(TargetError::Index)?;
+    let target = selector.target(bluez)?;
+
+    Ok((alias.to_string(), target))
+}
+
+/// Untrusts the device with `target`, and provides the message of the outcome for `alias`.
+///
+/// A failure is only written to `warn` as a warning, since the device is already disconnected. Then, the message is empty.
+fn untrust(
+    bluez: &crate::BluezClient,
+    warn: &mut impl io::Write,
+    alias: &str,
+    target: &str,
+) -> Result<String, Error> {
+    match bluez.set_trusted(target, false) {
+        Ok(_) => Ok(format!("untrusted device {}\n", alias)),
+        Err(e) => {
+            writeln!(
+                warn,
+                "disconnect: warning: could not untrust device {}: {}",
+                alias, e
+            )?;
+            Ok(String::new())
+        }
+    }
+}
+
+/// Provides the connected devices whose ALIAS matches `pattern`, as `name:` selectors so that an ALIAS is never parsed as another selector.
+fn matching_aliases(bluez: &crate::BluezClient, pattern: &str) -> Result<Vec<String>, Error> {
+    let regex = select::alias_regex(pattern)?;
+
+    let aliases = bluez
+        .connected_devices()?
+        .iter()
+        .filter(|d| regex.is_match(d.alias()))
+        .map(|d| format!("name:{}", d.alias()))
+        .collect::<Vec<String>>();
+
+    if aliases.is_empty() {
+        return Err(Error::NoMatch(pattern.to_string()));
+    }
+
+    Ok(aliases)
+}
+
+/// Reads whether the device with `alias` is connected every [`WAIT_POLL_INTERVAL`] until it is not, or until `timeout` passes.
+///
+/// The device is checked at least once, even if `timeout` is zero.
+fn wait_until_disconnected(
+    bluez: &crate::BluezClient,
+    alias: &str,
+    timeout: Duration,
+) -> Result<bool, BluezError> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if !bluez.is_connected(alias)? {
+            return Ok(true);
+        }
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Ok(false);
+        }
+
+        thread::sleep(remaining.min(WAIT_POLL_INTERVAL));
+    }
+}
+
+/// Drops the selected devices which disconnected on their own while the user was choosing, e.g. by going out of range.
+///
+/// Each dropped device is reported as a benign outcome.
+fn retain_connected_devices(
+    bluez: &crate::BluezClient,
+    w: &mut impl io::Write,
+    devices: Vec<bluez::BluezDevice>,
+) -> Result<Vec<bluez::BluezDevice>, Error> {
+    let connected_devices = bluez.connected_devices()?;
+
+    let mut still_connected = Vec::with_capacity(devices.len());
+    for device in devices {
+        if connected_devices
+            .iter()
+            .any(|d| d.address() == device.address())
+        {
+            still_connected.push(device);
+        } else {
+            writeln!(w, "device {} is already disconnected", device.alias())?;
+        }
+    }
+
+    Ok(still_connected)
+}
+
+fn get_devices_from_user(
     w: &mut impl io::Write,
     r: &mut impl io::BufRead,
     devices: Vec<bluez::BluezDevice>,
-) -> Result<Vec<String>, Error> {
+    select_by: &SelectBy,
+    attempts: Option<u8>,
+) -> Result<Vec<bluez::BluezDevice>, Error> {
     let dev_len = devices.len();
     if dev_len == 0 {
         return Err(Error::NoConnectedDevices);
@@ -344,28 +777,41 @@ fn get_aliases_from_user(
         .to_pretty(&DEFAULT_LISTING_COLUMNS)
         .to_string();
 
-    let prompt = [
+    let selected_idxs = select::read_selection(
+        w,
+        r,
         &devices,
-        "\n",
         "Select the device(s) you wish to disconnect: ",
-    ]
-    .concat();
-    w.write_all(prompt.as_bytes())?;
-    w.flush()?;
-
-    let mut answer = String::with_capacity(dev_len * 2);
-    r.read_line(&mut answer)?;
+        attempts.unwrap_or(select::DEFAULT_SELECT_ATTEMPTS),
+        |answer| {
+            answer
+                .split(",")
+                .map(|selection| {
+                    let selection = selection.trim();
+                    match selection
+                        .parse::<Selector>()?
+                        .resolve(&device_map, select_by)
+                    {
+                        NameMatch::Unique(idx) => Ok(idx),
+                        NameMatch::Ambiguous => Err(Error::AmbiguousAlias(selection.to_string())),
+                        NameMatch::NotFound => Err(Error::from(DeviceSelectionError {
+                            selection: selection.to_string(),
+                            device_count: dev_len,
+                        })),
+                    }
+                })
+                .collect::<Result<Vec<usize>, Error>>()
+        },
+    )?
+    .ok_or(Error::Cancelled)?;
 
-    let mut aliases: Vec<String> = Vec::with_capacity(dev_len);
-    for idx in answer.split(",") {
-        let idx = idx.trim().parse::<u8>()?;
-        let device = device_map
-            .remove(&(idx as usize))
-            .ok_or(Error::InvalidAlias)?;
-        aliases.push(device.alias().to_string());
+    let mut selected_devices = Vec::with_capacity(selected_idxs.len());
+    for idx in selected_idxs {
+        let device = device_map.remove(&idx).ok_or(Error::InvalidAlias)?;
+        selected_devices.push(device);
     }
 
-    Ok(aliases)
+    Ok(selected_devices)
 }
 
 #[cfg(test)]
@@ -380,8 +826,6 @@ mod tests {
         // executed by checking res.is_ok().
         bluez.set_erred_method_name("remove".to_string());
 
-        let force = false;
-
         for aliases in [None, Some(vec!["connected_device".to_string()])] {
             let mut in_buf = match aliases {
                 Some(_) => Cursor::new(vec![]),
@@ -392,7 +836,13 @@ mod tests {
             };
             let mut out_buf = Cursor::new(vec![]);
 
-            let result = disconnect(&bluez, &mut out_buf, &mut in_buf, &force, &aliases);
+            let args = DisconnectArgs {
+                force: false,
+                aliases,
+                ..Default::default()
+            };
+
+            let result = disconnect(&bluez, &mut out_buf, &mut io::sink(), &mut in_buf, &args);
 
             assert!(result.is_ok());
             assert!(!out_buf.into_inner().is_empty());
@@ -406,8 +856,6 @@ mod tests {
         // executed by checking res.is_ok().
         bluez.set_erred_method_name("disconnect".to_string());
 
-        let force = true;
-
         for aliases in [None, Some(vec!["connected_device".to_string()])] {
             let mut in_buf = match aliases {
                 Some(_) => Cursor::new(vec![]),
@@ -418,7 +866,13 @@ mod tests {
             };
             let mut out_buf = Cursor::new(vec![]);
 
-            let result = disconnect(&bluez, &mut out_buf, &mut in_buf, &force, &aliases);
+            let args = DisconnectArgs {
+                force: true,
+                aliases,
+                ..Default::default()
+            };
+
+            let result = disconnect(&bluez, &mut out_buf, &mut io::sink(), &mut in_buf, &args);
 
             assert!(result.is_ok());
             assert!(!out_buf.into_inner().is_empty());
@@ -432,10 +886,10 @@ mod tests {
         let user_device_selection = String::from("0\n");
         let mut in_buf = Cursor::new(user_device_selection.as_bytes().to_vec());
         let mut out_buf = Cursor::new(vec![]);
-        let force = false;
-        let aliases = None;
 
-        let result = disconnect(&bluez, &mut out_buf, &mut in_buf, &force, &aliases);
+        let args = DisconnectArgs::default();
+
+        let result = disconnect(&bluez, &mut out_buf, &mut io::sink(), &mut in_buf, &args);
 
         assert!(result.is_ok());
 
@@ -454,10 +908,10 @@ mod tests {
         let user_device_selection = String::from("0\n");
         let mut in_buf = Cursor::new(user_device_selection.as_bytes().to_vec());
         let mut out_buf = Cursor::new(vec![]);
-        let force = false;
-        let aliases = None;
 
-        let result = disconnect(&bluez, &mut out_buf, &mut in_buf, &force, &aliases);
+        let args = DisconnectArgs::default();
+
+        let result = disconnect(&bluez, &mut out_buf, &mut io::sink(), &mut in_buf, &args);
 
         assert!(result.is_err());
 
@@ -470,8 +924,6 @@ mod tests {
         let mut bluez = crate::BluezClient::new().unwrap();
         bluez.set_erred_method_name("disconnect".to_string());
 
-        let force = false;
-
         for aliases in [None, Some(vec!["connected_device".to_string()])] {
             let mut in_buf = match aliases {
                 Some(_) => Cursor::new(vec![]),
@@ -482,7 +934,13 @@ mod tests {
             };
             let mut out_buf = Cursor::new(vec![]);
 
-            let result = disconnect(&bluez, &mut out_buf, &mut in_buf, &force, &aliases);
+            let args = DisconnectArgs {
+                force: false,
+                aliases: aliases.clone(),
+                ..Default::default()
+            };
+
+            let result = disconnect(&bluez, &mut out_buf, &mut io::sink(), &mut in_buf, &args);
 
             assert!(result.is_err());
 
@@ -499,8 +957,6 @@ mod tests {
         let mut bluez = crate::BluezClient::new().unwrap();
         bluez.set_erred_method_name("remove".to_string());
 
-        let force = true;
-
         for aliases in [None, Some(vec!["connected_device".to_string()])] {
             let mut in_buf = match aliases {
                 Some(_) => Cursor::new(vec![]),
@@ -511,7 +967,13 @@ mod tests {
             };
             let mut out_buf = Cursor::new(vec![]);
 
-            let result = disconnect(&bluez, &mut out_buf, &mut in_buf, &force, &aliases);
+            let args = DisconnectArgs {
+                force: true,
+                aliases: aliases.clone(),
+                ..Default::default()
+            };
+
+            let result = disconnect(&bluez, &mut out_buf, &mut io::sink(), &mut in_buf, &args);
 
             assert!(result.is_err());
 
@@ -530,12 +992,551 @@ mod tests {
         let mut in_buf = Cursor::new([]);
         let mut out_buf = Cursor::new([]);
         out_buf.set_position(1);
-        let force = false;
-        let aliases = Some(vec!["connected_device".to_string()]);
 
-        let result = disconnect(&bluez, &mut out_buf, &mut in_buf, &force, &aliases);
+        let args = DisconnectArgs {
+            aliases: Some(vec!["connected_device".to_string()]),
+            ..Default::default()
+        };
+
+        let result = disconnect(&bluez, &mut out_buf, &mut io::sink(), &mut in_buf, &args);
 
         assert!(result.is_err());
         assert!(out_buf.into_inner().is_empty())
     }
+
+    #[test]
+    fn it_should_select_the_devices_by_name() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("Office Keyboard")
+                .connected(true)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("Office Mouse")
+                .connected(true)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("Headset")
+                .connected(true)
+                .build(),
+        ]);
+
+        let mut in_buf = Cursor::new("MOUSE, head\n".as_bytes().to_vec());
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = DisconnectArgs {
+            select_by: SelectBy::Name,
+            ..Default::default()
+        };
+
+        let result = disconnect(&bluez, &mut out_buf, &mut io::sink(), &mut in_buf, &args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert!(result.contains("disconnected from device Office Mouse\n"));
+        assert!(result.contains("disconnected from device Headset\n"));
+        assert!(!result.contains("disconnected from device Office Keyboard"));
+    }
+
+    #[test]
+    fn it_should_prompt_again_if_a_selection_is_invalid() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .connected(true)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .connected(true)
+                .build(),
+        ]);
+
+        let mut in_buf = Cursor::new("0,2\n0,1\n".as_bytes().to_vec());
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = DisconnectArgs::default();
+
+        let result = disconnect(&bluez, &mut out_buf, &mut io::sink(), &mut in_buf, &args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert!(result.contains(
+            "disconnect: '2' does not match any device, select an IDX between 0 and 1\nSelect the device(s) you wish to disconnect: "
+        ));
+        assert!(result.ends_with("disconnected from device dev1\ndisconnected from device dev2\n"));
+    }
+
+    #[test]
+    fn it_should_fail_if_the_attempts_run_out() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .connected(true)
+                .build(),
+        ]);
+
+        let mut in_buf = Cursor::new("3\n0\n".as_bytes().to_vec());
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = DisconnectArgs {
+            attempts: Some(1),
+            ..Default::default()
+        };
+
+        let result = disconnect(&bluez, &mut out_buf, &mut io::sink(), &mut in_buf, &args);
+
+        assert!(matches!(
+            result,
+            Err(Error::InvalidSelection(DeviceSelectionError { selection, device_count: 1 })) if selection == "3"
+        ));
+    }
+
+    #[test]
+    fn it_should_disconnect_from_every_device_matching_the_regex() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("Office Keyboard")
+                .connected(true)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("Office Mouse")
+                .connected(true)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("My Office Speaker")
+                .connected(true)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("Office Headset").build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = DisconnectArgs {
+            match_regex: Some(String::from("Office.*")),
+            ..Default::default()
+        };
+
+        let result = disconnect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut Cursor::new(vec![]),
+            &args,
+        );
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(
+            result,
+            "disconnected from device Office Keyboard\ndisconnected from device Office Mouse\n"
+        );
+    }
+
+    #[test]
+    fn it_should_fail_if_no_device_matches_the_regex() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("Office Mouse")
+                .connected(true)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("Home Speaker").build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = DisconnectArgs {
+            match_regex: Some(String::from("Home.*")),
+            ..Default::default()
+        };
+
+        let result = disconnect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut Cursor::new(vec![]),
+            &args,
+        );
+        assert!(matches!(result, Err(Error::NoMatch(pattern)) if pattern == "Home.*"));
+        assert!(out_buf.into_inner().is_empty());
+
+        let args = DisconnectArgs {
+            match_regex: Some(String::from("Office(")),
+            ..Default::default()
+        };
+
+        let result = disconnect(
+            &bluez,
+            &mut io::sink(),
+            &mut io::sink(),
+            &mut Cursor::new(vec![]),
+            &args,
+        );
+        assert!(matches!(result, Err(Error::InvalidRegex(_))));
+    }
+
+    #[test]
+    fn it_should_wait_until_the_link_drops() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .connected(true)
+                .build(),
+        ]);
+        bluez.set_lingering_polls(1);
+        assert!(bluez.is_connected("dev1").unwrap());
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = DisconnectArgs {
+            aliases: Some(vec![String::from("dev1")]),
+            wait: true,
+            ..Default::default()
+        };
+
+        let result = disconnect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut Cursor::new(vec![]),
+            &args,
+        );
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "disconnected from device dev1\n");
+        assert!(!bluez.is_connected("dev1").unwrap());
+    }
+
+    #[test]
+    fn it_should_count_a_failed_wait_as_a_failure_in_the_summary() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .connected(true)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .connected(true)
+                .build(),
+        ]);
+        bluez.set_erred_method_name("is_connected".to_string());
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = DisconnectArgs {
+            aliases: Some(vec![String::from("dev1"), String::from("dev2")]),
+            wait: true,
+            summary: true,
+            ..Default::default()
+        };
+
+        let result = disconnect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut Cursor::new(vec![]),
+            &args,
+        );
+        assert!(matches!(result, Err(Error::BatchFailed(2))));
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        let lines = result.lines().collect::<Vec<&str>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("failed to disconnect from device dev1: "));
+        assert!(lines[1].starts_with("failed to disconnect from device dev2: "));
+        assert_eq!(lines[2], "disconnected=0 failed=2");
+    }
+
+    #[test]
+    fn it_should_fail_if_the_link_does_not_drop_in_time() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .connected(true)
+                .build(),
+        ]);
+        bluez.set_lingering_polls(usize::MAX);
+
+        let args = DisconnectArgs {
+            aliases: Some(vec![String::from("dev1")]),
+            wait: true,
+            timeout: Some(0),
+            ..Default::default()
+        };
+
+        let result = disconnect(
+            &bluez,
+            &mut io::sink(),
+            &mut io::sink(),
+            &mut Cursor::new(vec![]),
+            &args,
+        );
+        assert!(matches!(result, Err(Error::NotDisconnected(alias)) if alias == "dev1"));
+    }
+
+    #[test]
+    fn it_should_fail_if_a_selected_name_is_ambiguous() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("Office Keyboard")
+                .connected(true)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("Office Mouse")
+                .connected(true)
+                .build(),
+        ]);
+
+        let mut in_buf = Cursor::new("office\n".as_bytes().to_vec());
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = DisconnectArgs {
+            select_by: SelectBy::Name,
+            ..Default::default()
+        };
+
+        let result = disconnect(&bluez, &mut out_buf, &mut io::sink(), &mut in_buf, &args);
+
+        assert!(matches!(result, Err(Error::AmbiguousAlias(name)) if name == "office"));
+    }
+
+    #[test]
+    fn it_should_read_the_alias_from_the_reader_if_alias_is_a_dash() {
+        let bluez = crate::BluezClient::new().unwrap();
+
+        let args = DisconnectArgs {
+            aliases: Some(vec!["dev1".to_string(), "-".to_string()]),
+            ..Default::default()
+        };
+
+        let mut in_buf = Cursor::new("piped_dev\n".as_bytes().to_vec());
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = disconnect(&bluez, &mut out_buf, &mut io::sink(), &mut in_buf, &args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(
+            result,
+            "disconnected from device dev1\ndisconnected from device piped_dev\n"
+        );
+
+        let mut in_buf = Cursor::new(vec![]);
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = disconnect(&bluez, &mut out_buf, &mut io::sink(), &mut in_buf, &args);
+        assert!(matches!(result, Err(Error::InvalidAlias)));
+    }
+
+    #[test]
+    fn it_should_disconnect_a_repeated_alias_once() {
+        let bluez = crate::BluezClient::new().unwrap();
+
+        let args = DisconnectArgs {
+            summary: true,
+            aliases: Some(vec![
+                "dev1".to_string(),
+                "dev1".to_string(),
+                "dev2".to_string(),
+                " dev1".to_string(),
+            ]),
+            ..Default::default()
+        };
+
+        let mut in_buf = Cursor::new(vec![]);
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = disconnect(&bluez, &mut out_buf, &mut io::sink(), &mut in_buf, &args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(
+            result,
+            "disconnected from device dev1\ndisconnected from device dev2\ndisconnected=2 failed=0\n"
+        );
+    }
+
+    #[test]
+    fn it_should_count_the_outcomes_if_summary_is_requested() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_erred_alias("dev2".to_string());
+
+        let mut args = DisconnectArgs {
+            summary: true,
+            aliases: Some(vec![
+                "dev1".to_string(),
+                "dev2".to_string(),
+                "dev3".to_string(),
+            ]),
+            ..Default::default()
+        };
+
+        let mut in_buf = Cursor::new(vec![]);
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = disconnect(&bluez, &mut out_buf, &mut io::sink(), &mut in_buf, &args);
+        assert!(matches!(result, Err(Error::BatchFailed(1))));
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert!(
+            result.starts_with(
+                "disconnected from device dev1\nfailed to disconnect from device dev2: "
+            )
+        );
+        assert!(result.ends_with("disconnected from device dev3\ndisconnected=2 failed=1\n"));
+
+        args.json = true;
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = disconnect(&bluez, &mut out_buf, &mut io::sink(), &mut in_buf, &args);
+        assert!(matches!(result, Err(Error::BatchFailed(1))));
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert!(result.ends_with("{\"ok\":2,\"failed\":1}\n"));
+    }
+
+    #[test]
+    fn it_should_skip_the_selected_device_if_it_is_already_disconnected() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .connected(true)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .address("BB:BB:BB:BB:BB:BB")
+                .connected(true)
+                .build(),
+        ]);
+        bluez.set_dropped_alias("dev1".to_string());
+        // NOTE: Disconnecting from dev1 would fail, so it must not be attempted.
+        bluez.set_erred_alias("AA:AA:AA:AA:AA:AA".to_string());
+
+        let mut in_buf = Cursor::new("0,1\n".as_bytes().to_vec());
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = disconnect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &DisconnectArgs::default(),
+        );
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert!(
+            result
+                .ends_with("device dev1 is already disconnected\ndisconnected from device dev2\n")
+        );
+    }
+
+    #[test]
+    fn it_should_disconnect_the_selected_device_by_its_address() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("-")
+                .address("AA:AA:AA:AA:AA:AA")
+                .connected(true)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("idx:0")
+                .address("BB:BB:BB:BB:BB:BB")
+                .connected(true)
+                .build(),
+        ]);
+
+        let mut in_buf = Cursor::new("0,1\n".as_bytes().to_vec());
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = disconnect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &DisconnectArgs::default(),
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            bluez.disconnected_aliases(),
+            vec!["AA:AA:AA:AA:AA:AA", "BB:BB:BB:BB:BB:BB"]
+        );
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert!(result.ends_with("disconnected from device -\ndisconnected from device idx:0\n"));
+    }
+
+    #[test]
+    fn it_should_untrust_the_devices_after_disconnecting() {
+        let bluez = crate::BluezClient::new().unwrap();
+
+        let args = DisconnectArgs {
+            aliases: Some(vec!["dev1".to_string(), "dev2".to_string()]),
+            untrust: true,
+            ..Default::default()
+        };
+
+        let mut in_buf = Cursor::new(vec![]);
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = disconnect(&bluez, &mut out_buf, &mut io::sink(), &mut in_buf, &args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(
+            result,
+            "disconnected from device dev1\nuntrusted device dev1\n\
+             disconnected from device dev2\nuntrusted device dev2\n"
+        );
+        assert_eq!(
+            bluez.trust_changes(),
+            vec![("dev1".to_string(), false), ("dev2".to_string(), false)]
+        );
+    }
+
+    #[test]
+    fn it_should_only_warn_if_a_disconnected_device_cannot_be_untrusted() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_erred_method_name("set_trusted".to_string());
+
+        let args = DisconnectArgs {
+            aliases: Some(vec!["dev1".to_string()]),
+            untrust: true,
+            summary: true,
+            ..Default::default()
+        };
+
+        let mut in_buf = Cursor::new(vec![]);
+        let mut out_buf = Cursor::new(vec![]);
+        let mut warn_buf = Cursor::new(vec![]);
+
+        let result = disconnect(&bluez, &mut out_buf, &mut warn_buf, &mut in_buf, &args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(
+            result,
+            "disconnected from device dev1\ndisconnected=1 failed=0\n"
+        );
+
+        let warnings = String::from_utf8(warn_buf.into_inner()).unwrap();
+        assert!(warnings.starts_with("disconnect: warning: could not untrust device dev1: "));
+    }
+
+    #[test]
+    fn it_should_cancel_the_selection_if_the_input_is_closed() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .connected(true)
+                .build(),
+        ]);
+
+        let mut in_buf = io::empty();
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = disconnect(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &mut in_buf,
+            &DisconnectArgs::default(),
+        );
+        assert!(matches!(result, Err(Error::Cancelled)));
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert!(result.ends_with("Select the device(s) you wish to disconnect: \n"));
+    }
 }
@@ -0,0 +1,225 @@
+use std::{error, fmt, io};
+
+use clap::Args;
+
+use crate::BluezError;
+
+/// Defines error variants that may be returned from a [`discoverable`] call.
+///
+/// [`discoverable`]: crate::discoverable
+#[derive(Debug)]
+pub enum Error {
+    /// Happens when the [`BluezClient`] fails during the process, e.g. because the adapter is off.
+    /// It holds the underlying [`BluezError`].
+    ///
+    /// [`BluezError`]: crate::BluezError
+    /// [`BluezClient`]: crate::BluezClient
+    Bluez(BluezError),
+
+    /// Happens when the result of [`discoverable`] could not be written to the given buffer.
+    /// It holds the underlying [`io::Error`].
+    ///
+    /// [`discoverable`]: crate::discoverable
+    /// [`io::Error`]: std::io::Error
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            Error::Bluez(error) => write!(f, "discoverable: bluez error: {}", error),
+            Error::Io(error) => write!(f, "discoverable: io error: {}", error),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<BluezError> for Error {
+    fn from(value: BluezError) -> Self {
+        Self::Bluez(value)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/// Defines the arguments that [`discoverable`] can take.
+///
+/// [`discoverable`]: crate::discoverable
+#[derive(Debug, Default, Args)]
+pub struct DiscoverableArgs {
+    /// Make the adapter discoverable, which is the default.
+    #[arg(long, conflicts_with = "off")]
+    pub on: bool,
+
+    /// Hide the adapter instead.
+    #[arg(long, default_value_t = false)]
+    pub off: bool,
+
+    /// Keep the adapter discoverable for SECS seconds, where 0 means forever.
+    ///
+    /// If it is not provided, the timeout of the adapter is kept (180 seconds by default).
+    #[arg(short, long, value_name = "SECS", conflicts_with = "off")]
+    pub timeout: Option<u32>,
+
+    /// Keep the adapter discoverable until it is hidden by --off, e.g. for a dedicated receiver. It is the same as --timeout 0.
+    ///
+    /// Any nearby device can see the adapter and attempt to pair with it in the meantime.
+    #[arg(long, conflicts_with_all = ["off", "timeout"])]
+    pub forever: bool,
+}
+
+/// Makes the Bluetooth adapter discoverable by the other devices by using a [`BluezClient`], e.g. to pair a device which initiates the pairing itself.
+///
+/// Bluez hides the adapter again once its discoverable timeout passes. If `args.timeout` is [`Some`], then the timeout is set to `args.timeout` seconds before the adapter is made discoverable, so that it is visible for exactly that long. A timeout of `0` keeps the adapter discoverable until it is hidden.
+///
+/// If `args.forever` is `true`, then the timeout is set to `0` as well, e.g. for a dedicated receiver that should always be visible. `args.on` has no effect, since making the adapter discoverable is the default.
+///
+/// If `args.off` is `true`, then the adapter is hidden instead.
+///
+/// The resulting state is written to the provided [`io::Write`], e.g. `discoverable: on (timeout: 60s)`, `discoverable: on (no timeout)` or `discoverable: off`. If the adapter stays discoverable without a timeout, then a warning is written to the provided `warn` [`io::Write`] as well, since any nearby device can see the adapter and attempt to pair with it until it is hidden.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function can return all variants of [`DiscoverableError`] based on given conditions. For more details, please see the error documentation.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+/// use bt::{discoverable, BluezClient, DiscoverableArgs};
+///
+/// let bluez_client = BluezClient::new().unwrap();
+///
+/// let args = DiscoverableArgs {
+///     timeout: Some(60),
+///     ..Default::default()
+/// };
+///
+/// if let Err(e) = discoverable(&bluez_client, &mut io::stdout(), &mut io::stderr(), &args) {
+///     eprintln!("{}", e);
+/// }
+/// ```
+///
+/// [`BluezClient`]: crate::BluezClient
+/// [`io::Write`]: std::io::Write
+/// [`Some`]: std::option::Option::Some
+/// [`DiscoverableError`]: crate::DiscoverableError
+pub fn discoverable(
+    bluez: &crate::BluezClient,
+    f: &mut impl io::Write,
+    warn: &mut impl io::Write,
+    args: &DiscoverableArgs,
+) -> Result<(), Error> {
+    if args.off {
+        bluez.set_discoverable(false)?;
+        f.write_all(b"discoverable: off")?;
+
+        return Ok(());
+    }
+
+    let timeout = if args.forever { Some(0) } else { args.timeout };
+    if let Some(timeout) = timeout {
+        bluez.set_discoverable_timeout(timeout)?;
+    }
+    bluez.set_discoverable(true)?;
+
+    let buf = match bluez.discoverable_timeout()? {
+        0 => {
+            writeln!(
+                warn,
+                "discoverable: warning: the adapter stays discoverable until 'bt discoverable --off', so any nearby device can see it and attempt to pair"
+            )?;
+            String::from("discoverable: on (no timeout)")
+        }
+        timeout => format!("discoverable: on (timeout: {}s)", timeout),
+    };
+    f.write_all(buf.as_bytes())?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_should_make_the_adapter_discoverable_for_the_timeout() {
+        let bluez = crate::BluezClient::new().unwrap();
+
+        let args = DiscoverableArgs {
+            timeout: Some(60),
+            ..Default::default()
+        };
+
+        let mut out_buf = Cursor::new(vec![]);
+        discoverable(&bluez, &mut out_buf, &mut io::sink(), &args).unwrap();
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "discoverable: on (timeout: 60s)");
+        assert_eq!(bluez.discoverable_timeout().unwrap(), 60);
+        assert!(bluez.discoverable().unwrap());
+
+        let args = DiscoverableArgs {
+            off: true,
+            ..Default::default()
+        };
+
+        let mut out_buf = Cursor::new(vec![]);
+        discoverable(&bluez, &mut out_buf, &mut io::sink(), &args).unwrap();
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "discoverable: off");
+        assert_eq!(bluez.discoverable_timeout().unwrap(), 60);
+        assert!(!bluez.discoverable().unwrap());
+    }
+
+    #[test]
+    fn it_should_not_make_the_adapter_discoverable_if_the_timeout_cannot_be_set() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_erred_method_name("set_discoverable_timeout".to_string());
+
+        let args = DiscoverableArgs {
+            timeout: Some(0),
+            ..Default::default()
+        };
+
+        let result = discoverable(&bluez, &mut io::sink(), &mut io::sink(), &args);
+
+        assert!(matches!(result, Err(Error::Bluez(_))));
+        assert!(!bluez.discoverable().unwrap());
+    }
+
+    #[test]
+    fn it_should_keep_the_adapter_discoverable_forever() {
+        let bluez = crate::BluezClient::new().unwrap();
+
+        let args = DiscoverableArgs {
+            on: true,
+            forever: true,
+            ..Default::default()
+        };
+
+        let mut out_buf = Cursor::new(vec![]);
+        let mut warn_buf = Cursor::new(vec![]);
+        discoverable(&bluez, &mut out_buf, &mut warn_buf, &args).unwrap();
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "discoverable: on (no timeout)");
+        assert_eq!(bluez.discoverable_timeout().unwrap(), 0);
+        assert!(bluez.discoverable().unwrap());
+
+        let warning = String::from_utf8(warn_buf.into_inner()).unwrap();
+        assert!(warning.starts_with("discoverable: warning: "));
+    }
+}
@@ -0,0 +1,264 @@
+use std::{error, fmt, fs, io, path::PathBuf};
+
+use clap::Args;
+use serde_json::Value;
+
+use crate::{
+    BluezError, Notes, bluez,
+    format::{CsvFormatter, JsonFormattable, JsonFormatter, TableFormattable},
+};
+
+/// Defines error variants that may be returned from an [`export`] call.
+///
+/// [`export`]: crate::export
+#[derive(Debug)]
+pub enum Error {
+    /// Happens when the [`BluezClient`] fails during an [`export`] call.
+    /// It holds the underlying [`BluezError`].
+    ///
+    /// [`BluezError`]: crate::BluezError
+    /// [`BluezClient`]: crate::BluezClient
+    /// [`export`]: crate::export
+    Bluez(BluezError),
+
+    /// Happens when [`export`] cannot write to the provided [`io::Write`], or to the output file.
+    ///
+    /// It holds the underlying [`io::Error`].
+    ///
+    /// [`export`]: crate::export
+    /// [`io::Error`]: std::io::Error
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Bluez(error) => write!(f, "export: bluez error: {}", error),
+            Error::Io(error) => write!(f, "export: io error: {}", error),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<BluezError> for Error {
+    fn from(value: BluezError) -> Self {
+        Self::Bluez(value)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/// Defines the formats of an [`export`] output, which [`import`] reads back.
+///
+/// [`export`]: crate::export
+/// [`import`]: crate::import
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// An array of objects keyed by the lowercase column names. A device without notes has `null` notes.
+    #[default]
+    Json,
+
+    /// RFC 4180 CSV with a header row. A device without notes has empty notes.
+    Csv,
+}
+
+/// Defines the arguments that [`export`] can take.
+///
+/// [`export`]: crate::export
+#[derive(Debug, Default, Args)]
+pub struct ExportArgs {
+    /// Set the format of the export.
+    #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+    pub format: ExportFormat,
+
+    /// Write the export to the file at PATH instead, and only report the number of devices.
+    ///
+    /// The file is created if it does not exist, and truncated if it does.
+    #[arg(short, long, value_name = "PATH")]
+    pub out: Option<PathBuf>,
+}
+
+const EXPORT_COLUMNS: [ExportColumn; 5] = [
+    ExportColumn::Alias,
+    ExportColumn::Address,
+    ExportColumn::Paired,
+    ExportColumn::Trusted,
+    ExportColumn::Notes,
+];
+
+#[derive(Copy, Clone)]
+enum ExportColumn {
+    Alias,
+    Address,
+    Paired,
+    Trusted,
+    Notes,
+}
+
+impl From<&ExportColumn> for String {
+    fn from(value: &ExportColumn) -> Self {
+        let str = match value {
+            ExportColumn::Alias => "ALIAS",
+            ExportColumn::Address => "ADDRESS",
+            ExportColumn::Paired => "PAIRED",
+            ExportColumn::Trusted => "TRUSTED",
+            ExportColumn::Notes => "NOTES",
+        };
+
+        str.to_string()
+    }
+}
+
+/// Defines a known device along with its notes.
+struct ExportedDevice<'a> {
+    device: &'a bluez::BluezDevice,
+    notes: Option<&'a str>,
+}
+
+impl TableFormattable<ExportColumn> for ExportedDevice<'_> {
+    fn get_cell_value_by_column(&self, column: &ExportColumn) -> String {
+        match column {
+            ExportColumn::Alias => self.device.alias().to_string(),
+            ExportColumn::Address => self.device.address().to_string(),
+            ExportColumn::Paired => self.device.paired().to_string(),
+            ExportColumn::Trusted => self.device.trusted().to_string(),
+            ExportColumn::Notes => self.notes.unwrap_or_default().to_string(),
+        }
+    }
+}
+
+impl JsonFormattable<ExportColumn> for ExportedDevice<'_> {
+    fn get_json_value_by_column(&self, column: &ExportColumn) -> Value {
+        match column {
+            ExportColumn::Alias => Value::from(self.device.alias()),
+            ExportColumn::Address => Value::from(self.device.address()),
+            ExportColumn::Paired => Value::from(self.device.paired()),
+            ExportColumn::Trusted => Value::from(self.device.trusted()),
+            ExportColumn::Notes => self.notes.map(Value::from).unwrap_or(Value::Null),
+        }
+    }
+}
+
+/// Exports the known Bluetooth devices on the host along with their notes by using a [`BluezClient`].
+///
+/// Each device is exported with its `ALIAS`, `ADDRESS`, `PAIRED`, `TRUSTED` and `NOTES`, where the notes are taken from the provided [`Notes`] by the address of the device. The export can be read back by [`import`] to restore the notes.
+///
+/// The format of the export is `args.format`, which is JSON by default. Please see [`ExportFormat`] for more details.
+///
+/// The export is written to the provided [`io::Write`]. If `args.out` is [`Some`], then it is written to that file instead, which is created or truncated. In that case, only the number of exported devices is written to the provided [`io::Write`], e.g. `exported 3 device(s) to backup.json`.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function can return all variants of [`ExportError`] based on given conditions. For more details, please see the error documentation.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io::Cursor;
+/// use bt::{export, BluezClient, ExportArgs, ExportFormat, Notes};
+///
+/// let bluez_client = BluezClient::new().unwrap();
+/// let notes = Notes::load().unwrap();
+/// let mut output = Cursor::new(vec![]);
+///
+/// let args = ExportArgs {
+///     format: ExportFormat::Csv,
+///     ..Default::default()
+/// };
+///
+/// match export(&bluez_client, &mut output, &notes, &args) {
+///     Ok(_) => println!("{}", String::from_utf8(output.into_inner()).unwrap()),
+///     Err(e) => eprintln!("{}", e),
+/// }
+/// ```
+///
+/// [`BluezClient`]: crate::BluezClient
+/// [`io::Write`]: std::io::Write
+/// [`Some`]: std::option::Option::Some
+/// [`Notes`]: crate::Notes
+/// [`ExportFormat`]: crate::ExportFormat
+/// [`ExportError`]: crate::ExportError
+/// [`import`]: crate::import
+pub fn export(
+    bluez: &crate::BluezClient,
+    w: &mut impl io::Write,
+    notes: &Notes,
+    args: &ExportArgs,
+) -> Result<(), Error> {
+    let devices = bluez.devices()?;
+    let exported_devices = devices.iter().map(|device| ExportedDevice {
+        device,
+        notes: notes.note(device.address()),
+    });
+
+    let out_buf = match args.format {
+        ExportFormat::Json => exported_devices.to_json(&EXPORT_COLUMNS, true).to_string(),
+        ExportFormat::Csv => exported_devices.to_csv(&EXPORT_COLUMNS).to_string(),
+    };
+
+    match &args.out {
+        Some(path) => {
+            fs::write(path, out_buf)?;
+            writeln!(
+                w,
+                "exported {} device(s) to {}",
+                devices.len(),
+                path.display()
+            )?;
+        }
+        None => w.write_all(out_buf.as_bytes())?,
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_should_export_the_devices_with_their_notes() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("speaker")
+                .address("AA:AA:AA:AA:AA:AA")
+                .paired(true)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("mouse")
+                .address("BB:BB:BB:BB:BB:BB")
+                .build(),
+        ]);
+
+        let mut notes = Notes::default();
+        notes.set("AA:AA:AA:AA:AA:AA", "living room, left");
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = ExportArgs {
+            format: ExportFormat::Csv,
+            ..Default::default()
+        };
+
+        let result = export(&bluez, &mut out_buf, &notes, &args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(
+            result,
+            "ALIAS,ADDRESS,PAIRED,TRUSTED,NOTES\r\n\
+             speaker,AA:AA:AA:AA:AA:AA,true,false,\"living room, left\"\r\n\
+             mouse,BB:BB:BB:BB:BB:BB,false,false,\r\n"
+        );
+    }
+}
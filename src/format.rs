@@ -1,9 +1,42 @@
-use std::fmt;
+use std::{
+    cmp::Ordering,
+    error, fmt,
+    io::{self, IsTerminal},
+};
 
-use tabled::{builder::Builder as TableBuilder, settings::Style};
+use clap::ValueEnum;
+
+use crate::bluez::BluezDevice;
+
+use serde_json::{Map, Value};
+use tabled::{
+    builder::Builder as TableBuilder,
+    settings::{Alignment, Padding, Style, object::Columns},
+};
 
 pub trait TableFormattable<C> {
     fn get_cell_value_by_column(&self, column: &C) -> String;
+
+    /// Provides the alignment of a column in the pretty output.
+    ///
+    /// The columns are left-aligned by default. Numeric columns should be right-aligned.
+    fn get_alignment_by_column(_column: &C) -> Alignment
+    where
+        Self: Sized,
+    {
+        Alignment::left()
+    }
+}
+
+/// Defines the styles of a pretty output.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum TableStyle {
+    /// The columns are separated by a blank gap, and the cells are padded on both sides.
+    #[default]
+    Blank,
+
+    /// The columns are separated by a single space, and the cells are not padded, which fits more on narrow terminals.
+    Compact,
 }
 
 pub trait PrettyFormatter<I, C>
@@ -12,6 +45,14 @@ where
     for<'a> &'a C: Into<String>,
 {
     fn to_pretty(self, columns: &[C]) -> impl fmt::Display
+    where
+        Self: Iterator<Item = I> + Sized,
+    {
+        self.to_pretty_with_style(columns, TableStyle::Blank)
+    }
+
+    /// Renders the table in `style` instead of the blank style.
+    fn to_pretty_with_style(self, columns: &[C], style: TableStyle) -> impl fmt::Display
     where
         Self: Iterator<Item = I> + Sized,
     {
@@ -32,6 +73,13 @@ where
 
         let mut table = builder.build();
         table.with(Style::blank());
+        if style == TableStyle::Compact {
+            table.with(Padding::zero());
+        }
+
+        for (idx, column) in columns.iter().enumerate() {
+            table.modify(Columns::new(idx..=idx), I::get_alignment_by_column(column));
+        }
 
         table
     }
@@ -49,30 +97,1075 @@ pub trait TerseFormatter<I, C>
 where
     I: TableFormattable<C>,
 {
-    fn to_terse(self, columns: &[C]) -> impl fmt::Display
+    fn to_terse_with_delimiter(self, columns: &[C], delimiter: &str) -> impl fmt::Display
+    where
+        Self: Iterator<Item = I> + Sized,
+    {
+        self.to_terse_with_terminator(columns, delimiter, '\n')
+    }
+
+    /// Terminates each record with `terminator` instead of a newline.
+    ///
+    /// Every record is terminated, including the last one.
+    fn to_terse_with_terminator(
+        self,
+        columns: &[C],
+        delimiter: &str,
+        terminator: char,
+    ) -> impl fmt::Display
+    where
+        Self: Iterator<Item = I> + Sized,
+    {
+        self.map(|i| {
+            let mut values = columns
+                .iter()
+                .map(|c| i.get_cell_value_by_column(c))
+                .collect::<Vec<String>>()
+                .join(delimiter);
+            values.push(terminator);
+            values
+        })
+        .collect::<String>()
+    }
+}
+
+impl<I, T, C> TerseFormatter<I, C> for T
+where
+    I: TableFormattable<C>,
+    T: Iterator<Item = I>,
+    for<'a> &'a C: Into<String>,
+{
+}
+
+/// Defines the layouts that the `--columns` and `--values` arguments of a listing resolve to.
+#[derive(Debug, PartialEq)]
+pub enum ListingLayout {
+    Pretty,
+    Terse,
+}
+
+/// Resolves the layout and the columns of a listing from its `--columns` and `--values` arguments.
+///
+/// `columns` takes precedence over `values`. The provided columns are kept verbatim, in the order they are given, so that every command and every format renders them the same way.
+/// If no columns are provided, or the provided list is empty, then `defaults` are used instead.
+pub fn resolve_columns<C: Copy>(
+    columns: Option<&[C]>,
+    values: Option<&[C]>,
+    defaults: &[C],
+) -> (ListingLayout, Vec<C>) {
+    let (layout, user_columns) = match (columns, values) {
+        (None, None) => (ListingLayout::Pretty, None),
+        (None, values) => (ListingLayout::Terse, values),
+        (columns, _) => (ListingLayout::Pretty, columns),
+    };
+
+    let columns = match user_columns {
+        Some(c) if !c.is_empty() => c.to_vec(),
+        _ => defaults.to_vec(),
+    };
+
+    (layout, columns)
+}
+
+/// Quotes a CSV field as described in RFC 4180.
+///
+/// A field is only quoted when it contains a comma, a double quote or a line break. Double quotes are escaped by doubling them.
+fn quote_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Parses RFC 4180 CSV into its records, which is the reverse of [`CsvFormatter::to_csv()`].
+///
+/// Both CRLF and LF terminated records are accepted, and the empty lines are skipped. [`None`] is returned if a quoted field is not closed.
+pub fn parse_csv(content: &str) -> Option<Vec<Vec<String>>> {
+    let mut records = vec![];
+    let (mut record, mut field) = (vec![], String::new());
+    let (mut in_quotes, mut is_quoted) = (false, false);
+
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if in_quotes => in_quotes = false,
+            '"' if field.is_empty() => (in_quotes, is_quoted) = (true, true),
+            _ if in_quotes => field.push(c),
+            ',' => {
+                record.push(std::mem::take(&mut field));
+                is_quoted = false;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                if is_quoted || !field.is_empty() || !record.is_empty() {
+                    record.push(std::mem::take(&mut field));
+                    records.push(std::mem::take(&mut record));
+                }
+                is_quoted = false;
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return None;
+    }
+    if is_quoted || !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    Some(records)
+}
+
+pub trait CsvFormatter<I, C>
+where
+    I: TableFormattable<C>,
+    for<'a> &'a C: Into<String>,
+{
+    /// Formats the items as RFC 4180 CSV, with a header row of the column names.
+    ///
+    /// Every record, including the header, is terminated by CRLF.
+    fn to_csv(self, columns: &[C]) -> impl fmt::Display
     where
         Self: Iterator<Item = I> + Sized,
     {
-        let output = self
-            .map(|i| {
-                let mut values = columns
+        let to_record = |fields: Vec<String>| {
+            let mut record = fields
+                .iter()
+                .map(|f| quote_csv_field(f))
+                .collect::<Vec<String>>()
+                .join(",");
+            record.push_str("\r\n");
+            record
+        };
+
+        let header = to_record(columns.iter().map(|c| c.into()).collect());
+        let records = self.map(|i| {
+            to_record(
+                columns
                     .iter()
                     .map(|c| i.get_cell_value_by_column(c))
-                    .collect::<Vec<String>>()
-                    .join("/");
-                values.push('\n');
-                values
-            })
-            .collect::<String>();
+                    .collect(),
+            )
+        });
 
-        output
+        std::iter::once(header).chain(records).collect::<String>()
     }
 }
 
-impl<I, T, C> TerseFormatter<I, C> for T
+impl<I, T, C> CsvFormatter<I, C> for T
+where
+    I: TableFormattable<C>,
+    T: Iterator<Item = I>,
+    for<'a> &'a C: Into<String>,
+{
+}
+
+pub trait PlainFormatter<I, C>
+where
+    I: TableFormattable<C>,
+    for<'a> &'a C: Into<String>,
+{
+    /// Formats the items as lines of their values separated by a single space, with a header line of the column names.
+    ///
+    /// Unlike [`PrettyFormatter`], the columns are not aligned, so the width of each value is kept as is.
+    fn to_plain(self, columns: &[C]) -> impl fmt::Display
+    where
+        Self: Iterator<Item = I> + Sized,
+    {
+        let to_line = |fields: Vec<String>| {
+            let mut line = fields.join(" ");
+            line.push('\n');
+            line
+        };
+
+        let header = to_line(columns.iter().map(|c| c.into()).collect());
+        let lines = self.map(|i| {
+            to_line(
+                columns
+                    .iter()
+                    .map(|c| i.get_cell_value_by_column(c))
+                    .collect(),
+            )
+        });
+
+        std::iter::once(header).chain(lines).collect::<String>()
+    }
+}
+
+impl<I, T, C> PlainFormatter<I, C> for T
 where
     I: TableFormattable<C>,
     T: Iterator<Item = I>,
     for<'a> &'a C: Into<String>,
 {
 }
+
+pub trait JsonFormattable<C> {
+    fn get_json_value_by_column(&self, column: &C) -> Value;
+}
+
+pub trait JsonFormatter<I, C>
+where
+    I: JsonFormattable<C>,
+    for<'a> &'a C: Into<String>,
+{
+    fn to_json(self, columns: &[C], pretty: bool) -> impl fmt::Display
+    where
+        Self: Iterator<Item = I> + Sized,
+    {
+        let listing = self.to_json_value(columns);
+
+        if pretty {
+            format!("{:#}\n", listing)
+        } else {
+            format!("{}\n", listing)
+        }
+    }
+
+    /// Provides the listing as an array of objects keyed by the lowercase column names, before it is rendered.
+    fn to_json_value(self, columns: &[C]) -> Value
+    where
+        Self: Iterator<Item = I> + Sized,
+    {
+        let listing = self
+            .map(|i| json_object(&i, columns))
+            .collect::<Vec<Value>>();
+
+        Value::Array(listing)
+    }
+}
+
+/// Provides the object of a single item of a JSON listing, which is keyed by the lowercase names of `columns`.
+fn json_object<I, C>(item: &I, columns: &[C]) -> Value
+where
+    I: JsonFormattable<C>,
+    for<'a> &'a C: Into<String>,
+{
+    let object = columns
+        .iter()
+        .map(|c| {
+            let key: String = c.into();
+            (key.to_lowercase(), item.get_json_value_by_column(c))
+        })
+        .collect::<Map<String, Value>>();
+
+    Value::Object(object)
+}
+
+impl<I, T, C> JsonFormatter<I, C> for T
+where
+    I: JsonFormattable<C>,
+    T: Iterator<Item = I>,
+    for<'a> &'a C: Into<String>,
+{
+}
+
+pub trait YamlFormatter<I, C>: JsonFormatter<I, C>
+where
+    I: JsonFormattable<C>,
+    for<'a> &'a C: Into<String>,
+{
+    /// Renders the same listing as [`JsonFormatter.to_json()`] in YAML, i.e. a sequence of mappings keyed by the lowercase column names.
+    ///
+    /// [`JsonFormatter.to_json()`]: JsonFormatter::to_json()
+    fn to_yaml(self, columns: &[C]) -> impl fmt::Display
+    where
+        Self: Iterator<Item = I> + Sized,
+    {
+        let mut yaml = yaml_lines(&self.to_json_value(columns)).join("\n");
+        yaml.push('\n');
+
+        yaml
+    }
+}
+
+/// Renders `value` as the lines of a YAML block.
+///
+/// The non-empty sequences and mappings are rendered in the block style, and the rest are rendered as scalars. Please see [`yaml_scalar`].
+fn yaml_lines(value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(items) if !items.is_empty() => items
+            .iter()
+            .flat_map(|item| {
+                yaml_lines(item)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, line)| match i {
+                        0 => format!("- {}", line),
+                        _ => format!("  {}", line),
+                    })
+            })
+            .collect(),
+        Value::Object(object) if !object.is_empty() => object
+            .iter()
+            .flat_map(|(key, value)| {
+                let key = yaml_string(key);
+
+                match value {
+                    Value::Array(a) if !a.is_empty() => nested_yaml_lines(key, value),
+                    Value::Object(o) if !o.is_empty() => nested_yaml_lines(key, value),
+                    _ => vec![format!("{}: {}", key, yaml_scalar(value))],
+                }
+            })
+            .collect(),
+        _ => vec![yaml_scalar(value)],
+    }
+}
+
+/// Renders the non-empty sequence or mapping `value` under `key`, indented by two spaces.
+fn nested_yaml_lines(key: String, value: &Value) -> Vec<String> {
+    let mut lines = vec![format!("{}:", key)];
+    lines.extend(
+        yaml_lines(value)
+            .into_iter()
+            .map(|line| format!("  {}", line)),
+    );
+
+    lines
+}
+
+/// Renders `value` as a YAML scalar in the flow style, e.g. `[]` for an empty sequence, or `null` for a JSON `null`.
+fn yaml_scalar(value: &Value) -> String {
+    match value {
+        Value::String(string) => yaml_string(string),
+        Value::Array(_) => String::from("[]"),
+        Value::Object(_) => String::from("{}"),
+        // NOTE: The JSON literals of the rest are valid YAML as well.
+        _ => value.to_string(),
+    }
+}
+
+/// Renders `string` as a YAML string, which is only quoted if it can be mistaken for another type or for the YAML syntax.
+///
+/// A quoted string uses the JSON escapes, which are valid in a double-quoted YAML string.
+fn yaml_string(string: &str) -> String {
+    let is_plain = string
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic())
+        && string
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.'))
+        && !string.ends_with(' ')
+        && !YAML_KEYWORDS.contains(&string.to_lowercase().as_str());
+
+    if is_plain {
+        string.to_string()
+    } else {
+        Value::String(string.to_string()).to_string()
+    }
+}
+
+/// The plain YAML scalars that are read as a boolean or a null, instead of a string.
+const YAML_KEYWORDS: [&str; 11] = [
+    "true", "false", "yes", "no", "on", "off", "y", "n", "null", "nan", "inf",
+];
+
+impl<I, T, C> YamlFormatter<I, C> for T
+where
+    I: JsonFormattable<C>,
+    T: Iterator<Item = I>,
+    for<'a> &'a C: Into<String>,
+{
+}
+
+/// Describes the values of a column in the JSON output, by a JSON Schema.
+pub trait JsonSchemaColumn {
+    fn get_json_schema(&self) -> Value;
+}
+
+/// Provides the JSON Schema of a JSON output with `columns`, which is an array of objects keyed by the lowercase column names.
+///
+/// Since the columns of an output can be picked, none of the keys are required.
+pub fn json_listing_schema<C>(title: &str, columns: &[C]) -> Value
+where
+    C: JsonSchemaColumn,
+    for<'a> &'a C: Into<String>,
+{
+    let properties = columns
+        .iter()
+        .map(|c| {
+            let key: String = c.into();
+            (key.to_lowercase(), c.get_json_schema())
+        })
+        .collect::<Map<String, Value>>();
+
+    serde_json::json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": title,
+        "type": "array",
+        "items": {
+            "type": "object",
+            "properties": properties,
+            "additionalProperties": false,
+        },
+    })
+}
+
+/// Provides the JSON Schemas of the values that are shared by the device columns.
+pub mod device_schema {
+    use serde_json::{Value, json};
+
+    pub fn string() -> Value {
+        json!({ "type": "string" })
+    }
+
+    pub fn address() -> Value {
+        json!({ "type": "string", "pattern": "^([0-9A-F]{2}:){5}[0-9A-F]{2}$" })
+    }
+
+    pub fn boolean() -> Value {
+        json!({ "type": "boolean" })
+    }
+
+    pub fn capabilities() -> Value {
+        json!({
+            "type": "array",
+            "items": { "enum": ["audio", "input", "phone", "network"] },
+        })
+    }
+
+    pub fn battery() -> Value {
+        json!({ "type": ["integer", "null"], "minimum": 0, "maximum": 100 })
+    }
+
+    pub fn state() -> Value {
+        json!({ "enum": ["connected", "connecting", "disconnected"] })
+    }
+
+    pub fn rssi() -> Value {
+        json!({ "type": ["integer", "null"] })
+    }
+
+    pub fn tx_power() -> Value {
+        json!({ "type": ["integer", "null"] })
+    }
+}
+
+/// Defines error variants that may be returned while parsing a [`Template`].
+#[derive(Debug, PartialEq)]
+pub enum TemplateError {
+    /// Happens when a placeholder does not match any known field.
+    /// It holds the name of the placeholder.
+    UnknownPlaceholder(String),
+
+    /// Happens when a `{` is not closed by a `}`.
+    UnclosedPlaceholder,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::UnknownPlaceholder(name) => {
+                write!(f, "unknown placeholder {{{}}} in template", name)
+            }
+            TemplateError::UnclosedPlaceholder => write!(f, "unclosed placeholder in template"),
+        }
+    }
+}
+
+impl error::Error for TemplateError {}
+
+pub trait TemplateFormattable<C> {
+    /// Provides the value that replaces the placeholder of `column`.
+    ///
+    /// A missing value is rendered as an empty string.
+    fn get_template_value_by_column(&self, column: &C) -> Option<String>;
+}
+
+enum TemplateSegment<C> {
+    Literal(String),
+    Placeholder(C),
+}
+
+/// A user-supplied format string where each `{field}` placeholder is replaced by the value of that field.
+///
+/// The field names are the names of the columns `C`, such as `{alias}` or `{address}`.
+pub struct Template<C> {
+    segments: Vec<TemplateSegment<C>>,
+}
+
+impl<C: ValueEnum> Template<C> {
+    pub fn parse(template: &str) -> Result<Self, TemplateError> {
+        let mut segments = vec![];
+        let mut rest = template;
+
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                segments.push(TemplateSegment::Literal(rest[..start].to_string()));
+            }
+
+            let end = rest[start..]
+                .find('}')
+                .ok_or(TemplateError::UnclosedPlaceholder)?
+                + start;
+
+            let name = &rest[start + 1..end];
+            let column = C::from_str(name, false)
+                .map_err(|_| TemplateError::UnknownPlaceholder(name.to_string()))?;
+            segments.push(TemplateSegment::Placeholder(column));
+
+            rest = &rest[end + 1..];
+        }
+
+        if !rest.is_empty() {
+            segments.push(TemplateSegment::Literal(rest.to_string()));
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// Provides the columns of the placeholders, in the order they appear in the template.
+    pub fn placeholders(&self) -> impl Iterator<Item = &C> {
+        self.segments.iter().filter_map(|segment| match segment {
+            TemplateSegment::Placeholder(column) => Some(column),
+            TemplateSegment::Literal(_) => None,
+        })
+    }
+
+    fn render(&self, item: &impl TemplateFormattable<C>) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                TemplateSegment::Literal(literal) => literal.clone(),
+                TemplateSegment::Placeholder(column) => item
+                    .get_template_value_by_column(column)
+                    .unwrap_or_default(),
+            })
+            .collect()
+    }
+}
+
+pub trait TemplateFormatter<I, C>
+where
+    I: TemplateFormattable<C>,
+    C: ValueEnum,
+{
+    /// Renders `template` for each item, and terminates each rendered item with `terminator`.
+    fn to_template(self, template: &Template<C>, terminator: char) -> impl fmt::Display
+    where
+        Self: Iterator<Item = I> + Sized,
+    {
+        self.map(|i| {
+            let mut rendered = template.render(&i);
+            rendered.push(terminator);
+            rendered
+        })
+        .collect::<String>()
+    }
+}
+
+impl<I, T, C> TemplateFormatter<I, C> for T
+where
+    I: TemplateFormattable<C>,
+    T: Iterator<Item = I>,
+    C: ValueEnum,
+{
+}
+
+pub trait SortableByColumn<C> {
+    fn cmp_by_column(&self, other: &Self, column: &C) -> Ordering;
+}
+
+/// Defines a key of a multi-key sort: the column to compare, and whether its order is reversed.
+pub struct SortKey<C> {
+    pub column: C,
+    pub reverse: bool,
+}
+
+/// Sorts `items` by each of the `keys` in order, where the next key only breaks the ties of the previous ones.
+///
+/// The sort is stable, so the items that are equal by all keys keep their original order.
+pub fn sort_by_keys<I, C>(items: &mut [I], keys: &[SortKey<C>])
+where
+    I: SortableByColumn<C>,
+{
+    items.sort_by(|a, b| {
+        keys.iter().fold(Ordering::Equal, |ordering, key| {
+            ordering.then_with(|| {
+                let ordering = a.cmp_by_column(b, &key.column);
+                if key.reverse {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            })
+        })
+    });
+}
+
+/// Defines the fields of a Bluetooth device that can be shown by a [`DeviceTable`].
+///
+/// It is shared by the commands that list devices, and each command picks its own default subset.
+///
+/// Besides their names, the fields can be referred to by a single letter: `a` (alias), `d` (address), `c` (connected), `t` (trusted), `b` (bonded), `p` (paired), `r` (rssi) and `y` (battery).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
+pub enum DeviceField {
+    /// The alias of the device.
+    #[value(alias = "a")]
+    Alias,
+
+    /// The MAC address of the device.
+    #[value(alias = "d")]
+    Address,
+
+    /// Whether the device is connected.
+    #[value(alias = "c")]
+    Connected,
+
+    /// Whether the device is trusted.
+    #[value(alias = "t")]
+    Trusted,
+
+    /// Whether the device is bonded.
+    #[value(alias = "b")]
+    Bonded,
+
+    /// Whether the device is paired.
+    #[value(alias = "p")]
+    Paired,
+
+    /// The categories of the device (`audio`, `input`, `phone`, `network`) derived from its service UUIDs.
+    ///
+    /// It can be selected as `profiles` as well.
+    #[value(alias = "profiles")]
+    Capabilities,
+
+    /// The battery percentage of the device, which is only known for the connected devices.
+    #[value(alias = "y")]
+    Battery,
+
+    /// The connection state of the device: `connected`, `connecting` or `disconnected`.
+    State,
+
+    /// The signal strength of the device, which is only known while it emits Bluetooth signals.
+    #[value(alias = "r")]
+    Rssi,
+
+    /// The transmission power (in dBm) advertised by the device, which is mostly known for the BLE devices.
+    ///
+    /// Along with the RSSI, it helps to estimate the distance of the device.
+    TxPower,
+
+    /// Whether the device is paired or bonded on the host.
+    Known,
+}
+
+impl From<&DeviceField> for String {
+    fn from(value: &DeviceField) -> Self {
+        let str = match value {
+            DeviceField::Alias => "ALIAS",
+            DeviceField::Address => "ADDRESS",
+            DeviceField::Connected => "CONNECTED",
+            DeviceField::Trusted => "TRUSTED",
+            DeviceField::Bonded => "BONDED",
+            DeviceField::Paired => "PAIRED",
+            DeviceField::Capabilities => "CAPABILITIES",
+            DeviceField::Battery => "BATTERY",
+            DeviceField::State => "STATE",
+            DeviceField::Rssi => "RSSI",
+            DeviceField::TxPower => "TX_POWER",
+            DeviceField::Known => "KNOWN",
+        };
+
+        str.to_string()
+    }
+}
+
+impl JsonSchemaColumn for DeviceField {
+    fn get_json_schema(&self) -> Value {
+        match self {
+            DeviceField::Alias => device_schema::string(),
+            DeviceField::Address => device_schema::address(),
+            DeviceField::Connected
+            | DeviceField::Trusted
+            | DeviceField::Bonded
+            | DeviceField::Paired
+            | DeviceField::Known => device_schema::boolean(),
+            DeviceField::Capabilities => device_schema::capabilities(),
+            DeviceField::Battery => device_schema::battery(),
+            DeviceField::State => device_schema::state(),
+            DeviceField::Rssi => device_schema::rssi(),
+            DeviceField::TxPower => device_schema::tx_power(),
+        }
+    }
+}
+
+/// Defines a row of a [`DeviceTable`], which is a device along with whether it is known by the host.
+#[derive(Copy, Clone)]
+pub struct DeviceRow<'a> {
+    pub device: &'a BluezDevice,
+    pub known: bool,
+}
+
+impl<'a> From<&'a BluezDevice> for DeviceRow<'a> {
+    /// A device is known if it is paired or bonded.
+    fn from(device: &'a BluezDevice) -> Self {
+        Self {
+            device,
+            known: device.paired() || device.bonded(),
+        }
+    }
+}
+
+impl TableFormattable<DeviceField> for DeviceRow<'_> {
+    fn get_cell_value_by_column(&self, column: &DeviceField) -> String {
+        let device = self.device;
+        match column {
+            DeviceField::Alias => device.alias().to_string(),
+            DeviceField::Address => device.address().to_string(),
+            DeviceField::Connected => device.connected().to_string(),
+            DeviceField::Trusted => device.trusted().to_string(),
+            DeviceField::Bonded => device.bonded().to_string(),
+            DeviceField::Paired => device.paired().to_string(),
+            DeviceField::Capabilities => {
+                let capabilities = device.capabilities();
+                if capabilities.is_empty() {
+                    "-".to_string()
+                } else {
+                    capabilities.join(",")
+                }
+            }
+            DeviceField::Battery => match device.battery() {
+                Some(battery) => battery.to_string(),
+                None => "-".to_string(),
+            },
+            DeviceField::State => device.connection_state().to_string(),
+            DeviceField::Rssi => device.rssi().unwrap_or(0).to_string(),
+            DeviceField::TxPower => match device.tx_power() {
+                Some(tx_power) => tx_power.to_string(),
+                None => "-".to_string(),
+            },
+            DeviceField::Known => self.known.to_string(),
+        }
+    }
+
+    fn get_alignment_by_column(column: &DeviceField) -> Alignment {
+        match column {
+            DeviceField::Battery | DeviceField::Rssi | DeviceField::TxPower => Alignment::right(),
+            _ => Alignment::left(),
+        }
+    }
+}
+
+impl TemplateFormattable<DeviceField> for DeviceRow<'_> {
+    fn get_template_value_by_column(&self, column: &DeviceField) -> Option<String> {
+        match column {
+            DeviceField::Battery => self.device.battery().map(|b| b.to_string()),
+            DeviceField::Capabilities => Some(self.device.capabilities().join(",")),
+            DeviceField::Rssi => self.device.rssi().map(|rssi| rssi.to_string()),
+            DeviceField::TxPower => self.device.tx_power().map(|t| t.to_string()),
+            _ => Some(self.get_cell_value_by_column(column)),
+        }
+    }
+}
+
+impl SortableByColumn<DeviceField> for DeviceRow<'_> {
+    fn cmp_by_column(&self, other: &Self, column: &DeviceField) -> Ordering {
+        let (device, other_device) = (self.device, other.device);
+        match column {
+            DeviceField::Alias => device.alias().cmp(other_device.alias()),
+            DeviceField::Address => device.address().cmp(other_device.address()),
+            DeviceField::Connected => device.connected().cmp(&other_device.connected()),
+            DeviceField::Trusted => device.trusted().cmp(&other_device.trusted()),
+            DeviceField::Bonded => device.bonded().cmp(&other_device.bonded()),
+            DeviceField::Paired => device.paired().cmp(&other_device.paired()),
+            DeviceField::Capabilities => device.capabilities().cmp(&other_device.capabilities()),
+            DeviceField::Battery => device.battery().cmp(other_device.battery()),
+            DeviceField::State => device
+                .connection_state()
+                .cmp(&other_device.connection_state()),
+            DeviceField::Rssi => device.rssi().cmp(other_device.rssi()),
+            DeviceField::TxPower => device.tx_power().cmp(other_device.tx_power()),
+            DeviceField::Known => self.known.cmp(&other.known),
+        }
+    }
+}
+
+impl JsonFormattable<DeviceField> for DeviceRow<'_> {
+    fn get_json_value_by_column(&self, column: &DeviceField) -> Value {
+        let device = self.device;
+        match column {
+            DeviceField::Alias => Value::from(device.alias()),
+            DeviceField::Address => Value::from(device.address()),
+            DeviceField::Connected => Value::from(device.connected()),
+            DeviceField::Trusted => Value::from(device.trusted()),
+            DeviceField::Bonded => Value::from(device.bonded()),
+            DeviceField::Paired => Value::from(device.paired()),
+            DeviceField::Capabilities => Value::from(device.capabilities()),
+            DeviceField::Battery => Value::from(*device.battery()),
+            DeviceField::State => Value::from(device.connection_state().to_string()),
+            DeviceField::Rssi => Value::from(*device.rssi()),
+            DeviceField::TxPower => Value::from(*device.tx_power()),
+            DeviceField::Known => Value::from(self.known),
+        }
+    }
+}
+
+/// Renders the same [`DeviceField`]'s of a list of devices in any of the listing formats, so that the commands do not need their own column types.
+pub struct DeviceTable<'a> {
+    fields: &'a [DeviceField],
+    rows: Vec<DeviceRow<'a>>,
+}
+
+impl<'a> DeviceTable<'a> {
+    pub fn new(fields: &'a [DeviceField], rows: impl IntoIterator<Item = DeviceRow<'a>>) -> Self {
+        Self {
+            fields,
+            rows: rows.into_iter().collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn to_pretty(&self) -> String {
+        self.rows.iter().copied().to_pretty(self.fields).to_string()
+    }
+
+    pub fn to_terse(&self, delimiter: &str, terminator: char) -> String {
+        self.rows
+            .iter()
+            .copied()
+            .to_terse_with_terminator(self.fields, delimiter, terminator)
+            .to_string()
+    }
+
+    pub fn to_json(&self, pretty: bool) -> String {
+        self.rows
+            .iter()
+            .copied()
+            .to_json(self.fields, pretty)
+            .to_string()
+    }
+
+    /// Renders the same JSON as [`DeviceTable::to_json()`], but as an object that maps the value of `key` in each row to the object of the row. If a key is repeated, then the last row wins.
+    pub fn to_keyed_json(&self, key: &DeviceField, pretty: bool) -> String {
+        let listing = self
+            .rows
+            .iter()
+            .map(|row| {
+                (
+                    row.get_cell_value_by_column(key),
+                    json_object(row, self.fields),
+                )
+            })
+            .collect::<Map<String, Value>>();
+
+        if pretty {
+            format!("{:#}\n", Value::Object(listing))
+        } else {
+            format!("{}\n", Value::Object(listing))
+        }
+    }
+
+    /// Renders `template` for each row instead of the fields of the table.
+    pub fn to_template(&self, template: &Template<DeviceField>, terminator: char) -> String {
+        self.rows
+            .iter()
+            .copied()
+            .to_template(template, terminator)
+            .to_string()
+    }
+}
+
+/// Defines when the output is colored.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorMode {
+    /// Color the output only if stdout is a terminal.
+    #[default]
+    Auto,
+
+    /// Always color the output.
+    Always,
+
+    /// Never color the output.
+    Never,
+}
+
+impl ColorMode {
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            ColorMode::Auto => io::stdout().is_terminal(),
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+        }
+    }
+}
+
+const ANSI_RED: &str = "\x1b[31m";
+const ANSI_GREEN: &str = "\x1b[32m";
+const ANSI_YELLOW: &str = "\x1b[33m";
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// Formats a battery percentage as `%N`.
+///
+/// If `color` is `true`, then the percentage is green above 50%, yellow above 20%, and red otherwise.
+pub fn format_battery(percentage: u8, color: bool) -> String {
+    if !color {
+        return format!("%{}", percentage);
+    }
+
+    let ansi_color = match percentage {
+        51.. => ANSI_GREEN,
+        21..=50 => ANSI_YELLOW,
+        _ => ANSI_RED,
+    };
+
+    format!("{}%{}{}", ansi_color, percentage, ANSI_RESET)
+}
+
+/// Formats the outcome counts of a batch command as its summary line, e.g. `disconnected=2 failed=1`.
+///
+/// If `json` is `true`, then the counts are formatted as JSON instead, e.g. `{"ok":2,"failed":1}`.
+pub fn format_summary(action: &str, ok: usize, failed: usize, json: bool) -> String {
+    // NOTE: The JSON is written by hand, since serde_json sorts the keys of
+    // an object, and "ok" is expected to come first.
+    match json {
+        true => format!("{{\"ok\":{},\"failed\":{}}}\n", ok, failed),
+        false => format!("{}={} failed={}\n", action, ok, failed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bluez::BluezTestDeviceBuilder;
+
+    fn test_devices() -> Vec<BluezDevice> {
+        vec![
+            BluezTestDeviceBuilder::new("Dev1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .connected(true)
+                .paired(true)
+                .battery(Some(80))
+                .rssi(Some(-40))
+                .uuids(&["0000110b-0000-1000-8000-00805f9b34fb"])
+                .build(),
+            BluezTestDeviceBuilder::new("Dev, \"2\"")
+                .address("BB:BB:BB:BB:BB:BB")
+                .build(),
+        ]
+    }
+
+    #[test]
+    fn it_should_render_the_fields_in_every_format() {
+        let devices = test_devices();
+        let fields = [
+            DeviceField::Alias,
+            DeviceField::Battery,
+            DeviceField::Rssi,
+            DeviceField::Known,
+        ];
+        let table = || DeviceTable::new(&fields, devices.iter().map(DeviceRow::from));
+
+        assert_eq!(
+            table().to_terse("/", '\n'),
+            "Dev1/80/-40/true\nDev, \"2\"/-/0/false\n"
+        );
+        assert_eq!(
+            table().to_json(false),
+            "[{\"alias\":\"Dev1\",\"battery\":80,\"known\":true,\"rssi\":-40},\
+             {\"alias\":\"Dev, \\\"2\\\"\",\"battery\":null,\"known\":false,\"rssi\":null}]\n"
+        );
+
+        let pretty = table().to_pretty();
+        let mut lines = pretty.lines();
+        assert!(lines.next().unwrap().trim_start().starts_with("ALIAS"));
+        assert!(lines.next().unwrap().trim_start().starts_with("Dev1"));
+
+        let template = Template::parse("{alias}:{capabilities}:{battery}").unwrap();
+        assert_eq!(
+            table().to_template(&template, '\n'),
+            "Dev1:audio:80\nDev, \"2\"::\n"
+        );
+    }
+
+    #[test]
+    fn it_should_sort_the_rows_by_the_fields() {
+        let devices = test_devices();
+        let fields = [DeviceField::Alias];
+
+        let mut rows = devices
+            .iter()
+            .map(DeviceRow::from)
+            .collect::<Vec<DeviceRow>>();
+        sort_by_keys(
+            &mut rows,
+            &[SortKey {
+                column: DeviceField::Known,
+                reverse: false,
+            }],
+        );
+        let table = DeviceTable::new(&fields, rows);
+
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.to_terse("/", '\n'), "Dev, \"2\"\nDev1\n");
+    }
+
+    #[test]
+    fn it_should_render_the_tx_power_of_the_devices_that_advertise_it() {
+        let devices = [
+            BluezTestDeviceBuilder::new("beacon")
+                .rssi(Some(-70))
+                .tx_power(Some(-59))
+                .build(),
+            BluezTestDeviceBuilder::new("headset")
+                .rssi(Some(-50))
+                .build(),
+        ];
+        let fields = [DeviceField::Alias, DeviceField::TxPower];
+        let table = || DeviceTable::new(&fields, devices.iter().map(DeviceRow::from));
+
+        assert_eq!(table().to_terse("/", '\n'), "beacon/-59\nheadset/-\n");
+        assert_eq!(
+            table().to_json(false),
+            "[{\"alias\":\"beacon\",\"tx_power\":-59},{\"alias\":\"headset\",\"tx_power\":null}]\n"
+        );
+
+        let template = Template::parse("{alias}:{tx-power}").unwrap();
+        assert_eq!(
+            table().to_template(&template, '\n'),
+            "beacon:-59\nheadset:\n"
+        );
+    }
+
+    #[test]
+    fn it_should_render_a_json_value_as_yaml() {
+        let value = serde_json::json!([
+            {
+                "alias": "Office Mouse",
+                "address": "AA:BB:CC:DD:EE:FF",
+                "battery": null,
+                "connected": true,
+                "note": "yes",
+                "rssi": -40,
+                "uuids": ["0000110b-0000-1000-8000-00805f9b34fb"],
+                "tags": [],
+            },
+        ]);
+
+        assert_eq!(
+            yaml_lines(&value).join("\n"),
+            "- address: \"AA:BB:CC:DD:EE:FF\"\n  \
+             alias: Office Mouse\n  \
+             battery: null\n  \
+             connected: true\n  \
+             note: \"yes\"\n  \
+             rssi: -40\n  \
+             tags: []\n  \
+             uuids:\n    \
+             - \"0000110b-0000-1000-8000-00805f9b34fb\""
+        );
+        assert_eq!(yaml_lines(&serde_json::json!([])), vec!["[]"]);
+        assert_eq!(yaml_string("line\nbreak"), "\"line\\nbreak\"");
+    }
+}
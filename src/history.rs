@@ -0,0 +1,265 @@
+use std::{
+    cmp::Ordering,
+    collections::BTreeMap,
+    env, error, fmt, fs, io,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::format::{SortKey, SortableByColumn, sort_by_keys};
+
+/// Defines error variants that may be returned while loading or saving a [`History`].
+///
+/// [`History`]: crate::History
+#[derive(Debug)]
+pub enum Error {
+    /// Happens when the history file exists but cannot be read, or when it cannot be written.
+    /// It holds the underlying [`io::Error`].
+    ///
+    /// [`io::Error`]: std::io::Error
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "history: io error: {}", error),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/// Defines the last-connected history of the devices, which is maintained locally by [`connect`].
+///
+/// The history is read from `$XDG_STATE_HOME/bt/history` (or `~/.local/state/bt/history`). Each line of the history file is an `ADDRESS,TIMESTAMP` pair, where the timestamp is in seconds since the Unix epoch. The malformed lines are skipped.
+///
+/// [`connect`]: crate::connect
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct History {
+    file: Option<PathBuf>,
+    last_connected: BTreeMap<String, u64>,
+}
+
+impl History {
+    /// Loads the history from its default location.
+    ///
+    /// If the history file does not exist, then an empty [`History`] is returned.
+    ///
+    /// # Errors
+    ///
+    /// This function can return all variants of [`HistoryError`] based on given conditions. For more details, please see the error documentation.
+    ///
+    /// [`History`]: crate::History
+    /// [`HistoryError`]: crate::HistoryError
+    pub fn load() -> Result<Self, Error> {
+        match Self::path() {
+            Some(path) => Self::load_from(path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Loads the history from `path`, which is also where [`History::save()`] writes to.
+    ///
+    /// Similar to [`History::load()`], an empty [`History`] is returned if the file does not exist.
+    ///
+    /// [`History`]: crate::History
+    /// [`History::load()`]: crate::History::load()
+    /// [`History::save()`]: crate::History::save()
+    pub fn load_from(path: impl Into<PathBuf>) -> Result<Self, Error> {
+        let path = path.into();
+
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let last_connected = content
+            .lines()
+            .filter_map(|line| {
+                let (address, timestamp) = line.trim().split_once(',')?;
+                Some((address.to_uppercase(), timestamp.parse::<u64>().ok()?))
+            })
+            .collect();
+
+        Ok(Self {
+            file: Some(path),
+            last_connected,
+        })
+    }
+
+    /// Provides the default location of the history file, which is `$XDG_STATE_HOME/bt/history`, or `$HOME/.local/state/bt/history` if `$XDG_STATE_HOME` is not set.
+    pub fn path() -> Option<PathBuf> {
+        env::var_os("XDG_STATE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("state"))
+            })
+            .map(|dir| dir.join("bt").join("history"))
+    }
+
+    /// Provides the timestamp of the last connection to the device with `address`, which is matched case-insensitively.
+    pub fn last_connected(&self, address: &str) -> Option<u64> {
+        self.last_connected.get(&address.to_uppercase()).copied()
+    }
+
+    /// Records that the device with `address` is connected just now.
+    pub fn record(&mut self, address: &str) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        self.record_at(address, timestamp);
+    }
+
+    /// Records that the device with `address` is connected at `timestamp`.
+    ///
+    /// An older `timestamp` than the recorded one is ignored.
+    pub fn record_at(&mut self, address: &str, timestamp: u64) {
+        let last_connected = self
+            .last_connected
+            .entry(address.to_uppercase())
+            .or_default();
+        *last_connected = timestamp.max(*last_connected);
+    }
+
+    /// Writes the history to the file it is loaded from. The parent directories of the file are created if they do not exist.
+    ///
+    /// A [`History`] which is not loaded from a file (e.g. [`History::default()`]) is not written anywhere.
+    ///
+    /// [`History`]: crate::History
+    /// [`History::default()`]: crate::History::default()
+    pub fn save(&self) -> Result<(), Error> {
+        let Some(path) = &self.file else {
+            return Ok(());
+        };
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let content = self
+            .last_connected
+            .iter()
+            .map(|(address, timestamp)| format!("{},{}\n", address, timestamp))
+            .collect::<String>();
+        fs::write(path, content)?;
+
+        Ok(())
+    }
+
+    /// Sorts `items` by the last connection to their address, most recent first.
+    ///
+    /// The items that are never connected are put last. The sort is stable, so the items with the same recency keep their original order.
+    pub(crate) fn sort_by_recency<T>(&self, items: Vec<T>, address: impl Fn(&T) -> &str) -> Vec<T> {
+        let mut recent_items = items
+            .into_iter()
+            .map(|item| Recent {
+                last_connected: self.last_connected(address(&item)),
+                item,
+            })
+            .collect::<Vec<Recent<T>>>();
+
+        sort_by_keys(
+            &mut recent_items,
+            &[SortKey {
+                column: Recency,
+                reverse: true,
+            }],
+        );
+
+        recent_items.into_iter().map(|recent| recent.item).collect()
+    }
+}
+
+/// Defines the only column of [`Recent`], which is the last connection of an item.
+struct Recency;
+
+/// Defines an item along with its last connection, so that the items can be sorted by [`sort_by_keys`].
+struct Recent<T> {
+    item: T,
+    last_connected: Option<u64>,
+}
+
+impl<T> SortableByColumn<Recency> for Recent<T> {
+    fn cmp_by_column(&self, other: &Self, _: &Recency) -> Ordering {
+        // NOTE: None is less than Some, so the items that are never connected
+        // are put last when the order is reversed.
+        self.last_connected.cmp(&other.last_connected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_file(name: &str) -> PathBuf {
+        let file = std::env::temp_dir()
+            .join(format!("bt-history-{}-{}", name, std::process::id()))
+            .join("history");
+        let _ = fs::remove_dir_all(file.parent().unwrap());
+
+        file
+    }
+
+    #[test]
+    fn it_should_save_and_load_the_history() {
+        let file = test_file("save");
+
+        let mut history = History::load_from(&file).unwrap();
+        assert_eq!(history, History::load_from(&file).unwrap());
+
+        history.record_at("aa:aa:aa:aa:aa:aa", 20);
+        history.record_at("AA:AA:AA:AA:AA:AA", 10);
+        history.record_at("BB:BB:BB:BB:BB:BB", 30);
+        history.save().unwrap();
+
+        fs::write(
+            &file,
+            fs::read_to_string(&file).unwrap() + "malformed\nCC:CC:CC:CC:CC:CC,soon\n",
+        )
+        .unwrap();
+
+        let history = History::load_from(&file).unwrap();
+        fs::remove_dir_all(file.parent().unwrap()).unwrap();
+
+        assert_eq!(history.last_connected("AA:AA:AA:AA:AA:AA"), Some(20));
+        assert_eq!(history.last_connected("bb:bb:bb:bb:bb:bb"), Some(30));
+        assert_eq!(history.last_connected("CC:CC:CC:CC:CC:CC"), None);
+    }
+
+    #[test]
+    fn it_should_sort_by_recency() {
+        let mut history = History::default();
+        history.record_at("BB:BB:BB:BB:BB:BB", 10);
+        history.record_at("CC:CC:CC:CC:CC:CC", 20);
+
+        let addresses = vec![
+            "AA:AA:AA:AA:AA:AA",
+            "BB:BB:BB:BB:BB:BB",
+            "DD:DD:DD:DD:DD:DD",
+            "CC:CC:CC:CC:CC:CC",
+        ];
+
+        let sorted = history.sort_by_recency(addresses, |address| address);
+
+        assert_eq!(
+            sorted,
+            vec![
+                "CC:CC:CC:CC:CC:CC",
+                "BB:BB:BB:BB:BB:BB",
+                "AA:AA:AA:AA:AA:AA",
+                "DD:DD:DD:DD:DD:DD",
+            ]
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use std::{error, fmt, fs, io, path::PathBuf};
+
+use clap::Args;
+use serde_json::Value;
+
+use crate::{ExportFormat, Notes, NotesError, format};
+
+/// Defines error variants that may be returned from an [`import`] call.
+///
+/// [`import`]: crate::import
+#[derive(Debug)]
+pub enum Error {
+    /// Happens when the file cannot be parsed in the given format, or when a device in the file has no address.
+    ///
+    /// It holds the reason.
+    Parse(String),
+
+    /// Happens when the imported notes cannot be saved.
+    /// It holds the underlying [`NotesError`].
+    ///
+    /// [`NotesError`]: crate::NotesError
+    Notes(NotesError),
+
+    /// Happens when the file cannot be read, or [`import`] cannot write to the provided [`io::Write`].
+    ///
+    /// It holds the underlying [`io::Error`].
+    ///
+    /// [`import`]: crate::import
+    /// [`io::Error`]: std::io::Error
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(reason) => write!(f, "import: parse error: {}", reason),
+            Error::Notes(error) => write!(f, "import: {}", error),
+            Error::Io(error) => write!(f, "import: io error: {}", error),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<NotesError> for Error {
+    fn from(value: NotesError) -> Self {
+        Self::Notes(value)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/// Defines the arguments that [`import`] can take.
+///
+/// [`import`]: crate::import
+#[derive(Debug, Default, Args)]
+pub struct ImportArgs {
+    /// The file created by export.
+    #[arg(value_name = "PATH")]
+    pub path: PathBuf,
+
+    /// Set the format of the file.
+    #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+    pub format: ExportFormat,
+}
+
+/// Restores the notes of the devices from a file created by [`export`].
+///
+/// The file is read in `args.format`, which must be the same format the file is exported with. The notes of each device are stored in the provided [`Notes`] by the address of the device, and then saved. A device without notes in the file keeps its existing notes.
+///
+/// Since the pairing keys are not exported, the devices themselves are not restored to the host.
+///
+/// Upon importing, [`import`] writes the number of devices whose notes are restored to the provided [`io::Write`], e.g. `imported the notes of 2 device(s)`.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function can return all variants of [`ImportError`] based on given conditions. For more details, please see the error documentation.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::{io, path::PathBuf};
+/// use bt::{import, ExportFormat, ImportArgs, Notes};
+///
+/// let mut notes = Notes::load().unwrap();
+///
+/// let args = ImportArgs {
+///     path: PathBuf::from("backup.csv"),
+///     format: ExportFormat::Csv,
+/// };
+///
+/// if let Err(e) = import(&mut io::stdout(), &mut notes, &args) {
+///     eprintln!("{}", e);
+/// }
+/// ```
+///
+/// [`io::Write`]: std::io::Write
+/// [`Notes`]: crate::Notes
+/// [`ImportError`]: crate::ImportError
+/// [`export`]: crate::export
+/// [`import`]: crate::import
+pub fn import(w: &mut impl io::Write, notes: &mut Notes, args: &ImportArgs) -> Result<(), Error> {
+    let content = fs::read_to_string(&args.path)?;
+
+    let records = match args.format {
+        ExportFormat::Json => parse_json(&content)?,
+        ExportFormat::Csv => parse_csv(&content)?,
+    };
+
+    let mut imported = 0;
+    for (address, note) in records {
+        if let Some(note) = note.filter(|note| !note.is_empty()) {
+            notes.set(&address, &note);
+            imported += 1;
+        }
+    }
+    notes.save()?;
+
+    writeln!(w, "imported the notes of {} device(s)", imported)?;
+
+    Ok(())
+}
+
+/// Parses the `(address, notes)` pairs of a JSON export.
+fn parse_json(content: &str) -> Result<Vec<(String, Option<String>)>, Error> {
+    let value = serde_json::from_str::<Value>(content).map_err(|e| Error::Parse(e.to_string()))?;
+    let devices = value
+        .as_array()
+        .ok_or_else(|| Error::Parse(String::from("expected an array of devices")))?;
+
+    devices
+        .iter()
+        .map(|device| {
+            let address = device
+                .get("address")
+                .and_then(Value::as_str)
+                .ok_or_else(|| Error::Parse(String::from("a device has no address")))?;
+            let note = device.get("notes").and_then(Value::as_str);
+
+            Ok((address.to_string(), note.map(String::from)))
+        })
+        .collect()
+}
+
+/// Parses the `(address, notes)` pairs of a CSV export, whose columns are located by the header row.
+fn parse_csv(content: &str) -> Result<Vec<(String, Option<String>)>, Error> {
+    let mut records = format::parse_csv(content)
+        .ok_or_else(|| Error::Parse(String::from("a quoted field is not closed")))?
+        .into_iter();
+
+    let header = records.next().unwrap_or_default();
+    let column = |name: &str| header.iter().position(|c| c.eq_ignore_ascii_case(name));
+    let address_idx =
+        column("ADDRESS").ok_or_else(|| Error::Parse(String::from("no ADDRESS column")))?;
+    let notes_idx = column("NOTES");
+
+    records
+        .map(|record| {
+            let address = record
+                .get(address_idx)
+                .filter(|address| !address.is_empty())
+                .ok_or_else(|| Error::Parse(String::from("a device has no address")))?;
+            let note = notes_idx.and_then(|idx| record.get(idx));
+
+            Ok((address.clone(), note.cloned()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{ExportArgs, bluez, export};
+
+    #[test]
+    fn it_should_restore_the_exported_notes() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("speaker")
+                .address("AA:AA:AA:AA:AA:AA")
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("mouse")
+                .address("BB:BB:BB:BB:BB:BB")
+                .build(),
+        ]);
+
+        let mut notes = Notes::default();
+        notes.set("AA:AA:AA:AA:AA:AA", "living room speaker, \"left\"");
+
+        for format in [ExportFormat::Json, ExportFormat::Csv] {
+            let path =
+                std::env::temp_dir().join(format!("bt-import-{:?}-{}", format, std::process::id()));
+
+            let args = ExportArgs {
+                format,
+                out: Some(path.clone()),
+            };
+            let result = export(&bluez, &mut io::sink(), &notes, &args);
+            assert!(result.is_ok());
+
+            let mut imported_notes = Notes::default();
+            let mut out_buf = Cursor::new(vec![]);
+
+            let args = ImportArgs {
+                path: path.clone(),
+                format,
+            };
+            let result = import(&mut out_buf, &mut imported_notes, &args);
+            fs::remove_file(&path).unwrap();
+            assert!(result.is_ok());
+
+            let result = String::from_utf8(out_buf.into_inner()).unwrap();
+            assert_eq!(result, "imported the notes of 1 device(s)\n");
+            assert_eq!(imported_notes, notes);
+        }
+    }
+
+    #[test]
+    fn it_should_fail_if_a_device_has_no_address() {
+        let path = std::env::temp_dir().join(format!("bt-import-invalid-{}", std::process::id()));
+        fs::write(&path, "ALIAS,NOTES\r\nspeaker,living room\r\n").unwrap();
+
+        let args = ImportArgs {
+            path: path.clone(),
+            format: ExportFormat::Csv,
+        };
+        let result = import(&mut io::sink(), &mut Notes::default(), &args);
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(Error::Parse(_))));
+    }
+}
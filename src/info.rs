@@ -0,0 +1,333 @@
+use std::{collections::BTreeMap, error, fmt, io};
+
+use clap::Args;
+use serde_json::{Value, json};
+
+use crate::{BluezError, bluez};
+
+/// Defines error variants that may be returned from an [`info`] call.
+///
+/// [`info`]: crate::info
+#[derive(Debug)]
+pub enum Error {
+    /// Happens when the [`BluezClient`] fails to provide the known devices during an [`info`] call.
+    /// It holds the underlying [`BluezError`].
+    ///
+    /// [`BluezError`]: crate::BluezError
+    /// [`BluezClient`]: crate::BluezClient
+    /// [`info`]: crate::info
+    Bluez(BluezError),
+
+    /// Happens when no known device has the ALIAS (or ADDRESS).
+    ///
+    /// It holds the ALIAS.
+    NotFound(String),
+
+    /// Happens when [`info`] cannot write to the provided [`io::Write`].
+    ///
+    /// It holds the underlying [`io::Error`].
+    ///
+    /// [`info`]: crate::info
+    /// [`io::Error`]: std::io::Error
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Bluez(error) => write!(f, "info: bluez error: {}", error),
+            Error::NotFound(alias) => write!(f, "info: no known device matches '{}'", alias),
+            Error::Io(error) => write!(f, "info: io error: {}", error),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<BluezError> for Error {
+    fn from(value: BluezError) -> Self {
+        Self::Bluez(value)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/// Defines the arguments that [`info`] can take.
+///
+/// [`info`]: crate::info
+#[derive(Debug, Default, Args)]
+pub struct InfoArgs {
+    /// The full ALIAS (or ADDRESS) of the known device.
+    #[arg(value_name = "ALIAS")]
+    pub alias: String,
+
+    /// Also show the raw advertisement data of the device, such as the manufacturer data of a BLE beacon, as a hex dump.
+    #[arg(long)]
+    pub raw: bool,
+
+    /// Show the properties as a single JSON object instead, where an unknown property is `null`.
+    #[arg(long, conflicts_with = "raw")]
+    pub json: bool,
+}
+
+/// The number of bytes on each line of a hex dump.
+const HEX_DUMP_WIDTH: usize = 16;
+
+/// Provides the properties of a single known device by using a [`BluezClient`].
+///
+/// The device is matched by its full ALIAS, or by its ADDRESS (case-insensitive). Each property is written to the provided [`io::Write`] on its own line as `name: value`, where an unknown value (e.g. the battery of a disconnected device) is `-`.
+///
+/// If `args.raw` is `true`, then the manufacturer data of the device is written after the properties as a hex dump, under a line for each company identifier, such as:
+///
+/// ```text
+/// manufacturer data 0x004c:
+///   0000  02 15 e2 c5 6d b5 df fb 48 d2 b0 60 d0 f5 a7 10
+///   0010  96 e0
+/// ```
+///
+/// Nothing is written for the devices that do not advertise manufacturer data, which is the case for most classic devices.
+///
+/// If `args.json` is `true`, then the properties are written as a single JSON object instead, along with whether the device is blocked, its TX power and its service UUIDs. An unknown property is `null`, and `args.raw` has no effect.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function can return all variants of [`InfoError`] based on given conditions. For more details, please see the error documentation.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+/// use bt::{info, BluezClient, InfoArgs};
+///
+/// let bluez_client = BluezClient::new().unwrap();
+///
+/// let args = InfoArgs {
+///     alias: "beacon".to_string(),
+///     raw: true,
+///     ..Default::default()
+/// };
+///
+/// if let Err(e) = info(&bluez_client, &mut io::stdout(), &args) {
+///     eprintln!("{}", e);
+/// }
+/// ```
+///
+/// [`BluezClient`]: crate::BluezClient
+/// [`io::Write`]: std::io::Write
+/// [`InfoError`]: crate::InfoError
+pub fn info(
+    bluez: &crate::BluezClient,
+    w: &mut impl io::Write,
+    args: &InfoArgs,
+) -> Result<(), Error> {
+    let device = bluez
+        .devices()?
+        .into_iter()
+        .find(|d| d.alias() == args.alias || d.address().eq_ignore_ascii_case(&args.alias))
+        .ok_or_else(|| Error::NotFound(args.alias.clone()))?;
+
+    if args.json {
+        writeln!(w, "{}", render_json(&device))?;
+        return Ok(());
+    }
+
+    let mut out_buf = render_properties(&device);
+    if args.raw
+        && let Some(data) = device.manufacturer_data()
+    {
+        out_buf.push_str(&render_manufacturer_data(data));
+    }
+
+    w.write_all(out_buf.as_bytes())?;
+
+    Ok(())
+}
+
+/// Renders the properties of `device`, one `name: value` pair per line.
+fn render_properties(device: &bluez::BluezDevice) -> String {
+    let or_unknown = |value: Option<String>| value.unwrap_or_else(|| String::from("-"));
+
+    [
+        ("alias", device.alias().to_string()),
+        ("address", device.address().to_string()),
+        ("adapter", device.adapter().to_string()),
+        ("connected", device.connected().to_string()),
+        ("paired", device.paired().to_string()),
+        ("trusted", device.trusted().to_string()),
+        ("bonded", device.bonded().to_string()),
+        (
+            "battery",
+            or_unknown(device.battery().map(|b| b.to_string())),
+        ),
+        ("rssi", or_unknown(device.rssi().map(|r| r.to_string()))),
+    ]
+    .iter()
+    .map(|(name, value)| format!("{}: {}\n", name, value))
+    .collect()
+}
+
+/// Renders the properties of `device` as a JSON object, where an unknown property is `null`.
+fn render_json(device: &bluez::BluezDevice) -> Value {
+    json!({
+        "alias": device.alias(),
+        "address": device.address(),
+        "adapter": device.adapter(),
+        "connected": device.connected(),
+        "paired": device.paired(),
+        "trusted": device.trusted(),
+        "bonded": device.bonded(),
+        "blocked": device.blocked(),
+        "battery": device.battery(),
+        "rssi": device.rssi(),
+        "tx_power": device.tx_power(),
+        "uuids": device.uuids(),
+    })
+}
+
+/// Renders a hex dump of the manufacturer data for each company identifier.
+fn render_manufacturer_data(data: &BTreeMap<u16, Vec<u8>>) -> String {
+    let mut buf = String::new();
+    for (company_id, bytes) in data {
+        buf.push_str(&format!("manufacturer data {:#06x}:\n", company_id));
+
+        for (idx, line) in bytes.chunks(HEX_DUMP_WIDTH).enumerate() {
+            let hex = line
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<Vec<String>>()
+                .join(" ");
+            buf.push_str(&format!("  {:04x}  {}\n", idx * HEX_DUMP_WIDTH, hex));
+        }
+    }
+
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn it_should_hex_dump_the_manufacturer_data() {
+        let ibeacon: Vec<u8> = (0..18).collect();
+
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("beacon")
+                .address("AA:AA:AA:AA:AA:AA")
+                .rssi(Some(-70))
+                .manufacturer_data(&[(0x004c, &ibeacon), (0x0059, &[0xca, 0xfe])])
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("headset")
+                .address("BB:BB:BB:BB:BB:BB")
+                .build(),
+        ]);
+
+        let properties = "alias: beacon\n\
+                          address: AA:AA:AA:AA:AA:AA\n\
+                          adapter: hci0\n\
+                          connected: false\n\
+                          paired: false\n\
+                          trusted: false\n\
+                          bonded: false\n\
+                          battery: -\n\
+                          rssi: -70\n";
+
+        for (alias, raw, expected) in [
+            ("beacon", false, properties.to_string()),
+            (
+                "aa:aa:aa:aa:aa:aa",
+                true,
+                format!(
+                    "{}\
+                     manufacturer data 0x004c:\n  \
+                     0000  00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f\n  \
+                     0010  10 11\n\
+                     manufacturer data 0x0059:\n  \
+                     0000  ca fe\n",
+                    properties
+                ),
+            ),
+        ] {
+            let mut out_buf = Cursor::new(vec![]);
+
+            let args = InfoArgs {
+                alias: alias.to_string(),
+                raw,
+                ..Default::default()
+            };
+            let result = info(&bluez, &mut out_buf, &args);
+            assert!(result.is_ok());
+
+            let result = String::from_utf8(out_buf.into_inner()).unwrap();
+            assert_eq!(result, expected);
+        }
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = InfoArgs {
+            alias: String::from("headset"),
+            raw: true,
+            ..Default::default()
+        };
+        let result = info(&bluez, &mut out_buf, &args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert!(result.ends_with("rssi: -\n"));
+    }
+
+    #[test]
+    fn it_should_write_the_properties_as_a_json_object() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("speaker")
+                .address("AA:AA:AA:AA:AA:AA")
+                .connected(true)
+                .blocked(true)
+                .battery(Some(80))
+                .uuids(&["0000110b-0000-1000-8000-00805f9b34fb"])
+                .build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = InfoArgs {
+            alias: String::from("speaker"),
+            json: true,
+            ..Default::default()
+        };
+        let result = info(&bluez, &mut out_buf, &args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        let result: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(
+            result,
+            json!({
+                "alias": "speaker",
+                "address": "AA:AA:AA:AA:AA:AA",
+                "adapter": "hci0",
+                "connected": true,
+                "paired": false,
+                "trusted": false,
+                "bonded": false,
+                "blocked": true,
+                "battery": 80,
+                "rssi": null,
+                "tx_power": null,
+                "uuids": ["0000110b-0000-1000-8000-00805f9b34fb"],
+            })
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use std::{error, fmt};
+
+use crate::BluezError;
+
+/// Defines error variants that may be returned from an [`is_on`] call.
+///
+/// [`is_on`]: crate::is_on
+#[derive(Debug)]
+pub enum Error {
+    /// Happens when the [`BluezClient`] fails during the process.
+    /// It holds the underlying [`BluezError`].
+    ///
+    /// [`BluezError`]: crate::BluezError
+    /// [`BluezClient`]: crate::BluezClient
+    Bluez(BluezError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            Error::Bluez(error) => write!(f, "is-on: bluez error: {}", error),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<BluezError> for Error {
+    fn from(value: BluezError) -> Self {
+        Self::Bluez(value)
+    }
+}
+
+/// Indicates whether the Bluetooth adapter is powered on by using a [`BluezClient`].
+///
+/// Unlike the other commands, [`is_on`] does not write anything. It is meant to be used as a predicate, where the caller maps the returned `bool` to an exit code.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function can return all variants of [`IsOnError`] based on given conditions. For more details, please see the error documentation.
+///
+/// [`BluezClient`]: crate::BluezClient
+/// [`IsOnError`]: crate::IsOnError
+/// [`is_on`]: crate::is_on
+///
+/// # Examples
+///
+/// Here is a basic [`is_on`] call.
+///
+/// ```no_run
+/// use std::process::ExitCode;
+/// use bt::{is_on, BluezClient};
+///
+/// let bluez_client = BluezClient::new().unwrap();
+///
+/// let exit_code = match is_on(&bluez_client) {
+///     Ok(true) => ExitCode::SUCCESS,
+///     Ok(false) => ExitCode::FAILURE,
+///     Err(e) => {
+///         eprintln!("{}", e);
+///         ExitCode::from(2)
+///     }
+/// };
+///```
+pub fn is_on(bluez: &crate::BluezClient) -> Result<bool, Error> {
+    let is_powered = bluez.is_powered()?;
+
+    Ok(is_powered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bluez::BluezPowerState;
+
+    #[test]
+    fn it_should_be_true_if_powered() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_power_state(BluezPowerState::On);
+
+        let result = is_on(&bluez);
+
+        assert!(result.unwrap());
+    }
+
+    #[test]
+    fn it_should_be_false_if_not_powered() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_power_state(BluezPowerState::Off);
+
+        let result = is_on(&bluez);
+
+        assert!(!result.unwrap());
+    }
+
+    #[test]
+    fn it_should_fail_if_power_state_cannot_be_read() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_erred_method_name("power_state".to_string());
+
+        let result = is_on(&bluez);
+
+        assert!(result.is_err());
+    }
+}
@@ -1,19 +1,59 @@
 pub mod api;
+mod battery;
 mod bluez;
+mod config;
 mod connect;
+mod deadline;
 mod disconnect;
+mod discoverable;
+mod export;
 mod format;
+mod history;
+mod import;
+mod info;
+mod is_on;
 mod list_devices;
+mod listen;
+mod notes;
+mod power;
+mod prune;
+mod rssi;
 mod scan;
+mod schema;
+mod select;
+mod shell;
 mod status;
 mod toggle;
+mod trust;
 
-pub use bluez::{BluezDevice, Client as BluezClient, Error as BluezError};
+pub use battery::{BatteryArgs, Error as BatteryError, battery};
+pub use bluez::{BluezConnectionState, BluezDevice, Client as BluezClient, Error as BluezError};
+pub use config::{Config, DeviceConfig, Error as ConfigError};
 pub use connect::{ConnectArgs, Error as ConnectError, connect};
-pub use disconnect::{Error as DisconnectError, disconnect};
+pub use deadline::{Error as DeadlineError, with_deadline};
+pub use disconnect::{DisconnectArgs, Error as DisconnectError, disconnect};
+pub use discoverable::{DiscoverableArgs, Error as DiscoverableError, discoverable};
+pub use export::{Error as ExportError, ExportArgs, ExportFormat, export};
+pub use format::{ColorMode, DeviceField, TemplateError};
+pub use history::{Error as HistoryError, History};
+pub use import::{Error as ImportError, ImportArgs, import};
+pub use info::{Error as InfoError, InfoArgs, info};
+pub use is_on::{Error as IsOnError, is_on};
 pub use list_devices::{
-    DeviceStatus, Error as ListDevicesError, ListDevicesArgs, ListDevicesColumn, list_devices,
+    BoolStyle, DeviceStatus, Error as ListDevicesError, ListDevicesArgs, ListDevicesColumn,
+    ListDevicesFormat, SnapshotAction, list_devices, list_devices_data,
 };
-pub use scan::{Error as ScanError, ScanArgs, ScanColumn, scan};
-pub use status::{Error as StatusError, status};
-pub use toggle::{Error as ToggleError, toggle};
+pub use listen::{Error as ListenError, ListenArgs, listen};
+pub use notes::{Error as NotesError, Notes};
+pub use prune::{Error as PruneError, PruneArgs, prune};
+pub use rssi::{Error as RssiError, RssiArgs, rssi};
+pub use scan::{
+    Error as ScanError, ScanArgs, ScanColumn, ScanDiff, ScanKey, ScanSnapshot, ScannedDevice, scan,
+    scan_data,
+};
+pub use schema::{Error as SchemaError, SchemaArgs, SchemaOutput, schema};
+pub use select::{DeviceSelectionError, SelectBy, Selector, SelectorError, TargetError};
+pub use shell::{Error as ShellError, shell};
+pub use status::{Error as StatusError, StatusArgs, StatusFormat, status};
+pub use toggle::{Error as ToggleError, ToggleArgs, toggle};
+pub use trust::{Error as TrustError, TrustArgs, trust};
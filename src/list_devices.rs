@@ -1,12 +1,20 @@
 use core::fmt;
-use std::{error, io};
+use std::{cmp::Ordering, error, fs, io, path::PathBuf};
 
-use clap::{Args, arg};
+use clap::{ArgGroup, Args};
+use tabled::settings::Alignment;
 
 use crate::{
-    BluezError, bluez,
-    format::{PrettyFormatter, TableFormattable, TerseFormatter},
+    BluezError, History, ScanSnapshot, bluez,
+    format::{
+        CsvFormatter, JsonFormattable, JsonFormatter, JsonSchemaColumn, ListingLayout,
+        PlainFormatter, PrettyFormatter, SortKey, SortableByColumn, TableFormattable, TableStyle,
+        Template, TemplateError, TemplateFormattable, TemplateFormatter, TerseFormatter,
+        YamlFormatter, device_schema, resolve_columns, sort_by_keys,
+    },
+    select,
 };
+use serde_json::Value;
 
 /// Defines error variants that may be returned from a [`list_devices`] call.
 ///
@@ -20,13 +28,30 @@ pub enum Error {
     /// [`BluezClient`]: crate::BluezClient
     Bluez(BluezError),
 
-    /// Happens when [`list_devices`] cannot write to the provided [`io::Write`].
+    /// Happens when [`list_devices`] cannot write to the provided [`io::Write`], or to the output file.
     ///
     /// It holds the underlying [`io::Error`].
     ///
     /// [`list_devices`]: crate::list_devices
     /// [`io::Error`]: std::io::Error
     Io(io::Error),
+
+    /// Happens when the provided `args.template` cannot be parsed.
+    ///
+    /// It holds the underlying [`TemplateError`].
+    ///
+    /// [`TemplateError`]: crate::TemplateError
+    Template(TemplateError),
+
+    /// Happens when `args.strict` is `true`, and some of the devices could not be fully read from Bluez D-Bus.
+    ///
+    /// It holds the number of devices that could not be read.
+    UnreadDevices(usize),
+
+    /// Happens when `args.diff` is `true` but no snapshot is saved, or when the default location of the snapshot cannot be determined.
+    ///
+    /// It holds the reason.
+    Snapshot(String),
 }
 
 impl fmt::Display for Error {
@@ -36,6 +61,11 @@ impl fmt::Display for Error {
                 write!(f, "list-devices: bluez error: {}", error)
             }
             Error::Io(error) => write!(f, "list-devices: io error: {}", error),
+            Error::Template(error) => write!(f, "list-devices: {}", error),
+            Error::UnreadDevices(unread) => {
+                write!(f, "list-devices: {} device(s) could not be read", unread)
+            }
+            Error::Snapshot(reason) => write!(f, "list-devices: snapshot error: {}", reason),
         }
     }
 }
@@ -54,33 +84,203 @@ impl From<io::Error> for Error {
     }
 }
 
+impl From<TemplateError> for Error {
+    fn from(value: TemplateError) -> Self {
+        Self::Template(value)
+    }
+}
+
 /// Defines the arguments that [`list_devices`] can take.
 ///
+/// On the command line, `--columns` and `--values` are mutually exclusive, since each of them selects a different output.
+///
 /// [`list_devices`]: crate::list_devices
-#[derive(Debug, Args)]
+#[derive(Debug, Default, Args)]
+#[command(group = ArgGroup::new("layout").args(["columns", "values"]))]
 pub struct ListDevicesArgs {
     /// Filter the table output based on given keys.
+    ///
+    /// It cannot be used with --values.
     #[arg(short, long, value_delimiter = ',')]
     pub columns: Option<Vec<ListDevicesColumn>>,
 
     /// Filter the terse output based on given keys.
+    ///
+    /// It cannot be used with --columns.
     #[arg(short, long, value_delimiter = ',')]
     pub values: Option<Vec<ListDevicesColumn>>,
 
+    /// Only show the ADDRESS of each device, one per line. It is a shortcut for `--values address`.
+    #[arg(long, conflicts_with_all = ["columns", "values", "aliases", "json", "json_pretty", "format", "template"])]
+    pub addresses: bool,
+
+    /// Only show the ALIAS of each device, one per line. It is a shortcut for `--values alias`.
+    #[arg(long, conflicts_with_all = ["columns", "values", "json", "json_pretty", "format", "template"])]
+    pub aliases: bool,
+
     /// Filter output based on device status.
     #[arg(short, long)]
     pub status: Option<DeviceStatus>,
+
+    /// Only show the devices whose ALIAS contains the name <CONTAINS_NAME>.
+    #[arg(long)]
+    pub contains_name: Option<String>,
+
+    /// Match --contains-name regardless of the case.
+    #[arg(long, requires = "contains_name")]
+    pub ignore_case: bool,
+
+    /// Split the table output into two labeled sections based on device status, e.g. `Connected:` and `Disconnected:`.
+    #[arg(long, value_name = "STATUS")]
+    pub group_by: Option<DeviceStatus>,
+
+    /// Show the output as compact JSON.
+    #[arg(long)]
+    pub json: bool,
+
+    /// Show the output as indented, multi-line JSON.
+    #[arg(long)]
+    pub json_pretty: bool,
+
+    /// Show the output in FORMAT, such as CSV.
+    ///
+    /// The columns are taken from --columns or --values.
+    #[arg(long, value_name = "FORMAT")]
+    pub format: Option<ListDevicesFormat>,
+
+    /// Show the boolean columns (connected, trusted, bonded, paired) in STYLE, such as `yes`/`no`.
+    ///
+    /// JSON always uses real booleans.
+    #[arg(long, value_name = "STYLE", value_enum, default_value_t = BoolStyle::TrueFalse)]
+    pub bool_style: BoolStyle,
+
+    /// Format each device with a TEMPLATE, where each {column} placeholder is replaced by the value of that column.
+    ///
+    /// Missing values are rendered empty.
+    #[arg(long, value_name = "TEMPLATE")]
+    pub template: Option<String>,
+
+    /// Sort the devices by COLUMN.
+    #[arg(long, value_name = "COLUMN")]
+    pub sort: Option<ListDevicesColumn>,
+
+    /// Reverse the order of --sort.
+    #[arg(long, requires = "sort")]
+    pub reverse: bool,
+
+    /// Order the devices that are equal by --sort by COLUMN.
+    #[arg(long, value_name = "COLUMN", requires = "sort")]
+    pub then: Option<ListDevicesColumn>,
+
+    /// Reverse the order of --then, independently of --reverse.
+    #[arg(long, requires = "then")]
+    pub reverse_then: bool,
+
+    /// Terminate the records of the terse output with NUL bytes instead of newlines.
+    ///
+    /// This is meant to be used with `xargs -0`. Every record is terminated, so the output never ends with a newline.
+    /// It has no effect on the pretty and JSON outputs.
+    #[arg(long)]
+    pub print0: bool,
+
+    /// Write the results to the file at PATH instead, and only report the number of devices.
+    ///
+    /// The file is created if it does not exist, and truncated if it does.
+    #[arg(long, value_name = "PATH")]
+    pub output_file: Option<PathBuf>,
+
+    /// Fail if any device cannot be fully read, instead of showing the devices that can be read.
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Order the devices by their last connection, most recent first.
+    ///
+    /// The last connections are recorded by connect. The devices that are never connected are listed last.
+    #[arg(long)]
+    pub recent: bool,
+
+    /// Skip the first N devices.
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub offset: usize,
+
+    /// Show at most N devices, after --offset is applied.
+    ///
+    /// Without --sort and --recent, the devices after the window are not read at all.
+    #[arg(long, value_name = "N")]
+    pub limit: Option<usize>,
+
+    /// Only show the devices that report a battery level, which are the connected devices that expose it.
+    ///
+    /// The default columns are ALIAS and BATTERY with this option.
+    #[arg(long)]
+    pub battery_only: bool,
+
+    /// Only show the devices whose battery level is at least PERCENT.
+    ///
+    /// The devices with an unknown battery level are not shown.
+    #[arg(long, value_name = "PERCENT")]
+    pub min_battery: Option<u8>,
+
+    /// Only show the devices whose battery level is at most PERCENT, e.g. the ones that need charging.
+    ///
+    /// The devices with an unknown battery level are not shown.
+    #[arg(long, value_name = "PERCENT")]
+    pub max_battery: Option<u8>,
+
+    /// Show the power state of the adapter above the table, e.g. `adapter hci0: enabled`.
+    ///
+    /// This option has no effect if the output is not a table.
+    #[arg(long)]
+    pub show_adapter: bool,
+
+    /// Save the listed devices as a snapshot, which --diff compares against later.
+    ///
+    /// The snapshot is kept in `$XDG_STATE_HOME/bt/snapshot.json`, unless --snapshot-file is set.
+    #[arg(long, value_name = "ACTION", conflicts_with = "diff")]
+    pub snapshot: Option<SnapshotAction>,
+
+    /// Show the devices that are added (+), removed (-) or whose status changed (~) since the saved snapshot.
+    ///
+    /// The output is always a table.
+    #[arg(long)]
+    pub diff: bool,
+
+    /// Keep the snapshot of --snapshot and --diff in the file at PATH instead.
+    #[arg(long, value_name = "PATH")]
+    pub snapshot_file: Option<PathBuf>,
+}
+
+/// Defines what [`list_devices`] does with a snapshot of the listed devices.
+///
+/// [`list_devices`]: crate::list_devices
+#[derive(Debug, Copy, Clone, clap::ValueEnum)]
+pub enum SnapshotAction {
+    /// Save the listed devices, overwriting the previous snapshot.
+    Save,
 }
 
 /// Defines the columns of a [`list_devices`] output.
+///
+/// Besides their names, the columns can be referred to by a single letter: `a` (alias), `d` (address), `c` (connected), `t` (trusted), `b` (bonded), `p` (paired) and `y` (battery).
 #[derive(Debug, Copy, Clone, clap::ValueEnum)]
 pub enum ListDevicesColumn {
+    #[value(alias = "a")]
     Alias,
+    #[value(alias = "d")]
     Address,
+    #[value(alias = "c")]
     Connected,
+    #[value(alias = "t")]
     Trusted,
+    #[value(alias = "b")]
     Bonded,
+    #[value(alias = "p")]
     Paired,
+    Capabilities,
+    #[value(alias = "y")]
+    Battery,
+    State,
+    TxPower,
 }
 
 /// Defines the available statuses of Bluetooth devices.
@@ -92,6 +292,18 @@ pub enum DeviceStatus {
     Paired,
 }
 
+impl DeviceStatus {
+    /// Provides the labels of the groups that have and do not have the status, respectively.
+    fn group_labels(&self) -> (&'static str, &'static str) {
+        match self {
+            DeviceStatus::Connected => ("Connected", "Disconnected"),
+            DeviceStatus::Trusted => ("Trusted", "Untrusted"),
+            DeviceStatus::Bonded => ("Bonded", "Unbonded"),
+            DeviceStatus::Paired => ("Paired", "Unpaired"),
+        }
+    }
+}
+
 impl TableFormattable<ListDevicesColumn> for bluez::BluezDevice {
     fn get_cell_value_by_column(&self, column: &ListDevicesColumn) -> String {
         match column {
@@ -101,6 +313,92 @@ impl TableFormattable<ListDevicesColumn> for bluez::BluezDevice {
             ListDevicesColumn::Trusted => self.trusted().to_string(),
             ListDevicesColumn::Bonded => self.bonded().to_string(),
             ListDevicesColumn::Paired => self.paired().to_string(),
+            ListDevicesColumn::Capabilities => {
+                let capabilities = self.capabilities();
+                if capabilities.is_empty() {
+                    "-".to_string()
+                } else {
+                    capabilities.join(",")
+                }
+            }
+            ListDevicesColumn::Battery => match self.battery() {
+                Some(battery) => battery.to_string(),
+                None => "-".to_string(),
+            },
+            ListDevicesColumn::State => self.connection_state().to_string(),
+            ListDevicesColumn::TxPower => match self.tx_power() {
+                Some(tx_power) => tx_power.to_string(),
+                None => "-".to_string(),
+            },
+        }
+    }
+
+    fn get_alignment_by_column(column: &ListDevicesColumn) -> Alignment {
+        match column {
+            ListDevicesColumn::Battery | ListDevicesColumn::TxPower => Alignment::right(),
+            _ => Alignment::left(),
+        }
+    }
+}
+
+impl TemplateFormattable<ListDevicesColumn> for bluez::BluezDevice {
+    fn get_template_value_by_column(&self, column: &ListDevicesColumn) -> Option<String> {
+        match column {
+            ListDevicesColumn::Battery => self.battery().map(|b| b.to_string()),
+            ListDevicesColumn::Capabilities => Some(self.capabilities().join(",")),
+            ListDevicesColumn::TxPower => self.tx_power().map(|t| t.to_string()),
+            _ => Some(self.get_cell_value_by_column(column)),
+        }
+    }
+}
+
+impl SortableByColumn<ListDevicesColumn> for bluez::BluezDevice {
+    fn cmp_by_column(&self, other: &Self, column: &ListDevicesColumn) -> Ordering {
+        match column {
+            ListDevicesColumn::Alias => self.alias().cmp(other.alias()),
+            ListDevicesColumn::Address => self.address().cmp(other.address()),
+            ListDevicesColumn::Connected => self.connected().cmp(&other.connected()),
+            ListDevicesColumn::Trusted => self.trusted().cmp(&other.trusted()),
+            ListDevicesColumn::Bonded => self.bonded().cmp(&other.bonded()),
+            ListDevicesColumn::Paired => self.paired().cmp(&other.paired()),
+            ListDevicesColumn::Capabilities => self.capabilities().cmp(&other.capabilities()),
+            ListDevicesColumn::Battery => self.battery().cmp(other.battery()),
+            ListDevicesColumn::State => self.connection_state().cmp(&other.connection_state()),
+            ListDevicesColumn::TxPower => self.tx_power().cmp(other.tx_power()),
+        }
+    }
+}
+
+impl JsonFormattable<ListDevicesColumn> for bluez::BluezDevice {
+    fn get_json_value_by_column(&self, column: &ListDevicesColumn) -> Value {
+        match column {
+            ListDevicesColumn::Alias => Value::from(self.alias()),
+            ListDevicesColumn::Address => Value::from(self.address()),
+            ListDevicesColumn::Connected => Value::from(self.connected()),
+            ListDevicesColumn::Trusted => Value::from(self.trusted()),
+            ListDevicesColumn::Bonded => Value::from(self.bonded()),
+            ListDevicesColumn::Paired => Value::from(self.paired()),
+            ListDevicesColumn::Capabilities => Value::from(self.capabilities()),
+            ListDevicesColumn::Battery => Value::from(*self.battery()),
+            ListDevicesColumn::State => Value::from(self.connection_state().to_string()),
+            ListDevicesColumn::TxPower => Value::from(*self.tx_power()),
+        }
+    }
+}
+
+impl JsonSchemaColumn for ListDevicesColumn {
+    fn get_json_schema(&self) -> Value {
+        match self {
+            ListDevicesColumn::Alias => device_schema::string(),
+            ListDevicesColumn::Address => device_schema::address(),
+            ListDevicesColumn::Connected
+            | ListDevicesColumn::Trusted
+            | ListDevicesColumn::Bonded
+            | ListDevicesColumn::Paired => device_schema::boolean(),
+            ListDevicesColumn::Capabilities => device_schema::capabilities(),
+            ListDevicesColumn::Battery => device_schema::battery(),
+            ListDevicesColumn::State => device_schema::state(),
+            ListDevicesColumn::TxPower => device_schema::tx_power(),
         }
     }
 }
@@ -114,6 +412,10 @@ impl From<&ListDevicesColumn> for String {
             ListDevicesColumn::Trusted => "TRUSTED",
             ListDevicesColumn::Bonded => "BONDED",
             ListDevicesColumn::Paired => "PAIRED",
+            ListDevicesColumn::Capabilities => "CAPABILITIES",
+            ListDevicesColumn::Battery => "BATTERY",
+            ListDevicesColumn::State => "STATE",
+            ListDevicesColumn::TxPower => "TX_POWER",
         };
 
         str.to_string()
@@ -143,9 +445,156 @@ const DEFAULT_LISTING_COLUMNS: [ListDevicesColumn; 6] = [
     ListDevicesColumn::Paired,
 ];
 
+const BATTERY_LISTING_COLUMNS: [ListDevicesColumn; 2] =
+    [ListDevicesColumn::Alias, ListDevicesColumn::Battery];
+
+/// Defines the additional output formats of [`list_devices`].
+///
+/// [`list_devices`]: crate::list_devices
+#[derive(Debug, Copy, Clone, clap::ValueEnum)]
+pub enum ListDevicesFormat {
+    /// RFC 4180 CSV with a header row. The values that contain commas or double quotes are quoted.
+    Csv,
+
+    /// A dense table whose columns are separated by a single space, without padding.
+    TableCompact,
+
+    /// The values of each row separated by a single space, with a header row, where the columns are not aligned at all.
+    Plain,
+
+    /// A YAML sequence of mappings keyed by the lowercase column names, like the JSON output.
+    Yaml,
+}
+
+/// Defines how [`list_devices`] renders the boolean columns, such as `CONNECTED`.
+///
+/// [`list_devices`]: crate::list_devices
+#[derive(Debug, Default, Copy, Clone, clap::ValueEnum)]
+pub enum BoolStyle {
+    /// `yes` or `no`.
+    #[value(name = "yesno")]
+    YesNo,
+
+    /// `✓` or `✗`.
+    Checkmark,
+
+    /// `true` or `false`.
+    #[default]
+    #[value(name = "truefalse")]
+    TrueFalse,
+}
+
+impl BoolStyle {
+    /// Renders `value` in this style.
+    fn render(&self, value: bool) -> String {
+        let str = match (self, value) {
+            (BoolStyle::YesNo, true) => "yes",
+            (BoolStyle::YesNo, false) => "no",
+            (BoolStyle::Checkmark, true) => "✓",
+            (BoolStyle::Checkmark, false) => "✗",
+            (BoolStyle::TrueFalse, true) => "true",
+            (BoolStyle::TrueFalse, false) => "false",
+        };
+
+        str.to_string()
+    }
+}
+
+/// Defines a device of a [`list_devices`] output, along with the style of its boolean columns.
+///
+/// [`list_devices`]: crate::list_devices
+#[derive(Copy, Clone)]
+struct ListedDevice<'a> {
+    device: &'a bluez::BluezDevice,
+    bool_style: BoolStyle,
+}
+
+impl TableFormattable<ListDevicesColumn> for ListedDevice<'_> {
+    fn get_cell_value_by_column(&self, column: &ListDevicesColumn) -> String {
+        match column {
+            ListDevicesColumn::Connected => self.bool_style.render(self.device.connected()),
+            ListDevicesColumn::Trusted => self.bool_style.render(self.device.trusted()),
+            ListDevicesColumn::Bonded => self.bool_style.render(self.device.bonded()),
+            ListDevicesColumn::Paired => self.bool_style.render(self.device.paired()),
+            _ => self.device.get_cell_value_by_column(column),
+        }
+    }
+
+    fn get_alignment_by_column(column: &ListDevicesColumn) -> Alignment {
+        bluez::BluezDevice::get_alignment_by_column(column)
+    }
+}
+
+impl TemplateFormattable<ListDevicesColumn> for ListedDevice<'_> {
+    fn get_template_value_by_column(&self, column: &ListDevicesColumn) -> Option<String> {
+        match column {
+            ListDevicesColumn::Connected
+            | ListDevicesColumn::Trusted
+            | ListDevicesColumn::Bonded
+            | ListDevicesColumn::Paired => Some(self.get_cell_value_by_column(column)),
+            _ => self.device.get_template_value_by_column(column),
+        }
+    }
+}
+
+// NOTE: JSON keeps the real booleans regardless of the bool style.
+impl JsonFormattable<ListDevicesColumn> for ListedDevice<'_> {
+    fn get_json_value_by_column(&self, column: &ListDevicesColumn) -> Value {
+        self.device.get_json_value_by_column(column)
+    }
+}
+
+/// Applies `bool_style` to each of `devices`.
+fn styled(
+    devices: &[bluez::BluezDevice],
+    bool_style: BoolStyle,
+) -> impl Iterator<Item = ListedDevice<'_>> {
+    devices
+        .iter()
+        .map(move |device| ListedDevice { device, bool_style })
+}
+
+#[derive(Copy, Clone)]
+enum DiffColumn {
+    Change,
+    Device(ListDevicesColumn),
+}
+
+impl From<&DiffColumn> for String {
+    fn from(value: &DiffColumn) -> Self {
+        match value {
+            DiffColumn::Change => String::from("CHANGE"),
+            DiffColumn::Device(column) => column.into(),
+        }
+    }
+}
+
+/// Defines a device of a [`list_devices`] diff, along with the marker of its change.
+///
+/// [`list_devices`]: crate::list_devices
+struct DiffRow<'a> {
+    marker: &'a str,
+    device: ListedDevice<'a>,
+}
+
+impl TableFormattable<DiffColumn> for DiffRow<'_> {
+    fn get_cell_value_by_column(&self, column: &DiffColumn) -> String {
+        match column {
+            DiffColumn::Change => self.marker.to_string(),
+            DiffColumn::Device(column) => self.device.get_cell_value_by_column(column),
+        }
+    }
+}
+
 enum ListDevicesOutput {
-    Pretty,
+    Pretty(TableStyle),
     Terse,
+    Csv,
+    Plain,
+    Template(Template<ListDevicesColumn>),
+    Json,
+    JsonPretty,
+    Yaml,
 }
 
 /// Provides a list of known Bluetooth devices on the host by using a [`BluezClient`].
@@ -156,8 +605,36 @@ enum ListDevicesOutput {
 ///
 /// - If `args.columns` are [`Some`], then [`list_devices`] uses the pretty formatting, which is a table.
 /// - If `args.values` are [`Some`], then [`list_devices`] uses the terse formatting, which is a listing where each property of the scanned devices are concatenated by the delimiter `/`.
-/// - If both `args.columns` and `args.values` are [`Some`], then [`list_devices`] uses the pretty formatting.
+/// - If both `args.columns` and `args.values` are [`Some`], then [`list_devices`] uses the pretty formatting. The CLI does not allow this, since `--columns` and `--values` are mutually exclusive.
 /// - If both `args.columns` and `args.values` are [`None`], then [`list_devices`] uses the pretty formatting with the default columns `ALIAS, ADDRESS, CONNECTED, TRUSTED, BONDED, PAIRED`.
+/// - If `args.json` is `true`, then [`list_devices`] uses compact JSON, which is an array of objects keyed by the lowercase column names.
+/// - If `args.json_pretty` is `true`, then [`list_devices`] uses the same JSON with indentation. It takes precedence over `args.json`.
+/// - If `args.format` is [`ListDevicesFormat::Csv`], then [`list_devices`] uses RFC 4180 CSV with a header row. Unlike terse formatting, the values that contain commas or double quotes (such as `CAPABILITIES`) are quoted. JSON takes precedence over CSV, and CSV takes precedence over the template.
+/// - If `args.format` is [`ListDevicesFormat::TableCompact`], then [`list_devices`] uses the pretty formatting, but the columns are separated by a single space without padding. Like CSV, it takes precedence over the template.
+/// - If `args.format` is [`ListDevicesFormat::Plain`], then [`list_devices`] writes a header row and a row per device, where the values are separated by a single space. Unlike [`ListDevicesFormat::TableCompact`], the columns are not aligned, so each row is as wide as its values. Like CSV, it takes precedence over the template.
+/// - If `args.format` is [`ListDevicesFormat::Yaml`], then [`list_devices`] uses YAML, which is a sequence of mappings with the same keys and values as the JSON output, e.g. `battery: null` for an unknown battery level. Like CSV, it takes precedence over the template.
+///
+/// # Snapshots
+///
+/// If `args.snapshot` is [`SnapshotAction::Save`], then the listed devices are saved as a snapshot instead of being written, and only the number of saved devices is written, e.g. `saved a snapshot of 3 device(s) to ~/.local/state/bt/snapshot.json`. Only the ALIAS, ADDRESS and the statuses of the devices are saved. Please see [`ScanSnapshot::path()`] for the default location, which `args.snapshot_file` overrides.
+///
+/// If `args.diff` is `true`, then the listed devices are compared with the saved snapshot by [`ScanSnapshot.diff_by()`], and a table of the devices that are added (`+`), removed (`-`) or whose connected, trusted, bonded or paired status changed (`~`) is written, with a leading `CHANGE` column. If nothing changed, then `no changes since the snapshot` is written instead.
+///
+/// If `args.addresses` or `args.aliases` is `true`, then [`list_devices`] uses the terse formatting with the `ADDRESS` or `ALIAS` column only, which results in one value per line.
+///
+/// If `args.print0` is `true`, then each record of the terse formatting is terminated by a NUL byte instead of a newline, including the last one.
+///
+/// If `args.template` is [`Some`], then [`list_devices`] formats each device with the template instead, which is a string where each `{column}` placeholder (e.g. `{alias}`) is replaced by the value of that column. Missing values, such as the battery of a disconnected device, are rendered empty. The template takes precedence over `args.columns` and `args.values`, and JSON takes precedence over the template.
+///
+/// If `args.output_file` is [`Some`], then the formatted output is written to that file instead, which is created or truncated. In that case, only the number of written devices is written to the provided [`io::Write`], e.g. `wrote 3 device(s) to scan.txt`.
+///
+/// If `args.sort` is [`Some`], then the devices are sorted by that column, in reverse if `args.reverse` is `true`. The devices that are equal by `args.sort` are then ordered by `args.then`, which has its own `args.reverse_then`. Devices that are equal by both keys keep the order provided by [`BluezClient`].
+///
+/// If `args.offset` is not zero, then the first `args.offset` devices are skipped. If `args.limit` is [`Some`], then at most that many devices are shown after the skipped ones. The window is applied after the devices are filtered and sorted. If neither `args.sort` nor `args.recent` is set, then the devices are read lazily via [`BluezClient.devices_iter()`], and the devices after the window are not read at all. In that case, only the devices that are read count towards the unread warning (and `args.strict`).
+///
+/// If `args.recent` is `true`, then the devices are ordered by their last connection in `history`, most recent first, and the devices that are never connected are listed last. The order of `args.sort` only breaks the ties in that case.
+///
+/// In JSON formatting, the columns are still taken from `args.columns` or `args.values`. JSON is never colored.
 ///
 /// Here is how pretty formatting looks like:
 ///
@@ -174,10 +651,36 @@ enum ListDevicesOutput {
 /// KnownDev2/XX:XX:XX:XX:XX:XX/false/true/false/false
 /// ```
 ///
+/// Here is how compact JSON formatting looks like (filtered by `ALIAS, CONNECTED`):
+///
+/// ```txt
+/// [{"alias":"KnownDev1","connected":true},{"alias":"KnownDev2","connected":false}]
+/// ```
+///
 /// The columns can be filtered by the provided [`ListDevicesColumn`] in `args.columns` or `args.values`.
 ///
+/// The `STATE` column is not shown by default. It is a richer version of `CONNECTED`, which shows `connected`, `connecting` or `disconnected`. A device is `connecting` while it is connected but its services are not resolved yet.
+///
+/// The `CAPABILITIES` column is not shown by default. It lists the categories of a device (`audio`, `input`, `phone`, `network`) derived from its advertised service UUIDs, or `-` if none are known.
+///
 /// The devices can be filtered by the provided [`DeviceStatus`] in `args.status`.
 ///
+/// If `args.contains_name` is [`Some`], then only the devices whose ALIAS contains it are listed. It is a simple substring, not a pattern, and it is matched regardless of the case if `args.ignore_case` is `true`.
+///
+/// If `args.min_battery` or `args.max_battery` is [`Some`], then only the devices whose battery level is within the bounds (inclusive) are listed, and the devices with an unknown battery level are excluded.
+///
+/// If `args.battery_only` is `true`, then only the devices with a known battery level are listed. Since the battery is only read for the connected devices, the disconnected ones are never listed. The default columns become `ALIAS, BATTERY` in that case.
+///
+/// If `args.show_adapter` is `true`, then the pretty output starts with the name and the power state of the selected adapter, e.g. `adapter hci0: enabled`, so that an empty table is not confusing when Bluetooth is off. It has no effect on the other formats.
+///
+/// The boolean columns (`CONNECTED`, `TRUSTED`, `BONDED` and `PAIRED`) are rendered by `args.bool_style`, e.g. `yes`/`no` for [`BoolStyle::YesNo`] or `✓`/`✗` for [`BoolStyle::Checkmark`]. The JSON output always uses real booleans.
+///
+/// If `args.group_by` is [`Some`], then the pretty output is split into two labeled tables based on the provided [`DeviceStatus`], such as `Connected:` and `Disconnected:`. Each table has the same columns, and a group without devices only has the header. It has no effect on the other formats.
+///
+/// The devices that cannot be fully read from Bluez D-Bus are skipped, and their count is written as a warning to the provided `warn` [`io::Write`], which is usually stderr. If `args.strict` is `true`, then [`list_devices`] fails with [`ListDevicesError::UnreadDevices`] instead, without writing any device.
+///
+/// The listed devices are gathered by [`list_devices_data`], which provides them without any formatting.
+///
 /// # Panics
 ///
 /// This function does not panic.
@@ -193,18 +696,20 @@ enum ListDevicesOutput {
 ///
 /// ```no_run
 /// use std::io::Cursor;
-/// use bt::{list_devices, BluezClient, ListDevicesArgs};
+/// use bt::{list_devices, BluezClient, History, ListDevicesArgs};
 ///
 /// let bluez_client = BluezClient::new().unwrap();
+/// let mut warnings = std::io::stderr();
 /// let mut output = Cursor::new(vec![]);
 ///
 /// let args = ListDevicesArgs {
 ///     columns: None,
 ///     values: None,
 ///     status: None,
+///     ..Default::default()
 /// };
 ///
-/// let list_dev_result = list_devices(&bluez_client, &mut output, &args);
+/// let list_dev_result = list_devices(&bluez_client, &mut output, &mut warnings, &History::default(), &args);
 /// match list_dev_result {
 ///     Ok(_) => {
 ///          let pretty_out = String::from_utf8(output.into_inner()).unwrap();
@@ -218,9 +723,10 @@ enum ListDevicesOutput {
 ///
 ///```no_run
 /// use std::io::Cursor;
-/// use bt::{list_devices, BluezClient, ListDevicesArgs, ListDevicesColumn};
+/// use bt::{list_devices, BluezClient, History, ListDevicesArgs, ListDevicesColumn};
 ///
 /// let bluez_client = BluezClient::new().unwrap();
+/// let mut warnings = std::io::stderr();
 /// let mut output = Cursor::new(vec![]);
 ///
 /// // Only ALIAS, CONNECTED, and TRUSTED columns are shown.
@@ -228,9 +734,10 @@ enum ListDevicesOutput {
 ///     columns: Some(vec![ListDevicesColumn::Alias, ListDevicesColumn::Connected, ListDevicesColumn::Trusted]),
 ///     values: None,
 ///     status: None,
+///     ..Default::default()
 /// };
 ///
-/// let list_dev_result = list_devices(&bluez_client, &mut output, &args);
+/// let list_dev_result = list_devices(&bluez_client, &mut output, &mut warnings, &History::default(), &args);
 /// match list_dev_result {
 ///     Ok(_) => {
 ///          let pretty_out = String::from_utf8(output.into_inner()).unwrap();
@@ -244,9 +751,10 @@ enum ListDevicesOutput {
 ///
 ///```no_run
 /// use std::io::Cursor;
-/// use bt::{list_devices, BluezClient, ListDevicesArgs, ListDevicesColumn, DeviceStatus};
+/// use bt::{list_devices, BluezClient, History, ListDevicesArgs, ListDevicesColumn, DeviceStatus};
 ///
 /// let bluez_client = BluezClient::new().unwrap();
+/// let mut warnings = std::io::stderr();
 /// let mut output = Cursor::new(vec![]);
 ///
 /// // Only the ALIAS of connected devices are shown.
@@ -254,9 +762,10 @@ enum ListDevicesOutput {
 ///     columns: Some(vec![ListDevicesColumn::Alias]),
 ///     values: None,
 ///     status: Some(DeviceStatus::Connected),
+///     ..Default::default()
 /// };
 ///
-/// let list_dev_result = list_devices(&bluez_client, &mut output, &args);
+/// let list_dev_result = list_devices(&bluez_client, &mut output, &mut warnings, &History::default(), &args);
 /// match list_dev_result {
 ///     Ok(_) => {
 ///          let pretty_out = String::from_utf8(output.into_inner()).unwrap();
@@ -270,18 +779,20 @@ enum ListDevicesOutput {
 ///
 /// ```no_run
 /// use std::io::Cursor;
-/// use bt::{list_devices, BluezClient, ListDevicesArgs, ListDevicesError};
+/// use bt::{list_devices, BluezClient, History, ListDevicesArgs, ListDevicesError};
 ///
 /// let bluez_client = BluezClient::new().unwrap();
+/// let mut warnings = std::io::stderr();
 /// let mut output = Cursor::new([]);
 ///
 /// let args = ListDevicesArgs {
 ///     columns: None,
 ///     values: None,
 ///     status: None,
+///     ..Default::default()
 /// };
 ///
-/// let list_dev_result = list_devices(&bluez_client, &mut output, &args);
+/// let list_dev_result = list_devices(&bluez_client, &mut output, &mut warnings, &History::default(), &args);
 /// match list_dev_result {
 ///     Err(ListDevicesError::Io(err)) => eprintln!("{}", err),
 ///     _ => unreachable!(),
@@ -289,6 +800,7 @@ enum ListDevicesOutput {
 ///```
 ///
 /// [`BluezClient`]: crate::BluezClient
+/// [`BluezClient.devices_iter()`]: crate::BluezClient::devices_iter()
 /// [`io::Write`]: std::io::Write
 /// [`Some`]: std::option::Option::Some
 /// [`None`]: std::option::Option::None
@@ -296,38 +808,335 @@ enum ListDevicesOutput {
 /// [`list_devices`]: crate::list_devices
 /// [`ListDevicesArgs`]: crate::ListDevicesArgs
 /// [`DeviceStatus`]: crate::DeviceStatus
+/// [`ListDevicesFormat::Csv`]: crate::ListDevicesFormat::Csv
+/// [`ListDevicesFormat::TableCompact`]: crate::ListDevicesFormat::TableCompact
+/// [`ListDevicesFormat::Plain`]: crate::ListDevicesFormat::Plain
+/// [`ListDevicesFormat::Yaml`]: crate::ListDevicesFormat::Yaml
+/// [`SnapshotAction::Save`]: crate::SnapshotAction::Save
+/// [`ScanSnapshot::path()`]: crate::ScanSnapshot::path()
+/// [`ScanSnapshot.diff_by()`]: crate::ScanSnapshot::diff_by()
+/// [`ListDevicesError::UnreadDevices`]: crate::ListDevicesError::UnreadDevices
+/// [`list_devices_data`]: crate::list_devices_data
+/// [`BoolStyle::YesNo`]: crate::BoolStyle::YesNo
+/// [`BoolStyle::Checkmark`]: crate::BoolStyle::Checkmark
 pub fn list_devices(
     bluez: &crate::BluezClient,
     f: &mut impl io::Write,
+    warn: &mut impl io::Write,
+    history: &History,
     args: &ListDevicesArgs,
 ) -> Result<(), Error> {
-    let (out_format, user_listing_keys) = match (&args.columns, &args.values) {
-        (None, None) => (ListDevicesOutput::Pretty, None),
-        (None, values) => (ListDevicesOutput::Terse, values.as_ref()),
-        (columns, _) => (ListDevicesOutput::Pretty, columns.as_ref()),
+    let shortcut_values = match (args.addresses, args.aliases) {
+        (true, _) => Some(vec![ListDevicesColumn::Address]),
+        (_, true) => Some(vec![ListDevicesColumn::Alias]),
+        _ => None,
     };
+    let values = shortcut_values.as_ref().or(args.values.as_ref());
 
-    let listing_keys = match user_listing_keys {
-        Some(keys) => keys,
-        None => &DEFAULT_LISTING_COLUMNS.to_vec(),
+    let (layout, listing_keys) = resolve_columns(
+        args.columns.as_deref(),
+        values.map(|v| v.as_slice()),
+        if args.battery_only {
+            &BATTERY_LISTING_COLUMNS
+        } else {
+            &DEFAULT_LISTING_COLUMNS
+        },
+    );
+    let mut out_format = match layout {
+        ListingLayout::Pretty => ListDevicesOutput::Pretty(TableStyle::Blank),
+        ListingLayout::Terse => ListDevicesOutput::Terse,
     };
 
-    let devices = bluez.devices()?;
-    let devices = devices.into_iter().filter(|d| match &args.status {
-        Some(s) => d.filter_cell_value_by_status(s),
-        None => true,
-    });
+    if args.json_pretty {
+        out_format = ListDevicesOutput::JsonPretty;
+    } else if args.json {
+        out_format = ListDevicesOutput::Json;
+    } else if let Some(ListDevicesFormat::Csv) = args.format {
+        out_format = ListDevicesOutput::Csv;
+    } else if let Some(ListDevicesFormat::TableCompact) = args.format {
+        out_format = ListDevicesOutput::Pretty(TableStyle::Compact);
+    } else if let Some(ListDevicesFormat::Plain) = args.format {
+        out_format = ListDevicesOutput::Plain;
+    } else if let Some(ListDevicesFormat::Yaml) = args.format {
+        out_format = ListDevicesOutput::Yaml;
+    } else if let Some(template) = &args.template {
+        out_format = ListDevicesOutput::Template(Template::parse(template)?);
+    }
+
+    let (devices, unread) = list_devices_data(bluez, history, args)?;
+    if unread > 0 {
+        writeln!(
+            warn,
+            "list-devices: warning: {} device(s) could not be read",
+            unread
+        )?;
+    }
+
+    if let Some(SnapshotAction::Save) = args.snapshot {
+        let path = snapshot_path(args)?;
+        let device_count = devices.len();
+        ScanSnapshot::new(devices).save(&path)?;
+        writeln!(
+            f,
+            "saved a snapshot of {} device(s) to {}",
+            device_count,
+            path.display()
+        )?;
+
+        return Ok(());
+    }
+
+    if args.diff {
+        let path = snapshot_path(args)?;
+        let snapshot = ScanSnapshot::load_from(&path).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => Error::Snapshot(format!(
+                "no snapshot is saved in {}, save one with --snapshot save",
+                path.display()
+            )),
+            _ => Error::Io(e),
+        })?;
+
+        let out_buf = to_diff_pretty(&snapshot, devices, &listing_keys, args.bool_style);
+        f.write_all(out_buf.as_bytes())?;
+
+        return Ok(());
+    }
 
+    let header = match out_format {
+        ListDevicesOutput::Pretty(_) if args.show_adapter => format!(
+            "adapter {}: {}\n",
+            bluez.selected_adapter(),
+            bluez.power_state()?
+        ),
+        _ => String::new(),
+    };
+
+    let device_count = devices.len();
+    let rows = styled(&devices, args.bool_style);
+
+    let terminator = if args.print0 { '\0' } else { '\n' };
     let out_buf = match out_format {
-        ListDevicesOutput::Pretty => devices.to_pretty(listing_keys).to_string(),
-        ListDevicesOutput::Terse => devices.to_terse(listing_keys).to_string(),
+        ListDevicesOutput::Pretty(style) => match &args.group_by {
+            Some(status) => to_grouped_pretty(rows, status, &listing_keys, style),
+            None => rows.to_pretty_with_style(&listing_keys, style).to_string(),
+        },
+        ListDevicesOutput::Terse => rows
+            .to_terse_with_terminator(&listing_keys, "/", terminator)
+            .to_string(),
+        ListDevicesOutput::Csv => rows.to_csv(&listing_keys).to_string(),
+        ListDevicesOutput::Plain => rows.to_plain(&listing_keys).to_string(),
+        ListDevicesOutput::Template(template) => {
+            rows.to_template(&template, terminator).to_string()
+        }
+        ListDevicesOutput::Json => rows.to_json(&listing_keys, false).to_string(),
+        ListDevicesOutput::JsonPretty => rows.to_json(&listing_keys, true).to_string(),
+        ListDevicesOutput::Yaml => rows.to_yaml(&listing_keys).to_string(),
     };
+    let out_buf = header + &out_buf;
 
-    f.write_all(out_buf.as_bytes())?;
+    match &args.output_file {
+        Some(path) => {
+            fs::write(path, out_buf)?;
+            writeln!(f, "wrote {} device(s) to {}", device_count, path.display())?;
+        }
+        None => f.write_all(out_buf.as_bytes())?,
+    }
 
     Ok(())
 }
 
+/// Provides the devices that [`list_devices`] would list, without formatting them, e.g. to embed the listing into a GUI.
+///
+/// The devices are filtered by `args.status`, `args.contains_name`, `args.battery_only`, `args.min_battery` and `args.max_battery`, sorted by `args.sort`, `args.then` and `args.recent` (by using `history`), and windowed by `args.offset` and `args.limit`, the same way as [`list_devices`]. The formatting arguments, such as `args.columns` or `args.json`, are ignored.
+///
+/// Along with the devices, the number of devices that cannot be fully read from Bluez D-Bus is provided. If `args.strict` is `true` and there are such devices, then it fails with [`ListDevicesError::UnreadDevices`] instead.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function can return [`ListDevicesError::Bluez`] and [`ListDevicesError::UnreadDevices`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use bt::{list_devices_data, BluezClient, DeviceStatus, History, ListDevicesArgs};
+///
+/// let bluez_client = BluezClient::new().unwrap();
+///
+/// let args = ListDevicesArgs {
+///     status: Some(DeviceStatus::Connected),
+///     ..Default::default()
+/// };
+///
+/// let (devices, _) = list_devices_data(&bluez_client, &History::default(), &args).unwrap();
+/// for device in devices {
+///     println!("{} {:?}", device.alias(), device.battery());
+/// }
+/// ```
+///
+/// [`ListDevicesError::Bluez`]: crate::ListDevicesError::Bluez
+/// [`ListDevicesError::UnreadDevices`]: crate::ListDevicesError::UnreadDevices
+/// [`list_devices`]: crate::list_devices
+pub fn list_devices_data(
+    bluez: &crate::BluezClient,
+    history: &History,
+    args: &ListDevicesArgs,
+) -> Result<(Vec<bluez::BluezDevice>, usize), Error> {
+    let has_status = |d: &bluez::BluezDevice| {
+        let has_status = match &args.status {
+            Some(s) => d.filter_cell_value_by_status(s),
+            None => true,
+        };
+
+        let has_name = args
+            .contains_name
+            .as_deref()
+            .is_none_or(|name| select::contains_name(d.alias(), name, args.ignore_case));
+
+        has_status
+            && has_name
+            && (!args.battery_only || d.battery().is_some())
+            && in_battery_range(d, args)
+    };
+    let limit = args.limit.unwrap_or(usize::MAX);
+
+    let (devices, unread) = if args.sort.is_some() || args.recent {
+        let (mut devices, unread) = bluez.devices_with_unread()?;
+        devices.retain(has_status);
+        sort_by_keys(&mut devices, &sort_keys(args));
+        if args.recent {
+            devices = history.sort_by_recency(devices, |d| d.address());
+        }
+
+        let devices = devices.into_iter().skip(args.offset).take(limit);
+        (devices.collect::<Vec<bluez::BluezDevice>>(), unread)
+    } else {
+        // NOTE: Without a sort, the window is known before reading the
+        // devices, so the devices after the window are never read.
+        let mut unread = 0;
+        let devices = bluez
+            .devices_iter()?
+            .filter_map(|dev| {
+                if dev.is_none() {
+                    unread += 1;
+                }
+
+                dev
+            })
+            .filter(has_status)
+            .skip(args.offset)
+            .take(limit)
+            .collect::<Vec<bluez::BluezDevice>>();
+
+        (devices, unread)
+    };
+
+    if unread > 0 && args.strict {
+        return Err(Error::UnreadDevices(unread));
+    }
+
+    Ok((devices, unread))
+}
+
+/// Indicates whether the battery level of `device` is within `args.min_battery` and `args.max_battery`.
+///
+/// A device with an unknown battery level is only in range if neither bound is set.
+fn in_battery_range(device: &bluez::BluezDevice, args: &ListDevicesArgs) -> bool {
+    if args.min_battery.is_none() && args.max_battery.is_none() {
+        return true;
+    }
+
+    device.battery().is_some_and(|battery| {
+        args.min_battery.is_none_or(|min| battery >= min)
+            && args.max_battery.is_none_or(|max| battery <= max)
+    })
+}
+
+/// Renders a labeled table for the devices that have `status`, and another one for the devices that do not.
+fn to_grouped_pretty<'a>(
+    devices: impl Iterator<Item = ListedDevice<'a>>,
+    status: &DeviceStatus,
+    columns: &[ListDevicesColumn],
+    style: TableStyle,
+) -> String {
+    let (with_status, without_status): (Vec<_>, Vec<_>) =
+        devices.partition(|d| d.device.filter_cell_value_by_status(status));
+    let (with_label, without_label) = status.group_labels();
+
+    format!(
+        "{}:\n{}\n\n{}:\n{}",
+        with_label,
+        with_status.into_iter().to_pretty_with_style(columns, style),
+        without_label,
+        without_status
+            .into_iter()
+            .to_pretty_with_style(columns, style)
+    )
+}
+
+/// Provides the location of the snapshot, which is `args.snapshot_file` if it is set.
+fn snapshot_path(args: &ListDevicesArgs) -> Result<PathBuf, Error> {
+    args.snapshot_file
+        .clone()
+        .or_else(ScanSnapshot::path)
+        .ok_or_else(|| Error::Snapshot(String::from("neither $XDG_STATE_HOME nor $HOME is set")))
+}
+
+/// Renders a table of the devices that changed between `snapshot` and the listed `devices`.
+fn to_diff_pretty(
+    snapshot: &ScanSnapshot,
+    devices: Vec<bluez::BluezDevice>,
+    columns: &[ListDevicesColumn],
+    bool_style: BoolStyle,
+) -> String {
+    let diff = snapshot.diff_by(&ScanSnapshot::new(devices), |before, after| {
+        before.connected() != after.connected()
+            || before.trusted() != after.trusted()
+            || before.bonded() != after.bonded()
+            || before.paired() != after.paired()
+    });
+    if diff.is_empty() {
+        return String::from("no changes since the snapshot\n");
+    }
+
+    let columns = [DiffColumn::Change]
+        .into_iter()
+        .chain(columns.iter().copied().map(DiffColumn::Device))
+        .collect::<Vec<DiffColumn>>();
+
+    marked("+", diff.added(), bool_style)
+        .chain(marked("-", diff.removed(), bool_style))
+        .chain(marked("~", diff.changed(), bool_style))
+        .to_pretty(&columns)
+        .to_string()
+}
+
+/// Marks each of `devices` by `marker` in a diff.
+fn marked<'a>(
+    marker: &'a str,
+    devices: &'a [bluez::BluezDevice],
+    bool_style: BoolStyle,
+) -> impl Iterator<Item = DiffRow<'a>> {
+    styled(devices, bool_style).map(move |device| DiffRow { marker, device })
+}
+
+/// Builds the sort keys from `args.sort` and `args.then`.
+fn sort_keys(args: &ListDevicesArgs) -> Vec<SortKey<ListDevicesColumn>> {
+    let primary = args.sort.map(|column| SortKey {
+        column,
+        reverse: args.reverse,
+    });
+    let secondary = args.then.map(|column| SortKey {
+        column,
+        reverse: args.reverse_then,
+    });
+
+    primary.into_iter().chain(secondary).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::vec;
@@ -347,9 +1156,16 @@ mod tests {
             columns: None,
             values: None,
             status: None,
+            ..Default::default()
         };
 
-        let result = list_devices(&bluez, &mut out_buf, &args);
+        let result = list_devices(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &History::default(),
+            &args,
+        );
 
         assert!(result.is_ok());
         assert!(!out_buf.into_inner().is_empty());
@@ -366,9 +1182,16 @@ mod tests {
             columns: None,
             values: None,
             status: None,
+            ..Default::default()
         };
 
-        let result = list_devices(&bluez, &mut out_buf, &args);
+        let result = list_devices(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &History::default(),
+            &args,
+        );
 
         assert!(result.is_err());
         assert!(out_buf.into_inner().is_empty());
@@ -385,16 +1208,29 @@ mod tests {
             columns: None,
             values: None,
             status: None,
+            ..Default::default()
         };
 
-        let result = list_devices(&bluez, &mut unfiltered_out_buf, &args);
+        let result = list_devices(
+            &bluez,
+            &mut unfiltered_out_buf,
+            &mut io::sink(),
+            &History::default(),
+            &args,
+        );
         assert!(result.is_ok());
         let unfiltered_len = unfiltered_out_buf.into_inner().len();
 
         // NOTE: There are no bonded devices returning from BluezTestClient.
         args.status = Some(DeviceStatus::Bonded);
 
-        let result = list_devices(&bluez, &mut filtered_out_buf, &args);
+        let result = list_devices(
+            &bluez,
+            &mut filtered_out_buf,
+            &mut io::sink(),
+            &History::default(),
+            &args,
+        );
         assert!(result.is_ok());
         let filtered_len = filtered_out_buf.into_inner().len();
 
@@ -411,11 +1247,1046 @@ mod tests {
             columns: None,
             values: None,
             status: None,
+            ..Default::default()
         };
 
-        let result = list_devices(&bluez, &mut out_buf, &args);
+        let result = list_devices(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &History::default(),
+            &args,
+        );
 
         assert!(result.is_err());
         assert!(out_buf.into_inner().is_empty())
     }
+
+    #[test]
+    fn it_should_show_devices_as_compact_or_pretty_json() {
+        let bluez = crate::BluezClient::new().unwrap();
+
+        let mut compact_out_buf = Cursor::new(vec![]);
+        let mut pretty_out_buf = Cursor::new(vec![]);
+
+        let mut args = ListDevicesArgs {
+            json: true,
+            ..Default::default()
+        };
+
+        let result = list_devices(
+            &bluez,
+            &mut compact_out_buf,
+            &mut io::sink(),
+            &History::default(),
+            &args,
+        );
+        assert!(result.is_ok());
+
+        args.json_pretty = true;
+
+        let result = list_devices(
+            &bluez,
+            &mut pretty_out_buf,
+            &mut io::sink(),
+            &History::default(),
+            &args,
+        );
+        assert!(result.is_ok());
+
+        let compact = String::from_utf8(compact_out_buf.into_inner()).unwrap();
+        let pretty = String::from_utf8(pretty_out_buf.into_inner()).unwrap();
+
+        assert_eq!(compact.lines().count(), 1);
+        assert!(pretty.lines().count() > 1);
+        assert!(pretty.contains("\n  "));
+
+        let compact: Value = serde_json::from_str(&compact).unwrap();
+        let pretty: Value = serde_json::from_str(&pretty).unwrap();
+        assert_eq!(compact, pretty);
+        assert_eq!(compact[0]["alias"], "test_dev");
+        assert_eq!(compact[0]["connected"], true);
+    }
+
+    #[test]
+    fn it_should_show_device_capabilities_derived_from_uuids() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("headset")
+                .uuids(&[
+                    "0000110b-0000-1000-8000-00805f9b34fb",
+                    "0000110e-0000-1000-8000-00805f9b34fb",
+                    "00001124-0000-1000-8000-00805F9B34FB",
+                    "0000fe59-0000-1000-8000-00805f9b34fb",
+                ])
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("beacon").build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = ListDevicesArgs {
+            values: Some(vec![
+                ListDevicesColumn::Alias,
+                ListDevicesColumn::Capabilities,
+            ]),
+            ..Default::default()
+        };
+
+        let result = list_devices(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &History::default(),
+            &args,
+        );
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "headset/audio,input\nbeacon/-\n");
+    }
+
+    #[test]
+    fn it_should_terminate_terse_records_with_nul_bytes() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev 1").build(),
+            bluez::BluezTestDeviceBuilder::new("dev 2").build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = ListDevicesArgs {
+            values: Some(vec![ListDevicesColumn::Alias]),
+            print0: true,
+            ..Default::default()
+        };
+
+        let result = list_devices(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &History::default(),
+            &args,
+        );
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "dev 1\0dev 2\0");
+        assert!(!result.contains('\n'));
+    }
+
+    #[test]
+    fn it_should_format_devices_with_a_template() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .connected(true)
+                .battery(Some(80))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .address("BB:BB:BB:BB:BB:BB")
+                .build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = ListDevicesArgs {
+            template: Some("{alias} {address} [{battery}]".to_string()),
+            ..Default::default()
+        };
+
+        let result = list_devices(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &History::default(),
+            &args,
+        );
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(
+            result,
+            "dev1 AA:AA:AA:AA:AA:AA [80]\ndev2 BB:BB:BB:BB:BB:BB []\n"
+        );
+    }
+
+    #[test]
+    fn it_should_fail_if_the_template_has_an_unknown_placeholder() {
+        let bluez = crate::BluezClient::new().unwrap();
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        for (template, expected) in [
+            (
+                "{alias} {nickname}",
+                TemplateError::UnknownPlaceholder("nickname".to_string()),
+            ),
+            ("{alias", TemplateError::UnclosedPlaceholder),
+        ] {
+            let args = ListDevicesArgs {
+                template: Some(template.to_string()),
+                ..Default::default()
+            };
+
+            let result = list_devices(
+                &bluez,
+                &mut out_buf,
+                &mut io::sink(),
+                &History::default(),
+                &args,
+            );
+
+            assert!(matches!(result, Err(Error::Template(e)) if e == expected));
+        }
+
+        assert!(out_buf.into_inner().is_empty());
+    }
+
+    #[test]
+    fn it_should_right_align_battery_values() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("headset")
+                .battery(Some(100))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("keyboard")
+                .battery(Some(5))
+                .build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = ListDevicesArgs {
+            columns: Some(vec![ListDevicesColumn::Battery, ListDevicesColumn::Alias]),
+            ..Default::default()
+        };
+
+        let result = list_devices(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &History::default(),
+            &args,
+        );
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        let battery_ends = result
+            .lines()
+            .skip(1)
+            .map(|l| {
+                let battery = l.split_whitespace().next().unwrap();
+                l.find(battery).unwrap() + battery.len()
+            })
+            .collect::<Vec<usize>>();
+
+        assert_eq!(battery_ends.len(), 2);
+        assert_eq!(battery_ends[0], battery_ends[1]);
+    }
+
+    #[test]
+    fn it_should_sort_devices_by_primary_and_secondary_keys() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("b").build(),
+            bluez::BluezTestDeviceBuilder::new("c")
+                .connected(true)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("a").build(),
+            bluez::BluezTestDeviceBuilder::new("d")
+                .connected(true)
+                .build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = ListDevicesArgs {
+            values: Some(vec![ListDevicesColumn::Alias]),
+            sort: Some(ListDevicesColumn::Connected),
+            reverse: true,
+            then: Some(ListDevicesColumn::Alias),
+            ..Default::default()
+        };
+
+        let result = list_devices(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &History::default(),
+            &args,
+        );
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "c\nd\na\nb\n");
+    }
+
+    #[test]
+    fn it_should_show_the_connection_state_of_devices() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1").build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .connected(true)
+                .services_resolved(false)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev3")
+                .connected(true)
+                .build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = ListDevicesArgs {
+            values: Some(vec![
+                ListDevicesColumn::Alias,
+                ListDevicesColumn::Connected,
+                ListDevicesColumn::State,
+            ]),
+            ..Default::default()
+        };
+
+        let result = list_devices(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &History::default(),
+            &args,
+        );
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(
+            result,
+            "dev1/false/disconnected\ndev2/true/connecting\ndev3/true/connected\n"
+        );
+    }
+
+    #[test]
+    fn it_should_quote_csv_values_with_commas_and_double_quotes() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("Kitchen, \"Left\" Speaker")
+                .address("AA:AA:AA:AA:AA:AA")
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("Mouse")
+                .address("BB:BB:BB:BB:BB:BB")
+                .build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = ListDevicesArgs {
+            columns: Some(vec![ListDevicesColumn::Alias, ListDevicesColumn::Address]),
+            format: Some(ListDevicesFormat::Csv),
+            ..Default::default()
+        };
+
+        let result = list_devices(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &History::default(),
+            &args,
+        );
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(
+            result,
+            "ALIAS,ADDRESS\r\n\"Kitchen, \"\"Left\"\" Speaker\",AA:AA:AA:AA:AA:AA\r\nMouse,BB:BB:BB:BB:BB:BB\r\n"
+        );
+    }
+
+    #[test]
+    fn it_should_show_devices_as_a_compact_table() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("Mouse")
+                .address("BB:BB:BB:BB:BB:BB")
+                .connected(true)
+                .build(),
+        ]);
+
+        let render = |format: Option<ListDevicesFormat>| {
+            let mut out_buf = Cursor::new(vec![]);
+
+            let args = ListDevicesArgs {
+                columns: Some(vec![
+                    ListDevicesColumn::Alias,
+                    ListDevicesColumn::Address,
+                    ListDevicesColumn::Connected,
+                ]),
+                format,
+                ..Default::default()
+            };
+
+            list_devices(
+                &bluez,
+                &mut out_buf,
+                &mut io::sink(),
+                &History::default(),
+                &args,
+            )
+            .unwrap();
+
+            String::from_utf8(out_buf.into_inner()).unwrap()
+        };
+
+        let blank = render(None);
+        let compact = render(Some(ListDevicesFormat::TableCompact));
+
+        assert_eq!(
+            compact.lines().collect::<Vec<&str>>(),
+            vec![
+                "ALIAS ADDRESS           CONNECTED",
+                "Mouse BB:BB:BB:BB:BB:BB true     "
+            ]
+        );
+        for (blank_line, compact_line) in blank.lines().zip(compact.lines()) {
+            assert_eq!(
+                blank_line.split_whitespace().collect::<Vec<&str>>(),
+                compact_line.split_whitespace().collect::<Vec<&str>>()
+            );
+            assert!(compact_line.len() < blank_line.len());
+        }
+    }
+
+    #[test]
+    fn it_should_warn_or_fail_if_a_device_cannot_be_read() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_unread_devices(1);
+
+        let mut out_buf = Cursor::new(vec![]);
+        let mut warn_buf = Cursor::new(vec![]);
+
+        let result = list_devices(
+            &bluez,
+            &mut out_buf,
+            &mut warn_buf,
+            &History::default(),
+            &ListDevicesArgs::default(),
+        );
+        assert!(result.is_ok());
+        assert!(!out_buf.into_inner().is_empty());
+
+        let warning = String::from_utf8(warn_buf.into_inner()).unwrap();
+        assert_eq!(
+            warning,
+            "list-devices: warning: 1 device(s) could not be read\n"
+        );
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = ListDevicesArgs {
+            strict: true,
+            ..Default::default()
+        };
+        let result = list_devices(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &History::default(),
+            &args,
+        );
+
+        assert!(matches!(result, Err(Error::UnreadDevices(1))));
+        assert!(out_buf.into_inner().is_empty());
+    }
+
+    #[test]
+    fn it_should_show_one_address_per_line() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .address("BB:BB:BB:BB:BB:BB")
+                .build(),
+        ]);
+
+        for (args, expected) in [
+            (
+                ListDevicesArgs {
+                    addresses: true,
+                    ..Default::default()
+                },
+                "AA:AA:AA:AA:AA:AA\nBB:BB:BB:BB:BB:BB\n",
+            ),
+            (
+                ListDevicesArgs {
+                    aliases: true,
+                    ..Default::default()
+                },
+                "dev1\ndev2\n",
+            ),
+        ] {
+            let mut out_buf = Cursor::new(vec![]);
+
+            let result = list_devices(
+                &bluez,
+                &mut out_buf,
+                &mut io::sink(),
+                &History::default(),
+                &args,
+            );
+            assert!(result.is_ok());
+
+            let result = String::from_utf8(out_buf.into_inner()).unwrap();
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn it_should_group_devices_by_status() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1").build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .connected(true)
+                .build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = ListDevicesArgs {
+            columns: Some(vec![ListDevicesColumn::Alias]),
+            group_by: Some(DeviceStatus::Connected),
+            ..Default::default()
+        };
+
+        let result = list_devices(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &History::default(),
+            &args,
+        );
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        let (connected, disconnected) = result.split_once("\n\n").unwrap();
+
+        assert!(connected.starts_with("Connected:\n"));
+        assert!(connected.contains("dev2"));
+        assert!(!connected.contains("dev1"));
+
+        assert!(disconnected.starts_with("Disconnected:\n"));
+        assert!(disconnected.contains("dev1"));
+        assert!(!disconnected.contains("dev2"));
+    }
+
+    #[test]
+    fn it_should_order_the_devices_by_recency() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .address("BB:BB:BB:BB:BB:BB")
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev3")
+                .address("CC:CC:CC:CC:CC:CC")
+                .build(),
+        ]);
+
+        let mut history = History::default();
+        history.record_at("CC:CC:CC:CC:CC:CC", 10);
+        history.record_at("BB:BB:BB:BB:BB:BB", 20);
+
+        for (recent, expected) in [(false, "dev1\ndev2\ndev3\n"), (true, "dev2\ndev3\ndev1\n")] {
+            let mut out_buf = Cursor::new(vec![]);
+
+            let args = ListDevicesArgs {
+                aliases: true,
+                recent,
+                ..Default::default()
+            };
+
+            let result = list_devices(&bluez, &mut out_buf, &mut io::sink(), &history, &args);
+            assert!(result.is_ok());
+
+            let result = String::from_utf8(out_buf.into_inner()).unwrap();
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn it_should_stop_reading_the_devices_after_the_window() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(
+            (1..=5)
+                .map(|i| bluez::BluezTestDeviceBuilder::new(&format!("dev{}", i)).build())
+                .collect(),
+        );
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = ListDevicesArgs {
+            aliases: true,
+            offset: 1,
+            limit: Some(2),
+            ..Default::default()
+        };
+
+        let result = list_devices(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &History::default(),
+            &args,
+        );
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "dev2\ndev3\n");
+        assert_eq!(bluez.read_devices(), 3);
+    }
+
+    #[test]
+    fn it_should_apply_the_window_after_sorting() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev2").build(),
+            bluez::BluezTestDeviceBuilder::new("dev3").build(),
+            bluez::BluezTestDeviceBuilder::new("dev1").build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = ListDevicesArgs {
+            aliases: true,
+            sort: Some(ListDevicesColumn::Alias),
+            offset: 1,
+            limit: Some(5),
+            ..Default::default()
+        };
+
+        let result = list_devices(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &History::default(),
+            &args,
+        );
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "dev2\ndev3\n");
+    }
+
+    #[test]
+    fn it_should_provide_the_filtered_and_sorted_devices_without_writing() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev3")
+                .trusted(true)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev2").build(),
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .trusted(true)
+                .build(),
+        ]);
+        bluez.set_unread_devices(1);
+
+        let args = ListDevicesArgs {
+            status: Some(DeviceStatus::Trusted),
+            sort: Some(ListDevicesColumn::Alias),
+            ..Default::default()
+        };
+
+        let (devices, unread) = list_devices_data(&bluez, &History::default(), &args).unwrap();
+
+        let aliases = devices.iter().map(|d| d.alias()).collect::<Vec<&str>>();
+        assert_eq!(aliases, vec!["dev1", "dev3"]);
+        assert_eq!(unread, 1);
+
+        let args = ListDevicesArgs {
+            strict: true,
+            ..args
+        };
+        let result = list_devices_data(&bluez, &History::default(), &args);
+        assert!(matches!(result, Err(Error::UnreadDevices(1))));
+    }
+
+    #[test]
+    fn it_should_only_show_the_devices_with_a_battery() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("headset")
+                .connected(true)
+                .battery(Some(80))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("speaker")
+                .connected(true)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("mouse").build(),
+            bluez::BluezTestDeviceBuilder::new("keyboard")
+                .connected(true)
+                .battery(Some(15))
+                .build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = ListDevicesArgs {
+            battery_only: true,
+            json: true,
+            ..Default::default()
+        };
+
+        let result = list_devices(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &History::default(),
+            &args,
+        );
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(
+            result,
+            "[{\"alias\":\"headset\",\"battery\":80},{\"alias\":\"keyboard\",\"battery\":15}]\n"
+        );
+    }
+
+    #[test]
+    fn it_should_filter_the_devices_by_battery_range() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("headset")
+                .connected(true)
+                .battery(Some(80))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("keyboard")
+                .connected(true)
+                .battery(Some(25))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("mouse")
+                .connected(true)
+                .battery(Some(10))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("speaker")
+                .connected(true)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("watch").build(),
+        ]);
+
+        for (min_battery, max_battery, expected) in [
+            (None, Some(25), "keyboard\nmouse\n"),
+            (Some(26), None, "headset\n"),
+            (Some(20), Some(80), "headset\nkeyboard\n"),
+            (Some(0), Some(100), "headset\nkeyboard\nmouse\n"),
+        ] {
+            let mut out_buf = Cursor::new(vec![]);
+
+            let args = ListDevicesArgs {
+                aliases: true,
+                min_battery,
+                max_battery,
+                ..Default::default()
+            };
+
+            let result = list_devices(
+                &bluez,
+                &mut out_buf,
+                &mut io::sink(),
+                &History::default(),
+                &args,
+            );
+            assert!(result.is_ok());
+
+            let result = String::from_utf8(out_buf.into_inner()).unwrap();
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn it_should_show_the_adapter_above_the_table() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_adapters(&[
+            ("hci0", bluez::BluezPowerState::On),
+            ("hci1", bluez::BluezPowerState::Off),
+        ]);
+        bluez.select_adapter("hci1").unwrap();
+        bluez.set_devices(vec![bluez::BluezTestDeviceBuilder::new("dev1").build()]);
+
+        for (show_adapter, json, has_header) in [
+            (true, false, true),
+            (false, false, false),
+            (true, true, false),
+        ] {
+            let mut out_buf = Cursor::new(vec![]);
+
+            let args = ListDevicesArgs {
+                columns: Some(vec![ListDevicesColumn::Alias]),
+                show_adapter,
+                json,
+                ..Default::default()
+            };
+
+            let result = list_devices(
+                &bluez,
+                &mut out_buf,
+                &mut io::sink(),
+                &History::default(),
+                &args,
+            );
+            assert!(result.is_ok());
+
+            let result = String::from_utf8(out_buf.into_inner()).unwrap();
+            assert_eq!(result.starts_with("adapter hci1: disabled\n"), has_header);
+        }
+    }
+
+    #[test]
+    fn it_should_show_the_devices_in_plain_format() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("Mouse")
+                .address("BB:BB:BB:BB:BB:BB")
+                .connected(true)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("Living Room Speaker")
+                .address("AA:AA:AA:AA:AA:AA")
+                .build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = ListDevicesArgs {
+            columns: Some(vec![ListDevicesColumn::Alias, ListDevicesColumn::Connected]),
+            format: Some(ListDevicesFormat::Plain),
+            ..Default::default()
+        };
+
+        let result = list_devices(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &History::default(),
+            &args,
+        );
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(
+            result,
+            "ALIAS CONNECTED\nMouse true\nLiving Room Speaker false\n"
+        );
+    }
+
+    #[test]
+    fn it_should_diff_the_devices_against_the_saved_snapshot() {
+        let file = std::env::temp_dir()
+            .join(format!("bt-list-devices-snapshot-{}", std::process::id()))
+            .join("snapshot.json");
+        let _ = fs::remove_dir_all(file.parent().unwrap());
+
+        let mut bluez = crate::BluezClient::new().unwrap();
+        let run = |bluez: &crate::BluezClient, snapshot, diff| {
+            let mut out_buf = Cursor::new(vec![]);
+
+            let args = ListDevicesArgs {
+                columns: Some(vec![ListDevicesColumn::Alias, ListDevicesColumn::Connected]),
+                snapshot,
+                diff,
+                snapshot_file: Some(file.clone()),
+                ..Default::default()
+            };
+
+            list_devices(
+                bluez,
+                &mut out_buf,
+                &mut io::sink(),
+                &History::default(),
+                &args,
+            )
+            .map(|_| String::from_utf8(out_buf.into_inner()).unwrap())
+        };
+
+        let result = run(&bluez, None, true);
+        assert!(matches!(result, Err(Error::Snapshot(_))));
+
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("speaker")
+                .address("AA:AA:AA:AA:AA:AA")
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("mouse")
+                .address("BB:BB:BB:BB:BB:BB")
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("keyboard")
+                .address("CC:CC:CC:CC:CC:CC")
+                .build(),
+        ]);
+        let result = run(&bluez, Some(SnapshotAction::Save), false).unwrap();
+        assert_eq!(
+            result,
+            format!("saved a snapshot of 3 device(s) to {}\n", file.display())
+        );
+
+        let result = run(&bluez, None, true).unwrap();
+        assert_eq!(result, "no changes since the snapshot\n");
+
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("speaker")
+                .address("AA:AA:AA:AA:AA:AA")
+                .connected(true)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("mouse")
+                .address("BB:BB:BB:BB:BB:BB")
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("headset")
+                .address("DD:DD:DD:DD:DD:DD")
+                .build(),
+        ]);
+        let result = run(&bluez, None, true);
+        fs::remove_dir_all(file.parent().unwrap()).unwrap();
+
+        let result = result.unwrap();
+        let lines = result
+            .lines()
+            .map(|line| line.split_whitespace().collect::<Vec<&str>>())
+            .collect::<Vec<Vec<&str>>>();
+        assert_eq!(
+            lines,
+            vec![
+                vec!["CHANGE", "ALIAS", "CONNECTED"],
+                vec!["+", "headset", "false"],
+                vec!["-", "keyboard", "false"],
+                vec!["~", "speaker", "true"],
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_render_the_boolean_columns_in_each_bool_style() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .connected(true)
+                .trusted(true)
+                .build(),
+        ]);
+
+        let run = |bool_style, json| {
+            let mut out_buf = Cursor::new(vec![]);
+
+            let args = ListDevicesArgs {
+                values: Some(vec![
+                    ListDevicesColumn::Alias,
+                    ListDevicesColumn::Connected,
+                    ListDevicesColumn::Bonded,
+                ]),
+                json,
+                bool_style,
+                ..Default::default()
+            };
+            let result = list_devices(
+                &bluez,
+                &mut out_buf,
+                &mut io::sink(),
+                &History::default(),
+                &args,
+            );
+            assert!(result.is_ok());
+
+            String::from_utf8(out_buf.into_inner()).unwrap()
+        };
+
+        assert_eq!(run(BoolStyle::TrueFalse, false), "dev1/true/false\n");
+        assert_eq!(run(BoolStyle::YesNo, false), "dev1/yes/no\n");
+        assert_eq!(run(BoolStyle::Checkmark, false), "dev1/✓/✗\n");
+
+        for bool_style in [BoolStyle::TrueFalse, BoolStyle::YesNo, BoolStyle::Checkmark] {
+            assert_eq!(
+                run(bool_style, true),
+                "[{\"alias\":\"dev1\",\"bonded\":false,\"connected\":true}]\n"
+            );
+        }
+    }
+
+    #[test]
+    fn it_should_write_the_devices_as_yaml() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .connected(true)
+                .battery(Some(80))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .address("BB:BB:BB:BB:BB:BB")
+                .build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = ListDevicesArgs {
+            columns: Some(vec![
+                ListDevicesColumn::Alias,
+                ListDevicesColumn::Connected,
+                ListDevicesColumn::Battery,
+            ]),
+            format: Some(ListDevicesFormat::Yaml),
+            ..Default::default()
+        };
+        let result = list_devices(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &History::default(),
+            &args,
+        );
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(
+            result,
+            "- alias: dev1\n  battery: 80\n  connected: true\n\
+             - alias: dev2\n  battery: null\n  connected: false\n"
+        );
+    }
+
+    #[test]
+    fn it_should_filter_the_devices_by_name() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("LE-Keyboard").build(),
+            bluez::BluezTestDeviceBuilder::new("keyboard cover").build(),
+            bluez::BluezTestDeviceBuilder::new("mouse").build(),
+        ]);
+
+        for (ignore_case, expected) in [
+            (false, "keyboard cover\n"),
+            (true, "LE-Keyboard\nkeyboard cover\n"),
+        ] {
+            let mut out_buf = Cursor::new(vec![]);
+
+            let args = ListDevicesArgs {
+                aliases: true,
+                contains_name: Some(String::from("keyboard")),
+                ignore_case,
+                ..Default::default()
+            };
+
+            let result = list_devices(
+                &bluez,
+                &mut out_buf,
+                &mut io::sink(),
+                &History::default(),
+                &args,
+            );
+            assert!(result.is_ok());
+
+            let result = String::from_utf8(out_buf.into_inner()).unwrap();
+            assert_eq!(result, expected);
+        }
+    }
 }
@@ -0,0 +1,231 @@
+use std::{
+    error, fmt,
+    fs::OpenOptions,
+    io::{self, Write},
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use clap::Args;
+
+use crate::BluezError;
+
+/// Defines error variants that may be returned from a [`listen`] call.
+///
+/// [`listen`]: crate::listen
+#[derive(Debug)]
+pub enum Error {
+    /// Happens when the [`BluezClient`] fails during a [`listen`] call, e.g. when the device cannot be found.
+    /// It holds the underlying [`BluezError`].
+    ///
+    /// [`BluezError`]: crate::BluezError
+    /// [`BluezClient`]: crate::BluezClient
+    /// [`listen`]: crate::listen
+    Bluez(BluezError),
+
+    /// Happens when the output file cannot be opened, or the readings cannot be appended to it.
+    /// It holds the underlying [`io::Error`].
+    ///
+    /// [`io::Error`]: std::io::Error
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Bluez(error) => write!(f, "listen: bluez error: {}", error),
+            Error::Io(error) => write!(f, "listen: io error: {}", error),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<BluezError> for Error {
+    fn from(value: BluezError) -> Self {
+        Self::Bluez(value)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/// Defines the arguments that [`listen`] can take.
+///
+/// [`listen`]: crate::listen
+#[derive(Debug, Args)]
+pub struct ListenArgs {
+    /// The full ALIAS of the device.
+    #[arg(value_name = "ALIAS")]
+    pub alias: String,
+
+    /// Set the interval between the reads in seconds.
+    #[arg(short, long, default_value_t = 1u64)]
+    pub interval: u64,
+
+    /// Append the readings to the file at PATH.
+    ///
+    /// The file is created if it does not exist.
+    #[arg(short, long, value_name = "PATH")]
+    pub out: PathBuf,
+
+    /// Stop listening after COUNT reads.
+    #[arg(short, long, value_name = "COUNT")]
+    pub count: Option<u32>,
+}
+
+impl Default for ListenArgs {
+    fn default() -> Self {
+        Self {
+            alias: String::new(),
+            interval: 1,
+            out: PathBuf::new(),
+            count: None,
+        }
+    }
+}
+
+/// Logs the signal strength (RSSI) of a single device to a file over time by using a [`BluezClient`].
+///
+/// Similar to [`rssi`], [`listen`] starts a discovery first, and reads the RSSI on every `args.interval` seconds.
+/// Each read is appended to the file at `args.out` as a `timestamp,rssi` CSV row, where the timestamp is in seconds since the Unix epoch. If the device does not emit Bluetooth signals, the RSSI field is left empty.
+///
+/// The file is opened in append mode, so the readings of multiple runs are kept in the same file. This makes it easy to diagnose intermittent drops of a device.
+///
+/// [`listen`] keeps reading until interrupted, or until `args.count` reads if `args.count` is [`Some`]. The discovery is stopped once the reads are done.
+///
+/// [`listen`] is a blocking call.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function can return all variants of [`ListenError`] based on given conditions. For more details, please see the error documentation.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::PathBuf;
+/// use bt::{listen, BluezClient, ListenArgs};
+///
+/// let bluez_client = BluezClient::new().unwrap();
+///
+/// let args = ListenArgs {
+///     alias: "flaky_dev".to_string(),
+///     interval: 2,
+///     out: PathBuf::from("rssi.log"),
+///     ..Default::default()
+/// };
+///
+/// // Appends the RSSI of "flaky_dev" to rssi.log every 2 seconds until interrupted.
+/// if let Err(e) = listen(&bluez_client, &args) {
+///     eprintln!("{}", e);
+/// }
+/// ```
+///
+/// [`BluezClient`]: crate::BluezClient
+/// [`Some`]: std::option::Option::Some
+/// [`ListenError`]: crate::ListenError
+/// [`listen`]: crate::listen
+/// [`rssi`]: crate::rssi
+pub fn listen(bluez: &crate::BluezClient, args: &ListenArgs) -> Result<(), Error> {
+    let mut out_file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&args.out)?;
+
+    crate::rssi::poll_rssi(bluez, &args.alias, args.interval, args.count, |rssi| {
+        let rssi = rssi.map(|rssi| rssi.to_string()).unwrap_or_default();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        writeln!(out_file, "{},{}", timestamp, rssi)?;
+
+        Ok::<(), Error>(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::bluez;
+
+    fn test_bluez() -> crate::BluezClient {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("near")
+                .rssi(Some(-42))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("silent").build(),
+        ]);
+
+        bluez
+    }
+
+    fn test_out(name: &str) -> PathBuf {
+        let out = std::env::temp_dir().join(format!("bt-listen-{}-{}", name, std::process::id()));
+        let _ = fs::remove_file(&out);
+
+        out
+    }
+
+    #[test]
+    fn it_should_append_a_row_for_each_read() {
+        let bluez = test_bluez();
+        let out = test_out("rows");
+        fs::write(&out, "0,-50\n").unwrap();
+
+        for alias in ["near", "silent"] {
+            let args = ListenArgs {
+                alias: alias.to_string(),
+                interval: 0,
+                out: out.clone(),
+                count: Some(2),
+            };
+
+            let result = listen(&bluez, &args);
+            assert!(result.is_ok());
+        }
+
+        let result = fs::read_to_string(&out).unwrap();
+        fs::remove_file(&out).unwrap();
+
+        let rows = result.lines().collect::<Vec<&str>>();
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[0], "0,-50");
+        assert!(rows[1..3].iter().all(|row| row.ends_with(",-42")));
+        assert!(rows[3..].iter().all(|row| row.ends_with(',')));
+    }
+
+    #[test]
+    fn it_should_fail_if_the_device_cannot_be_found() {
+        let bluez = test_bluez();
+        let out = test_out("unknown");
+
+        let args = ListenArgs {
+            alias: "unknown".to_string(),
+            interval: 0,
+            out: out.clone(),
+            count: Some(2),
+        };
+
+        let result = listen(&bluez, &args);
+        let rows = fs::read_to_string(&out).unwrap();
+        fs::remove_file(&out).unwrap();
+
+        assert!(matches!(result, Err(Error::Bluez(_))));
+        assert!(rows.is_empty());
+        assert_eq!(
+            bluez.discovery_calls(),
+            vec!["start_discovery", "stop_discovery"]
+        );
+    }
+}
@@ -1,47 +1,50 @@
-use std::{error, io, process::ExitCode};
+use std::{error, io, process::ExitCode, time::Duration};
 
 use bt::api::{BtCommand, Cli};
 use clap::Parser;
 
 const PROGRAM: &str = "bt";
 
+type RunResult = Result<ExitCode, Box<dyn error::Error + Send + Sync>>;
+
 fn main() -> ExitCode {
-    match run() {
-        Ok(_) => ExitCode::SUCCESS,
+    let args = Cli::parse();
+
+    let deadline = args.deadline;
+    let adapter = args.adapter.clone();
+    let command = args.into_command().unwrap_or(BtCommand::Status {
+        args: bt::StatusArgs::default(),
+    });
+    let error_exit_code = command.error_exit_code();
+
+    let result = match deadline {
+        Some(secs) => bt::with_deadline(Duration::from_secs(secs), || run(adapter, command))
+            .unwrap_or_else(|e| Err(e.into())),
+        None => run(adapter, command),
+    };
+
+    match result {
+        Ok(exit_code) => exit_code,
         Err(e) => {
             eprintln!("{PROGRAM}: {}", e);
 
-            ExitCode::FAILURE
+            error_exit_code
         }
     }
 }
 
-fn run() -> Result<(), Box<dyn error::Error>> {
-    let bluez = bt::BluezClient::new()?;
-
-    let args = Cli::parse();
+fn run(adapter: Option<String>, command: BtCommand) -> RunResult {
+    let mut bluez = bt::BluezClient::new()?;
+    if let Some(adapter) = &adapter {
+        bluez.select_adapter(adapter)?;
+    }
 
-    let mut stdout = io::stdout();
-    let stdin = io::stdin();
-
-    if let Some(subcommand) = args.command {
-        match subcommand {
-            BtCommand::Status => bt::status(&bluez, &mut stdout)?,
-            BtCommand::Toggle => bt::toggle(&bluez, &mut stdout)?,
-            BtCommand::Scan { args } => bt::scan(&bluez, &mut stdout, &args)?,
-            BtCommand::Connect { args } => {
-                let mut stdin_handle = stdin.lock();
-                bt::connect(&bluez, &mut stdout, &mut stdin_handle, &args)?
-            }
-            BtCommand::Disconnect { force, aliases } => {
-                let mut stdin_handle = stdin.lock();
-                bt::disconnect(&bluez, &mut stdout, &mut stdin_handle, &force, &aliases)?
-            }
-            BtCommand::ListDevices { args } => bt::list_devices(&bluez, &mut stdout, &args)?,
-        }
-    } else {
-        bt::status(&bluez, &mut stdout)?
-    };
+    let exit_code = command.execute(
+        &mut bluez,
+        &mut io::stdout(),
+        &mut io::stderr(),
+        &mut io::stdin().lock(),
+    )?;
 
-    Ok(())
+    Ok(exit_code)
 }
@@ -0,0 +1,173 @@
+use std::{collections::BTreeMap, env, error, fmt, fs, io, path::PathBuf};
+
+/// Defines error variants that may be returned while loading or saving [`Notes`].
+///
+/// [`Notes`]: crate::Notes
+#[derive(Debug)]
+pub enum Error {
+    /// Happens when the notes file exists but cannot be read, or when it cannot be written.
+    /// It holds the underlying [`io::Error`].
+    ///
+    /// [`io::Error`]: std::io::Error
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "notes: io error: {}", error),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/// Defines the free-form notes of the devices (e.g. "living room speaker"), which are kept locally by their address.
+///
+/// The notes are read from `$XDG_STATE_HOME/bt/notes` (or `~/.local/state/bt/notes`). Each line of the notes file is an `ADDRESS,NOTE` pair, where the note is the rest of the line. The malformed lines are skipped.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Notes {
+    file: Option<PathBuf>,
+    notes: BTreeMap<String, String>,
+}
+
+impl Notes {
+    /// Loads the notes from their default location.
+    ///
+    /// If the notes file does not exist, then empty [`Notes`] are returned.
+    ///
+    /// # Errors
+    ///
+    /// This function can return all variants of [`NotesError`] based on given conditions. For more details, please see the error documentation.
+    ///
+    /// [`Notes`]: crate::Notes
+    /// [`NotesError`]: crate::NotesError
+    pub fn load() -> Result<Self, Error> {
+        match Self::path() {
+            Some(path) => Self::load_from(path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Loads the notes from `path`, which is also where [`Notes::save()`] writes to.
+    ///
+    /// Similar to [`Notes::load()`], empty [`Notes`] are returned if the file does not exist.
+    ///
+    /// [`Notes`]: crate::Notes
+    /// [`Notes::load()`]: crate::Notes::load()
+    /// [`Notes::save()`]: crate::Notes::save()
+    pub fn load_from(path: impl Into<PathBuf>) -> Result<Self, Error> {
+        let path = path.into();
+
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let notes = content
+            .lines()
+            .filter_map(|line| {
+                let (address, note) = line.split_once(',')?;
+                Some((address.trim().to_uppercase(), note.to_string()))
+            })
+            .filter(|(_, note)| !note.is_empty())
+            .collect();
+
+        Ok(Self {
+            file: Some(path),
+            notes,
+        })
+    }
+
+    /// Provides the default location of the notes file, which is `$XDG_STATE_HOME/bt/notes`, or `$HOME/.local/state/bt/notes` if `$XDG_STATE_HOME` is not set.
+    pub fn path() -> Option<PathBuf> {
+        env::var_os("XDG_STATE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("state"))
+            })
+            .map(|dir| dir.join("bt").join("notes"))
+    }
+
+    /// Provides the note of the device with `address`, which is matched case-insensitively.
+    pub fn note(&self, address: &str) -> Option<&str> {
+        self.notes.get(&address.to_uppercase()).map(String::as_str)
+    }
+
+    /// Sets the note of the device with `address`. An empty `note` removes the note instead.
+    ///
+    /// Since each note is kept on a single line, the line breaks of `note` are replaced by spaces.
+    pub fn set(&mut self, address: &str, note: &str) {
+        let address = address.to_uppercase();
+        let note = note.replace(['\r', '\n'], " ");
+
+        if note.is_empty() {
+            self.notes.remove(&address);
+        } else {
+            self.notes.insert(address, note);
+        }
+    }
+
+    /// Writes the notes to the file they are loaded from. The parent directories of the file are created if they do not exist.
+    ///
+    /// [`Notes`] which are not loaded from a file (e.g. [`Notes::default()`]) are not written anywhere.
+    ///
+    /// [`Notes`]: crate::Notes
+    /// [`Notes::default()`]: crate::Notes::default()
+    pub fn save(&self) -> Result<(), Error> {
+        let Some(path) = &self.file else {
+            return Ok(());
+        };
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let content = self
+            .notes
+            .iter()
+            .map(|(address, note)| format!("{},{}\n", address, note))
+            .collect::<String>();
+        fs::write(path, content)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_save_and_load_the_notes() {
+        let file = std::env::temp_dir()
+            .join(format!("bt-notes-{}", std::process::id()))
+            .join("notes");
+        let _ = fs::remove_dir_all(file.parent().unwrap());
+
+        let mut notes = Notes::load_from(&file).unwrap();
+        notes.set("aa:aa:aa:aa:aa:aa", "living room speaker, left");
+        notes.set("BB:BB:BB:BB:BB:BB", "desk\nmouse");
+        notes.set("CC:CC:CC:CC:CC:CC", "gone");
+        notes.set("CC:CC:CC:CC:CC:CC", "");
+        notes.save().unwrap();
+
+        let notes = Notes::load_from(&file).unwrap();
+        fs::remove_dir_all(file.parent().unwrap()).unwrap();
+
+        assert_eq!(
+            notes.note("AA:AA:AA:AA:AA:AA"),
+            Some("living room speaker, left")
+        );
+        assert_eq!(notes.note("bb:bb:bb:bb:bb:bb"), Some("desk mouse"));
+        assert_eq!(notes.note("CC:CC:CC:CC:CC:CC"), None);
+    }
+}
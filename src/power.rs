@@ -0,0 +1,32 @@
+use crate::{BluezError, bluez::BluezPowerState};
+
+/// Defines the outcomes of [`ensure_powered`].
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum PowerCheck {
+    /// The adapter was already powered on.
+    On,
+
+    /// The adapter was off, and it is powered on by [`ensure_powered`].
+    PoweredOn,
+
+    /// The adapter is not powered on, and it is left as it is.
+    Unpowered(BluezPowerState),
+}
+
+/// Checks whether the adapter is powered on before a command starts using it.
+///
+/// If the adapter is off and `auto_power` is `true`, then the adapter is powered on. A blocked adapter is never powered on, since it cannot be until the rfkill switch is unblocked.
+pub(crate) fn ensure_powered(
+    bluez: &crate::BluezClient,
+    auto_power: bool,
+) -> Result<PowerCheck, BluezError> {
+    match bluez.power_state()? {
+        BluezPowerState::On => Ok(PowerCheck::On),
+        BluezPowerState::Off if auto_power => {
+            bluez.set_powered(BluezPowerState::On)?;
+
+            Ok(PowerCheck::PoweredOn)
+        }
+        power_state => Ok(PowerCheck::Unpowered(power_state)),
+    }
+}
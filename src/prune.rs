@@ -0,0 +1,379 @@
+use std::{error, fmt, io};
+
+use clap::Args;
+
+use crate::{
+    BluezError, bluez,
+    format::{self, PrettyFormatter, TableFormattable},
+    select,
+};
+
+/// Defines error variants that may be returned from a [`prune`] call.
+///
+/// [`prune`]: crate::prune
+#[derive(Debug)]
+pub enum Error {
+    /// Happens when the [`BluezClient`] fails during a [`prune`] call.
+    /// It holds the underlying [`BluezError`].
+    ///
+    /// [`BluezError`]: crate::BluezError
+    /// [`BluezClient`]: crate::BluezClient
+    Bluez(BluezError),
+
+    /// Happens when `args.summary` is `true`, and some of the stale devices could not be removed. The summary is written before this error is returned.
+    ///
+    /// It holds the number of failed devices.
+    BatchFailed(usize),
+
+    /// Happens when [`prune`] cannot write to the provided [`io::Write`] or cannot read from the provided [`io::BufRead`].
+    ///
+    /// It holds the underlying [`io::Error`].
+    ///
+    /// [`prune`]: crate::prune
+    /// [`io::Error`]: std::io::Error
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Bluez(error) => write!(f, "prune: bluez error: {}", error),
+            Error::BatchFailed(failed) => {
+                write!(f, "prune: {} device(s) could not be removed", failed)
+            }
+            Error::Io(error) => write!(f, "prune: io error: {}", error),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<BluezError> for Error {
+    fn from(value: BluezError) -> Self {
+        Self::Bluez(value)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/// Defines the arguments that [`prune`] can take.
+///
+/// [`prune`]: crate::prune
+#[derive(Debug, Default, Args)]
+pub struct PruneArgs {
+    /// Remove the stale devices without asking for confirmation.
+    #[arg(short, long, default_value_t = false)]
+    pub yes: bool,
+
+    /// Keep going when a device fails, and print the outcome counts at the end, e.g. `removed=2 failed=1`.
+    #[arg(long, default_value_t = false)]
+    pub summary: bool,
+
+    /// Print the summary as JSON, e.g. `{"ok":2,"failed":1}`.
+    #[arg(long, default_value_t = false, requires = "summary")]
+    pub json: bool,
+}
+
+const DEFAULT_LISTING_COLUMNS: [PruneColumn; 2] = [PruneColumn::Alias, PruneColumn::Address];
+
+#[derive(Copy, Clone)]
+enum PruneColumn {
+    Alias,
+    Address,
+}
+
+impl From<&PruneColumn> for String {
+    fn from(value: &PruneColumn) -> Self {
+        let str = match value {
+            PruneColumn::Alias => "ALIAS",
+            PruneColumn::Address => "ADDRESS",
+        };
+
+        str.to_string()
+    }
+}
+
+impl TableFormattable<PruneColumn> for &bluez::BluezDevice {
+    fn get_cell_value_by_column(&self, column: &PruneColumn) -> String {
+        match column {
+            PruneColumn::Alias => self.alias().to_string(),
+            PruneColumn::Address => self.address().to_string(),
+        }
+    }
+}
+
+/// Indicates whether a known device is stale.
+///
+/// A device is stale when it is neither paired, bonded nor connected, and it is not advertising (has no RSSI).
+fn is_stale(device: &bluez::BluezDevice) -> bool {
+    !device.paired() && !device.bonded() && !device.connected() && device.rssi().is_none()
+}
+
+/// Provides the ability of removing stale devices from the known devices list by using a [`BluezClient`].
+///
+/// Bluez keeps the devices it has seen for a while, even if they were never paired. [`prune`] removes such devices to keep the known devices list tidy.
+///
+/// A known device is considered stale when:
+///
+/// - It is not paired, bonded or connected.
+/// - It is not advertising at the moment, meaning that it does not have an RSSI.
+///
+/// Before removing, the stale devices are written to the provided [`io::Write`] in pretty format, and a confirmation is read from the provided [`io::BufRead`].
+/// Only `y` or `yes` (case-insensitive) confirms the removal, and an empty answer declines it. Any other answer asks for the confirmation again. If `args.yes` is `true`, then the confirmation is skipped.
+///
+/// Upon removing each device, [`prune`] writes a message to the provided [`io::Write`].
+///
+/// By default, [`prune`] stops at the first device that cannot be removed. If `args.summary` is `true`, then it writes a message for the failed device instead and keeps going. Once every device is processed, a summary line is written, such as `removed=2 failed=1`.
+/// If `args.json` is `true`, then the summary is written as JSON instead, such as `{"ok":2,"failed":1}`.
+///
+/// If any device fails, [`PruneError::BatchFailed`] is returned after the summary is written.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function can return all variants of [`PruneError`] based on given conditions. For more details, please see the error documentation.
+///
+/// # Examples
+///
+/// Here is a [`prune`] call that does not ask for confirmation.
+///
+/// ```no_run
+/// use std::io;
+/// use bt::{prune, BluezClient, PruneArgs};
+///
+/// let bluez_client = BluezClient::new().unwrap();
+/// let mut input = io::stdin();
+/// let mut output = io::stdout();
+///
+/// let args = PruneArgs {
+///     yes: true,
+///     ..Default::default()
+/// };
+///
+/// match prune(&bluez_client, &mut output, &mut input.lock(), &args) {
+///     Ok(_) => {
+///          // `output` contains a message for each removed device.
+///          // ...
+///     },
+///     Err(e) => eprintln!("prune error: {}", e)
+/// }
+/// ```
+///
+/// [`BluezClient`]: crate::BluezClient
+/// [`io::Write`]: std::io::Write
+/// [`io::BufRead`]: std::io::BufRead
+/// [`PruneError`]: crate::PruneError
+/// [`PruneError::BatchFailed`]: crate::PruneError::BatchFailed
+/// [`prune`]: crate::prune
+pub fn prune(
+    bluez: &crate::BluezClient,
+    w: &mut impl io::Write,
+    r: &mut impl io::BufRead,
+    args: &PruneArgs,
+) -> Result<(), Error> {
+    let devices = bluez.devices()?;
+    let stale_devices = devices
+        .iter()
+        .filter(|d| is_stale(d))
+        .collect::<Vec<&bluez::BluezDevice>>();
+
+    if stale_devices.is_empty() {
+        w.write_all(b"there are no stale devices to prune\n")?;
+        return Ok(());
+    }
+
+    if !args.yes {
+        let devices = stale_devices
+            .iter()
+            .copied()
+            .to_pretty(&DEFAULT_LISTING_COLUMNS)
+            .to_string();
+
+        writeln!(w, "{}", devices)?;
+        if !select::confirm(w, r, "Remove the stale device(s) above?", false)? {
+            w.write_all(b"prune cancelled\n")?;
+            return Ok(());
+        }
+    }
+
+    // NOTE: The stale devices are removed by their ADDRESS, since their
+    // ALIAS may not be unique, e.g. a known device may share it.
+    let mut ok = 0;
+    let mut failed = 0;
+    for device in stale_devices {
+        let result = match bluez.remove(device.address()) {
+            Ok(_) => {
+                ok += 1;
+                format!("removed device {}\n", device.alias())
+            }
+            Err(e) if args.summary => {
+                failed += 1;
+                format!("failed to remove device {}: {}\n", device.alias(), e)
+            }
+            Err(e) => return Err(e.into()),
+        };
+        w.write_all(result.as_bytes())?;
+    }
+
+    if args.summary {
+        let summary = format::format_summary("removed", ok, failed, args.json);
+        w.write_all(summary.as_bytes())?;
+
+        if failed > 0 {
+            return Err(Error::BatchFailed(failed));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn mixed_devices() -> Vec<bluez::BluezDevice> {
+        vec![
+            bluez::BluezTestDeviceBuilder::new("stale")
+                .address("AA:AA:AA:AA:AA:AA")
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("advertising")
+                .address("BB:BB:BB:BB:BB:BB")
+                .rssi(Some(-60))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("stale")
+                .address("CC:CC:CC:CC:CC:CC")
+                .paired(true)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("another stale")
+                .address("DD:DD:DD:DD:DD:DD")
+                .build(),
+        ]
+    }
+
+    fn yes_args() -> PruneArgs {
+        PruneArgs {
+            yes: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn it_should_remove_only_stale_devices() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(mixed_devices());
+
+        let mut in_buf = Cursor::new(vec![]);
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = prune(&bluez, &mut out_buf, &mut in_buf, &yes_args());
+        assert!(result.is_ok());
+
+        assert_eq!(
+            bluez.removed_aliases(),
+            vec!["AA:AA:AA:AA:AA:AA", "DD:DD:DD:DD:DD:DD"]
+        );
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(
+            result,
+            "removed device stale\nremoved device another stale\n"
+        );
+    }
+
+    #[test]
+    fn it_should_ask_for_confirmation_before_removing() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(mixed_devices());
+
+        let mut in_buf = Cursor::new("n\n".as_bytes().to_vec());
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = prune(&bluez, &mut out_buf, &mut in_buf, &PruneArgs::default());
+        assert!(result.is_ok());
+        assert!(bluez.removed_aliases().is_empty());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert!(result.contains("another stale"));
+        assert!(!result.contains("advertising"));
+        assert!(result.ends_with("prune cancelled\n"));
+
+        let mut in_buf = Cursor::new("Yes\n".as_bytes().to_vec());
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = prune(&bluez, &mut out_buf, &mut in_buf, &PruneArgs::default());
+        assert!(result.is_ok());
+        assert_eq!(
+            bluez.removed_aliases(),
+            vec!["AA:AA:AA:AA:AA:AA", "DD:DD:DD:DD:DD:DD"]
+        );
+    }
+
+    #[test]
+    fn it_should_not_prompt_if_there_are_no_stale_devices() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("advertising")
+                .rssi(Some(-60))
+                .build(),
+        ]);
+
+        let mut in_buf = Cursor::new(vec![]);
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = prune(&bluez, &mut out_buf, &mut in_buf, &PruneArgs::default());
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "there are no stale devices to prune\n");
+    }
+
+    #[test]
+    fn it_should_fail_if_a_device_cannot_be_removed() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(mixed_devices());
+        bluez.set_erred_method_name("remove".to_string());
+
+        let mut in_buf = Cursor::new(vec![]);
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = prune(&bluez, &mut out_buf, &mut in_buf, &yes_args());
+
+        assert!(matches!(result, Err(Error::Bluez(_))));
+    }
+
+    #[test]
+    fn it_should_count_the_outcomes_if_summary_is_requested() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(mixed_devices());
+        bluez.set_erred_alias(String::from("AA:AA:AA:AA:AA:AA"));
+
+        for (json, expected) in [
+            (false, "removed=1 failed=1\n"),
+            (true, "{\"ok\":1,\"failed\":1}\n"),
+        ] {
+            let mut in_buf = Cursor::new(vec![]);
+            let mut out_buf = Cursor::new(vec![]);
+
+            let args = PruneArgs {
+                summary: true,
+                json,
+                ..yes_args()
+            };
+            let result = prune(&bluez, &mut out_buf, &mut in_buf, &args);
+            assert!(matches!(result, Err(Error::BatchFailed(1))));
+
+            let result = String::from_utf8(out_buf.into_inner()).unwrap();
+            assert!(result.starts_with("failed to remove device stale: "));
+            assert!(result.ends_with(&format!("removed device another stale\n{}", expected)));
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use std::{error, fmt, io, thread, time::Duration};
+
+use clap::Args;
+
+use crate::BluezError;
+
+/// Defines error variants that may be returned from an [`rssi`] call.
+///
+/// [`rssi`]: crate::rssi
+#[derive(Debug)]
+pub enum Error {
+    /// Happens when the [`BluezClient`] fails during an [`rssi`] call, e.g. when the device cannot be found.
+    /// It holds the underlying [`BluezError`].
+    ///
+    /// [`BluezError`]: crate::BluezError
+    /// [`BluezClient`]: crate::BluezClient
+    /// [`rssi`]: crate::rssi
+    Bluez(BluezError),
+
+    /// Happens when the RSSI could not be written to the given buffer.
+    /// It holds the underlying [`io::Error`].
+    ///
+    /// [`io::Error`]: std::io::Error
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Bluez(error) => write!(f, "rssi: bluez error: {}", error),
+            Error::Io(error) => write!(f, "rssi: io error: {}", error),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<BluezError> for Error {
+    fn from(value: BluezError) -> Self {
+        Self::Bluez(value)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/// Defines the arguments that [`rssi`] can take.
+///
+/// [`rssi`]: crate::rssi
+#[derive(Debug, Args)]
+pub struct RssiArgs {
+    /// The full ALIAS of the device.
+    #[arg(value_name = "ALIAS")]
+    pub alias: String,
+
+    /// Keep reading the RSSI on every interval until interrupted.
+    #[arg(short, long, default_value_t = false)]
+    pub watch: bool,
+
+    /// Set the interval between the reads in seconds.
+    #[arg(short, long, default_value_t = 1u64)]
+    pub interval: u64,
+
+    /// Stop watching after COUNT reads.
+    ///
+    /// This option has no effect without --watch.
+    #[arg(short, long, value_name = "COUNT")]
+    pub count: Option<u32>,
+}
+
+impl Default for RssiArgs {
+    fn default() -> Self {
+        Self {
+            alias: String::new(),
+            watch: false,
+            interval: 1,
+            count: None,
+        }
+    }
+}
+
+/// Provides the live signal strength (RSSI) of a single device by using a [`BluezClient`].
+///
+/// Since the RSSI values are only updated during a device discovery, [`rssi`] starts a discovery first and reads the RSSI after `args.interval` seconds.
+/// Each read is written to the provided [`io::Write`] on its own line. If the device does not emit Bluetooth signals, `-` is written instead.
+///
+/// If `args.watch` is `true`, then [`rssi`] keeps reading the RSSI on every `args.interval` seconds, which makes it easy to locate a device by watching its RSSI rise while getting closer.
+/// The watch stops after `args.count` reads, or never if `args.count` is [`None`].
+///
+/// [`rssi`] is a blocking call.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function can return all variants of [`RssiError`] based on given conditions. For more details, please see the error documentation.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+/// use bt::{rssi, BluezClient, RssiArgs};
+///
+/// let bluez_client = BluezClient::new().unwrap();
+/// let mut output = io::stdout();
+///
+/// let args = RssiArgs {
+///     alias: "lost_dev".to_string(),
+///     watch: true,
+///     ..Default::default()
+/// };
+///
+/// // Writes the RSSI of "lost_dev" every second until interrupted.
+/// if let Err(e) = rssi(&bluez_client, &mut output, &args) {
+///     eprintln!("{}", e);
+/// }
+/// ```
+///
+/// [`BluezClient`]: crate::BluezClient
+/// [`io::Write`]: std::io::Write
+/// [`None`]: std::option::Option::None
+/// [`RssiError`]: crate::RssiError
+/// [`rssi`]: crate::rssi
+pub fn rssi(
+    bluez: &crate::BluezClient,
+    f: &mut impl io::Write,
+    args: &RssiArgs,
+) -> Result<(), Error> {
+    let max_reads = if args.watch { args.count } else { Some(1) };
+
+    poll_rssi(bluez, &args.alias, args.interval, max_reads, |rssi| {
+        let out_buf = match rssi {
+            Some(rssi) => format!("{}\n", rssi),
+            None => String::from("-\n"),
+        };
+        f.write_all(out_buf.as_bytes())?;
+        f.flush()?;
+
+        Ok::<(), Error>(())
+    })
+}
+
+/// Starts a device discovery, and passes the RSSI of the device with `alias` to `on_read` every `interval` seconds, until `count` reads are done. If `count` is [`None`], then it keeps reading until interrupted.
+///
+/// The discovery is stopped on every path. If a read (or `on_read`) fails, then a failure to stop the discovery is ignored, so that it does not hide the failure of the read.
+///
+/// [`None`]: std::option::Option::None
+pub(crate) fn poll_rssi<E>(
+    bluez: &crate::BluezClient,
+    alias: &str,
+    interval: u64,
+    count: Option<u32>,
+    mut on_read: impl FnMut(Option<i16>) -> Result<(), E>,
+) -> Result<(), E>
+where
+    E: From<BluezError>,
+{
+    bluez.start_discovery()?;
+
+    let mut reads = 0;
+    let result = loop {
+        thread::sleep(Duration::from_secs(interval));
+
+        if let Err(e) = bluez.rssi(alias).map_err(E::from).and_then(&mut on_read) {
+            break Err(e);
+        }
+
+        reads += 1;
+        if count.is_some_and(|count| reads >= count) {
+            break Ok(());
+        }
+    };
+
+    match result {
+        Ok(()) => Ok(bluez.stop_discovery()?),
+        Err(e) => {
+            let _ = bluez.stop_discovery();
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use io::Cursor;
+
+    use super::*;
+    use crate::bluez;
+
+    fn test_bluez() -> crate::BluezClient {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("near")
+                .rssi(Some(-42))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("silent").build(),
+        ]);
+
+        bluez
+    }
+
+    #[test]
+    fn it_should_write_the_rssi_of_a_device() {
+        let bluez = test_bluez();
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = RssiArgs {
+            alias: "near".to_string(),
+            interval: 0,
+            ..Default::default()
+        };
+
+        let result = rssi(&bluez, &mut out_buf, &args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "-42\n");
+    }
+
+    #[test]
+    fn it_should_write_a_dash_if_the_device_has_no_signal() {
+        let bluez = test_bluez();
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = RssiArgs {
+            alias: "silent".to_string(),
+            interval: 0,
+            ..Default::default()
+        };
+
+        let result = rssi(&bluez, &mut out_buf, &args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "-\n");
+    }
+
+    #[test]
+    fn it_should_keep_reading_in_watch_mode() {
+        let bluez = test_bluez();
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = RssiArgs {
+            alias: "near".to_string(),
+            watch: true,
+            interval: 0,
+            count: Some(3),
+        };
+
+        let result = rssi(&bluez, &mut out_buf, &args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "-42\n-42\n-42\n");
+    }
+
+    #[test]
+    fn it_should_fail_if_the_device_cannot_be_found() {
+        let bluez = test_bluez();
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = RssiArgs {
+            alias: "unknown".to_string(),
+            interval: 0,
+            ..Default::default()
+        };
+
+        let result = rssi(&bluez, &mut out_buf, &args);
+
+        assert!(matches!(result, Err(Error::Bluez(_))));
+        assert!(out_buf.into_inner().is_empty());
+        assert_eq!(
+            bluez.discovery_calls(),
+            vec!["start_discovery", "stop_discovery"]
+        );
+    }
+}
@@ -0,0 +1,1799 @@
+mod snapshot;
+
+use core::fmt;
+use std::{
+    collections::{HashMap, HashSet},
+    error, fs, io,
+    path::PathBuf,
+    thread,
+    time::Duration,
+};
+
+use clap::Args;
+
+use crate::{
+    BluezError,
+    bluez::{self, BluezPowerState},
+    format::{
+        DeviceField, DeviceRow, DeviceTable, ListingLayout, SortKey, Template, TemplateError,
+        resolve_columns, sort_by_keys,
+    },
+    power::{self, PowerCheck},
+    select,
+};
+
+pub use snapshot::{ScanDiff, ScanSnapshot};
+
+/// Defines error variants that may be returned from a [`scan`] call.
+///
+/// [`scan`]: crate::scan
+#[derive(Debug)]
+pub enum Error {
+    /// Happens when the [`BluezClient`] fails during a [`scan`] call.
+    /// It holds the underlying [`BluezError`].
+    ///
+    /// [`BluezError`]: crate::BluezError
+    /// [`BluezClient`]: crate::BluezClient
+    Bluez(BluezError),
+
+    /// Happens when the result of [`scan`] could not be written to the given buffer, or to the output file.
+    /// It holds the underlying [`io::Error`].
+    ///
+    /// [`scan`]: crate::scan
+    /// [`io::Error`]: std::io::Error
+    Io(io::Error),
+
+    /// Happens when the provided `args.template` cannot be parsed.
+    ///
+    /// It holds the underlying [`TemplateError`].
+    ///
+    /// [`TemplateError`]: crate::TemplateError
+    Template(TemplateError),
+
+    /// Happens when `args.strict` is `true`, and some of the devices could not be fully read from Bluez D-Bus.
+    ///
+    /// It holds the number of devices that could not be read.
+    UnreadDevices(usize),
+
+    /// Happens when the adapter is off, and `args.auto_power` is `false`.
+    PoweredOff,
+
+    /// Happens when the adapter is off because Bluetooth is blocked by an rfkill switch.
+    Blocked,
+
+    /// Happens when `args.cache` is `true`, but the default location of the cached scan cannot be determined.
+    ///
+    /// It holds the reason.
+    Cache(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Bluez(error) => write!(f, "scan: bluez error: {}", error),
+            Error::Io(error) => write!(f, "scan: io error: {}", error),
+            Error::Template(error) => write!(f, "scan: {}", error),
+            Error::UnreadDevices(unread) => {
+                write!(f, "scan: {} device(s) could not be read", unread)
+            }
+            Error::PoweredOff => write!(f, "scan: bluetooth is off; run 'bt toggle --on'"),
+            Error::Blocked => write!(f, "scan: {}", BluezError::Blocked),
+            Error::Cache(reason) => write!(f, "scan: cache error: {}", reason),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<BluezError> for Error {
+    fn from(value: BluezError) -> Self {
+        Self::Bluez(value)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<TemplateError> for Error {
+    fn from(value: TemplateError) -> Self {
+        Self::Template(value)
+    }
+}
+
+/// Defines the arguments that [`scan`] can take.
+///
+/// [`scan`]: crate::scan
+#[derive(Debug, Args)]
+pub struct ScanArgs {
+    /// Set the duration of the scan.
+    #[arg(short, long, default_value_t = 5u8)]
+    pub duration: u8,
+
+    /// Filter the pretty output based on given columns.
+    ///
+    /// If no columns are provided, then the full pretty output is shown to the user.
+    #[arg(short, long, value_delimiter = ',', num_args = 0.., default_value = None)]
+    pub columns: Option<Vec<DeviceField>>,
+
+    /// Filter the terse output based on given columns.
+    ///
+    /// If no columns are provided, then the full terse output is shown to the user.
+    #[arg(short, long, value_delimiter = ',', num_args = 0.., default_value = None)]
+    pub values: Option<Vec<DeviceField>>,
+
+    /// Show the output as compact JSON.
+    #[arg(long)]
+    pub json: bool,
+
+    /// Show the output as indented, multi-line JSON.
+    #[arg(long)]
+    pub json_pretty: bool,
+
+    /// Show whether the scanned devices are already known to the host.
+    ///
+    /// It adds the KNOWN column to the output. A device is known if it is paired or bonded.
+    #[arg(long)]
+    pub known: bool,
+
+    /// Format each device with a TEMPLATE, where each {column} placeholder is replaced by the value of that column.
+    ///
+    /// Missing values are rendered empty.
+    #[arg(long, value_name = "TEMPLATE")]
+    pub template: Option<String>,
+
+    /// Sort the devices by COLUMN.
+    #[arg(long, value_name = "COLUMN")]
+    pub sort: Option<DeviceField>,
+
+    /// Reverse the order of --sort.
+    #[arg(long, requires = "sort")]
+    pub reverse: bool,
+
+    /// Order the devices that are equal by --sort by COLUMN.
+    #[arg(long, value_name = "COLUMN", requires = "sort")]
+    pub then: Option<DeviceField>,
+
+    /// Reverse the order of --then, independently of --reverse.
+    #[arg(long, requires = "then")]
+    pub reverse_then: bool,
+
+    /// Terminate the records of the terse output with NUL bytes instead of newlines.
+    ///
+    /// This is meant to be used with `xargs -0`. Every record is terminated, so the output never ends with a newline.
+    /// It has no effect on the pretty and JSON outputs.
+    #[arg(long)]
+    pub print0: bool,
+
+    /// Write the results to the file at PATH instead, and only report the number of devices.
+    ///
+    /// The file is created if it does not exist, and truncated if it does.
+    #[arg(long, value_name = "PATH")]
+    pub output_file: Option<PathBuf>,
+
+    /// Fail if any device cannot be fully read, instead of showing the devices that can be read.
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Only show the devices that contain the name <CONTAINS_NAME>.
+    #[arg(long)]
+    pub contains_name: Option<String>,
+
+    /// Hide the devices that contain the name <EXCLUDE_NAME>.
+    ///
+    /// It is applied after --contains-name.
+    #[arg(long)]
+    pub exclude_name: Option<String>,
+
+    /// Power on the adapter before scanning if it is off, instead of failing.
+    #[arg(long)]
+    pub auto_power: bool,
+
+    /// Power off the adapter again after scanning, if it is powered on by --auto-power.
+    #[arg(long, requires = "auto_power")]
+    pub auto_power_restore: bool,
+
+    /// Start (and stop) the discovery even if another program is already discovering.
+    #[arg(long)]
+    pub force_discovery: bool,
+
+    /// Cache the scanned devices, so that `bt connect --index IDX` can connect to one of them later without scanning again.
+    ///
+    /// IDX is the position of the device in the output, starting from 0. The cache is kept in `$XDG_STATE_HOME/bt/last-scan.json`, unless --cache-file is set.
+    #[arg(long)]
+    pub cache: bool,
+
+    /// Keep the cached scan of --cache in the file at PATH instead.
+    #[arg(long, value_name = "PATH", requires = "cache")]
+    pub cache_file: Option<PathBuf>,
+
+    /// Run the discovery again, up to N times (default 1), if no devices are found, e.g. on an adapter whose first discovery after powering on finds nothing.
+    #[arg(
+        long,
+        value_name = "N",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "1"
+    )]
+    pub retry_if_empty: Option<u8>,
+
+    /// Show the JSON output as an object that maps the KEY of each device to its properties, instead of an array.
+    ///
+    /// If KEY is repeated, then the last device wins. This option has no effect without --json or --json-pretty.
+    #[arg(long, value_name = "KEY", value_enum)]
+    pub key: Option<ScanKey>,
+}
+
+impl Default for ScanArgs {
+    fn default() -> Self {
+        Self {
+            duration: 5,
+            columns: None,
+            values: None,
+            json: false,
+            json_pretty: false,
+            known: false,
+            template: None,
+            sort: None,
+            reverse: false,
+            then: None,
+            reverse_then: false,
+            print0: false,
+            output_file: None,
+            strict: false,
+            contains_name: None,
+            exclude_name: None,
+            auto_power: false,
+            auto_power_restore: false,
+            force_discovery: false,
+            cache: false,
+            cache_file: None,
+            retry_if_empty: None,
+            key: None,
+        }
+    }
+}
+
+/// Defines the columns that are used to filter the output of [`scan`], which are the shared [`DeviceField`]'s.
+///
+/// [`scan`]: crate::scan
+/// [`DeviceField`]: crate::DeviceField
+pub type ScanColumn = DeviceField;
+
+/// Defines the fields that can key the JSON output of [`scan`], e.g. to build a lookup table in a script.
+///
+/// [`scan`]: crate::scan
+#[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
+pub enum ScanKey {
+    /// Key the devices by their MAC address.
+    Address,
+
+    /// Key the devices by their alias.
+    Alias,
+}
+
+impl From<ScanKey> for DeviceField {
+    fn from(value: ScanKey) -> Self {
+        match value {
+            ScanKey::Address => DeviceField::Address,
+            ScanKey::Alias => DeviceField::Alias,
+        }
+    }
+}
+
+const DEFAULT_LISTING_COLUMNS: [DeviceField; 3] =
+    [DeviceField::Alias, DeviceField::Address, DeviceField::Rssi];
+
+/// Defines a device provided by [`scan_data`], along with whether it is known by the host.
+///
+/// [`scan_data`]: crate::scan_data
+#[derive(Debug, Clone)]
+pub struct ScannedDevice {
+    device: bluez::BluezDevice,
+    known: bool,
+}
+impl ScannedDevice {
+    /// Provides the scanned [`BluezDevice`].
+    ///
+    /// [`BluezDevice`]: crate::BluezDevice
+    pub fn device(&self) -> &bluez::BluezDevice {
+        &self.device
+    }
+
+    /// Indicates whether the device is already paired or bonded on the host.
+    ///
+    /// It is only read if the known devices are needed by the [`ScanArgs`], e.g. if `args.known` is `true`. Otherwise, it is always `false`.
+    ///
+    /// [`ScanArgs`]: crate::ScanArgs
+    pub fn known(&self) -> bool {
+        self.known
+    }
+}
+
+enum ScanOutput {
+    Pretty,
+    Terse,
+    Template(Template<DeviceField>),
+    Json,
+    JsonPretty,
+}
+
+/// Provides the ability of scanning available devices by using a [`BluezClient`].
+///
+/// The list is written to the provided [`io::Write`].
+///
+/// The format of the list depends on the arguments passed:
+///
+/// - If `args.columns` are [`Some`], then [`scan`] uses the pretty formatting, which is a table.
+/// - If `args.values` are [`Some`], then [`scan`] uses the terse formatting, which is a listing where each property of the scanned devices are concatenated by the delimiter `/`.
+/// - If both `args.columns` and `args.values` are [`Some`], then [`scan`] uses the pretty formatting.
+/// - If both `args.columns` and `args.values` are [`None`], then [`scan`] uses the pretty formatting with the default columns `ALIAS, ADDRESS, RSSI`.
+/// - If `args.json` is `true`, then [`scan`] uses compact JSON, which is an array of objects keyed by the lowercase column names.
+/// - If `args.json_pretty` is `true`, then [`scan`] uses the same JSON with indentation. It takes precedence over `args.json`.
+/// - If `args.key` is [`Some`] along with `args.json` or `args.json_pretty`, then the JSON is an object that maps the [`ScanKey`] of each device to its object instead, where the last device wins if a key is repeated.
+///
+/// The columns are [`DeviceField`]'s, which are shared by the commands that list devices. Any of them can be shown, such as `BATTERY` or `STATE`.
+///
+/// If `args.print0` is `true`, then each record of the terse formatting is terminated by a NUL byte instead of a newline, including the last one.
+///
+/// If `args.template` is [`Some`], then [`scan`] formats each device with the template instead, which is a string where each `{column}` placeholder (e.g. `{alias}`) is replaced by the value of that column. Missing values are rendered empty. The template takes precedence over `args.columns` and `args.values`, and JSON takes precedence over the template.
+///
+/// If `args.output_file` is [`Some`], then the formatted output is written to that file instead, which is created or truncated. In that case, only the number of written devices is written to the provided [`io::Write`], e.g. `wrote 3 device(s) to scan.txt`.
+///
+/// If `args.sort` is [`Some`], then the devices are sorted by that column, in reverse if `args.reverse` is `true`. The devices that are equal by `args.sort` are then ordered by `args.then`, which has its own `args.reverse_then`. Devices that are equal by both keys keep the order provided by [`BluezClient`].
+///
+/// In JSON formatting, the columns are still taken from `args.columns` or `args.values`. JSON is never colored.
+///
+/// Here is how pretty formatting looks like:
+///
+/// ```txt
+/// ALIAS   ADDRESS             RSSI
+/// Dev1    XX:XX:XX:XX:XX:XX   -68
+/// Dev2    XX:XX:XX:XX:XX:XX   -94
+/// Dev3    XX:XX:XX:XX:XX:XX   -93
+/// ```
+///
+/// Here is how terse formatting looks like:
+///
+/// ```txt
+/// Dev1/XX:XX:XX:XX:XX:XX/-92
+/// Dev2/XX:XX:XX:XX:XX:XX/-97
+/// Dev3/XX:XX:XX:XX:XX:XX/-94
+/// ```
+///
+/// The scanned devices can be filtered by their ALIAS. If `args.contains_name` is [`Some`], then only the devices whose ALIAS contains it are written. If `args.exclude_name` is [`Some`], then the devices whose ALIAS contains it are hidden, after `args.contains_name` is applied. Both are simple substrings, not patterns.
+///
+/// If `args.known` is `true`, then the `KNOWN` column is added to the output. It shows whether a scanned device is already paired or bonded on the host, which requires [`scan`] to read the known devices as well.
+///
+/// The scan duration can be adjusted by providing `args.duration` of [`ScanArgs`].
+/// Setting `args.duration` to 0 is not recommended since a certain amount of time needs to be passed to discover available devices.
+///
+/// [`scan`] is a blocking call. It blocks the current thread by `args.duration` seconds.
+///
+/// The scan is the regular device discovery of Bluez, which scans actively, i.e. the scan responses of the devices (such as their full names) are requested as well. Bluez does not provide a passive discovery through its D-Bus API.
+///
+/// The scanned devices are gathered by [`scan_data`], which provides them without any formatting.
+///
+/// The devices that cannot be fully read from Bluez D-Bus are skipped, and their count is written as a warning to the provided `warn` [`io::Write`]. If `args.strict` is `true`, then [`scan`] fails with [`ScanError::UnreadDevices`] instead, without writing any device.
+///
+/// Before the scan starts, [`scan`] checks whether the adapter is powered on. If it is off, then [`scan`] fails with [`ScanError::PoweredOff`], unless `args.auto_power` is `true`, in which case the adapter is powered on first. If `args.auto_power_restore` is also `true`, then the adapter is powered off again once the scan is done, even if the scan fails. Then, a failure to power off the adapter is only written to `warn` as a warning, and the error of the scan is returned. A blocked adapter is never powered on, and [`scan`] fails with [`ScanError::Blocked`] instead.
+///
+/// If `args.retry_if_empty` is [`Some`], and no devices are found, then the discovery is stopped and run again for `args.duration` seconds, up to that many times, before [`scan`] writes the empty result. This helps with the adapters whose first discovery after powering on finds nothing.
+///
+/// If `args.cache` is `true`, then the scanned devices are also cached in the order they are written, so that [`connect`] can connect to one of them by its index later, without scanning again. Please see [`ScanSnapshot::cache_path()`] for the default location, which `args.cache_file` overrides.
+///
+/// If another program (e.g. a GUI) is already discovering on the adapter, then [`scan`] neither starts nor stops the discovery, and only writes the devices scanned during `args.duration`, so that the discovery of the other program is left intact. If `args.force_discovery` is `true`, then the discovery is started and stopped regardless.
+///
+/// The device discovery is stopped after the scanned devices are written. If stopping the discovery fails at that point, the scanned devices are already written, so [`scan`] does not fail. Instead, a warning is written to the provided `warn` [`io::Write`], which is usually stderr.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function can return all variants of [`ScanError`] based on given conditions. For more details, please see the error documentation.
+///
+/// # Examples
+///
+/// Here is a basic [`scan`] call that will use pretty formatting.
+///
+/// ```no_run
+/// use std::io::Cursor;
+/// use bt::{scan, BluezClient, ScanArgs};
+///
+/// let bluez_client = BluezClient::new().unwrap();
+/// let mut warnings = std::io::stderr();
+/// let mut output = Cursor::new(vec![]);
+///
+/// let args = ScanArgs {
+///     duration: 5,
+///     columns: None,
+///     values: None,
+///     ..Default::default()
+/// };
+///
+/// let scan_result = scan(&bluez_client, &mut output, &mut warnings, &args);
+/// match scan_result {
+///     Ok(_) => {
+///          let pretty_out = String::from_utf8(output.into_inner()).unwrap();
+///          println!("{}", pretty_out);
+///     },
+///     Err(e) => eprintln!("scan error: {}", e)
+/// }
+///```
+///
+/// Here is an example to showcase how to filter the scan output. The same filtering can be used for terse formatting by using `args.values` instead.
+///
+///```no_run
+/// use std::io::Cursor;
+/// use bt::{scan, BluezClient, DeviceField, ScanArgs};
+///
+/// let bluez_client = BluezClient::new().unwrap();
+/// let mut warnings = std::io::stderr();
+/// let mut output = Cursor::new(vec![]);
+///
+/// // The address column is stripped out from the output.
+/// let args = ScanArgs {
+///     duration: 5,
+///     columns: Some(vec![DeviceField::Alias, DeviceField::Rssi]),
+///     values: None,
+///     ..Default::default()
+/// };
+///
+/// let scan_result = scan(&bluez_client, &mut output, &mut warnings, &args);
+/// match scan_result {
+///     Ok(_) => {
+///          let pretty_out = String::from_utf8(output.into_inner()).unwrap();
+///          println!("{}", pretty_out);
+///     },
+///     Err(e) => eprintln!("scan error: {}", e)
+/// }
+/// ```
+///
+/// Here is an error case. The example triggers an [`io::Error`] by passing an array as a buffer, instead of a growable buffer.
+///
+/// ```no_run
+/// use std::io::Cursor;
+/// use bt::{scan, BluezClient, ScanArgs, ScanError};
+///
+/// let bluez_client = BluezClient::new().unwrap();
+/// let mut warnings = std::io::stderr();
+/// let mut output = Cursor::new([]);
+///
+/// let args = ScanArgs {
+///     duration: 5,
+///     columns: None,
+///     values: None,
+///     ..Default::default()
+/// };
+///
+/// let scan_result = scan(&bluez_client, &mut output, &mut warnings, &args);
+///
+/// match scan_result {
+///     Err(ScanError::Io(err)) => eprintln!("{}", err),
+///     _ => unreachable!(),
+/// }
+///```
+///
+/// [`BluezClient`]: crate::BluezClient
+/// [`io::Write`]: std::io::Write
+/// [`Some`]: std::option::Option::Some
+/// [`None`]: std::option::Option::None
+/// [`ScanError`]: crate::ScanError
+/// [`ScanError::UnreadDevices`]: crate::ScanError::UnreadDevices
+/// [`ScanError::PoweredOff`]: crate::ScanError::PoweredOff
+/// [`ScanError::Blocked`]: crate::ScanError::Blocked
+/// [`scan`]: crate::scan
+/// [`ScanArgs`]: crate::ScanArgs
+/// [`DeviceField`]: crate::DeviceField
+/// [`ScanKey`]: crate::ScanKey
+/// [`scan_data`]: crate::scan_data
+/// [`connect`]: crate::connect
+/// [`ScanSnapshot::cache_path()`]: crate::ScanSnapshot::cache_path()
+pub fn scan(
+    bluez: &crate::BluezClient,
+    f: &mut impl io::Write,
+    warn: &mut impl io::Write,
+    args: &ScanArgs,
+) -> Result<(), Error> {
+    let (layout, mut listing_keys) = resolve_columns(
+        args.columns.as_deref(),
+        args.values.as_deref(),
+        &DEFAULT_LISTING_COLUMNS,
+    );
+    let mut out_format = match layout {
+        ListingLayout::Pretty => ScanOutput::Pretty,
+        ListingLayout::Terse => ScanOutput::Terse,
+    };
+
+    if args.json_pretty {
+        out_format = ScanOutput::JsonPretty;
+    } else if args.json {
+        out_format = ScanOutput::Json;
+    } else if let Some(template) = &args.template {
+        out_format = ScanOutput::Template(Template::parse(template)?);
+    }
+
+    if args.known && !listing_keys.iter().any(|k| matches!(k, DeviceField::Known)) {
+        listing_keys.push(DeviceField::Known);
+    }
+
+    let did_power_on = match power::ensure_powered(bluez, args.auto_power)? {
+        PowerCheck::On => false,
+        PowerCheck::PoweredOn => true,
+        PowerCheck::Unpowered(BluezPowerState::Blocked) => return Err(Error::Blocked),
+        PowerCheck::Unpowered(_) => return Err(Error::PoweredOff),
+    };
+
+    let result = scan_and_write(bluez, f, warn, args, out_format, &listing_keys);
+
+    // NOTE: A failure to power off the adapter must not hide why the scan failed.
+    if did_power_on
+        && args.auto_power_restore
+        && let Err(e) = bluez.set_powered(BluezPowerState::Off)
+    {
+        match result {
+            Ok(_) => return Err(e.into()),
+            Err(_) => writeln!(
+                warn,
+                "scan: warning: could not power off the adapter: {}",
+                e
+            )?,
+        }
+    }
+
+    result
+}
+
+/// Runs the discovery for `args.duration` seconds, and writes the scanned devices in `out_format`.
+///
+/// The discovery started by [`scan_and_write`] is stopped on every path, including the failures.
+fn scan_and_write(
+    bluez: &crate::BluezClient,
+    f: &mut impl io::Write,
+    warn: &mut impl io::Write,
+    args: &ScanArgs,
+    out_format: ScanOutput,
+    listing_keys: &[DeviceField],
+) -> Result<(), Error> {
+    // NOTE: Stopping a discovery that is started by another program (e.g.
+    // a GUI) would stop it for that program too.
+    let is_external = !args.force_discovery && bluez.is_discovering()?;
+
+    let mut is_discovering = false;
+    let result = discover_and_write(
+        bluez,
+        f,
+        warn,
+        args,
+        out_format,
+        listing_keys,
+        (!is_external).then_some(&mut is_discovering),
+    );
+
+    if is_discovering && let Err(e) = bluez.stop_discovery() {
+        writeln!(warn, "scan: warning: could not stop the discovery: {}", e)?;
+    }
+
+    result
+}
+
+/// Writes the scanned devices of [`scan_and_write`].
+///
+/// If `is_discovering` is [`Some`], then the discovery is started (and restarted on each retry) here, and `is_discovering` tells whether it is still running once this returns. Otherwise, the discovery is run by another program.
+fn discover_and_write(
+    bluez: &crate::BluezClient,
+    f: &mut impl io::Write,
+    warn: &mut impl io::Write,
+    args: &ScanArgs,
+    out_format: ScanOutput,
+    listing_keys: &[DeviceField],
+    mut is_discovering: Option<&mut bool>,
+) -> Result<(), Error> {
+    let mut retries = args.retry_if_empty.unwrap_or(0);
+    let (scanned_devices, unread) = loop {
+        if let Some(is_discovering) = is_discovering.as_deref_mut() {
+            bluez.start_discovery()?;
+            *is_discovering = true;
+        }
+        thread::sleep(Duration::from_secs(u64::from(args.duration)));
+
+        let (scanned_devices, unread) = scan_data(bluez, args)?;
+        if !scanned_devices.is_empty() || retries == 0 {
+            break (scanned_devices, unread);
+        }
+
+        // NOTE: Each retry restarts the discovery, since continuing the
+        // session that found nothing does not help a flaky adapter.
+        retries -= 1;
+        if let Some(is_discovering) = is_discovering.as_deref_mut() {
+            bluez.stop_discovery()?;
+            *is_discovering = false;
+        }
+    };
+    if unread > 0 {
+        writeln!(
+            warn,
+            "scan: warning: {} device(s) could not be read",
+            unread
+        )?;
+    }
+
+    let table = DeviceTable::new(
+        listing_keys,
+        scanned_devices.iter().map(|scanned| DeviceRow {
+            device: &scanned.device,
+            known: scanned.known,
+        }),
+    );
+
+    let device_count = table.len();
+    let terminator = if args.print0 { '\0' } else { '\n' };
+    let out_buf = match out_format {
+        ScanOutput::Pretty => table.to_pretty(),
+        ScanOutput::Terse => table.to_terse("/", terminator),
+        ScanOutput::Template(template) => table.to_template(&template, terminator),
+        ScanOutput::Json => match args.key {
+            Some(key) => table.to_keyed_json(&key.into(), false),
+            None => table.to_json(false),
+        },
+        ScanOutput::JsonPretty => match args.key {
+            Some(key) => table.to_keyed_json(&key.into(), true),
+            None => table.to_json(true),
+        },
+    };
+
+    match &args.output_file {
+        Some(path) => {
+            fs::write(path, out_buf)?;
+            writeln!(f, "wrote {} device(s) to {}", device_count, path.display())?;
+        }
+        None => f.write_all(out_buf.as_bytes())?,
+    }
+
+    if args.cache {
+        let path = args
+            .cache_file
+            .clone()
+            .or_else(ScanSnapshot::cache_path)
+            .ok_or_else(|| {
+                Error::Cache(String::from("neither $XDG_STATE_HOME nor $HOME is set"))
+            })?;
+        ScanSnapshot::new(scanned_devices.into_iter().map(|s| s.device).collect()).save(&path)?;
+    }
+
+    Ok(())
+}
+
+/// Provides the devices that [`scan`] would write, without formatting them, e.g. to embed the scan into a GUI.
+///
+/// The devices are read from a device discovery that is already started by [`BluezClient.start_discovery()`]. Unlike [`scan`], it does not start or stop the discovery, and it does not check whether the adapter is powered on.
+///
+/// Bluez can report the same device more than once during a discovery, e.g. once per adapter path. The devices are de-duplicated by their ADDRESS (case-insensitive), and only the entry with the strongest RSSI is kept, in the position of the first entry.
+///
+/// The devices are filtered by `args.contains_name` and `args.exclude_name`, and sorted by `args.sort` and `args.then` the same way as [`scan`]. The formatting arguments, such as `args.columns` or `args.json`, are ignored, except that the known devices are only read if `args.known` is `true`, or the `KNOWN` column is used by `args.columns`, `args.values`, `args.sort` or `args.then`. Please see [`ScannedDevice`] for more details.
+///
+/// Along with the devices, the number of devices that cannot be fully read from Bluez D-Bus is provided. If `args.strict` is `true` and there are such devices, then it fails with [`ScanError::UnreadDevices`] instead.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function can return [`ScanError::Bluez`] and [`ScanError::UnreadDevices`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::{thread, time::Duration};
+/// use bt::{scan_data, BluezClient, ScanArgs};
+///
+/// let bluez_client = BluezClient::new().unwrap();
+///
+/// bluez_client.start_discovery().unwrap();
+/// thread::sleep(Duration::from_secs(5));
+///
+/// let (devices, _) = scan_data(&bluez_client, &ScanArgs::default()).unwrap();
+/// for scanned in devices {
+///     println!("{} {:?}", scanned.device().alias(), scanned.device().rssi());
+/// }
+///
+/// bluez_client.stop_discovery().unwrap();
+/// ```
+///
+/// [`BluezClient.start_discovery()`]: crate::BluezClient::start_discovery()
+/// [`ScannedDevice`]: crate::ScannedDevice
+/// [`ScanError::Bluez`]: crate::ScanError::Bluez
+/// [`ScanError::UnreadDevices`]: crate::ScanError::UnreadDevices
+/// [`scan`]: crate::scan
+pub fn scan_data(
+    bluez: &crate::BluezClient,
+    args: &ScanArgs,
+) -> Result<(Vec<ScannedDevice>, usize), Error> {
+    let (scanned_devices, unread) = bluez.scanned_devices_with_unread()?;
+    if unread > 0 && args.strict {
+        return Err(Error::UnreadDevices(unread));
+    }
+    let scanned_devices = dedup_by_address(scanned_devices);
+
+    let known_addresses = if reads_known(args) {
+        bluez
+            .devices()?
+            .into_iter()
+            .filter(|d| d.paired() || d.bonded())
+            .map(|d| d.address().to_string())
+            .collect::<HashSet<String>>()
+    } else {
+        HashSet::new()
+    };
+
+    let mut rows = scanned_devices
+        .iter()
+        .filter(|d| {
+            select::filter_by_name(
+                d.alias(),
+                args.contains_name.as_deref(),
+                args.exclude_name.as_deref(),
+            )
+        })
+        .map(|device| DeviceRow {
+            device,
+            known: known_addresses.contains(device.address()),
+        })
+        .collect::<Vec<DeviceRow>>();
+    sort_by_keys(&mut rows, &sort_keys(args));
+
+    let devices = rows
+        .into_iter()
+        .map(|row| ScannedDevice {
+            device: row.device.clone(),
+            known: row.known,
+        })
+        .collect();
+
+    Ok((devices, unread))
+}
+
+/// Keeps a single entry for each ADDRESS (case-insensitive) of `devices`, which is the one with the strongest RSSI.
+fn dedup_by_address(devices: Vec<bluez::BluezDevice>) -> Vec<bluez::BluezDevice> {
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut deduped: Vec<bluez::BluezDevice> = vec![];
+
+    for device in devices {
+        match positions.get(&device.address().to_uppercase()) {
+            Some(&idx) if deduped[idx].rssi() < device.rssi() => deduped[idx] = device,
+            Some(_) => {}
+            None => {
+                positions.insert(device.address().to_uppercase(), deduped.len());
+                deduped.push(device);
+            }
+        }
+    }
+
+    deduped
+}
+
+/// Indicates whether the known devices are needed to provide the `KNOWN` column of `args`, including the `{known}` placeholder of `args.template`.
+fn reads_known(args: &ScanArgs) -> bool {
+    let is_known = |field: &DeviceField| matches!(field, DeviceField::Known);
+
+    args.known
+        || args.columns.iter().flatten().any(is_known)
+        || args.values.iter().flatten().any(is_known)
+        || args.sort.iter().chain(&args.then).any(is_known)
+        || args
+            .template
+            .as_deref()
+            .and_then(|template| Template::<DeviceField>::parse(template).ok())
+            .is_some_and(|template| template.placeholders().any(is_known))
+}
+
+/// Builds the sort keys from `args.sort` and `args.then`.
+fn sort_keys(args: &ScanArgs) -> Vec<SortKey<DeviceField>> {
+    let primary = args.sort.map(|column| SortKey {
+        column,
+        reverse: args.reverse,
+    });
+    let secondary = args.then.map(|column| SortKey {
+        column,
+        reverse: args.reverse_then,
+    });
+
+    primary.into_iter().chain(secondary).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use io::Cursor;
+    use serde_json::Value;
+
+    use crate::bluez;
+
+    #[test]
+    fn it_should_write_scanned_devices() {
+        let bluez = crate::BluezClient::new().unwrap();
+        let mut out_buf = Cursor::new(vec![]);
+
+        let scan_args = ScanArgs {
+            duration: 0,
+            columns: None,
+            values: None,
+            ..Default::default()
+        };
+
+        let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+
+        assert!(result.is_ok());
+        assert!(!out_buf.into_inner().is_empty());
+    }
+
+    #[test]
+    fn it_should_fail_when_scan_is_not_started() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_erred_method_name("start_discovery".to_string());
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let scan_args = ScanArgs {
+            duration: 0,
+            columns: None,
+            values: None,
+            ..Default::default()
+        };
+
+        let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+
+        assert!(result.is_err());
+        assert!(out_buf.into_inner().is_empty());
+    }
+
+    #[test]
+    fn it_should_fail_when_scanned_devices_are_not_read() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_erred_method_name("scanned_devices".to_string());
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let scan_args = ScanArgs {
+            duration: 0,
+            columns: None,
+            values: None,
+            ..Default::default()
+        };
+
+        let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+
+        assert!(result.is_err());
+        assert!(out_buf.into_inner().is_empty());
+    }
+
+    #[test]
+    fn it_should_warn_when_scan_is_not_stopped() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_erred_method_name("stop_discovery".to_string());
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let scan_args = ScanArgs {
+            duration: 0,
+            columns: None,
+            values: None,
+            ..Default::default()
+        };
+
+        let mut warn_buf = Cursor::new(vec![]);
+
+        let result = scan(&bluez, &mut out_buf, &mut warn_buf, &scan_args);
+
+        assert!(result.is_ok());
+        assert!(!out_buf.into_inner().is_empty());
+
+        let warning = String::from_utf8(warn_buf.into_inner()).unwrap();
+        assert!(warning.starts_with("scan: warning: could not stop the discovery: "));
+    }
+
+    #[test]
+    fn it_should_keep_the_strongest_entry_of_a_duplicated_device() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .rssi(Some(-80))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .address("BB:BB:BB:BB:BB:BB")
+                .rssi(Some(-60))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("aa:aa:aa:aa:aa:aa")
+                .adapter("hci1")
+                .rssi(Some(-40))
+                .build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let scan_args = ScanArgs {
+            duration: 0,
+            values: Some(vec![ScanColumn::Alias, ScanColumn::Rssi]),
+            ..Default::default()
+        };
+
+        let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "dev1/-40\ndev2/-60\n");
+    }
+
+    #[test]
+    fn it_should_retry_the_discovery_if_no_devices_are_found() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .rssi(Some(-60))
+                .build(),
+        ]);
+        bluez.set_empty_scans(1);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let scan_args = ScanArgs {
+            duration: 0,
+            values: Some(vec![ScanColumn::Alias]),
+            retry_if_empty: Some(2),
+            ..Default::default()
+        };
+
+        let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "dev1\n");
+        assert_eq!(
+            bluez.discovery_calls(),
+            vec![
+                String::from("start_discovery"),
+                String::from("stop_discovery"),
+                String::from("start_discovery"),
+                String::from("stop_discovery")
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_stop_the_discovery_when_a_retried_scan_fails() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_erred_method_name("devices".to_string());
+        bluez.set_empty_scans(1);
+
+        let scan_args = ScanArgs {
+            duration: 0,
+            known: true,
+            retry_if_empty: Some(2),
+            ..Default::default()
+        };
+
+        let result = scan(&bluez, &mut io::sink(), &mut io::sink(), &scan_args);
+        assert!(matches!(result, Err(Error::Bluez(_))));
+        assert_eq!(
+            bluez.discovery_calls(),
+            vec![
+                String::from("start_discovery"),
+                String::from("stop_discovery")
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_stop_the_discovery_when_the_scan_cannot_be_cached() {
+        let bluez = crate::BluezClient::new().unwrap();
+
+        // NOTE: The parent of the cache file is a file, so the cache cannot be saved.
+        let parent = std::env::temp_dir().join(format!("bt-scan-cache-{}", std::process::id()));
+        fs::write(&parent, "").unwrap();
+
+        let scan_args = ScanArgs {
+            duration: 0,
+            cache: true,
+            cache_file: Some(parent.join("last-scan.json")),
+            ..Default::default()
+        };
+
+        let result = scan(&bluez, &mut io::sink(), &mut io::sink(), &scan_args);
+        fs::remove_file(&parent).unwrap();
+
+        assert!(result.is_err());
+        assert_eq!(
+            bluez.discovery_calls(),
+            vec![
+                String::from("start_discovery"),
+                String::from("stop_discovery")
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_not_start_the_discovery_when_already_discovering() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .rssi(Some(-60))
+                .build(),
+        ]);
+        bluez.set_discovering(true);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let scan_args = ScanArgs {
+            duration: 0,
+            values: Some(vec![ScanColumn::Alias]),
+            ..Default::default()
+        };
+
+        let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "dev1\n");
+        assert!(bluez.discovery_calls().is_empty());
+
+        let scan_args = ScanArgs {
+            force_discovery: true,
+            ..scan_args
+        };
+
+        let result = scan(&bluez, &mut io::sink(), &mut io::sink(), &scan_args);
+        assert!(result.is_ok());
+        assert_eq!(
+            bluez.discovery_calls(),
+            vec![
+                String::from("start_discovery"),
+                String::from("stop_discovery")
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_fail_when_result_cannot_be_written_to_buf() {
+        let bluez = crate::BluezClient::new().unwrap();
+
+        let mut out_buf = Cursor::new([]);
+        out_buf.set_position(1);
+
+        let scan_args = ScanArgs {
+            duration: 0,
+            columns: None,
+            values: None,
+            ..Default::default()
+        };
+
+        let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+
+        assert!(result.is_err());
+        assert!(out_buf.into_inner().is_empty())
+    }
+
+    #[test]
+    fn it_should_write_scanned_devices_as_compact_or_pretty_json() {
+        let bluez = crate::BluezClient::new().unwrap();
+
+        let mut compact_out_buf = Cursor::new(vec![]);
+        let mut pretty_out_buf = Cursor::new(vec![]);
+
+        let mut scan_args = ScanArgs {
+            duration: 0,
+            json: true,
+            ..Default::default()
+        };
+
+        let result = scan(&bluez, &mut compact_out_buf, &mut io::sink(), &scan_args);
+        assert!(result.is_ok());
+
+        scan_args.json_pretty = true;
+
+        let result = scan(&bluez, &mut pretty_out_buf, &mut io::sink(), &scan_args);
+        assert!(result.is_ok());
+
+        let compact = String::from_utf8(compact_out_buf.into_inner()).unwrap();
+        let pretty = String::from_utf8(pretty_out_buf.into_inner()).unwrap();
+
+        assert_eq!(compact.lines().count(), 1);
+        assert!(pretty.lines().count() > 1);
+
+        let compact: Value = serde_json::from_str(&compact).unwrap();
+        let pretty: Value = serde_json::from_str(&pretty).unwrap();
+        assert_eq!(compact, pretty);
+        assert_eq!(compact[0]["rssi"], 50);
+    }
+
+    #[test]
+    fn it_should_key_the_json_by_the_address_or_the_alias() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("speaker")
+                .address("AA:AA:AA:AA:AA:AA")
+                .rssi(Some(-60))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("speaker")
+                .address("BB:BB:BB:BB:BB:BB")
+                .rssi(Some(-40))
+                .build(),
+        ]);
+
+        for (key, expected) in [
+            (
+                ScanKey::Address,
+                serde_json::json!({
+                    "AA:AA:AA:AA:AA:AA": {"alias": "speaker", "address": "AA:AA:AA:AA:AA:AA", "rssi": -60},
+                    "BB:BB:BB:BB:BB:BB": {"alias": "speaker", "address": "BB:BB:BB:BB:BB:BB", "rssi": -40},
+                }),
+            ),
+            (
+                ScanKey::Alias,
+                serde_json::json!({
+                    "speaker": {"alias": "speaker", "address": "BB:BB:BB:BB:BB:BB", "rssi": -40},
+                }),
+            ),
+        ] {
+            let mut out_buf = Cursor::new(vec![]);
+
+            let scan_args = ScanArgs {
+                duration: 0,
+                json: true,
+                sort: Some(ScanColumn::Address),
+                key: Some(key),
+                ..Default::default()
+            };
+
+            let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+            assert!(result.is_ok());
+
+            let result = String::from_utf8(out_buf.into_inner()).unwrap();
+            let result: Value = serde_json::from_str(&result).unwrap();
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn it_should_right_align_battery_values() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .battery(Some(100))
+                .rssi(Some(-50))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .address("BB:BB:BB:BB:BB:BB")
+                .battery(Some(5))
+                .rssi(Some(-60))
+                .build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let scan_args = ScanArgs {
+            duration: 0,
+            columns: Some(vec![ScanColumn::Battery, ScanColumn::Alias]),
+            ..Default::default()
+        };
+
+        let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        let battery_ends = result
+            .lines()
+            .skip(1)
+            .map(|l| {
+                let battery = l.split_whitespace().next().unwrap();
+                l.find(battery).unwrap() + battery.len()
+            })
+            .collect::<Vec<usize>>();
+
+        assert_eq!(battery_ends.len(), 2);
+        assert_eq!(battery_ends[0], battery_ends[1]);
+    }
+
+    #[test]
+    fn it_should_right_align_rssi_values() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .rssi(Some(-5))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .address("BB:BB:BB:BB:BB:BB")
+                .rssi(Some(-100))
+                .build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let scan_args = ScanArgs {
+            duration: 0,
+            columns: Some(vec![ScanColumn::Rssi, ScanColumn::Alias]),
+            ..Default::default()
+        };
+
+        let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        let rssi_ends = result
+            .lines()
+            .skip(1)
+            .map(|l| {
+                let rssi = l.split_whitespace().next().unwrap();
+                l.find(rssi).unwrap() + rssi.len()
+            })
+            .collect::<Vec<usize>>();
+
+        assert_eq!(rssi_ends.len(), 2);
+        assert_eq!(rssi_ends[0], rssi_ends[1]);
+        assert!(result.lines().nth(1).unwrap().contains("  -5"));
+    }
+
+    #[test]
+    fn it_should_terminate_terse_records_with_nul_bytes() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .rssi(Some(-50))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .address("BB:BB:BB:BB:BB:BB")
+                .rssi(Some(-60))
+                .build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let scan_args = ScanArgs {
+            duration: 0,
+            values: Some(vec![ScanColumn::Alias, ScanColumn::Rssi]),
+            print0: true,
+            ..Default::default()
+        };
+
+        let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "dev1/-50\0dev2/-60\0");
+        assert!(!result.contains('\n'));
+    }
+
+    #[test]
+    fn it_should_annotate_known_devices() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("paired")
+                .address("AA:AA:AA:AA:AA:AA")
+                .paired(true)
+                .rssi(Some(-50))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("bonded")
+                .address("BB:BB:BB:BB:BB:BB")
+                .bonded(true)
+                .rssi(Some(-60))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("stranger")
+                .address("CC:CC:CC:CC:CC:CC")
+                .rssi(Some(-70))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("out of range")
+                .address("DD:DD:DD:DD:DD:DD")
+                .paired(true)
+                .build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let scan_args = ScanArgs {
+            duration: 0,
+            values: Some(vec![ScanColumn::Alias]),
+            known: true,
+            ..Default::default()
+        };
+
+        let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "paired/true\nbonded/true\nstranger/false\n");
+    }
+
+    #[test]
+    fn it_should_provide_the_filtered_and_sorted_devices_without_writing() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("LE-stranger")
+                .address("AA:AA:AA:AA:AA:AA")
+                .rssi(Some(-40))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("LE-paired")
+                .address("BB:BB:BB:BB:BB:BB")
+                .paired(true)
+                .rssi(Some(-50))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("headset")
+                .address("CC:CC:CC:CC:CC:CC")
+                .paired(true)
+                .rssi(Some(-60))
+                .build(),
+        ]);
+
+        let scan_args = ScanArgs {
+            contains_name: Some("LE-".to_string()),
+            sort: Some(ScanColumn::Known),
+            reverse: true,
+            ..Default::default()
+        };
+
+        let (devices, unread) = scan_data(&bluez, &scan_args).unwrap();
+
+        let devices = devices
+            .iter()
+            .map(|scanned| (scanned.device().alias(), scanned.known()))
+            .collect::<Vec<(&str, bool)>>();
+        assert_eq!(devices, vec![("LE-paired", true), ("LE-stranger", false)]);
+        assert_eq!(unread, 0);
+    }
+
+    #[test]
+    fn it_should_fail_when_known_devices_are_not_read() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_erred_method_name("devices".to_string());
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let scan_args = ScanArgs {
+            duration: 0,
+            known: true,
+            ..Default::default()
+        };
+
+        let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+
+        assert!(result.is_err());
+        assert!(out_buf.into_inner().is_empty());
+    }
+
+    #[test]
+    fn it_should_format_scanned_devices_with_a_template() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .rssi(Some(-50))
+                .build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let scan_args = ScanArgs {
+            duration: 0,
+            template: Some("{rssi}dBm {alias}".to_string()),
+            ..Default::default()
+        };
+
+        let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "-50dBm dev1\n");
+    }
+
+    #[test]
+    fn it_should_annotate_known_devices_in_a_template() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("paired")
+                .address("AA:AA:AA:AA:AA:AA")
+                .paired(true)
+                .rssi(Some(-50))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("stranger")
+                .address("BB:BB:BB:BB:BB:BB")
+                .rssi(Some(-60))
+                .build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let scan_args = ScanArgs {
+            duration: 0,
+            template: Some("{alias} {known}".to_string()),
+            ..Default::default()
+        };
+
+        let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "paired true\nstranger false\n");
+    }
+
+    #[test]
+    fn it_should_break_sort_ties_with_the_secondary_key() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev3")
+                .address("AA:AA:AA:AA:AA:AA")
+                .rssi(Some(-60))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("BB:BB:BB:BB:BB:BB")
+                .rssi(Some(-60))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev4")
+                .address("CC:CC:CC:CC:CC:CC")
+                .rssi(Some(-40))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .address("DD:DD:DD:DD:DD:DD")
+                .rssi(Some(-60))
+                .build(),
+        ]);
+
+        for (reverse_then, expected) in [
+            (false, "dev4/-40\ndev1/-60\ndev2/-60\ndev3/-60\n"),
+            (true, "dev4/-40\ndev3/-60\ndev2/-60\ndev1/-60\n"),
+        ] {
+            let mut out_buf = Cursor::new(vec![]);
+
+            let scan_args = ScanArgs {
+                duration: 0,
+                values: Some(vec![ScanColumn::Alias, ScanColumn::Rssi]),
+                sort: Some(ScanColumn::Rssi),
+                reverse: true,
+                then: Some(ScanColumn::Alias),
+                reverse_then,
+                ..Default::default()
+            };
+
+            let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+            assert!(result.is_ok());
+
+            let result = String::from_utf8(out_buf.into_inner()).unwrap();
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn it_should_write_the_results_to_the_output_file() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .rssi(Some(-60))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .address("BB:BB:BB:BB:BB:BB")
+                .rssi(Some(-40))
+                .build(),
+        ]);
+
+        let path = std::env::temp_dir().join(format!("bt-scan-{}.txt", std::process::id()));
+        fs::write(&path, "stale content that should be truncated\n").unwrap();
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let scan_args = ScanArgs {
+            duration: 0,
+            values: Some(vec![ScanColumn::Alias, ScanColumn::Rssi]),
+            output_file: Some(path.clone()),
+            ..Default::default()
+        };
+
+        let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+        let written = fs::read_to_string(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert!(result.is_ok());
+        assert_eq!(written.unwrap(), "dev1/-60\ndev2/-40\n");
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, format!("wrote 2 device(s) to {}\n", path.display()));
+    }
+
+    #[test]
+    fn it_should_warn_or_fail_if_a_device_cannot_be_read() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_unread_devices(1);
+
+        let mut out_buf = Cursor::new(vec![]);
+        let mut warn_buf = Cursor::new(vec![]);
+
+        let scan_args = ScanArgs {
+            duration: 0,
+            ..Default::default()
+        };
+        let result = scan(&bluez, &mut out_buf, &mut warn_buf, &scan_args);
+        assert!(result.is_ok());
+        assert!(!out_buf.into_inner().is_empty());
+
+        let warning = String::from_utf8(warn_buf.into_inner()).unwrap();
+        assert_eq!(warning, "scan: warning: 1 device(s) could not be read\n");
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let scan_args = ScanArgs {
+            duration: 0,
+            strict: true,
+            ..Default::default()
+        };
+        let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+
+        assert!(matches!(result, Err(Error::UnreadDevices(1))));
+        assert!(out_buf.into_inner().is_empty());
+        assert_eq!(
+            bluez.discovery_calls().split_off(2),
+            vec![
+                String::from("start_discovery"),
+                String::from("stop_discovery")
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_order_the_columns_the_same_way_as_list_devices() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .rssi(Some(-60))
+                .build(),
+        ]);
+
+        for terse in [true, false] {
+            let scan_columns = Some(vec![ScanColumn::Address, ScanColumn::Alias]);
+            let ls_columns = Some(vec![
+                crate::ListDevicesColumn::Address,
+                crate::ListDevicesColumn::Alias,
+            ]);
+
+            let (scan_args, ls_args) = if terse {
+                (
+                    ScanArgs {
+                        duration: 0,
+                        values: scan_columns,
+                        ..Default::default()
+                    },
+                    crate::ListDevicesArgs {
+                        values: ls_columns,
+                        ..Default::default()
+                    },
+                )
+            } else {
+                (
+                    ScanArgs {
+                        duration: 0,
+                        columns: scan_columns,
+                        ..Default::default()
+                    },
+                    crate::ListDevicesArgs {
+                        columns: ls_columns,
+                        ..Default::default()
+                    },
+                )
+            };
+
+            let mut scan_out_buf = Cursor::new(vec![]);
+            let result = scan(&bluez, &mut scan_out_buf, &mut io::sink(), &scan_args);
+            assert!(result.is_ok());
+
+            let mut ls_out_buf = Cursor::new(vec![]);
+            let result = crate::list_devices(
+                &bluez,
+                &mut ls_out_buf,
+                &mut io::sink(),
+                &crate::History::default(),
+                &ls_args,
+            );
+            assert!(result.is_ok());
+
+            let scan_result = String::from_utf8(scan_out_buf.into_inner()).unwrap();
+            let ls_result = String::from_utf8(ls_out_buf.into_inner()).unwrap();
+            assert_eq!(scan_result, ls_result);
+            assert!(
+                scan_result
+                    .trim_start()
+                    .starts_with(if terse { "AA:AA" } else { "ADDRESS" })
+            );
+        }
+    }
+
+    #[test]
+    fn it_should_retry_the_discovery_if_the_adapter_is_not_ready() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_failing_calls(
+            "start_discovery",
+            [0],
+            crate::BluezClient::method_error("org.bluez.Error.NotReady", "Resource Not Ready"),
+        );
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let scan_args = ScanArgs {
+            duration: 0,
+            ..Default::default()
+        };
+        let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+
+        assert!(result.is_ok());
+
+        let scan_result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert!(scan_result.contains("test_dev"));
+
+        bluez.set_failing_calls(
+            "start_discovery",
+            0..5,
+            crate::BluezClient::method_error("org.bluez.Error.NotReady", "Resource Not Ready"),
+        );
+
+        let mut out_buf = Cursor::new(vec![]);
+        let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+
+        assert!(matches!(result, Err(Error::Bluez(_))));
+        assert!(out_buf.into_inner().is_empty());
+    }
+
+    #[test]
+    fn it_should_fail_if_bluetooth_is_off_or_blocked() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        // NOTE: The discovery is set to err to see that it is not started.
+        bluez.set_erred_method_name("start_discovery".to_string());
+
+        for power_state in [BluezPowerState::Off, BluezPowerState::Blocked] {
+            bluez.set_power_state(power_state);
+            let mut out_buf = Cursor::new(vec![]);
+
+            let scan_args = ScanArgs {
+                duration: 0,
+                ..Default::default()
+            };
+
+            let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+
+            match power_state {
+                BluezPowerState::Blocked => assert!(matches!(result, Err(Error::Blocked))),
+                _ => assert!(matches!(result, Err(Error::PoweredOff))),
+            }
+            assert!(out_buf.into_inner().is_empty());
+        }
+    }
+
+    #[test]
+    fn it_should_power_on_before_scanning_with_auto_power() {
+        for (auto_power_restore, expected_state) in
+            [(false, BluezPowerState::On), (true, BluezPowerState::Off)]
+        {
+            let mut bluez = crate::BluezClient::new().unwrap();
+            bluez.set_power_state(BluezPowerState::Off);
+            let mut out_buf = Cursor::new(vec![]);
+
+            let scan_args = ScanArgs {
+                duration: 0,
+                auto_power: true,
+                auto_power_restore,
+                ..Default::default()
+            };
+
+            let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+
+            assert!(result.is_ok());
+            assert!(!out_buf.into_inner().is_empty());
+            assert_eq!(bluez.power_state().unwrap(), expected_state);
+        }
+    }
+
+    #[test]
+    fn it_should_keep_the_scan_error_if_the_adapter_cannot_be_powered_off() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_power_state(BluezPowerState::Off);
+        bluez.set_erred_method_name("scanned_devices".to_string());
+        // NOTE: The first call powers the adapter on, and the second one powers it off.
+        bluez.set_failing_calls("set_powered", [1], zbus::Error::InvalidReply);
+
+        let mut warn_buf = Cursor::new(vec![]);
+
+        let scan_args = ScanArgs {
+            duration: 0,
+            auto_power: true,
+            auto_power_restore: true,
+            ..Default::default()
+        };
+
+        let result = scan(&bluez, &mut io::sink(), &mut warn_buf, &scan_args);
+        assert!(
+            matches!(result, Err(Error::Bluez(BluezError::Process(name, _))) if name == "test_proc")
+        );
+
+        let warning = String::from_utf8(warn_buf.into_inner()).unwrap();
+        assert!(warning.starts_with("scan: warning: could not power off the adapter: "));
+    }
+
+    #[test]
+    fn it_should_filter_the_devices_by_name() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("LE-Bose 1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .rssi(Some(-40))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("LE-Keyboard")
+                .address("BB:BB:BB:BB:BB:BB")
+                .rssi(Some(-50))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("Mouse")
+                .address("CC:CC:CC:CC:CC:CC")
+                .rssi(Some(-60))
+                .build(),
+        ]);
+
+        for (contains_name, expected) in [
+            (None, "LE-Keyboard\nMouse\n"),
+            (Some("LE-"), "LE-Keyboard\n"),
+        ] {
+            let mut out_buf = Cursor::new(vec![]);
+
+            let scan_args = ScanArgs {
+                duration: 0,
+                values: Some(vec![ScanColumn::Alias]),
+                contains_name: contains_name.map(String::from),
+                exclude_name: Some("Bose".to_string()),
+                ..Default::default()
+            };
+
+            let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+            assert!(result.is_ok());
+
+            let result = String::from_utf8(out_buf.into_inner()).unwrap();
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn it_should_summarize_the_profiles_of_the_scanned_devices() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("headset")
+                .address("AA:AA:AA:AA:AA:AA")
+                .uuids(&[
+                    "0000110b-0000-1000-8000-00805f9b34fb",
+                    "00001124-0000-1000-8000-00805f9b34fb",
+                ])
+                .rssi(Some(-50))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("beacon")
+                .address("BB:BB:BB:BB:BB:BB")
+                .rssi(Some(-60))
+                .build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let profiles = <ScanColumn as clap::ValueEnum>::from_str("profiles", true).unwrap();
+        let scan_args = ScanArgs {
+            duration: 0,
+            values: Some(vec![ScanColumn::Alias, profiles]),
+            ..Default::default()
+        };
+
+        let result = scan(&bluez, &mut out_buf, &mut io::sink(), &scan_args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "headset/audio,input\nbeacon/-\n");
+    }
+}
@@ -0,0 +1,265 @@
+use std::{
+    collections::HashMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value;
+
+use crate::bluez;
+
+/// Defines the devices found by a single scan, e.g. one round of a repeated scan.
+///
+/// Two snapshots can be compared by [`ScanSnapshot.diff()`] to see which devices appeared, disappeared or changed their RSSI in between.
+///
+/// [`ScanSnapshot.diff()`]: crate::ScanSnapshot::diff()
+#[derive(Debug, Default, Clone)]
+pub struct ScanSnapshot {
+    devices: Vec<bluez::BluezDevice>,
+}
+
+impl ScanSnapshot {
+    /// Creates a [`ScanSnapshot`] of the scanned `devices`.
+    ///
+    /// [`ScanSnapshot`]: crate::ScanSnapshot
+    pub fn new(devices: Vec<bluez::BluezDevice>) -> Self {
+        Self { devices }
+    }
+
+    /// Provides the devices of the snapshot, in the order they are scanned.
+    pub fn devices(&self) -> &[bluez::BluezDevice] {
+        &self.devices
+    }
+
+    /// Compares the snapshot with a later one, `other`, by the ADDRESS of the devices (case-insensitive).
+    ///
+    /// A device is added if it is only in `other`, removed if it is only in this snapshot, and changed if it is in both but with a different RSSI. The added and changed devices are taken from `other`, so that they have the latest properties. Each list keeps the scan order of the snapshot it is taken from.
+    pub fn diff(&self, other: &ScanSnapshot) -> ScanDiff {
+        self.diff_by(other, |before, after| before.rssi() != after.rssi())
+    }
+
+    /// Compares the snapshot with a later one, `other`, the same way as [`ScanSnapshot.diff()`], except that a device in both snapshots is changed if `is_changed` returns `true` for its earlier and later versions.
+    ///
+    /// [`ScanSnapshot.diff()`]: crate::ScanSnapshot::diff()
+    pub fn diff_by(
+        &self,
+        other: &ScanSnapshot,
+        is_changed: impl Fn(&bluez::BluezDevice, &bluez::BluezDevice) -> bool,
+    ) -> ScanDiff {
+        let before = by_address(&self.devices);
+        let after = by_address(&other.devices);
+
+        let mut diff = ScanDiff::default();
+        for device in &other.devices {
+            match before.get(&device.address().to_uppercase()) {
+                None => diff.added.push(device.clone()),
+                Some(previous) if is_changed(previous, device) => diff.changed.push(device.clone()),
+                Some(_) => {}
+            }
+        }
+        diff.removed = self
+            .devices
+            .iter()
+            .filter(|device| !after.contains_key(&device.address().to_uppercase()))
+            .cloned()
+            .collect();
+
+        diff
+    }
+
+    /// Provides the default location of a saved snapshot, which is `$XDG_STATE_HOME/bt/snapshot.json`, or `$HOME/.local/state/bt/snapshot.json` if `$XDG_STATE_HOME` is not set.
+    pub fn path() -> Option<PathBuf> {
+        state_path("snapshot.json")
+    }
+
+    /// Provides the default location of the cached scan, which is `$XDG_STATE_HOME/bt/last-scan.json`, or `$HOME/.local/state/bt/last-scan.json` if `$XDG_STATE_HOME` is not set.
+    ///
+    /// The cached scan is a snapshot of the devices written by the last `bt scan --cache`, in the scan order.
+    pub fn cache_path() -> Option<PathBuf> {
+        state_path("last-scan.json")
+    }
+
+    /// Writes the snapshot to `path` as a JSON array. The parent directories of the file are created if they do not exist.
+    ///
+    /// Only the ALIAS, ADDRESS and the statuses (connected, trusted, bonded, paired) of the devices are saved.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let devices = self.devices.iter().map(to_saved).collect();
+        fs::write(path, format!("{:#}\n", Value::Array(devices)))
+    }
+
+    /// Loads a snapshot written by [`ScanSnapshot.save()`] from `path`.
+    ///
+    /// It fails with [`io::ErrorKind::InvalidData`] if the file is not a saved snapshot.
+    ///
+    /// [`ScanSnapshot.save()`]: crate::ScanSnapshot::save()
+    /// [`io::ErrorKind::InvalidData`]: std::io::ErrorKind::InvalidData
+    pub fn load_from(path: &Path) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "not a saved snapshot");
+
+        let value = serde_json::from_str::<Value>(&content).map_err(|_| invalid())?;
+        let devices = value
+            .as_array()
+            .ok_or_else(invalid)?
+            .iter()
+            .map(from_saved)
+            .collect::<Option<Vec<bluez::BluezDevice>>>()
+            .ok_or_else(invalid)?;
+
+        Ok(Self { devices })
+    }
+}
+
+impl From<Vec<bluez::BluezDevice>> for ScanSnapshot {
+    fn from(devices: Vec<bluez::BluezDevice>) -> Self {
+        Self::new(devices)
+    }
+}
+
+impl IntoIterator for ScanSnapshot {
+    type Item = bluez::BluezDevice;
+    type IntoIter = std::vec::IntoIter<bluez::BluezDevice>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.devices.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ScanSnapshot {
+    type Item = &'a bluez::BluezDevice;
+    type IntoIter = std::slice::Iter<'a, bluez::BluezDevice>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.devices.iter()
+    }
+}
+
+/// Defines the difference between two [`ScanSnapshot`]'s, which is provided by [`ScanSnapshot.diff()`].
+///
+/// [`ScanSnapshot`]: crate::ScanSnapshot
+/// [`ScanSnapshot.diff()`]: crate::ScanSnapshot::diff()
+#[derive(Debug, Default, Clone)]
+pub struct ScanDiff {
+    added: Vec<bluez::BluezDevice>,
+    removed: Vec<bluez::BluezDevice>,
+    changed: Vec<bluez::BluezDevice>,
+}
+
+impl ScanDiff {
+    /// Provides the devices that newly appeared in the later snapshot.
+    pub fn added(&self) -> &[bluez::BluezDevice] {
+        &self.added
+    }
+
+    /// Provides the devices that disappeared from the later snapshot.
+    pub fn removed(&self) -> &[bluez::BluezDevice] {
+        &self.removed
+    }
+
+    /// Provides the devices whose RSSI changed between the snapshots (or whatever [`ScanSnapshot.diff_by()`] compares), with their latest properties.
+    ///
+    /// [`ScanSnapshot.diff_by()`]: crate::ScanSnapshot::diff_by()
+    pub fn changed(&self) -> &[bluez::BluezDevice] {
+        &self.changed
+    }
+
+    /// Indicates whether the snapshots have the same devices, none of which changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Indexes `devices` by their uppercase ADDRESS.
+fn by_address(devices: &[bluez::BluezDevice]) -> HashMap<String, &bluez::BluezDevice> {
+    devices
+        .iter()
+        .map(|device| (device.address().to_uppercase(), device))
+        .collect()
+}
+
+/// Provides the ALIAS, ADDRESS and the statuses of `device` as a JSON object, which is how it is saved in a snapshot.
+fn to_saved(device: &bluez::BluezDevice) -> Value {
+    serde_json::json!({
+        "alias": device.alias(),
+        "address": device.address(),
+        "connected": device.connected(),
+        "trusted": device.trusted(),
+        "bonded": device.bonded(),
+        "paired": device.paired(),
+    })
+}
+
+/// Restores a device saved by [`to_saved`]. Its battery, RSSI and UUIDs are unknown.
+///
+/// [`None`] is returned if the JSON object does not have every saved property.
+///
+/// [`None`]: std::option::Option::None
+fn from_saved(value: &Value) -> Option<bluez::BluezDevice> {
+    let string = |key: &str| value.get(key)?.as_str().map(String::from);
+    let boolean = |key: &str| value.get(key)?.as_bool();
+
+    Some(bluez::BluezDevice::with_statuses(
+        string("alias")?,
+        string("address")?,
+        boolean("connected")?,
+        boolean("trusted")?,
+        boolean("bonded")?,
+        boolean("paired")?,
+    ))
+}
+
+/// Provides the location of the state file `name` of `bt`, under `$XDG_STATE_HOME/bt` (or `$HOME/.local/state/bt`).
+fn state_path(name: &str) -> Option<PathBuf> {
+    env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("state"))
+        })
+        .map(|dir| dir.join("bt").join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(alias: &str, address: &str, rssi: i16) -> bluez::BluezDevice {
+        bluez::BluezTestDeviceBuilder::new(alias)
+            .address(address)
+            .rssi(Some(rssi))
+            .build()
+    }
+
+    fn aliases(devices: &[bluez::BluezDevice]) -> Vec<&str> {
+        devices.iter().map(|d| d.alias()).collect()
+    }
+
+    #[test]
+    fn it_should_classify_the_devices_by_address() {
+        let before = ScanSnapshot::new(vec![
+            device("gone", "AA:AA:AA:AA:AA:AA", -60),
+            device("still", "BB:BB:BB:BB:BB:BB", -60),
+            device("closer", "CC:CC:CC:CC:CC:CC", -80),
+        ]);
+        let after = ScanSnapshot::from(vec![
+            device("closer", "cc:cc:cc:cc:cc:cc", -50),
+            device("new", "DD:DD:DD:DD:DD:DD", -70),
+            device("still", "BB:BB:BB:BB:BB:BB", -60),
+        ]);
+
+        let diff = before.diff(&after);
+
+        assert_eq!(aliases(diff.added()), vec!["new"]);
+        assert_eq!(aliases(diff.removed()), vec!["gone"]);
+        assert_eq!(aliases(diff.changed()), vec!["closer"]);
+        assert_eq!(diff.changed()[0].rssi(), &Some(-50));
+        assert!(!diff.is_empty());
+
+        assert!(after.diff(&after).is_empty());
+        assert_eq!((&after).into_iter().count(), 3);
+    }
+}
@@ -0,0 +1,160 @@
+use std::{error, fmt, io};
+
+use clap::{Args, ValueEnum};
+
+use crate::{DeviceField, ListDevicesColumn, format};
+
+/// Defines error variants that may be returned from a [`schema`] call.
+///
+/// [`schema`]: crate::schema
+#[derive(Debug)]
+pub enum Error {
+    /// Happens when [`schema`] cannot write to the provided [`io::Write`].
+    /// It holds the underlying [`io::Error`].
+    ///
+    /// [`schema`]: crate::schema
+    /// [`io::Write`]: std::io::Write
+    /// [`io::Error`]: std::io::Error
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "schema: io error: {}", error),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/// Defines the JSON outputs that [`schema`] can describe.
+///
+/// [`schema`]: crate::schema
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum SchemaOutput {
+    /// The output of `bt ls --json`.
+    #[default]
+    Devices,
+
+    /// The output of `bt scan --json`.
+    ///
+    /// With `--key`, the output is an object that maps the key of each device to an item of this schema instead.
+    Scan,
+}
+
+/// Defines the arguments that [`schema`] can take.
+///
+/// [`schema`]: crate::schema
+#[derive(Debug, Default, Args)]
+pub struct SchemaArgs {
+    /// The JSON output to describe.
+    #[arg(value_enum)]
+    pub output: SchemaOutput,
+}
+
+/// Writes the JSON Schema of a JSON output of `bt` to the provided [`io::Write`], so that the consumers of the output can validate it or generate code from it.
+///
+/// Each JSON output is an array of objects keyed by the lowercase column names. Since the columns can be picked by `--columns`, the schema declares every column of the output, but none of them are required.
+///
+/// The schema does not describe the object of `bt scan --json --key KEY`, which maps the KEY of each device to its object. Each value of that object matches the `items` of the [`SchemaOutput::Scan`] schema.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function can return all variants of [`SchemaError`] based on given conditions. For more details, please see the error documentation.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+/// use bt::{schema, SchemaArgs, SchemaOutput};
+///
+/// let args = SchemaArgs {
+///     output: SchemaOutput::Scan,
+/// };
+///
+/// if let Err(e) = schema(&mut io::stdout(), &args) {
+///     eprintln!("{}", e);
+/// }
+/// ```
+///
+/// [`io::Write`]: std::io::Write
+/// [`SchemaError`]: crate::SchemaError
+/// [`SchemaOutput::Scan`]: crate::SchemaOutput::Scan
+pub fn schema(w: &mut impl io::Write, args: &SchemaArgs) -> Result<(), Error> {
+    let schema = match args.output {
+        SchemaOutput::Devices => {
+            format::json_listing_schema("bt ls --json", ListDevicesColumn::value_variants())
+        }
+        SchemaOutput::Scan => {
+            format::json_listing_schema("bt scan --json", DeviceField::value_variants())
+        }
+    };
+
+    writeln!(w, "{:#}", schema)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use serde_json::Value;
+
+    use super::*;
+
+    fn render_schema(output: SchemaOutput) -> Value {
+        let mut out_buf = Cursor::new(vec![]);
+
+        schema(&mut out_buf, &SchemaArgs { output }).unwrap();
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        serde_json::from_str(&result).unwrap()
+    }
+
+    #[test]
+    fn it_should_write_the_schema_of_each_output() {
+        let devices = render_schema(SchemaOutput::Devices);
+        assert_eq!(devices["type"], "array");
+        assert_eq!(devices["items"]["type"], "object");
+
+        let properties = devices["items"]["properties"].as_object().unwrap();
+        let keys = properties.keys().map(String::as_str).collect::<Vec<&str>>();
+        assert_eq!(
+            keys,
+            vec![
+                "address",
+                "alias",
+                "battery",
+                "bonded",
+                "capabilities",
+                "connected",
+                "paired",
+                "state",
+                "trusted",
+                "tx_power"
+            ]
+        );
+        assert_eq!(
+            properties["battery"]["type"],
+            serde_json::json!(["integer", "null"])
+        );
+
+        let scan = render_schema(SchemaOutput::Scan);
+        let properties = scan["items"]["properties"].as_object().unwrap();
+        assert!(properties.contains_key("rssi"));
+        assert!(properties.contains_key("known"));
+        assert_eq!(properties.len(), DeviceField::value_variants().len());
+    }
+}
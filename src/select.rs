@@ -0,0 +1,621 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    error, fmt, io,
+    str::FromStr,
+};
+
+use regex::Regex;
+
+use crate::{BluezError, bluez};
+
+/// Defines how a device is selected from the list shown by the interactive modes of [`connect`] and [`disconnect`].
+///
+/// [`connect`]: crate::connect
+/// [`disconnect`]: crate::disconnect
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
+pub enum SelectBy {
+    /// Select a device by entering its IDX on the list.
+    #[default]
+    Index,
+
+    /// Select a device by entering a part of its ALIAS.
+    ///
+    /// The entered name is matched case-insensitively, and it must match exactly one device on the list.
+    Name,
+}
+
+/// The ALIAS that makes [`connect`] and [`disconnect`] read the actual ALIAS from their [`io::BufRead`].
+///
+/// [`connect`]: crate::connect
+/// [`disconnect`]: crate::disconnect
+/// [`io::BufRead`]: std::io::BufRead
+pub const STDIN_ALIAS: &str = "-";
+
+/// Reads a single trimmed line from `r` as an ALIAS. An empty line results in [`None`].
+pub fn read_stdin_alias(r: &mut impl io::BufRead) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    r.read_line(&mut line)?;
+
+    let alias = line.trim();
+    if alias.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(alias.to_string()))
+    }
+}
+
+/// Removes the repeated ALIAS'es of a batch, keeping the first occurrence of each in order. The ALIAS'es are trimmed.
+///
+/// Each [`STDIN_ALIAS`] is kept, since each of them reads another ALIAS.
+pub fn dedup_aliases(aliases: &[String]) -> Vec<&str> {
+    let mut seen = HashSet::new();
+
+    aliases
+        .iter()
+        .map(|alias| alias.trim())
+        .filter(|alias| *alias == STDIN_ALIAS || seen.insert(*alias))
+        .collect()
+}
+
+/// Indicates whether `alias` passes the name filters of the scans.
+///
+/// `contains` is applied first, and then `exclude`: `alias` passes if it contains `contains` (if any), and it does not contain `exclude` (if any). Both are plain substrings, not patterns.
+pub fn filter_by_name(alias: &str, contains: Option<&str>, exclude: Option<&str>) -> bool {
+    contains.is_none_or(|name| contains_name(alias, name, false))
+        && exclude.is_none_or(|name| !contains_name(alias, name, false))
+}
+
+/// Indicates whether `alias` contains `name`, which is a plain substring, not a pattern.
+///
+/// If `ignore_case` is `true`, then both are compared in lowercase.
+pub fn contains_name(alias: &str, name: &str, ignore_case: bool) -> bool {
+    if ignore_case {
+        alias.to_lowercase().contains(&name.to_lowercase())
+    } else {
+        alias.contains(name)
+    }
+}
+
+pub enum NameMatch {
+    Unique(usize),
+    Ambiguous,
+    NotFound,
+}
+
+pub fn find_by_name(devices: &BTreeMap<usize, bluez::BluezDevice>, name: &str) -> NameMatch {
+    let name = name.to_lowercase();
+    if name.is_empty() {
+        return NameMatch::NotFound;
+    }
+
+    let mut matches = devices
+        .iter()
+        .filter(|(_, d)| d.alias().to_lowercase().contains(&name))
+        .map(|(idx, _)| *idx);
+
+    match (matches.next(), matches.next()) {
+        (Some(idx), None) => NameMatch::Unique(idx),
+        (Some(_), Some(_)) => NameMatch::Ambiguous,
+        _ => NameMatch::NotFound,
+    }
+}
+
+pub fn find_by_address(devices: &BTreeMap<usize, bluez::BluezDevice>, address: &str) -> NameMatch {
+    let mut matches = devices
+        .iter()
+        .filter(|(_, d)| d.address().eq_ignore_ascii_case(address))
+        .map(|(idx, _)| *idx);
+
+    match (matches.next(), matches.next()) {
+        (Some(idx), None) => NameMatch::Unique(idx),
+        (Some(_), Some(_)) => NameMatch::Ambiguous,
+        _ => NameMatch::NotFound,
+    }
+}
+
+/// Indicates whether `value` is a Bluetooth address, such as `AA:BB:CC:DD:EE:FF`.
+pub(crate) fn is_address(value: &str) -> bool {
+    let parts = value.split(':').collect::<Vec<&str>>();
+
+    parts.len() == 6
+        && parts
+            .iter()
+            .all(|p| p.len() == 2 && p.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Defines error variants that may be returned while parsing a [`Selector`].
+#[derive(Debug, PartialEq)]
+pub enum SelectorError {
+    /// Happens when the value of an `idx:` selector is not a number.
+    /// It holds the value.
+    InvalidIndex(String),
+
+    /// Happens when the value of a `mac:` selector is not a Bluetooth address.
+    /// It holds the value.
+    InvalidAddress(String),
+}
+
+impl fmt::Display for SelectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelectorError::InvalidIndex(value) => write!(f, "'{}' is not a valid index", value),
+            SelectorError::InvalidAddress(value) => {
+                write!(f, "'{}' is not a valid address", value)
+            }
+        }
+    }
+}
+
+impl error::Error for SelectorError {}
+
+/// Defines error variants that may be returned while resolving a [`Selector`] in a non-interactive mode, see [`Selector::target()`].
+///
+/// [`Selector::target()`]: crate::Selector::target()
+#[derive(Debug)]
+pub enum TargetError {
+    /// Happens when an `idx:` selector is used, since there is no list to select from in a non-interactive mode.
+    Index,
+
+    /// Happens when no known device matches a `mac:` or a `name:` selector.
+    /// It holds the value of the selector.
+    NotFound(String),
+
+    /// Happens when more than one known device matches a `name:` selector.
+    /// It holds the value of the selector.
+    Ambiguous(String),
+
+    /// Happens when the known devices cannot be read.
+    /// It holds the underlying [`BluezError`].
+    ///
+    /// [`BluezError`]: crate::BluezError
+    Bluez(BluezError),
+}
+
+impl fmt::Display for TargetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TargetError::Index => write!(f, "idx: can only be used in the interactive mode"),
+            TargetError::NotFound(value) => write!(f, "no known device matches '{}'", value),
+            TargetError::Ambiguous(value) => {
+                write!(f, "the name '{}' matches more than one known device", value)
+            }
+            TargetError::Bluez(error) => write!(f, "bluez error: {}", error),
+        }
+    }
+}
+
+impl error::Error for TargetError {}
+
+impl From<BluezError> for TargetError {
+    fn from(value: BluezError) -> Self {
+        Self::Bluez(value)
+    }
+}
+
+/// Defines the error of an interactive selection which does not match any device on the list.
+///
+/// It holds the entered selection along with the number of devices on the list, so that the valid IDX range can be shown to the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceSelectionError {
+    /// The selection entered by the user.
+    pub selection: String,
+
+    /// The number of devices on the list.
+    pub device_count: usize,
+}
+
+impl fmt::Display for DeviceSelectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.device_count {
+            0 => write!(
+                f,
+                "'{}' is not valid, there are no devices to select",
+                self.selection
+            ),
+            count => write!(
+                f,
+                "'{}' does not match any device, select an IDX between 0 and {}",
+                self.selection,
+                count - 1
+            ),
+        }
+    }
+}
+
+impl error::Error for DeviceSelectionError {}
+
+/// The number of times the interactive modes of [`connect`] and [`disconnect`] read a selection before giving up, unless overridden by `--attempts`.
+///
+/// [`connect`]: crate::connect
+/// [`disconnect`]: crate::disconnect
+pub const DEFAULT_SELECT_ATTEMPTS: u8 = 2;
+
+/// Writes `listing` and `prompt` to `w`, and reads a selection from `r` until `resolve` accepts it.
+///
+/// If `resolve` fails, then the error is written to `w` along with `prompt` again, and another selection is read. The error of the last selection is returned after `attempts` reads, or once `r` has no more lines.
+///
+/// If `r` has no more lines before any selection is read (e.g. the pipe is closed), then the selection is cancelled, which results in [`None`].
+pub fn read_selection<T, E: fmt::Display + From<io::Error>>(
+    w: &mut impl io::Write,
+    r: &mut impl io::BufRead,
+    listing: &str,
+    prompt: &str,
+    attempts: u8,
+    mut resolve: impl FnMut(&str) -> Result<T, E>,
+) -> Result<Option<T>, E> {
+    write!(w, "{}\n{}", listing, prompt)?;
+    w.flush()?;
+
+    let (mut attempt, mut last_error) = (1, None);
+    loop {
+        let mut read_buf = String::new();
+        if r.read_line(&mut read_buf)? == 0 {
+            return match last_error.take() {
+                Some(e) => Err(e),
+                None => {
+                    writeln!(w)?;
+                    Ok(None)
+                }
+            };
+        }
+
+        match resolve(read_buf.trim()) {
+            Ok(selected) => return Ok(Some(selected)),
+            Err(e) if attempt >= attempts => return Err(e),
+            Err(e) => {
+                write!(w, "{}\n{}", e, prompt)?;
+                w.flush()?;
+                last_error = Some(e);
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Asks a yes/no question by writing `message` to `w` along with `[Y/n]` or `[y/N]` based on `default`, and reads the answer from `r`.
+///
+/// The answer is case-insensitive: `y` or `yes` confirms, `n` or `no` declines, and an empty line results in `default`. Any other answer is not accepted, and the question is asked again.
+///
+/// If `r` has no more lines (e.g. the pipe is closed), then the question is not confirmed, regardless of `default`.
+pub fn confirm(
+    w: &mut impl io::Write,
+    r: &mut impl io::BufRead,
+    message: &str,
+    default: bool,
+) -> io::Result<bool> {
+    let choices = if default { "[Y/n]" } else { "[y/N]" };
+
+    loop {
+        write!(w, "{} {}: ", message, choices)?;
+        w.flush()?;
+
+        let mut answer = String::new();
+        if r.read_line(&mut answer)? == 0 {
+            writeln!(w)?;
+            return Ok(false);
+        }
+
+        match answer.trim().to_lowercase().as_str() {
+            "" => return Ok(default),
+            "y" | "yes" => return Ok(true),
+            "n" | "no" => return Ok(false),
+            _ => writeln!(w, "please answer y or n")?,
+        }
+    }
+}
+
+/// Defines how a single device is selected by [`connect`] and [`disconnect`].
+///
+/// A selector can carry a prefix to make the selection explicit:
+///
+/// - `idx:0` selects the device by its IDX on the list. It is only valid in the interactive modes.
+/// - `mac:AA:BB:CC:DD:EE:FF` selects the device by its ADDRESS.
+/// - `name:Keyboard` selects the device by its ALIAS. In the interactive modes, a part of the ALIAS is enough.
+///
+/// A value without a prefix is bare, and it keeps the default behavior: it is matched against both the ALIAS and the ADDRESS, or in the interactive modes, it is resolved based on [`SelectBy`].
+/// A value with any other prefix stays bare as well, so that an ALIAS such as `JBL:Flip` or `audio:Speaker` is matched as is. An ALIAS that starts with one of the prefixes can be selected via `name:`, e.g. `name:mac:book`.
+///
+/// [`connect`]: crate::connect
+/// [`disconnect`]: crate::disconnect
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Selector {
+    Index(usize),
+    Address(String),
+    Name(String),
+    Bare(String),
+}
+
+impl FromStr for Selector {
+    type Err = SelectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim();
+        if is_address(value) {
+            return Ok(Selector::Bare(value.to_string()));
+        }
+
+        match value.split_once(':') {
+            Some(("idx", rest)) => rest
+                .trim()
+                .parse::<usize>()
+                .map(Selector::Index)
+                .map_err(|_| SelectorError::InvalidIndex(rest.to_string())),
+            Some(("mac", rest)) if is_address(rest.trim()) => {
+                Ok(Selector::Address(rest.trim().to_string()))
+            }
+            Some(("mac", rest)) => Err(SelectorError::InvalidAddress(rest.to_string())),
+            Some(("name", rest)) => Ok(Selector::Name(rest.to_string())),
+            _ => Ok(Selector::Bare(value.to_string())),
+        }
+    }
+}
+
+/// Compiles `pattern` into a [`Regex`] that matches a device ALIAS as a whole, e.g. `Office.*` matches `Office Mouse` but not `My Office Mouse`.
+pub fn alias_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    Regex::new(&format!("^(?:{})$", pattern))
+}
+
+impl Selector {
+    /// Resolves the selector against the device list of an interactive mode, where each device is keyed by its IDX.
+    ///
+    /// A bare selector is resolved based on `select_by`.
+    pub fn resolve(
+        &self,
+        devices: &BTreeMap<usize, bluez::BluezDevice>,
+        select_by: &SelectBy,
+    ) -> NameMatch {
+        let find_by_index = |idx: usize| {
+            if devices.contains_key(&idx) {
+                NameMatch::Unique(idx)
+            } else {
+                NameMatch::NotFound
+            }
+        };
+
+        match self {
+            Selector::Index(idx) => find_by_index(*idx),
+            Selector::Address(address) => find_by_address(devices, address),
+            Selector::Name(name) => find_by_name(devices, name),
+            Selector::Bare(value) => match select_by {
+                SelectBy::Index => value
+                    .parse::<usize>()
+                    .map_or(NameMatch::NotFound, find_by_index),
+                SelectBy::Name => find_by_name(devices, value),
+            },
+        }
+    }
+
+    /// Provides the value of the selector without its prefix, e.g. to show it in a message.
+    ///
+    /// It is [`None`] for an `idx:` selector.
+    ///
+    /// [`None`]: std::option::Option::None
+    pub fn value(&self) -> Option<&str> {
+        match self {
+            Selector::Index(_) => None,
+            Selector::Address(value) | Selector::Name(value) | Selector::Bare(value) => Some(value),
+        }
+    }
+
+    /// Provides the ALIAS or ADDRESS that a non-interactive mode passes to the [`BluezClient`].
+    ///
+    /// A bare selector is passed as is, so it is matched against both the ALIAS and the ADDRESS of the known devices. A `mac:` or a `name:` selector is resolved against the known devices instead, so that it only matches their ADDRESS or their full ALIAS respectively. Then, the ADDRESS of the matching device is provided.
+    ///
+    /// # Errors
+    ///
+    /// It fails with [`TargetError::Index`] for an `idx:` selector, since there is no list to select from. It fails with [`TargetError::NotFound`] or [`TargetError::Ambiguous`] if a `mac:` or a `name:` selector does not match exactly one known device.
+    ///
+    /// [`BluezClient`]: crate::BluezClient
+    /// [`TargetError::Index`]: crate::TargetError::Index
+    /// [`TargetError::NotFound`]: crate::TargetError::NotFound
+    /// [`TargetError::Ambiguous`]: crate::TargetError::Ambiguous
+    pub fn target(&self, bluez: &crate::BluezClient) -> Result<String, TargetError> {
+        let matches = |value: &str, is_match: &dyn Fn(&bluez::BluezDevice) -> bool| {
+            let devices = bluez.devices()?;
+            let mut matching = devices.iter().filter(|d| is_match(d));
+
+            match (matching.next(), matching.next()) {
+                (Some(device), None) => Ok(device.address().to_string()),
+                (None, _) => Err(TargetError::NotFound(value.to_string())),
+                (Some(_), Some(_)) => Err(TargetError::Ambiguous(value.to_string())),
+            }
+        };
+
+        match self {
+            Selector::Index(_) => Err(TargetError::Index),
+            Selector::Bare(value) => Ok(value.clone()),
+            Selector::Address(address) => {
+                matches(address, &|d| d.address().eq_ignore_ascii_case(address))
+            }
+            Selector::Name(name) => matches(name, &|d| d.alias() == name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn test_devices() -> BTreeMap<usize, bluez::BluezDevice> {
+        BTreeMap::from_iter(
+            [
+                bluez::BluezTestDeviceBuilder::new("Office Keyboard")
+                    .address("AA:AA:AA:AA:AA:AA")
+                    .build(),
+                bluez::BluezTestDeviceBuilder::new("Office Mouse")
+                    .address("BB:BB:BB:BB:BB:BB")
+                    .build(),
+            ]
+            .into_iter()
+            .enumerate(),
+        )
+    }
+
+    #[test]
+    fn it_should_parse_the_prefixes() {
+        assert_eq!("idx:1".parse(), Ok(Selector::Index(1)));
+        assert_eq!(
+            "mac:BB:BB:BB:BB:BB:BB".parse(),
+            Ok(Selector::Address("BB:BB:BB:BB:BB:BB".to_string()))
+        );
+        assert_eq!(
+            "name:Mouse".parse(),
+            Ok(Selector::Name("Mouse".to_string()))
+        );
+    }
+
+    #[test]
+    fn it_should_fall_back_to_a_bare_selector() {
+        for value in [
+            "Office Mouse",
+            "BB:BB:BB:BB:BB:BB",
+            "JBL:Flip",
+            "audio:Speaker",
+            "1",
+        ] {
+            assert_eq!(value.parse(), Ok(Selector::Bare(value.to_string())));
+        }
+    }
+
+    #[test]
+    fn it_should_fail_on_invalid_selectors() {
+        assert_eq!(
+            "idx:one".parse::<Selector>(),
+            Err(SelectorError::InvalidIndex("one".to_string()))
+        );
+        assert_eq!(
+            "mac:BB:BB".parse::<Selector>(),
+            Err(SelectorError::InvalidAddress("BB:BB".to_string()))
+        );
+    }
+
+    #[test]
+    fn it_should_resolve_the_target_of_each_selector_against_the_known_devices() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("Office Keyboard")
+                .address("AA:AA:AA:AA:AA:AA")
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("BB:BB:BB:BB:BB:BB")
+                .address("CC:CC:CC:CC:CC:CC")
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("Speaker")
+                .address("DD:DD:DD:DD:DD:DD")
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("Speaker")
+                .address("EE:EE:EE:EE:EE:EE")
+                .build(),
+        ]);
+
+        for (selector, expected) in [
+            ("Office Keyboard", "Office Keyboard"),
+            ("mac:aa:aa:aa:aa:aa:aa", "AA:AA:AA:AA:AA:AA"),
+            ("name:Office Keyboard", "AA:AA:AA:AA:AA:AA"),
+            ("name:BB:BB:BB:BB:BB:BB", "CC:CC:CC:CC:CC:CC"),
+        ] {
+            let selector = selector.parse::<Selector>().unwrap();
+            assert_eq!(selector.target(&bluez).unwrap(), expected);
+        }
+
+        // NOTE: A `mac:` selector never matches an ALIAS, and a `name:` selector never matches an ADDRESS.
+        for selector in [
+            "mac:BB:BB:BB:BB:BB:BB",
+            "name:AA:AA:AA:AA:AA:AA",
+            "name:office",
+        ] {
+            let selector = selector.parse::<Selector>().unwrap();
+            assert!(matches!(
+                selector.target(&bluez),
+                Err(TargetError::NotFound(_))
+            ));
+        }
+
+        let selector = "name:Speaker".parse::<Selector>().unwrap();
+        assert!(matches!(
+            selector.target(&bluez),
+            Err(TargetError::Ambiguous(name)) if name == "Speaker"
+        ));
+
+        let selector = "idx:0".parse::<Selector>().unwrap();
+        assert!(matches!(selector.target(&bluez), Err(TargetError::Index)));
+    }
+
+    #[test]
+    fn it_should_resolve_each_selector_against_the_devices() {
+        let devices = test_devices();
+
+        for (selector, select_by) in [
+            ("idx:1", SelectBy::Name),
+            ("mac:bb:bb:bb:bb:bb:bb", SelectBy::Index),
+            ("name:mouse", SelectBy::Index),
+            ("1", SelectBy::Index),
+            ("mouse", SelectBy::Name),
+        ] {
+            let selector = selector.parse::<Selector>().unwrap();
+
+            assert!(matches!(
+                selector.resolve(&devices, &select_by),
+                NameMatch::Unique(1)
+            ));
+        }
+
+        let selector = "idx:2".parse::<Selector>().unwrap();
+        assert!(matches!(
+            selector.resolve(&devices, &SelectBy::Index),
+            NameMatch::NotFound
+        ));
+    }
+
+    #[test]
+    fn it_should_apply_the_name_filters() {
+        assert!(filter_by_name("LE-Keyboard", None, None));
+        assert!(filter_by_name("LE-Keyboard", Some("LE-"), Some("Bose")));
+        assert!(!filter_by_name("LE-Bose", Some("LE-"), Some("Bose")));
+        assert!(!filter_by_name("LE-Bose", None, Some("Bose")));
+        assert!(!filter_by_name("Mouse", Some("LE-"), None));
+    }
+
+    #[test]
+    fn it_should_match_the_name_case_insensitively_if_asked() {
+        assert!(contains_name("LE-Keyboard", "Key", false));
+        assert!(!contains_name("LE-Keyboard", "key", false));
+        assert!(contains_name("LE-Keyboard", "key", true));
+        assert!(contains_name("le-keyboard", "LE-KEY", true));
+        assert!(!contains_name("LE-Keyboard", "mouse", true));
+    }
+
+    #[test]
+    fn it_should_confirm_by_the_answer_or_the_default() {
+        for (answer, default, expected) in [
+            ("\n", true, true),
+            ("\n", false, false),
+            ("Yes\n", false, true),
+            ("y\n", false, true),
+            ("N\n", true, false),
+            ("no\n", true, false),
+            ("", true, false),
+        ] {
+            let mut in_buf = Cursor::new(answer.as_bytes().to_vec());
+
+            let result = confirm(&mut io::sink(), &mut in_buf, "Remove?", default);
+            assert_eq!(result.unwrap(), expected, "answer {:?}", answer);
+        }
+    }
+
+    #[test]
+    fn it_should_ask_again_if_the_answer_is_not_yes_or_no() {
+        let mut in_buf = Cursor::new("maybe\ny\n".as_bytes().to_vec());
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = confirm(&mut out_buf, &mut in_buf, "Remove?", false);
+        assert!(result.unwrap());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(
+            result,
+            "Remove? [y/N]: please answer y or n\nRemove? [y/N]: "
+        );
+    }
+}
@@ -0,0 +1,457 @@
+use std::{
+    env, error, fmt, fs,
+    io::{self, Write},
+    iter,
+    path::PathBuf,
+    process,
+};
+
+use clap::Parser;
+
+use crate::api::{BtCommand, Cli};
+
+/// Defines error variants that may be returned from a [`shell`] call.
+///
+/// [`shell`]: crate::shell
+#[derive(Debug)]
+pub enum Error {
+    /// Happens when [`shell`] cannot write to the provided [`io::Write`] or cannot read from the provided [`io::BufRead`].
+    ///
+    /// It holds the underlying [`io::Error`].
+    ///
+    /// [`shell`]: crate::shell
+    /// [`io::Error`]: std::io::Error
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "shell: io error: {}", error),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/// The prompt that [`shell`] writes before reading each line.
+///
+/// [`shell`]: crate::shell
+const PROMPT: &str = "bt> ";
+
+/// Runs an interactive shell, where each line read from the provided [`io::BufRead`] is run as a `bt` command against the same [`BluezClient`].
+///
+/// Each line is parsed like the arguments of `bt`, without the program name, e.g. `scan --duration 5` or `connect "Office Mouse"`. The arguments are split by whitespace, and a quoted argument (`"..."` or `'...'`) can contain whitespace. An empty line is skipped.
+///
+/// The output of each command is written to the provided [`io::Write`], and its warnings to `warn`. The last line of the output is ended before the next prompt. The commands that read an input (e.g. the interactive mode of [`connect`]) read it from the same [`io::BufRead`], on the following lines.
+///
+/// A line that cannot be parsed, or a command that fails, does not end the shell. Its error is written to `warn`, and the next line is read. The `--adapter` option selects the adapter for the rest of the shell. The `--deadline` option is not supported, and another shell cannot be started within the shell.
+///
+/// A `scan` keeps the device discovery running until the shell ends, since Bluez forgets an unpaired device shortly after the discovery that found it. Its devices are cached (see `args.cache` of [`ScanArgs`]) to a file of the shell under `$XDG_RUNTIME_DIR`, so that `connect IDX` connects to the device at IDX of the latest scan, e.g. `scan` followed by `connect 0`.
+///
+/// The shell ends on `exit` (or `quit`), or once the provided [`io::BufRead`] has no more lines (e.g. Ctrl-D). The discovery started by a `scan` is stopped then.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function can return all variants of [`ShellError`] based on given conditions. For more details, please see the error documentation.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+/// use bt::{shell, BluezClient};
+///
+/// let mut bluez_client = BluezClient::new().unwrap();
+///
+/// let result = shell(
+///     &mut bluez_client,
+///     &mut io::stdout(),
+///     &mut io::stderr(),
+///     &mut io::stdin().lock(),
+/// );
+/// if let Err(e) = result {
+///     eprintln!("{}", e);
+/// }
+/// ```
+///
+/// [`BluezClient`]: crate::BluezClient
+/// [`io::Write`]: std::io::Write
+/// [`io::BufRead`]: std::io::BufRead
+/// [`ShellError`]: crate::ShellError
+/// [`connect`]: crate::connect
+/// [`ScanArgs`]: crate::ScanArgs
+/// [`shell`]: crate::shell
+pub fn shell(
+    bluez: &mut crate::BluezClient,
+    w: &mut impl io::Write,
+    warn: &mut impl io::Write,
+    r: &mut impl io::BufRead,
+) -> Result<(), Error> {
+    let mut session = Session {
+        is_discovering: false,
+        scan_cache: scan_cache_path(),
+        last_scan: None,
+    };
+
+    let result = run_lines(bluez, w, warn, r, &mut session);
+
+    if session.is_discovering
+        && let Err(e) = bluez.stop_discovery()
+    {
+        writeln!(warn, "shell: warning: could not stop the discovery: {}", e)?;
+    }
+    // NOTE: The scan cache of the shell may not exist, e.g. if nothing was scanned.
+    if let Some(scan_cache) = &session.scan_cache {
+        let _ = fs::remove_file(scan_cache);
+    }
+
+    result
+}
+
+/// The state that [`shell`] keeps between its lines.
+struct Session {
+    /// Indicates whether the shell has started a discovery that is still running.
+    is_discovering: bool,
+
+    /// The file where the scans of the shell are cached, unless a scan sets its own `--cache`.
+    /// If it is [`None`], then the scans are cached to [`ScanSnapshot::cache_path()`].
+    ///
+    /// [`None`]: std::option::Option::None
+    /// [`ScanSnapshot::cache_path()`]: crate::ScanSnapshot::cache_path()
+    scan_cache: Option<PathBuf>,
+
+    /// The file where the latest scan is cached, which `connect IDX` connects from.
+    last_scan: Option<PathBuf>,
+}
+
+/// Provides the location of the scan cache of the shell, which is `$XDG_RUNTIME_DIR/bt/shell-<PID>.json`, or a file next to [`ScanSnapshot::cache_path()`] if `$XDG_RUNTIME_DIR` is not set.
+///
+/// [`ScanSnapshot::cache_path()`]: crate::ScanSnapshot::cache_path()
+fn scan_cache_path() -> Option<PathBuf> {
+    // NOTE: The cache is kept out of the shared temporary directory, where
+    // another user could create the file first, e.g. as a symlink.
+    let file_name = format!("shell-{}.json", process::id());
+
+    env::var_os("XDG_RUNTIME_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| PathBuf::from(dir).join("bt").join(&file_name))
+        .or_else(|| crate::ScanSnapshot::cache_path().map(|path| path.with_file_name(&file_name)))
+}
+
+/// Runs each line read from `r` as a `bt` command, until the shell ends. Please see [`shell`] for more details.
+fn run_lines(
+    bluez: &mut crate::BluezClient,
+    w: &mut impl io::Write,
+    warn: &mut impl io::Write,
+    r: &mut impl io::BufRead,
+    session: &mut Session,
+) -> Result<(), Error> {
+    let w = &mut LineTracker {
+        inner: w,
+        is_line_open: false,
+    };
+
+    loop {
+        // NOTE: The output of a command may not end its last line, e.g. the
+        // message of connect, so the line is ended before the prompt.
+        if w.is_line_open {
+            writeln!(w)?;
+        }
+        write!(w, "{}", PROMPT)?;
+        w.flush()?;
+        // NOTE: The line read after the prompt is ended by the user.
+        w.is_line_open = false;
+
+        let mut line = String::new();
+        if r.read_line(&mut line)? == 0 {
+            writeln!(w)?;
+            return Ok(());
+        }
+
+        let line = line.trim();
+        match line {
+            "" => continue,
+            "exit" | "quit" => return Ok(()),
+            _ => {}
+        }
+
+        let Some(words) = split_words(line) else {
+            writeln!(warn, "shell: a quoted argument is not closed")?;
+            continue;
+        };
+
+        let cli = match Cli::try_parse_from(iter::once(String::from("bt")).chain(words)) {
+            Ok(cli) => cli,
+            Err(e) => {
+                // NOTE: The help and the version are "errors" too, but they
+                // belong to the output.
+                if e.use_stderr() {
+                    write!(warn, "{}", e.render())?;
+                } else {
+                    write!(w, "{}", e.render())?;
+                }
+                continue;
+            }
+        };
+
+        if cli.deadline.is_some() {
+            writeln!(warn, "shell: --deadline is not supported in the shell")?;
+            continue;
+        }
+
+        if let Some(adapter) = &cli.adapter
+            && let Err(e) = bluez.select_adapter(adapter)
+        {
+            writeln!(warn, "bt: {}", e)?;
+            continue;
+        }
+
+        let command = match cli.into_command() {
+            Some(BtCommand::Shell) => {
+                writeln!(warn, "shell: the shell is already running")?;
+                continue;
+            }
+            Some(command) => session.prepare(bluez, command),
+            None => BtCommand::Status {
+                args: crate::StatusArgs::default(),
+            },
+        };
+
+        if let Err(e) = command.execute(bluez, w, warn, r) {
+            writeln!(warn, "bt: {}", e)?;
+        }
+    }
+}
+
+/// Keeps track of whether the last line written to `inner` is not ended yet, so that [`shell`] can end it before the prompt.
+///
+/// The writer is not generic, so that the shell within the shell does not nest the types of the writers.
+///
+/// [`shell`]: crate::shell
+struct LineTracker<'a> {
+    inner: &'a mut dyn io::Write,
+    is_line_open: bool,
+}
+
+impl io::Write for LineTracker<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        if let Some(last) = buf[..written].last() {
+            self.is_line_open = *last != b'\n';
+        }
+
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl Session {
+    /// Adapts `command` to the shell.
+    ///
+    /// A scan starts the discovery of the shell if it is not running yet, and it is cached. A connection to a bare IDX connects to the device at IDX of the latest scan.
+    fn prepare(&mut self, bluez: &crate::BluezClient, command: BtCommand) -> BtCommand {
+        match command {
+            BtCommand::Scan { mut args } => {
+                // NOTE: A discovery that is already running is not stopped by
+                // the scan, so it keeps running until the shell ends. If it
+                // cannot be started, then the scan reports the reason itself.
+                if !self.is_discovering && bluez.start_discovery().is_ok() {
+                    self.is_discovering = true;
+                }
+
+                if !args.cache {
+                    args.cache = true;
+                    args.cache_file = self.scan_cache.clone();
+                }
+                self.last_scan = args
+                    .cache_file
+                    .clone()
+                    .or_else(crate::ScanSnapshot::cache_path);
+
+                BtCommand::Scan { args }
+            }
+            BtCommand::Connect { mut args } => {
+                let index = args.alias.as_deref().and_then(|a| a.parse::<usize>().ok());
+                if let (Some(index), None, Some(last_scan)) = (index, args.index, &self.last_scan) {
+                    args.alias = None;
+                    args.index = Some(index);
+                    args.cache_file = Some(last_scan.clone());
+                }
+
+                BtCommand::Connect { args }
+            }
+            command => command,
+        }
+    }
+}
+
+/// Splits `line` into arguments by whitespace, where a quoted argument can contain whitespace.
+///
+/// It returns [`None`] if a quote is not closed.
+///
+/// [`None`]: std::option::Option::None
+fn split_words(line: &str) -> Option<Vec<String>> {
+    let mut words = vec![];
+    let mut word: Option<String> = None;
+    let mut quote = None;
+
+    for c in line.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.get_or_insert_default().push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                word.get_or_insert_default();
+            }
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, c) => word.get_or_insert_default().push(c),
+        }
+    }
+
+    if quote.is_some() {
+        return None;
+    }
+    words.extend(word);
+
+    Some(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::bluez;
+
+    #[test]
+    fn it_should_run_each_line_against_the_same_client() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("Office Mouse")
+                .address("AA:AA:AA:AA:AA:AA")
+                .connected(true)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("speaker")
+                .address("BB:BB:BB:BB:BB:BB")
+                .build(),
+        ]);
+
+        let mut in_buf = Cursor::new(
+            "ls --aliases\n\n--bogus\ndisconnect \"Office Mouse\"\nis-on\nexit\nls --aliases\n"
+                .as_bytes()
+                .to_vec(),
+        );
+        let mut out_buf = Cursor::new(vec![]);
+        let mut warn_buf = Cursor::new(vec![]);
+
+        let result = shell(&mut bluez, &mut out_buf, &mut warn_buf, &mut in_buf);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(
+            result,
+            "bt> Office Mouse\nspeaker\n\
+             bt> bt> \
+             bt> disconnected from device Office Mouse\n\
+             bt> bt> "
+        );
+
+        let warnings = String::from_utf8(warn_buf.into_inner()).unwrap();
+        assert!(warnings.starts_with("error: unexpected argument '--bogus'"));
+    }
+
+    #[test]
+    fn it_should_connect_to_a_device_of_the_latest_scan_by_index() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("Office Mouse")
+                .address("AA:AA:AA:AA:AA:AA")
+                .rssi(Some(-40))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("speaker")
+                .address("BB:BB:BB:BB:BB:BB")
+                .rssi(Some(-50))
+                .build(),
+        ]);
+
+        let mut in_buf = Cursor::new(
+            "scan --duration 0 --values alias\nconnect 1\nexit\n"
+                .as_bytes()
+                .to_vec(),
+        );
+        let mut out_buf = Cursor::new(vec![]);
+        let mut warn_buf = Cursor::new(vec![]);
+
+        let result = shell(&mut bluez, &mut out_buf, &mut warn_buf, &mut in_buf);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(
+            result,
+            "bt> Office Mouse\nspeaker\n\
+             bt> connected to device: speaker\n\
+             bt> "
+        );
+        assert!(warn_buf.into_inner().is_empty());
+
+        assert_eq!(bluez.connected_aliases(), vec!["BB:BB:BB:BB:BB:BB"]);
+        // NOTE: The discovery of the scan keeps running until the shell ends.
+        assert_eq!(
+            bluez.discovery_calls(),
+            vec!["start_discovery", "stop_discovery"]
+        );
+    }
+
+    #[test]
+    fn it_should_end_the_last_output_line_before_the_prompt() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+
+        let mut in_buf = Cursor::new("connect known_dev\nexit\n".as_bytes().to_vec());
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = shell(&mut bluez, &mut out_buf, &mut io::sink(), &mut in_buf);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "bt> connected to device: known_dev\nbt> ");
+    }
+
+    #[test]
+    fn it_should_end_the_shell_once_the_input_ends() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = shell(&mut bluez, &mut out_buf, &mut io::sink(), &mut io::empty());
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "bt> \n");
+    }
+
+    #[test]
+    fn it_should_split_the_quoted_words() {
+        assert_eq!(
+            split_words("connect  \"Office Mouse\" --select-by ''"),
+            Some(vec![
+                String::from("connect"),
+                String::from("Office Mouse"),
+                String::from("--select-by"),
+                String::new(),
+            ])
+        );
+        assert_eq!(split_words("connect \"Office"), None);
+    }
+}
@@ -1,6 +1,16 @@
-use std::{error, fmt, io};
+use std::{
+    error, fmt, fs, io,
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
 
-use crate::BluezError;
+use clap::Args;
+
+use crate::{
+    BluezConnectionState, BluezError, bluez,
+    format::{self, ColorMode, TableFormattable, TerseFormatter},
+};
 
 /// Defines error variants that may be returned from a [`status`] call.
 ///
@@ -45,6 +55,124 @@ impl From<io::Error> for Error {
     }
 }
 
+/// Defines the arguments that [`status`] can take.
+///
+/// [`status`]: crate::status
+#[derive(Debug, Args)]
+pub struct StatusArgs {
+    /// Set the output format.
+    #[arg(short, long, value_enum, default_value_t = StatusFormat::Human)]
+    pub format: StatusFormat,
+
+    /// Set the field delimiter of the terse output.
+    ///
+    /// This option has no effect if the format is not terse.
+    #[arg(short, long, default_value = "\t")]
+    pub delimiter: String,
+
+    /// Color the battery percentages by their level.
+    ///
+    /// This option has no effect if the format is not human, or with --fifo.
+    #[arg(long, value_enum, default_value_t = ColorMode::Auto)]
+    pub color: ColorMode,
+
+    /// Only show the power state of the adapter, without reading the connected devices.
+    #[arg(long, conflicts_with = "format")]
+    pub adapter_only: bool,
+
+    /// Show a labeled status section for each adapter on the host, with the devices connected through it.
+    #[arg(long, conflicts_with = "format")]
+    pub all_adapters: bool,
+
+    /// Keep reading the status on every interval, and write it again whenever it changes.
+    #[arg(short, long, default_value_t = false)]
+    pub watch: bool,
+
+    /// Set the interval between the reads in seconds.
+    ///
+    /// This option has no effect without --watch.
+    #[arg(short, long, default_value_t = 1u64)]
+    pub interval: u64,
+
+    /// Stop watching after COUNT reads.
+    ///
+    /// This option has no effect without --watch.
+    #[arg(short, long, value_name = "COUNT")]
+    pub count: Option<u32>,
+
+    /// Write the watched status to the named pipe at PATH instead, e.g. for a status bar to tail.
+    ///
+    /// Opening the pipe blocks until a reader connects. The pipe is reopened when its reader goes away.
+    #[arg(long, value_name = "PATH", requires = "watch")]
+    pub fifo: Option<PathBuf>,
+}
+
+impl Default for StatusArgs {
+    fn default() -> Self {
+        Self {
+            format: StatusFormat::Human,
+            delimiter: String::from("\t"),
+            color: ColorMode::Auto,
+            adapter_only: false,
+            all_adapters: false,
+            watch: false,
+            interval: 1,
+            count: None,
+            fifo: None,
+        }
+    }
+}
+
+/// Defines the output formats of [`status`].
+///
+/// [`status`]: crate::status
+#[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
+pub enum StatusFormat {
+    /// Human shows the adapter status and the connected devices in a human readable form.
+    Human,
+
+    /// Terse shows only the connected devices, one per line, with their fields separated by a delimiter.
+    Terse,
+}
+
+#[derive(Clone, Copy)]
+enum StatusColumn {
+    Alias,
+    Address,
+    Battery,
+}
+
+impl From<&StatusColumn> for String {
+    fn from(value: &StatusColumn) -> Self {
+        let str = match value {
+            StatusColumn::Alias => "ALIAS",
+            StatusColumn::Address => "ADDRESS",
+            StatusColumn::Battery => "BATTERY",
+        };
+
+        str.to_string()
+    }
+}
+
+impl TableFormattable<StatusColumn> for bluez::BluezDevice {
+    fn get_cell_value_by_column(&self, column: &StatusColumn) -> String {
+        match column {
+            StatusColumn::Alias => self.alias().to_string(),
+            StatusColumn::Address => self.address().to_string(),
+            StatusColumn::Battery => match self.battery() {
+                Some(battery) => battery.to_string(),
+                None => "-".to_string(),
+            },
+        }
+    }
+}
+
+const TERSE_COLUMNS: [StatusColumn; 3] = [
+    StatusColumn::Alias,
+    StatusColumn::Address,
+    StatusColumn::Battery,
+];
+
 /// Provides the Bluetooth adapter status and connected Device-MAC address pairs by using a [`BluezClient`].
 ///
 /// The Bluetooth adapter status and Device-MAC address pairs are written to the provided [`io::Write`].
@@ -61,10 +189,31 @@ impl From<io::Error> for Error {
 /// DevN/AddrN (batt: battN%)
 /// ```
 ///
+/// If `args.format` is [`StatusFormat::Terse`], then only the connected devices are written, one per line. Their alias, address and battery are separated by `args.delimiter` (a tab by default), which makes the output easy to parse:
+///
+/// ```txt
+/// Dev1\tAddr1\tBatt1
+/// Dev2\tAddr2\tBatt2
+/// ```
+///
+/// In human format, the battery percentages are colored by their level (green, yellow or red) based on `args.color`. By default, they are colored only if stdout is a terminal.
+///
+/// In human format, the devices that are still connecting (connected, but their services are not resolved yet) are marked with `(connecting)`.
+///
+/// In both formats, an unknown battery percentage is shown as `-`. A connected device is still listed when its battery percentage cannot be read.
+///
+/// On many distributions, Bluez exposes the battery percentages only if its experimental features are enabled. If there are connected devices but none of them report a battery percentage, then a hint about it is written to the provided `warn` [`io::Write`], which is usually stderr.
+///
+/// If `args.adapter_only` is `true`, then only the `bluetooth: <power state>` line is written, and the connected devices are not read at all.
+///
+/// If `args.all_adapters` is `true`, then the human status is written for each adapter on the host, under an `adapter <name>:` label, and each section lists the devices connected through that adapter. The sections are separated by an empty line. It can be combined with `args.adapter_only`.
+///
+/// If `args.watch` is `true`, then [`status`] keeps reading the status on every `args.interval` seconds, and writes it again only when it changes. Each written status ends with a line break. The watch stops after `args.count` reads, or never if `args.count` is [`None`].
+/// If `args.fifo` is [`Some`], then the watched status is written to that named pipe instead of the provided [`io::Write`], e.g. for a status bar to tail. The pipe is kept open across the updates, and reopened when its reader goes away.
+///
 /// # Panics
 ///
-/// This function panics when the battery percentage of a connected device is not known.
-/// [`status`] assumes that all connected devices have their battery percentages and [`BluezClient`] is able to provide those.
+/// This function does not panic.
 ///
 /// # Errors
 ///
@@ -74,6 +223,9 @@ impl From<io::Error> for Error {
 /// [`io::Write`]: std::io::Write
 /// [`StatusError`]: crate::StatusError
 /// [`status`]: crate::status
+/// [`StatusFormat::Terse`]: crate::StatusFormat::Terse
+/// [`None`]: std::option::Option::None
+/// [`Some`]: std::option::Option::Some
 ///
 /// # Examples
 ///
@@ -81,38 +233,198 @@ impl From<io::Error> for Error {
 ///
 /// ```no_run
 /// use std::io::Cursor;
-/// use bt::{status, BluezClient};
+/// use bt::{status, BluezClient, StatusArgs};
 ///
 /// let bluez_client = BluezClient::new().unwrap();
+/// let mut warnings = std::io::stderr();
 /// let mut output = Cursor::new(vec![]);
 ///
-/// let status_result = status(&bluez_client, &mut output);
+/// let status_result = status(&bluez_client, &mut output, &mut warnings, &StatusArgs::default());
 ///
 /// assert!(status_result.is_ok());
 /// let status_str = String::from_utf8(output.into_inner()).unwrap();
 /// assert_eq!(status_str, "bluetooth: enabled\nconnected devices:\nDev1/Addr1\nDev2/Addr2");
 ///```
 ///
+/// Here is a terse [`status`] call that separates the fields by `,`.
+///
+/// ```no_run
+/// use std::io::Cursor;
+/// use bt::{status, BluezClient, StatusArgs, StatusFormat};
+///
+/// let bluez_client = BluezClient::new().unwrap();
+/// let mut warnings = std::io::stderr();
+/// let mut output = Cursor::new(vec![]);
+///
+/// let args = StatusArgs {
+///     format: StatusFormat::Terse,
+///     delimiter: String::from(","),
+///     ..Default::default()
+/// };
+///
+/// let status_result = status(&bluez_client, &mut output, &mut warnings, &args);
+///
+/// assert!(status_result.is_ok());
+/// let status_str = String::from_utf8(output.into_inner()).unwrap();
+/// assert_eq!(status_str, "Dev1,Addr1,50\nDev2,Addr2,42\n");
+///```
+///
 /// Here is an error case. The example triggers an [`io::Error`] by passing an array as a buffer, instead of a growable buffer.
 ///
 /// ```no_run
 /// use std::io::Cursor;
-/// use bt::{status, BluezClient, StatusError};
+/// use bt::{status, BluezClient, StatusArgs, StatusError};
 ///
 /// let bluez_client = BluezClient::new().unwrap();
+/// let mut warnings = std::io::stderr();
 /// let mut output = Cursor::new([]);
 ///
-/// let status_result = status(&bluez_client, &mut output);
+/// let status_result = status(&bluez_client, &mut output, &mut warnings, &StatusArgs::default());
 ///
 /// match status_result {
 ///     Err(StatusError::Io(err)) => eprintln!("{}", err),
 ///     _ => unreachable!(),
 /// }
 ///```
-pub fn status(bluez: &crate::BluezClient, f: &mut impl io::Write) -> Result<(), Error> {
+pub fn status(
+    bluez: &crate::BluezClient,
+    f: &mut impl io::Write,
+    warn: &mut impl io::Write,
+    args: &StatusArgs,
+) -> Result<(), Error> {
+    if args.watch {
+        return match &args.fifo {
+            Some(path) => watch(bluez, &mut FifoWriter::open(path)?, warn, args),
+            None => watch(bluez, f, warn, args),
+        };
+    }
+
+    let out_buf = render(bluez, warn, args)?;
+    f.write_all(out_buf.as_bytes())?;
+
+    Ok(())
+}
+
+/// Writes the status on every `args.interval` seconds, but only when it differs from the last written one.
+///
+/// Each written status ends with a line break, so that a reader can tell the updates apart.
+fn watch(
+    bluez: &crate::BluezClient,
+    f: &mut impl io::Write,
+    warn: &mut impl io::Write,
+    args: &StatusArgs,
+) -> Result<(), Error> {
+    let mut last_update = None;
+
+    let mut reads = 0;
+    loop {
+        // The battery hint is only written once, instead of on every read.
+        let mut update = if reads == 0 {
+            render(bluez, warn, args)?
+        } else {
+            render(bluez, &mut io::sink(), args)?
+        };
+        if !update.ends_with('\n') {
+            update.push('\n');
+        }
+
+        if last_update.as_ref() != Some(&update) {
+            f.write_all(update.as_bytes())?;
+            f.flush()?;
+            last_update = Some(update);
+        }
+
+        reads += 1;
+        if args.count.is_some_and(|count| reads >= count) {
+            break;
+        }
+
+        thread::sleep(Duration::from_secs(args.interval));
+    }
+
+    Ok(())
+}
+
+/// Renders the status in `args.format`.
+fn render(
+    bluez: &crate::BluezClient,
+    warn: &mut impl io::Write,
+    args: &StatusArgs,
+) -> Result<String, Error> {
+    if args.all_adapters {
+        return render_all_adapters(bluez, warn, args);
+    }
+
+    if args.adapter_only {
+        let power_state = bluez.power_state()?;
+
+        return Ok(format!("bluetooth: {}", power_state));
+    }
+
+    if args.format == StatusFormat::Terse {
+        let connected_devs = bluez.connected_devices()?;
+        warn_if_no_battery(warn, &connected_devs)?;
+        let out_buf = connected_devs
+            .into_iter()
+            .to_terse_with_delimiter(&TERSE_COLUMNS, &args.delimiter)
+            .to_string();
+
+        return Ok(out_buf);
+    }
+
     let power_state = bluez.power_state()?;
     let connected_devs = bluez.connected_devices()?;
+    warn_if_no_battery(warn, &connected_devs)?;
+
+    Ok(render_human(
+        &power_state,
+        connected_devs.iter(),
+        is_colored(args),
+    ))
+}
+
+/// Renders a labeled human status for each adapter, where each section only lists the devices connected through its adapter.
+fn render_all_adapters(
+    bluez: &crate::BluezClient,
+    warn: &mut impl io::Write,
+    args: &StatusArgs,
+) -> Result<String, Error> {
+    let connected_devs = if args.adapter_only {
+        vec![]
+    } else {
+        bluez.connected_devices()?
+    };
+    warn_if_no_battery(warn, &connected_devs)?;
 
+    let mut sections = vec![];
+    for adapter in bluez.adapters()? {
+        let power_state = bluez.adapter_power_state(&adapter)?;
+        let section = if args.adapter_only {
+            format!("bluetooth: {}", power_state)
+        } else {
+            let adapter_devs = connected_devs.iter().filter(|d| d.adapter() == adapter);
+            render_human(&power_state, adapter_devs, is_colored(args))
+        };
+
+        sections.push(format!("adapter {}:\n{}", adapter, section));
+    }
+
+    Ok(sections.join("\n\n"))
+}
+
+/// Indicates whether the battery percentages are colored by `args.color`.
+///
+/// The status written to `args.fifo` is never colored, since the reader of the pipe (e.g. a status bar) does not render the color codes like a terminal.
+fn is_colored(args: &StatusArgs) -> bool {
+    args.fifo.is_none() && args.color.is_enabled()
+}
+
+/// Renders the power state and the connected devices in human format.
+fn render_human<'a>(
+    power_state: &bluez::BluezPowerState,
+    connected_devs: impl Iterator<Item = &'a bluez::BluezDevice>,
+    color: bool,
+) -> String {
     let mut buf = [
         "bluetooth: ",
         &power_state.to_string(),
@@ -120,16 +432,67 @@ pub fn status(bluez: &crate::BluezClient, f: &mut impl io::Write) -> Result<(),
     ]
     .join("");
     for dev in connected_devs {
-        let format = format!(
-            "\n{}/{} (batt: %{})",
-            dev.alias(),
-            dev.address(),
-            dev.battery().unwrap()
-        );
+        let battery = match dev.battery() {
+            Some(battery) => format::format_battery(*battery, color),
+            None => String::from("-"),
+        };
+        let mut format = format!("\n{}/{} (batt: {})", dev.alias(), dev.address(), battery);
+        if dev.connection_state() == BluezConnectionState::Connecting {
+            format.push_str(" (connecting)");
+        }
         buf.push_str(&format)
     }
 
-    f.write_all(buf.as_bytes())?;
+    buf
+}
+
+/// Defines a writer to a named pipe, which is kept open across the writes.
+///
+/// When the reader of the pipe goes away, the pipe is reopened and the write is retried once.
+struct FifoWriter {
+    path: PathBuf,
+    file: fs::File,
+}
+
+impl FifoWriter {
+    /// Opens the named pipe at `path`, which blocks until a reader connects.
+    fn open(path: &Path) -> io::Result<Self> {
+        let file = fs::OpenOptions::new().append(true).open(path)?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+        })
+    }
+}
+
+impl io::Write for FifoWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.file.write(buf) {
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {
+                *self = Self::open(&self.path)?;
+                self.file.write(buf)
+            }
+            result => result,
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Hints that the experimental features of Bluez may be disabled, if there are connected devices but none of them report a battery percentage.
+fn warn_if_no_battery(
+    warn: &mut impl io::Write,
+    connected_devs: &[bluez::BluezDevice],
+) -> Result<(), Error> {
+    if !connected_devs.is_empty() && connected_devs.iter().all(|d| d.battery().is_none()) {
+        writeln!(
+            warn,
+            "status: hint: none of the connected devices report a battery, the experimental features of Bluez may be disabled (`Experimental = true` in /etc/bluetooth/main.conf)"
+        )?;
+    }
 
     Ok(())
 }
@@ -140,12 +503,19 @@ mod tests {
 
     use super::*;
 
+    fn plain_args() -> StatusArgs {
+        StatusArgs {
+            color: ColorMode::Never,
+            ..Default::default()
+        }
+    }
+
     #[test]
     fn it_should_write_bluetooth_status() {
         let bluez = crate::BluezClient::new().unwrap();
         let mut out_buf = Cursor::new(vec![]);
 
-        status(&bluez, &mut out_buf).unwrap();
+        status(&bluez, &mut out_buf, &mut io::sink(), &plain_args()).unwrap();
 
         let connected_device = &bluez.connected_devices().unwrap()[0];
         let expected = format!(
@@ -167,7 +537,12 @@ mod tests {
 
         let mut out_buf = Cursor::new(vec![]);
 
-        let result = status(&bluez, &mut out_buf);
+        let result = status(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &StatusArgs::default(),
+        );
 
         assert!(result.is_err())
     }
@@ -179,11 +554,56 @@ mod tests {
 
         let mut out_buf = Cursor::new(vec![]);
 
-        let result = status(&bluez, &mut out_buf);
+        let result = status(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &StatusArgs::default(),
+        );
 
         assert!(result.is_err())
     }
 
+    #[test]
+    fn it_should_only_write_the_power_state_if_adapter_only_is_set() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_erred_method_name("connected_devices".to_string());
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = StatusArgs {
+            adapter_only: true,
+            ..plain_args()
+        };
+        let result = status(&bluez, &mut out_buf, &mut io::sink(), &args);
+
+        assert!(result.is_ok());
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "bluetooth: enabled");
+    }
+
+    #[test]
+    fn it_should_hint_if_no_connected_device_reports_a_battery() {
+        for (battery, has_hint) in [(None, true), (Some(50), false)] {
+            let mut bluez = crate::BluezClient::new().unwrap();
+            bluez.set_devices(vec![
+                bluez::BluezTestDeviceBuilder::new("dev1")
+                    .address("AA:AA:AA:AA:AA:AA")
+                    .connected(true)
+                    .battery(battery)
+                    .build(),
+            ]);
+
+            let mut warn_buf = Cursor::new(vec![]);
+
+            let result = status(&bluez, &mut io::sink(), &mut warn_buf, &plain_args());
+            assert!(result.is_ok());
+
+            let warning = String::from_utf8(warn_buf.into_inner()).unwrap();
+            assert_eq!(warning.starts_with("status: hint: "), has_hint);
+        }
+    }
+
     #[test]
     fn it_should_fail_when_result_cannot_be_written_to_buf() {
         let bluez = crate::BluezClient::new().unwrap();
@@ -191,8 +611,384 @@ mod tests {
         let mut out_buf = Cursor::new([]);
         out_buf.set_position(1);
 
-        let result = status(&bluez, &mut out_buf);
+        let result = status(
+            &bluez,
+            &mut out_buf,
+            &mut io::sink(),
+            &StatusArgs::default(),
+        );
 
         assert!(result.is_err())
     }
+
+    #[test]
+    fn it_should_write_connected_devices_in_terse_format() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .connected(true)
+                .battery(Some(50))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .address("BB:BB:BB:BB:BB:BB")
+                .connected(true)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev3")
+                .address("CC:CC:CC:CC:CC:CC")
+                .build(),
+        ]);
+
+        let mut args = StatusArgs {
+            format: StatusFormat::Terse,
+            ..Default::default()
+        };
+
+        let mut out_buf = Cursor::new(vec![]);
+        status(&bluez, &mut out_buf, &mut io::sink(), &args).unwrap();
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(
+            result,
+            "dev1\tAA:AA:AA:AA:AA:AA\t50\ndev2\tBB:BB:BB:BB:BB:BB\t-\n"
+        );
+
+        args.delimiter = String::from(";");
+
+        let mut out_buf = Cursor::new(vec![]);
+        status(&bluez, &mut out_buf, &mut io::sink(), &args).unwrap();
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        let fields = result
+            .lines()
+            .next()
+            .unwrap()
+            .split(';')
+            .collect::<Vec<&str>>();
+        assert_eq!(fields, vec!["dev1", "AA:AA:AA:AA:AA:AA", "50"]);
+    }
+
+    #[test]
+    fn it_should_keep_connected_devices_whose_battery_cannot_be_read() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .connected(true)
+                .battery(Some(50))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .address("BB:BB:BB:BB:BB:BB")
+                .connected(true)
+                .battery(Some(80))
+                .build(),
+        ]);
+        bluez.set_erred_method_name("battery".to_string());
+
+        for (format, expected) in [
+            (
+                StatusFormat::Human,
+                "bluetooth: enabled\nconnected devices: \ndev1/AA:AA:AA:AA:AA:AA (batt: -)\ndev2/BB:BB:BB:BB:BB:BB (batt: -)",
+            ),
+            (
+                StatusFormat::Terse,
+                "dev1\tAA:AA:AA:AA:AA:AA\t-\ndev2\tBB:BB:BB:BB:BB:BB\t-\n",
+            ),
+        ] {
+            let args = StatusArgs {
+                format,
+                ..plain_args()
+            };
+
+            let mut out_buf = Cursor::new(vec![]);
+            status(&bluez, &mut out_buf, &mut io::sink(), &args).unwrap();
+
+            let result = String::from_utf8(out_buf.into_inner()).unwrap();
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn it_should_write_only_the_connected_devices() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .connected(true)
+                .battery(Some(50))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .address("BB:BB:BB:BB:BB:BB")
+                .battery(Some(80))
+                .build(),
+        ]);
+
+        for (format, expected) in [
+            (
+                StatusFormat::Human,
+                "bluetooth: enabled\nconnected devices: \ndev1/AA:AA:AA:AA:AA:AA (batt: %50)",
+            ),
+            (StatusFormat::Terse, "dev1\tAA:AA:AA:AA:AA:AA\t50\n"),
+        ] {
+            let args = StatusArgs {
+                format,
+                ..plain_args()
+            };
+
+            let mut out_buf = Cursor::new(vec![]);
+            let result = status(&bluez, &mut out_buf, &mut io::sink(), &args);
+            assert!(result.is_ok());
+
+            let result = String::from_utf8(out_buf.into_inner()).unwrap();
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn it_should_mark_connecting_devices() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .connected(true)
+                .services_resolved(false)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .address("BB:BB:BB:BB:BB:BB")
+                .connected(true)
+                .battery(Some(50))
+                .build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+        status(&bluez, &mut out_buf, &mut io::sink(), &plain_args()).unwrap();
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(
+            result,
+            "bluetooth: enabled\nconnected devices: \ndev1/AA:AA:AA:AA:AA:AA (batt: -) (connecting)\ndev2/BB:BB:BB:BB:BB:BB (batt: %50)"
+        );
+    }
+
+    #[test]
+    fn it_should_color_battery_percentages_only_if_color_is_enabled() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .connected(true)
+                .battery(Some(80))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .address("BB:BB:BB:BB:BB:BB")
+                .connected(true)
+                .battery(Some(10))
+                .build(),
+        ]);
+
+        let mut args = StatusArgs {
+            color: ColorMode::Always,
+            ..Default::default()
+        };
+
+        let mut out_buf = Cursor::new(vec![]);
+        status(&bluez, &mut out_buf, &mut io::sink(), &args).unwrap();
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert!(result.contains("(batt: \x1b[32m%80\x1b[0m)"));
+        assert!(result.contains("(batt: \x1b[31m%10\x1b[0m)"));
+
+        args.color = ColorMode::Never;
+
+        let mut out_buf = Cursor::new(vec![]);
+        status(&bluez, &mut out_buf, &mut io::sink(), &args).unwrap();
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert!(result.contains("(batt: %80)"));
+        assert!(result.contains("(batt: %10)"));
+        assert!(!result.contains('\x1b'));
+    }
+
+    #[test]
+    fn it_should_show_if_bluetooth_is_blocked_by_rfkill() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_power_state(bluez::BluezPowerState::Blocked);
+        bluez.set_devices(vec![]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let result = status(&bluez, &mut out_buf, &mut io::sink(), &plain_args());
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "bluetooth: blocked (rfkill)\nconnected devices: ");
+    }
+
+    #[test]
+    fn it_should_write_each_watched_update_to_the_fifo() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .connected(true)
+                .battery(Some(50))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .address("BB:BB:BB:BB:BB:BB")
+                .connected(true)
+                .battery(Some(42))
+                .build(),
+        ]);
+        bluez.set_dropped_alias("dev1".to_string());
+
+        // A regular file stands in for the named pipe, since both are opened the same way.
+        let path = std::env::temp_dir().join(format!("bt-status-fifo-{}", std::process::id()));
+        fs::write(&path, "").unwrap();
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = StatusArgs {
+            format: StatusFormat::Terse,
+            watch: true,
+            interval: 0,
+            count: Some(2),
+            fifo: Some(path.clone()),
+            ..plain_args()
+        };
+
+        let result = status(&bluez, &mut out_buf, &mut io::sink(), &args);
+        let written = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(result.is_ok());
+
+        assert!(out_buf.into_inner().is_empty());
+        assert_eq!(
+            written,
+            "dev1\tAA:AA:AA:AA:AA:AA\t50\ndev2\tBB:BB:BB:BB:BB:BB\t42\n\
+             dev2\tBB:BB:BB:BB:BB:BB\t42\n"
+        );
+    }
+
+    #[test]
+    fn it_should_not_color_the_status_written_to_the_fifo() {
+        let bluez = crate::BluezClient::new().unwrap();
+
+        let path =
+            std::env::temp_dir().join(format!("bt-status-fifo-color-{}", std::process::id()));
+        fs::write(&path, "").unwrap();
+
+        let args = StatusArgs {
+            color: ColorMode::Always,
+            watch: true,
+            interval: 0,
+            count: Some(1),
+            fifo: Some(path.clone()),
+            ..plain_args()
+        };
+
+        let result = status(&bluez, &mut io::sink(), &mut io::sink(), &args);
+        let written = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(result.is_ok());
+
+        assert!(!written.contains('\x1b'));
+        assert!(written.contains("(batt: %50)"));
+    }
+
+    #[test]
+    fn it_should_reopen_the_fifo_once_its_reader_goes_away() {
+        use io::{BufRead, Read, Write};
+
+        let path =
+            std::env::temp_dir().join(format!("bt-status-fifo-reopen-{}", std::process::id()));
+        let created = std::process::Command::new("mkfifo").arg(&path).status();
+        assert!(created.unwrap().success());
+
+        let first_reader = {
+            let path = path.clone();
+            thread::spawn(move || {
+                let mut line = String::new();
+                io::BufReader::new(fs::File::open(path).unwrap())
+                    .read_line(&mut line)
+                    .unwrap();
+                line
+            })
+        };
+
+        let mut fifo = FifoWriter::open(&path).unwrap();
+        fifo.write_all(b"first\n").unwrap();
+        assert_eq!(first_reader.join().unwrap(), "first\n");
+
+        // NOTE: The second reader connects once the write has already hit
+        // the broken pipe, so that the write has to reopen the pipe.
+        let second_reader = {
+            let path = path.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(100));
+
+                let mut written = String::new();
+                fs::File::open(path)
+                    .unwrap()
+                    .read_to_string(&mut written)
+                    .unwrap();
+                written
+            })
+        };
+
+        fifo.write_all(b"second\n").unwrap();
+        drop(fifo);
+        assert_eq!(second_reader.join().unwrap(), "second\n");
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn it_should_write_a_labeled_status_for_each_adapter() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_adapters(&[
+            ("hci0", bluez::BluezPowerState::On),
+            ("hci1", bluez::BluezPowerState::Off),
+        ]);
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("dev1")
+                .address("AA:AA:AA:AA:AA:AA")
+                .adapter("hci1")
+                .connected(true)
+                .battery(Some(50))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev2")
+                .address("BB:BB:BB:BB:BB:BB")
+                .adapter("hci0")
+                .connected(true)
+                .battery(Some(42))
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("dev3")
+                .address("CC:CC:CC:CC:CC:CC")
+                .adapter("hci1")
+                .build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = StatusArgs {
+            all_adapters: true,
+            ..plain_args()
+        };
+        let result = status(&bluez, &mut out_buf, &mut io::sink(), &args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(
+            result,
+            "adapter hci0:\n\
+             bluetooth: enabled\n\
+             connected devices: \n\
+             dev2/BB:BB:BB:BB:BB:BB (batt: %42)\n\
+             \n\
+             adapter hci1:\n\
+             bluetooth: disabled\n\
+             connected devices: \n\
+             dev1/AA:AA:AA:AA:AA:AA (batt: %50)"
+        );
+    }
 }
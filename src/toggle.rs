@@ -1,6 +1,8 @@
-use std::{error, fmt, io};
+use std::{error, fmt, io, time::Instant};
 
-use crate::BluezError;
+use clap::Args;
+
+use crate::{BluezError, bluez::BluezPowerState};
 
 /// Defines error variants that may be returned from a [`toggle`] call.
 ///
@@ -20,6 +22,9 @@ pub enum Error {
     /// [`toggle`]: crate::toggle
     /// [`io::Error`]: std::io::Error
     Io(io::Error),
+
+    /// Happens when the adapter needs to be powered on, but Bluetooth is blocked by an rfkill switch.
+    Blocked,
 }
 
 impl fmt::Display for Error {
@@ -29,6 +34,7 @@ impl fmt::Display for Error {
                 write!(f, "toggle: bluez error: {}", error)
             }
             Error::Io(error) => write!(f, "toggle: io error: {}", error),
+            Error::Blocked => write!(f, "toggle: {}", BluezError::Blocked),
         }
     }
 }
@@ -47,12 +53,52 @@ impl From<io::Error> for Error {
     }
 }
 
+/// Defines the arguments that [`toggle`] can take.
+///
+/// [`toggle`]: crate::toggle
+#[derive(Debug, Default, Args)]
+pub struct ToggleArgs {
+    /// Operate on every Bluetooth adapter on the host.
+    #[arg(short, long, default_value_t = false)]
+    pub all: bool,
+
+    /// Power the adapter(s) on instead of toggling.
+    #[arg(long, default_value_t = false, conflicts_with = "off")]
+    pub on: bool,
+
+    /// Power the adapter(s) off instead of toggling.
+    #[arg(long, default_value_t = false)]
+    pub off: bool,
+
+    /// Append the duration of setting the power state to the output, e.g. `(took 234ms)`.
+    #[arg(long, default_value_t = false)]
+    pub timing: bool,
+}
+
+impl ToggleArgs {
+    fn power_state(&self) -> Option<BluezPowerState> {
+        match (self.on, self.off) {
+            (true, _) => Some(BluezPowerState::On),
+            (_, true) => Some(BluezPowerState::Off),
+            _ => None,
+        }
+    }
+}
+
 /// Provides the ability of toggling the Bluetooth adapter status by using a [`BluezClient`].
 ///
 /// The updated Bluetooth adapter status is written to the provided [`io::Write`].
 ///
 /// The format of the Bluetooth status depends on [`BluezClient`].
 ///
+/// If `args.on` or `args.off` is `true`, then the adapter is powered on or off instead of being toggled.
+///
+/// If Bluetooth is blocked by an rfkill switch, then the adapter cannot be powered on, and [`toggle`] fails with [`ToggleError::Blocked`] instead.
+///
+/// If `args.all` is `true`, then every adapter on the host is toggled (or powered on/off), and the resulting state of each adapter is written on its own line, e.g. `hci0: enabled`.
+///
+/// If `args.timing` is `true`, then the time it takes to set the power state is appended to the output, e.g. `bluetooth: enabled (took 234ms)`. With `args.all`, each adapter is measured on its own.
+///
 /// # Panics
 ///
 /// This function does not panic.
@@ -64,6 +110,7 @@ impl From<io::Error> for Error {
 /// [`BluezClient`]: crate::BluezClient
 /// [`io::Write`]: std::io::Write
 /// [`ToggleError`]: crate::ToggleError
+/// [`ToggleError::Blocked`]: crate::ToggleError::Blocked
 /// [`toggle`]: crate::toggle
 ///
 /// # Examples
@@ -72,12 +119,12 @@ impl From<io::Error> for Error {
 ///
 /// ```no_run
 /// use std::io::Cursor;
-/// use bt::{toggle, BluezClient};
+/// use bt::{toggle, BluezClient, ToggleArgs};
 ///
 /// let bluez_client = BluezClient::new().unwrap();
 /// let mut output = Cursor::new(vec![]);
 ///
-/// let toggle_result = toggle(&bluez_client, &mut output);
+/// let toggle_result = toggle(&bluez_client, &mut output, &ToggleArgs::default());
 ///
 /// assert!(toggle_result.is_ok());
 /// let toggle_str = String::from_utf8(output.into_inner()).unwrap();
@@ -88,27 +135,75 @@ impl From<io::Error> for Error {
 ///
 /// ```no_run
 /// use std::io::Cursor;
-/// use bt::{toggle, BluezClient, ToggleError};
+/// use bt::{toggle, BluezClient, ToggleArgs, ToggleError};
 ///
 /// let bluez_client = BluezClient::new().unwrap();
 /// let mut output = Cursor::new([]);
 ///
-/// let toggle_result = toggle(&bluez_client, &mut output);
+/// let toggle_result = toggle(&bluez_client, &mut output, &ToggleArgs::default());
 ///
 /// match toggle_result {
 ///     Err(ToggleError::Io(err)) => eprintln!("{}", err),
 ///     _ => unreachable!(),
 /// }
 ///```
-pub fn toggle(bluez: &crate::BluezClient, f: &mut impl io::Write) -> Result<(), Error> {
-    let toggled_power_state = bluez.toggle_power_state()?;
+pub fn toggle(
+    bluez: &crate::BluezClient,
+    f: &mut impl io::Write,
+    args: &ToggleArgs,
+) -> Result<(), Error> {
+    if args.all {
+        let mut buf = String::new();
+        for adapter in bluez.adapters()? {
+            let power_state = match args.power_state() {
+                Some(power_state) => power_state,
+                None => !bluez.adapter_power_state(&adapter)?,
+            };
+            let start = Instant::now();
+            bluez.set_adapter_power_state(&adapter, power_state)?;
+
+            buf.push_str(&format!(
+                "{}: {}{}\n",
+                adapter,
+                power_state,
+                timing_suffix(args, start)
+            ));
+        }
+        f.write_all(buf.as_bytes())?;
+
+        return Ok(());
+    }
 
-    let buf = format!("bluetooth: {}", toggled_power_state);
+    if args.power_state() != Some(BluezPowerState::Off)
+        && bluez.power_state()? == BluezPowerState::Blocked
+    {
+        return Err(Error::Blocked);
+    }
+
+    let start = Instant::now();
+    let power_state = match args.power_state() {
+        Some(power_state) => {
+            bluez.set_powered(power_state)?;
+            power_state
+        }
+        None => bluez.toggle_power_state()?,
+    };
+
+    let buf = format!("bluetooth: {}{}", power_state, timing_suffix(args, start));
     f.write_all(buf.as_bytes())?;
 
     Ok(())
 }
 
+/// Provides the ` (took Nms)` suffix of the output if `args.timing` is `true`, or an empty string otherwise.
+fn timing_suffix(args: &ToggleArgs, start: Instant) -> String {
+    if args.timing {
+        format!(" (took {}ms)", start.elapsed().as_millis())
+    } else {
+        String::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -119,7 +214,7 @@ mod tests {
         let bluez = crate::BluezClient::new().unwrap();
         let mut out_buf = Cursor::new(vec![]);
 
-        let result = toggle(&bluez, &mut out_buf);
+        let result = toggle(&bluez, &mut out_buf, &ToggleArgs::default());
 
         assert!(result.is_ok());
         assert!(!out_buf.into_inner().is_empty());
@@ -132,7 +227,7 @@ mod tests {
 
         let mut out_buf = Cursor::new(vec![]);
 
-        let result = toggle(&bluez, &mut out_buf);
+        let result = toggle(&bluez, &mut out_buf, &ToggleArgs::default());
 
         assert!(result.is_err());
         assert!(out_buf.into_inner().is_empty())
@@ -145,9 +240,88 @@ mod tests {
         let mut out_buf = Cursor::new([]);
         out_buf.set_position(1);
 
-        let result = toggle(&bluez, &mut out_buf);
+        let result = toggle(&bluez, &mut out_buf, &ToggleArgs::default());
 
         assert!(result.is_err());
         assert!(out_buf.into_inner().is_empty())
     }
+
+    #[test]
+    fn it_should_set_the_requested_state_on_every_adapter() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_adapters(&[
+            ("hci0", BluezPowerState::Off),
+            ("hci1", BluezPowerState::On),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = ToggleArgs {
+            all: true,
+            on: true,
+            ..Default::default()
+        };
+        let result = toggle(&bluez, &mut out_buf, &args);
+        assert!(result.is_ok());
+
+        let states = bluez
+            .test_adapters()
+            .into_iter()
+            .map(|(name, power_state)| (name, bool::from(&power_state)))
+            .collect::<Vec<(String, bool)>>();
+        assert_eq!(
+            states,
+            vec![(String::from("hci0"), true), (String::from("hci1"), true)]
+        );
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "hci0: enabled\nhci1: enabled\n");
+    }
+
+    #[test]
+    fn it_should_fail_to_power_on_if_blocked_by_rfkill() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_power_state(BluezPowerState::Blocked);
+
+        for args in [
+            ToggleArgs::default(),
+            ToggleArgs {
+                on: true,
+                ..Default::default()
+            },
+        ] {
+            let mut out_buf = Cursor::new(vec![]);
+
+            let result = toggle(&bluez, &mut out_buf, &args);
+
+            assert!(matches!(result, Err(Error::Blocked)));
+            assert!(out_buf.into_inner().is_empty());
+        }
+    }
+
+    #[test]
+    fn it_should_append_the_duration_if_timing_is_set() {
+        let bluez = crate::BluezClient::new().unwrap();
+
+        for timing in [false, true] {
+            let mut out_buf = Cursor::new(vec![]);
+
+            let args = ToggleArgs {
+                on: true,
+                timing,
+                ..Default::default()
+            };
+
+            let result = toggle(&bluez, &mut out_buf, &args);
+            assert!(result.is_ok());
+
+            let result = String::from_utf8(out_buf.into_inner()).unwrap();
+            if timing {
+                assert!(result.starts_with("bluetooth: enabled (took "));
+                assert!(result.ends_with("ms)"));
+            } else {
+                assert_eq!(result, "bluetooth: enabled");
+            }
+        }
+    }
 }
@@ -0,0 +1,276 @@
+use std::{error, fmt, io};
+
+use clap::Args;
+
+use crate::{BluezError, format};
+
+/// Defines error variants that may be returned from a [`trust`] call.
+///
+/// [`trust`]: crate::trust
+#[derive(Debug)]
+pub enum Error {
+    /// Happens when the [`BluezClient`] fails to provide the connected devices during a [`trust`] call.
+    /// It holds the underlying [`BluezError`].
+    ///
+    /// [`BluezError`]: crate::BluezError
+    /// [`BluezClient`]: crate::BluezClient
+    /// [`trust`]: crate::trust
+    Bluez(BluezError),
+
+    /// Happens when `args.all_connected` is `true`, and there are no connected devices on the host to trust.
+    NoConnectedDevices,
+
+    /// Happens when some of the devices could not be trusted. A message is written for each of them before this error is returned.
+    ///
+    /// It holds the number of failed devices.
+    BatchFailed(usize),
+
+    /// Happens when [`trust`] cannot write to the provided [`io::Write`].
+    ///
+    /// It holds the underlying [`io::Error`].
+    ///
+    /// [`trust`]: crate::trust
+    /// [`io::Error`]: std::io::Error
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Bluez(error) => write!(f, "trust: bluez error: {}", error),
+            Error::NoConnectedDevices => {
+                write!(f, "trust: there are no connected devices to trust")
+            }
+            Error::BatchFailed(failed) => {
+                write!(f, "trust: {} device(s) could not be trusted", failed)
+            }
+            Error::Io(error) => write!(f, "trust: io error: {}", error),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<BluezError> for Error {
+    fn from(value: BluezError) -> Self {
+        Self::Bluez(value)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/// Defines the arguments that [`trust`] can take.
+///
+/// [`trust`]: crate::trust
+#[derive(Debug, Default, Args)]
+pub struct TrustArgs {
+    /// The ALIAS(es) (or ADDRESS(es)) of the known devices to trust.
+    #[arg(
+        value_name = "ALIAS",
+        value_delimiter = ',',
+        required_unless_present = "all_connected",
+        conflicts_with = "all_connected"
+    )]
+    pub aliases: Vec<String>,
+
+    /// Trust every connected device, so that they can reconnect on their own.
+    #[arg(long)]
+    pub all_connected: bool,
+
+    /// Print the outcome counts at the end, e.g. `trusted=2 failed=1`.
+    #[arg(long, default_value_t = false)]
+    pub summary: bool,
+
+    /// Print the summary as JSON, e.g. `{"ok":2,"failed":1}`.
+    #[arg(long, default_value_t = false, requires = "summary")]
+    pub json: bool,
+}
+
+/// Marks the known Bluetooth devices as trusted by using a [`BluezClient`], which allows them to reconnect to the host on their own.
+///
+/// The devices are either `args.aliases`, or every connected device if `args.all_connected` is `true`.
+///
+/// A failure to trust a device does not stop [`trust`]. For each device, it writes either `trusted device X` or `failed to trust device X: <reason>` to the provided [`io::Write`], and once every device is processed, it fails with [`TrustError::BatchFailed`] if any of them failed.
+///
+/// If `args.summary` is `true`, then a summary line is written once every device is processed, such as `trusted=2 failed=1`. If `args.json` is `true`, then the summary is written as JSON instead, such as `{"ok":2,"failed":1}`.
+///
+/// # Panics
+///
+/// This function does not panic.
+///
+/// # Errors
+///
+/// This function can return all variants of [`TrustError`] based on given conditions. For more details, please see the error documentation.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+/// use bt::{trust, BluezClient, TrustArgs};
+///
+/// let bluez_client = BluezClient::new().unwrap();
+///
+/// let args = TrustArgs {
+///     all_connected: true,
+///     ..Default::default()
+/// };
+///
+/// if let Err(e) = trust(&bluez_client, &mut io::stdout(), &args) {
+///     eprintln!("{}", e);
+/// }
+/// ```
+///
+/// [`BluezClient`]: crate::BluezClient
+/// [`io::Write`]: std::io::Write
+/// [`TrustError`]: crate::TrustError
+/// [`TrustError::BatchFailed`]: crate::TrustError::BatchFailed
+/// [`trust`]: crate::trust
+pub fn trust(
+    bluez: &crate::BluezClient,
+    w: &mut impl io::Write,
+    args: &TrustArgs,
+) -> Result<(), Error> {
+    // NOTE: The connected devices are trusted by their ADDRESS, since
+    // their ALIAS may not be unique.
+    let devices = if args.all_connected {
+        let devices = bluez
+            .connected_devices()?
+            .iter()
+            .map(|d| (d.alias().to_string(), d.address().to_string()))
+            .collect::<Vec<(String, String)>>();
+        if devices.is_empty() {
+            return Err(Error::NoConnectedDevices);
+        }
+
+        devices
+    } else {
+        args.aliases
+            .iter()
+            .map(|alias| (alias.clone(), alias.clone()))
+            .collect()
+    };
+
+    let mut ok = 0;
+    let mut failed = 0;
+    for (alias, target) in devices {
+        match bluez.set_trusted(&target, true) {
+            Ok(_) => {
+                ok += 1;
+                writeln!(w, "trusted device {}", alias)?;
+            }
+            Err(e) => {
+                failed += 1;
+                writeln!(w, "failed to trust device {}: {}", alias, e)?;
+            }
+        }
+    }
+
+    if args.summary {
+        let summary = format::format_summary("trusted", ok, failed, args.json);
+        w.write_all(summary.as_bytes())?;
+    }
+
+    if failed > 0 {
+        return Err(Error::BatchFailed(failed));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::bluez;
+
+    #[test]
+    fn it_should_trust_every_connected_device() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_devices(vec![
+            bluez::BluezTestDeviceBuilder::new("speaker")
+                .address("AA:AA:AA:AA:AA:AA")
+                .connected(true)
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("mouse")
+                .address("BB:BB:BB:BB:BB:BB")
+                .build(),
+            bluez::BluezTestDeviceBuilder::new("keyboard")
+                .address("CC:CC:CC:CC:CC:CC")
+                .connected(true)
+                .build(),
+        ]);
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = TrustArgs {
+            all_connected: true,
+            ..Default::default()
+        };
+        let result = trust(&bluez, &mut out_buf, &args);
+        assert!(result.is_ok());
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert_eq!(result, "trusted device speaker\ntrusted device keyboard\n");
+        assert_eq!(
+            bluez.trust_changes(),
+            vec![
+                (String::from("AA:AA:AA:AA:AA:AA"), true),
+                (String::from("CC:CC:CC:CC:CC:CC"), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_keep_trusting_after_a_device_fails() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_erred_alias(String::from("dev1"));
+
+        let mut out_buf = Cursor::new(vec![]);
+
+        let args = TrustArgs {
+            aliases: vec![String::from("dev1"), String::from("dev2")],
+            ..Default::default()
+        };
+        let result = trust(&bluez, &mut out_buf, &args);
+        assert!(matches!(result, Err(Error::BatchFailed(1))));
+
+        let result = String::from_utf8(out_buf.into_inner()).unwrap();
+        assert!(result.starts_with("failed to trust device dev1: "));
+        assert!(result.ends_with("trusted device dev2\n"));
+        assert_eq!(bluez.trust_changes(), vec![(String::from("dev2"), true)]);
+    }
+
+    #[test]
+    fn it_should_count_the_outcomes_if_summary_is_requested() {
+        let mut bluez = crate::BluezClient::new().unwrap();
+        bluez.set_erred_alias(String::from("dev2"));
+
+        for (json, expected) in [
+            (false, "trusted=2 failed=1\n"),
+            (true, "{\"ok\":2,\"failed\":1}\n"),
+        ] {
+            let mut out_buf = Cursor::new(vec![]);
+
+            let args = TrustArgs {
+                aliases: vec![
+                    String::from("dev1"),
+                    String::from("dev2"),
+                    String::from("dev3"),
+                ],
+                summary: true,
+                json,
+                ..Default::default()
+            };
+            let result = trust(&bluez, &mut out_buf, &args);
+            assert!(matches!(result, Err(Error::BatchFailed(1))));
+
+            let result = String::from_utf8(out_buf.into_inner()).unwrap();
+            assert!(result.ends_with(&format!("trusted device dev3\n{}", expected)));
+        }
+    }
+}